@@ -1,9 +1,38 @@
-use kml_rhi::{BackendType, DeviceDesc, Instance, InstanceDesc, InstanceFlags};
+//! Renders a grid of meshlets generated by a mesh shader into an offscreen texture.
+//!
+//! The shaders are compiled to SPIR-V ahead of time:
+//!
+//! ```sh
+//! glslangValidator -V --target-env vulkan1.3 examples/shaders/meshlet.mesh -o examples/shaders/meshlet.mesh.spv
+//! glslangValidator -V --target-env vulkan1.3 examples/shaders/meshlet.frag -o examples/shaders/meshlet.frag.spv
+//! ```
+
+use kml_rhi::{
+    BackendType, ColorAttachment, ColorAttachmentState, ColorWriteFlags, CommandListDesc,
+    DepthStencilState, DeviceDesc, Extent3d, FenceDesc, Format, Instance, InstanceDesc, LoadOp,
+    MeshPipelineDesc, MultisampleState, PhysicalDeviceFeatures, PipelineLayoutDesc, QueueType,
+    RasterizerState, RenderPassDesc, ShaderModuleDesc, ShaderStage, SpecializationConstant,
+    StoreOp, SubmitDesc, TextureDesc, TextureUsageFlags, TextureViewDesc,
+};
+
+const GRID_SIZE: u32 = 8;
+const EXTENT: Extent3d = Extent3d {
+    width: 512,
+    height: 512,
+    depth: 1,
+};
+
+fn read_spirv(name: &str) -> Vec<u8> {
+    let path = format!("{}/examples/shaders/{name}.spv", env!("CARGO_MANIFEST_DIR"));
+
+    std::fs::read(&path).unwrap_or_else(|e| panic!("Failed to read {path}: {e}"))
+}
 
 fn main() {
+    // Metal has no SPIR-V translation, so the example runs on Vulkan.
     let instance = unsafe {
         Instance::new(&InstanceDesc {
-            backend_type: BackendType::Metal,
+            backend_type: BackendType::Vulkan,
             ..Default::default()
         })
     }
@@ -13,6 +42,125 @@ fn main() {
     let physical_device = physical_devices[0].clone();
 
     let device = instance
-        .create_device(&DeviceDesc { physical_device })
+        .create_device(&DeviceDesc {
+            physical_device,
+            required_features: PhysicalDeviceFeatures::MESH_SHADER
+                | PhysicalDeviceFeatures::DYNAMIC_RENDERING,
+            optional_features: PhysicalDeviceFeatures::empty(),
+        })
+        .unwrap();
+
+    let mesh_shader = device
+        .create_shader_module(&ShaderModuleDesc {
+            code: &read_spirv("meshlet.mesh"),
+            entry_points: &["main"],
+            label: Some("meshlet.mesh"),
+        })
+        .unwrap();
+    let fragment_shader = device
+        .create_shader_module(&ShaderModuleDesc {
+            code: &read_spirv("meshlet.frag"),
+            entry_points: &["main"],
+            label: Some("meshlet.frag"),
+        })
+        .unwrap();
+
+    let layout = device
+        .create_pipeline_layout(&PipelineLayoutDesc {
+            descriptor_bindings: &[],
+            bindless_set: None,
+            push_constant_ranges: &[],
+            label: Some("meshlets"),
+        })
+        .unwrap();
+
+    let pipeline = device
+        .create_mesh_pipeline(&MeshPipelineDesc {
+            task_shader: None,
+            mesh_shader: ShaderStage {
+                shader_module: &mesh_shader,
+                entry_point: "main",
+                specialization_constants: &[SpecializationConstant::u32(0, GRID_SIZE)],
+            },
+            fragment_shader: Some(ShaderStage {
+                shader_module: &fragment_shader,
+                entry_point: "main",
+                specialization_constants: &[],
+            }),
+            layout: &layout,
+            rasterizer: RasterizerState::default(),
+            depth_stencil: DepthStencilState::default(),
+            multisample: MultisampleState::default(),
+            color_attachments: &[ColorAttachmentState {
+                format: Format::Rgba8Unorm,
+                blend: None,
+                write_mask: ColorWriteFlags::all(),
+            }],
+            depth_stencil_format: None,
+            label: Some("meshlets"),
+        })
+        .unwrap();
+
+    let target = device
+        .create_texture(&TextureDesc {
+            format: Format::Rgba8Unorm,
+            extent: EXTENT,
+            usage: TextureUsageFlags::COLOR_ATTACHMENT | TextureUsageFlags::TRANSFER_SRC,
+            tracked: true,
+            label: Some("meshlet target"),
+            ..Default::default()
+        })
+        .unwrap();
+    let target_view = target.create_view(&TextureViewDesc::default()).unwrap();
+
+    let mut command_list = device
+        .create_command_list(&CommandListDesc {
+            queue_type: QueueType::Graphics,
+            label: Some("meshlets"),
+        })
         .unwrap();
+
+    command_list.begin().unwrap();
+    command_list.begin_render_pass(&RenderPassDesc {
+        color_attachments: &[ColorAttachment {
+            view: &target_view,
+            load_op: LoadOp::Clear,
+            store_op: StoreOp::Store,
+            clear_color: [0.0, 0.0, 0.0, 1.0],
+            resolve_target: None,
+        }],
+        depth_stencil_attachment: None,
+        occlusion_query_pool: None,
+        label: Some("meshlets"),
+    });
+    command_list.bind_graphics_pipeline(&pipeline);
+    command_list.draw_mesh_tasks(GRID_SIZE * GRID_SIZE, 1, 1);
+    command_list.end_render_pass();
+    command_list.end().unwrap();
+
+    let fence = device
+        .create_fence(&FenceDesc {
+            signaled: false,
+            label: Some("meshlets"),
+        })
+        .unwrap();
+
+    device
+        .get_queue(QueueType::Graphics)
+        .submit(&SubmitDesc {
+            wait_semaphores: &[],
+            wait_upload: 0,
+            command_lists: &[&command_list],
+            signal_semaphores: &[],
+            fence: Some(&fence),
+        })
+        .unwrap();
+    fence.wait(None).unwrap();
+
+    println!(
+        "Rendered {} meshlets into a {}x{} target",
+        GRID_SIZE * GRID_SIZE,
+        EXTENT.width,
+        EXTENT.height
+    );
 }
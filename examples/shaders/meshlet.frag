@@ -0,0 +1,19 @@
+#version 460
+
+layout(location = 0) flat in uint meshlet_index;
+
+layout(location = 0) out vec4 out_color;
+
+vec3 hash_color(uint value) {
+    value = (value ^ 61u) ^ (value >> 16u);
+    value *= 9u;
+    value ^= value >> 4u;
+    value *= 0x27d4eb2du;
+    value ^= value >> 15u;
+
+    return vec3(value & 0xffu, (value >> 8u) & 0xffu, (value >> 16u) & 0xffu) / 255.0;
+}
+
+void main() {
+    out_color = vec4(hash_color(meshlet_index), 1.0);
+}
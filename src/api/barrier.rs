@@ -0,0 +1,60 @@
+use crate::{Buffer, QueueType, Texture, TextureSubresourceRange};
+
+/// A way commands access a resource, the scopes of a barrier are made of these.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Access {
+    IndirectCommand,
+    IndexBuffer,
+    VertexBuffer,
+    /// Uniform, sampled or storage reads in any shader stage.
+    ShaderRead,
+    /// Storage writes in any shader stage.
+    ShaderWrite,
+    ColorAttachmentRead,
+    ColorAttachmentWrite,
+    DepthStencilAttachmentRead,
+    DepthStencilAttachmentWrite,
+    TransferSrc,
+    TransferDst,
+    HostRead,
+    HostWrite,
+}
+
+/// Moves a resource between queue families. The same barrier is recorded twice, releasing the
+/// resource on the `src` queue and acquiring it on the `dst` queue after a semaphore wait.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct QueueTransfer {
+    pub src: QueueType,
+    pub dst: QueueType,
+}
+
+/// Makes the `before` accesses of the whole buffer available to the `after` accesses.
+#[derive(Clone)]
+pub struct BufferBarrier<'a> {
+    pub buffer: &'a Buffer,
+    pub before: &'a [Access],
+    pub after: &'a [Access],
+    pub queue_transfer: Option<QueueTransfer>,
+}
+
+/// Makes the `before` accesses of a texture subresource range available to the `after` accesses.
+///
+/// Untracked textures are always used in a single layout, so only their first barrier with
+/// `discard` set performs a layout transition.
+#[derive(Clone)]
+pub struct TextureBarrier<'a> {
+    pub texture: &'a Texture,
+    pub range: TextureSubresourceRange,
+    pub before: &'a [Access],
+    pub after: &'a [Access],
+    /// The previous contents are not needed, which is required for the first use of a texture.
+    pub discard: bool,
+    pub queue_transfer: Option<QueueTransfer>,
+}
+
+/// Orders the `before` accesses of every resource before the `after` accesses.
+#[derive(Clone, Default)]
+pub struct GlobalBarrier<'a> {
+    pub before: &'a [Access],
+    pub after: &'a [Access],
+}
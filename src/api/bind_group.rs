@@ -0,0 +1,572 @@
+#[cfg(feature = "metal")]
+use crate::metal::{MetalBindGroup, MetalBindGroupLayout, MetalBindGroupPool};
+#[cfg(feature = "null")]
+use crate::null::{NullBindGroup, NullBindGroupLayout, NullBindGroupPool};
+#[cfg(feature = "vulkan")]
+use crate::vulkan::{VulkanBindGroup, VulkanBindGroupLayout, VulkanBindGroupPool};
+use crate::{
+    Buffer, BufferUsageFlags, DescriptorBinding, DescriptorType, Error, Sampler, ShaderStageFlags,
+    TextureUsageFlags, TextureView,
+};
+
+/// One binding of a bind group layout, the part of a [`DescriptorBinding`] that determines the
+/// layout.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BindGroupLayoutEntry {
+    pub binding: u32,
+    pub descriptor_type: DescriptorType,
+    /// Number of array elements, runtime sized arrays are only supported by the bindless heap.
+    pub count: u32,
+    pub stages: ShaderStageFlags,
+}
+
+impl From<&DescriptorBinding> for BindGroupLayoutEntry {
+    #[inline]
+    fn from(binding: &DescriptorBinding) -> Self {
+        Self {
+            binding: binding.binding,
+            descriptor_type: binding.descriptor_type,
+            count: binding.count,
+            stages: binding.stages,
+        }
+    }
+}
+
+/// Layouts are cached by their entries, so they carry no label.
+#[derive(Clone, Debug, Default)]
+pub struct BindGroupLayoutDesc<'a> {
+    pub entries: &'a [BindGroupLayoutEntry],
+}
+
+impl BindGroupLayoutDesc<'_> {
+    /// Returns the entries of every binding of `set`, usually taken from
+    /// [`ShaderReflection::descriptor_bindings`](crate::ShaderReflection::descriptor_bindings).
+    #[inline]
+    pub fn entries_of_set(bindings: &[DescriptorBinding], set: u32) -> Vec<BindGroupLayoutEntry> {
+        bindings
+            .iter()
+            .filter(|binding| binding.set == set)
+            .map(BindGroupLayoutEntry::from)
+            .collect()
+    }
+}
+
+/// Sorts `entries` by binding, the order layouts are cached and laid out in.
+pub(crate) fn sort_bind_group_layout_entries(
+    entries: &[BindGroupLayoutEntry],
+) -> Result<Vec<BindGroupLayoutEntry>, String> {
+    let mut sorted_entries = entries.to_vec();
+    sorted_entries.sort_by_key(|entry| entry.binding);
+
+    for (i, entry) in sorted_entries.iter().enumerate() {
+        if entry.count == 0 {
+            return Err(format!(
+                "Runtime sized descriptor array at binding {} is not supported",
+                entry.binding
+            ));
+        }
+        if i > 0 && sorted_entries[i - 1].binding == entry.binding {
+            return Err(format!("Binding {} is declared twice", entry.binding));
+        }
+    }
+
+    Ok(sorted_entries)
+}
+
+/// Compares and hashes by entries, layouts created from the same entries are interchangeable.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum BindGroupLayout {
+    #[cfg(feature = "metal")]
+    Metal(MetalBindGroupLayout),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanBindGroupLayout),
+    #[cfg(feature = "null")]
+    Null(NullBindGroupLayout),
+}
+
+impl BindGroupLayout {
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalBindGroupLayout {
+        match self {
+            BindGroupLayout::Metal(layout) => layout,
+            #[allow(unreachable_patterns)]
+            _ => panic!("BindGroupLayout belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanBindGroupLayout {
+        match self {
+            BindGroupLayout::Vulkan(layout) => layout,
+            #[allow(unreachable_patterns)]
+            _ => panic!("BindGroupLayout belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullBindGroupLayout {
+        match self {
+            BindGroupLayout::Null(layout) => layout,
+            #[allow(unreachable_patterns)]
+            _ => panic!("BindGroupLayout belongs to a different backend"),
+        }
+    }
+
+    /// Returns the entries sorted by binding.
+    #[inline]
+    pub fn entries(&self) -> &[BindGroupLayoutEntry] {
+        match self {
+            #[cfg(feature = "metal")]
+            BindGroupLayout::Metal(layout) => layout.entries(),
+            #[cfg(feature = "vulkan")]
+            BindGroupLayout::Vulkan(layout) => layout.entries(),
+            #[cfg(feature = "null")]
+            BindGroupLayout::Null(layout) => layout.entries(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BindGroupPoolDesc<'a> {
+    /// Bind groups per block, the pool allocates another block once one runs out.
+    pub max_bind_groups: u32,
+    /// Descriptors of each type per block, no bind group may hold more.
+    pub max_descriptors: u32,
+    pub label: Option<&'a str>,
+}
+
+impl Default for BindGroupPoolDesc<'_> {
+    fn default() -> Self {
+        Self {
+            max_bind_groups: 256,
+            max_descriptors: 1024,
+            label: None,
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum BindingResource<'a> {
+    /// A uniform or storage buffer range, a `size` of `None` extends to the end of the buffer.
+    Buffer {
+        buffer: &'a Buffer,
+        offset: u64,
+        size: Option<u64>,
+    },
+    /// A sampled or storage texture.
+    TextureView(&'a TextureView),
+    Sampler(&'a Sampler),
+}
+
+#[derive(Copy, Clone)]
+pub struct BindGroupEntry<'a> {
+    pub binding: u32,
+    pub array_element: u32,
+    pub resource: BindingResource<'a>,
+}
+
+#[derive(Clone)]
+pub struct BindGroupDesc<'a> {
+    pub layout: &'a BindGroupLayout,
+    /// Writes every array element of every binding exactly once.
+    pub entries: &'a [BindGroupEntry<'a>],
+    pub label: Option<&'a str>,
+}
+
+/// Checks that `entries` write every descriptor of `layout_entries` once with a resource of the
+/// matching type.
+fn validate_bind_group_entries(
+    layout_entries: &[BindGroupLayoutEntry],
+    entries: &[BindGroupEntry],
+) -> Result<(), String> {
+    let mut written = layout_entries
+        .iter()
+        .map(|entry| vec![false; entry.count as usize])
+        .collect::<Vec<_>>();
+
+    for entry in entries {
+        let Some(i) = layout_entries
+            .iter()
+            .position(|layout_entry| layout_entry.binding == entry.binding)
+        else {
+            return Err(format!("Layout has no binding {}", entry.binding));
+        };
+        let layout_entry = &layout_entries[i];
+
+        let Some(is_written) = written[i].get_mut(entry.array_element as usize) else {
+            return Err(format!(
+                "Array element {} is out of bounds of binding {} with {} elements",
+                entry.array_element, entry.binding, layout_entry.count
+            ));
+        };
+        if std::mem::replace(is_written, true) {
+            return Err(format!(
+                "Binding {} array element {} is written twice",
+                entry.binding, entry.array_element
+            ));
+        }
+
+        match (layout_entry.descriptor_type, entry.resource) {
+            (
+                descriptor_type @ (DescriptorType::UniformBuffer | DescriptorType::StorageBuffer),
+                BindingResource::Buffer {
+                    buffer,
+                    offset,
+                    size,
+                },
+            ) => {
+                let usage = match descriptor_type {
+                    DescriptorType::UniformBuffer => BufferUsageFlags::UNIFORM,
+                    _ => BufferUsageFlags::STORAGE,
+                };
+                if !buffer.usage().contains(usage) {
+                    return Err(format!(
+                        "Buffer at binding {} requires {usage:?} usage",
+                        entry.binding
+                    ));
+                }
+
+                let size = size.unwrap_or(buffer.size().saturating_sub(offset));
+                if size == 0
+                    || offset
+                        .checked_add(size)
+                        .is_none_or(|end| end > buffer.size())
+                {
+                    return Err(format!(
+                        "Buffer range at binding {} is empty or out of bounds",
+                        entry.binding
+                    ));
+                }
+            }
+            (
+                descriptor_type @ (DescriptorType::SampledTexture | DescriptorType::StorageTexture),
+                BindingResource::TextureView(view),
+            ) => {
+                let usage = match descriptor_type {
+                    DescriptorType::SampledTexture => TextureUsageFlags::SAMPLED,
+                    _ => TextureUsageFlags::STORAGE,
+                };
+                if !view.texture().usage().contains(usage) {
+                    return Err(format!(
+                        "Texture at binding {} requires {usage:?} usage",
+                        entry.binding
+                    ));
+                }
+            }
+            (DescriptorType::Sampler, BindingResource::Sampler(_)) => {}
+            (descriptor_type, _) => {
+                return Err(format!(
+                    "Binding {} of type {descriptor_type:?} cannot be written with this resource",
+                    entry.binding
+                ));
+            }
+        }
+    }
+
+    if let Some(layout_entry) = layout_entries
+        .iter()
+        .zip(&written)
+        .find_map(|(layout_entry, written)| written.contains(&false).then_some(layout_entry))
+    {
+        return Err(format!(
+            "Binding {} is not fully written",
+            layout_entry.binding
+        ));
+    }
+
+    Ok(())
+}
+
+/// Allocates bind groups in blocks that are all freed at once by [`reset`](Self::reset), meant
+/// to be used once per frame in flight.
+pub enum BindGroupPool {
+    #[cfg(feature = "metal")]
+    Metal(MetalBindGroupPool),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanBindGroupPool),
+    #[cfg(feature = "null")]
+    Null(NullBindGroupPool),
+}
+
+impl BindGroupPool {
+    #[inline]
+    pub fn create_bind_group(&self, desc: &BindGroupDesc) -> Result<BindGroup, Error> {
+        validate_bind_group_entries(desc.layout.entries(), desc.entries)
+            .map_err(Error::InvalidBindGroup)?;
+
+        match self {
+            #[cfg(feature = "metal")]
+            BindGroupPool::Metal(pool) => Ok(BindGroup::Metal(pool.create_bind_group(desc)?)),
+            #[cfg(feature = "vulkan")]
+            BindGroupPool::Vulkan(pool) => Ok(BindGroup::Vulkan(pool.create_bind_group(desc)?)),
+            #[cfg(feature = "null")]
+            BindGroupPool::Null(pool) => Ok(BindGroup::Null(pool.create_bind_group(desc)?)),
+        }
+    }
+
+    /// Frees every bind group at once, the work using them must have completed.
+    ///
+    /// Bind groups created before the reset can no longer be bound.
+    #[inline]
+    pub fn reset(&mut self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            BindGroupPool::Metal(pool) => Ok(pool.reset()?),
+            #[cfg(feature = "vulkan")]
+            BindGroupPool::Vulkan(pool) => Ok(pool.reset()?),
+            #[cfg(feature = "null")]
+            BindGroupPool::Null(pool) => Ok(pool.reset()?),
+        }
+    }
+}
+
+/// Resources bound to the bindings of a [`BindGroupLayout`], bound with
+/// [`CommandList::bind_bind_group`](crate::CommandList::bind_bind_group).
+#[derive(Clone)]
+pub enum BindGroup {
+    #[cfg(feature = "metal")]
+    Metal(MetalBindGroup),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanBindGroup),
+    #[cfg(feature = "null")]
+    Null(NullBindGroup),
+}
+
+impl BindGroup {
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalBindGroup {
+        match self {
+            BindGroup::Metal(bind_group) => bind_group,
+            #[allow(unreachable_patterns)]
+            _ => panic!("BindGroup belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanBindGroup {
+        match self {
+            BindGroup::Vulkan(bind_group) => bind_group,
+            #[allow(unreachable_patterns)]
+            _ => panic!("BindGroup belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullBindGroup {
+        match self {
+            BindGroup::Null(bind_group) => bind_group,
+            #[allow(unreachable_patterns)]
+            _ => panic!("BindGroup belongs to a different backend"),
+        }
+    }
+
+    #[inline]
+    pub fn layout(&self) -> BindGroupLayout {
+        match self {
+            #[cfg(feature = "metal")]
+            BindGroup::Metal(bind_group) => BindGroupLayout::Metal(bind_group.layout().clone()),
+            #[cfg(feature = "vulkan")]
+            BindGroup::Vulkan(bind_group) => BindGroupLayout::Vulkan(bind_group.layout().clone()),
+            #[cfg(feature = "null")]
+            BindGroup::Null(bind_group) => BindGroupLayout::Null(bind_group.layout().clone()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "null"))]
+mod tests {
+    use super::*;
+    use crate::{
+        null::test_utils::create_device, BufferDesc, Device, Extent3d, PhysicalDeviceFeatures,
+        SamplerDesc, TextureDesc, TextureViewDesc,
+    };
+
+    fn entry(binding: u32, descriptor_type: DescriptorType, count: u32) -> BindGroupLayoutEntry {
+        BindGroupLayoutEntry {
+            binding,
+            descriptor_type,
+            count,
+            stages: ShaderStageFlags::FRAGMENT,
+        }
+    }
+
+    fn create_layout(
+        device: &Device,
+        entries: &[BindGroupLayoutEntry],
+    ) -> Result<BindGroupLayout, Error> {
+        device.create_bind_group_layout(&BindGroupLayoutDesc { entries })
+    }
+
+    #[test]
+    fn layouts_with_equal_entries_are_shared() {
+        let device = create_device(PhysicalDeviceFeatures::empty());
+        let uniform_buffer = entry(0, DescriptorType::UniformBuffer, 1);
+        let sampler = entry(1, DescriptorType::Sampler, 1);
+
+        let layout = create_layout(&device, &[uniform_buffer, sampler]).unwrap();
+        assert_eq!(layout.entries(), [uniform_buffer, sampler]);
+        assert!(layout == create_layout(&device, &[sampler, uniform_buffer]).unwrap());
+
+        let fragment_and_vertex = BindGroupLayoutEntry {
+            stages: ShaderStageFlags::FRAGMENT | ShaderStageFlags::VERTEX,
+            ..sampler
+        };
+        assert!(layout != create_layout(&device, &[uniform_buffer, fragment_and_vertex]).unwrap());
+
+        assert!(create_layout(&device, &[uniform_buffer, uniform_buffer]).is_err());
+        assert!(create_layout(&device, &[entry(0, DescriptorType::SampledTexture, 0)]).is_err());
+    }
+
+    #[test]
+    fn bind_group_entries_are_validated() {
+        let device = create_device(PhysicalDeviceFeatures::empty());
+        let layout = create_layout(
+            &device,
+            &[
+                entry(0, DescriptorType::UniformBuffer, 1),
+                entry(1, DescriptorType::SampledTexture, 2),
+                entry(2, DescriptorType::Sampler, 1),
+            ],
+        )
+        .unwrap();
+        let pool = device
+            .create_bind_group_pool(&BindGroupPoolDesc::default())
+            .unwrap();
+
+        let create_buffer = |usage| {
+            device
+                .create_buffer(&BufferDesc {
+                    size: 256,
+                    usage,
+                    ..Default::default()
+                })
+                .unwrap()
+        };
+        let uniform_buffer = create_buffer(BufferUsageFlags::UNIFORM);
+        let storage_buffer = create_buffer(BufferUsageFlags::STORAGE);
+        let view = device
+            .create_texture(&TextureDesc {
+                extent: Extent3d {
+                    width: 4,
+                    height: 4,
+                    depth: 1,
+                },
+                usage: TextureUsageFlags::SAMPLED,
+                ..Default::default()
+            })
+            .unwrap()
+            .create_view(&TextureViewDesc::default())
+            .unwrap();
+        let sampler = device.create_sampler(&SamplerDesc::default()).unwrap();
+
+        let buffer = |buffer, offset, size| {
+            BindGroupEntry {
+                binding: 0,
+                array_element: 0,
+                resource: BindingResource::Buffer {
+                    buffer,
+                    offset,
+                    size,
+                },
+            }
+        };
+        let texture = |array_element| {
+            BindGroupEntry {
+                binding: 1,
+                array_element,
+                resource: BindingResource::TextureView(&view),
+            }
+        };
+        let sampler = BindGroupEntry {
+            binding: 2,
+            array_element: 0,
+            resource: BindingResource::Sampler(&sampler),
+        };
+        let create_bind_group = |entries: &[BindGroupEntry]| {
+            pool.create_bind_group(&BindGroupDesc {
+                layout: &layout,
+                entries,
+                label: None,
+            })
+        };
+
+        let uniform = buffer(&uniform_buffer, 0, None);
+        assert!(create_bind_group(&[uniform, texture(0), texture(1), sampler]).is_ok());
+        assert!(create_bind_group(&[
+            buffer(&uniform_buffer, 128, Some(128)),
+            texture(1),
+            texture(0),
+            sampler
+        ])
+        .is_ok());
+
+        let invalid_entries: [&[BindGroupEntry]; 9] = [
+            // Not fully written.
+            &[uniform, texture(0), sampler],
+            // Written twice.
+            &[uniform, texture(0), texture(0), texture(1), sampler],
+            // Out of bounds array element.
+            &[uniform, texture(0), texture(1), texture(2), sampler],
+            // Missing binding.
+            &[
+                uniform,
+                texture(0),
+                texture(1),
+                sampler,
+                BindGroupEntry {
+                    binding: 3,
+                    ..sampler
+                },
+            ],
+            // Missing buffer usage.
+            &[
+                buffer(&storage_buffer, 0, None),
+                texture(0),
+                texture(1),
+                sampler,
+            ],
+            // Empty and out of bounds buffer ranges.
+            &[
+                buffer(&uniform_buffer, 256, None),
+                texture(0),
+                texture(1),
+                sampler,
+            ],
+            &[
+                buffer(&uniform_buffer, 128, Some(256)),
+                texture(0),
+                texture(1),
+                sampler,
+            ],
+            &[
+                buffer(&uniform_buffer, u64::MAX, Some(2)),
+                texture(0),
+                texture(1),
+                sampler,
+            ],
+            // Resource of the wrong type.
+            &[
+                uniform,
+                texture(0),
+                texture(1),
+                BindGroupEntry {
+                    binding: 2,
+                    ..texture(0)
+                },
+            ],
+        ];
+
+        for entries in invalid_entries {
+            assert!(matches!(
+                create_bind_group(entries),
+                Err(Error::InvalidBindGroup(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::VecDeque;
+
+use crate::DescriptorType;
+
+/// Binding of the storage buffer array in the bindless descriptor set.
+pub const BINDLESS_STORAGE_BUFFER_BINDING: u32 = 0;
+/// Binding of the sampled texture array in the bindless descriptor set.
+pub const BINDLESS_SAMPLED_TEXTURE_BINDING: u32 = 1;
+/// Binding of the storage texture array in the bindless descriptor set.
+pub const BINDLESS_STORAGE_TEXTURE_BINDING: u32 = 2;
+
+/// Upper bound of bindless indices, backends lower it to what the device supports.
+pub(crate) const BINDLESS_HEAP_CAPACITY: u32 = 1 << 16;
+
+/// Returns the descriptor type of a binding of the bindless descriptor set.
+pub(crate) fn bindless_descriptor_type(binding: u32) -> Option<DescriptorType> {
+    match binding {
+        BINDLESS_STORAGE_BUFFER_BINDING => Some(DescriptorType::StorageBuffer),
+        BINDLESS_SAMPLED_TEXTURE_BINDING => Some(DescriptorType::SampledTexture),
+        BINDLESS_STORAGE_TEXTURE_BINDING => Some(DescriptorType::StorageTexture),
+        _ => None,
+    }
+}
+
+enum BindlessSlot<R> {
+    Free,
+    Live(R),
+    /// Released while submitted work may still read it.
+    Released(R),
+}
+
+/// Hands out the indices of a bindless heap.
+///
+/// Each index keeps whatever backend data `R` it was registered with until it is recycled.
+/// Released indices are recycled in release order once the work submitted before their release
+/// has completed, `T` is whatever the backend uses to tell.
+pub(crate) struct BindlessSlots<R, T> {
+    capacity: u32,
+    slots: Vec<BindlessSlot<R>>,
+    free_indices: Vec<u32>,
+    released_indices: VecDeque<(u32, T)>,
+}
+
+impl<R, T> BindlessSlots<R, T> {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            capacity,
+            slots: Vec::new(),
+            free_indices: Vec::new(),
+            released_indices: VecDeque::new(),
+        }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Stores `resource` at a free index, `None` if the heap is full.
+    ///
+    /// `is_complete` is asked about released indices from oldest to newest until it returns
+    /// `false`.
+    pub fn insert(&mut self, resource: R, mut is_complete: impl FnMut(&T) -> bool) -> Option<u32> {
+        while let Some((index, submitted)) = self.released_indices.front() {
+            if !is_complete(submitted) {
+                break;
+            }
+
+            self.slots[*index as usize] = BindlessSlot::Free;
+            self.free_indices.push(*index);
+            self.released_indices.pop_front();
+        }
+
+        let index = match self.free_indices.pop() {
+            Some(index) => index,
+            None if (self.slots.len() as u32) < self.capacity => {
+                self.slots.push(BindlessSlot::Free);
+                self.slots.len() as u32 - 1
+            }
+            None => return None,
+        };
+
+        self.slots[index as usize] = BindlessSlot::Live(resource);
+
+        Some(index)
+    }
+
+    /// Releases a live index, returns `false` if it is not live.
+    pub fn release(&mut self, index: u32, submitted: T) -> bool {
+        let Some(slot) = self.slots.get_mut(index as usize) else {
+            return false;
+        };
+
+        match std::mem::replace(slot, BindlessSlot::Free) {
+            BindlessSlot::Live(resource) => {
+                *slot = BindlessSlot::Released(resource);
+                self.released_indices.push_back((index, submitted));
+                true
+            }
+            other => {
+                *slot = other;
+                false
+            }
+        }
+    }
+
+    /// Returns the data of every live index.
+    #[cfg(feature = "metal")]
+    pub fn live_resources(&self) -> impl Iterator<Item = &R> {
+        self.slots.iter().filter_map(|slot| {
+            match slot {
+                BindlessSlot::Live(resource) => Some(resource),
+                _ => None,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Released indices carry the value submitted to each queue, like the Vulkan backend's.
+    fn is_complete(completed: &[u64]) -> impl FnMut(&Vec<u64>) -> bool + '_ {
+        move |submitted| {
+            submitted
+                .iter()
+                .zip(completed)
+                .all(|(submitted, completed)| submitted <= completed)
+        }
+    }
+
+    #[test]
+    fn released_indices_are_reused_once_complete() {
+        let mut slots = BindlessSlots::<(), Vec<u64>>::new(3);
+
+        assert_eq!(slots.insert((), is_complete(&[0, 0])), Some(0));
+        assert_eq!(slots.insert((), is_complete(&[0, 0])), Some(1));
+        assert!(slots.release(0, vec![1, 0]));
+
+        // Submitted work may still read index 0.
+        assert_eq!(slots.insert((), is_complete(&[0, 0])), Some(2));
+        assert_eq!(slots.insert((), is_complete(&[0, 0])), None);
+
+        assert_eq!(slots.insert((), is_complete(&[1, 0])), Some(0));
+        assert_eq!(slots.insert((), is_complete(&[1, 0])), None);
+    }
+
+    #[test]
+    fn released_indices_are_recycled_in_release_order() {
+        let mut slots = BindlessSlots::<(), Vec<u64>>::new(2);
+
+        assert_eq!(slots.insert((), is_complete(&[0, 0])), Some(0));
+        assert_eq!(slots.insert((), is_complete(&[0, 0])), Some(1));
+        assert!(slots.release(1, vec![0, 2]));
+        assert!(slots.release(0, vec![1, 0]));
+
+        // Index 0 completed first but waits for the older release of index 1.
+        assert_eq!(slots.insert((), is_complete(&[1, 0])), None);
+
+        assert_eq!(slots.insert((), is_complete(&[1, 2])), Some(0));
+        assert_eq!(slots.insert((), is_complete(&[1, 2])), Some(1));
+    }
+
+    #[test]
+    fn only_live_indices_are_released() {
+        let mut slots = BindlessSlots::<(), Vec<u64>>::new(2);
+
+        assert!(!slots.release(0, vec![0]));
+
+        assert_eq!(slots.insert((), is_complete(&[0])), Some(0));
+        assert!(slots.release(0, vec![1]));
+        assert!(!slots.release(0, vec![1]));
+        assert!(!slots.release(1, vec![1]));
+    }
+}
@@ -0,0 +1,194 @@
+use std::ptr::NonNull;
+
+use bitflags::bitflags;
+
+#[cfg(feature = "metal")]
+use crate::metal::MetalBuffer;
+#[cfg(feature = "null")]
+use crate::null::NullBuffer;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanBuffer;
+
+bitflags! {
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct BufferUsageFlags : u32 {
+        const TRANSFER_SRC = 1 << 0;
+        const TRANSFER_DST = 1 << 1;
+        const UNIFORM = 1 << 2;
+        const STORAGE = 1 << 3;
+        const INDEX = 1 << 4;
+        const VERTEX = 1 << 5;
+        const INDIRECT = 1 << 6;
+        const DEVICE_ADDRESS = 1 << 7;
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MemoryLocation {
+    /// Device local memory that is not accessible from the CPU.
+    #[default]
+    GpuOnly,
+    /// Host visible memory the CPU writes sequentially and the GPU reads.
+    Upload,
+    /// Host visible, cached memory the GPU writes and the CPU reads back.
+    Readback,
+}
+
+impl MemoryLocation {
+    #[inline]
+    pub fn is_host_visible(&self) -> bool {
+        !matches!(self, Self::GpuOnly)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BufferDesc<'a> {
+    pub size: u64,
+    pub usage: BufferUsageFlags,
+    pub memory_location: MemoryLocation,
+    /// Lets command lists insert the barriers for the buffer automatically, see
+    /// [`CommandList`](crate::CommandList).
+    pub tracked: bool,
+    pub label: Option<&'a str>,
+}
+
+#[derive(Clone)]
+pub enum Buffer {
+    #[cfg(feature = "metal")]
+    Metal(MetalBuffer),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanBuffer),
+    #[cfg(feature = "null")]
+    Null(NullBuffer),
+}
+
+impl Buffer {
+    #[inline]
+    pub fn size(&self) -> u64 {
+        match self {
+            #[cfg(feature = "metal")]
+            Buffer::Metal(buffer) => buffer.size(),
+            #[cfg(feature = "vulkan")]
+            Buffer::Vulkan(buffer) => buffer.size(),
+            #[cfg(feature = "null")]
+            Buffer::Null(buffer) => buffer.size(),
+        }
+    }
+
+    #[inline]
+    pub fn usage(&self) -> BufferUsageFlags {
+        match self {
+            #[cfg(feature = "metal")]
+            Buffer::Metal(buffer) => buffer.usage(),
+            #[cfg(feature = "vulkan")]
+            Buffer::Vulkan(buffer) => buffer.usage(),
+            #[cfg(feature = "null")]
+            Buffer::Null(buffer) => buffer.usage(),
+        }
+    }
+
+    #[inline]
+    pub fn memory_location(&self) -> MemoryLocation {
+        match self {
+            #[cfg(feature = "metal")]
+            Buffer::Metal(buffer) => buffer.memory_location(),
+            #[cfg(feature = "vulkan")]
+            Buffer::Vulkan(buffer) => buffer.memory_location(),
+            #[cfg(feature = "null")]
+            Buffer::Null(buffer) => buffer.memory_location(),
+        }
+    }
+
+    /// Returns the persistently mapped pointer for [`MemoryLocation::Upload`] and
+    /// [`MemoryLocation::Readback`] buffers, `None` for GPU only buffers.
+    #[inline]
+    pub fn mapped_ptr(&self) -> Option<NonNull<u8>> {
+        match self {
+            #[cfg(feature = "metal")]
+            Buffer::Metal(buffer) => buffer.mapped_ptr(),
+            #[cfg(feature = "vulkan")]
+            Buffer::Vulkan(buffer) => buffer.mapped_ptr(),
+            #[cfg(feature = "null")]
+            Buffer::Null(buffer) => buffer.mapped_ptr(),
+        }
+    }
+
+    /// Returns the GPU virtual address if the buffer was created with
+    /// [`BufferUsageFlags::DEVICE_ADDRESS`].
+    #[inline]
+    pub fn device_address(&self) -> Option<u64> {
+        match self {
+            #[cfg(feature = "metal")]
+            Buffer::Metal(buffer) => buffer.device_address(),
+            #[cfg(feature = "vulkan")]
+            Buffer::Vulkan(buffer) => buffer.device_address(),
+            #[cfg(feature = "null")]
+            Buffer::Null(buffer) => buffer.device_address(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalBuffer {
+        match self {
+            Buffer::Metal(buffer) => buffer,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Buffer belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanBuffer {
+        match self {
+            Buffer::Vulkan(buffer) => buffer,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Buffer belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullBuffer {
+        match self {
+            Buffer::Null(buffer) => buffer,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Buffer belongs to a different backend"),
+        }
+    }
+
+    /// Copies `data` into the mapped memory at `offset`.
+    ///
+    /// # Safety
+    /// The caller must ensure the GPU does not access the range while it is written.
+    pub unsafe fn write(&self, offset: u64, data: &[u8]) {
+        let ptr = self.mapped_ptr().expect("Buffer is not host visible");
+
+        assert!(
+            offset + data.len() as u64 <= self.size(),
+            "Write out of bounds"
+        );
+
+        std::ptr::copy_nonoverlapping(data.as_ptr(), ptr.as_ptr().add(offset as usize), data.len());
+    }
+
+    /// Copies the mapped memory at `offset` into `data`.
+    ///
+    /// # Safety
+    /// The caller must ensure the GPU has finished writing the range.
+    pub unsafe fn read(&self, offset: u64, data: &mut [u8]) {
+        let ptr = self.mapped_ptr().expect("Buffer is not host visible");
+
+        assert!(
+            offset + data.len() as u64 <= self.size(),
+            "Read out of bounds"
+        );
+
+        std::ptr::copy_nonoverlapping(
+            ptr.as_ptr().add(offset as usize),
+            data.as_mut_ptr(),
+            data.len(),
+        );
+    }
+}
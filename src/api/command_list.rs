@@ -0,0 +1,815 @@
+use bytemuck::Pod;
+
+#[cfg(feature = "metal")]
+use crate::metal::MetalCommandList;
+#[cfg(feature = "null")]
+use crate::null::NullCommandList;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanCommandList;
+use crate::{
+    BindGroup, Buffer, BufferBarrier, ComputePipeline, Error, Extent3d, GlobalBarrier, GpuProfiler,
+    GraphicsPipeline, Origin3d, ProfileScope, QueryPool, QueueType, RenderPassDesc, ScissorRect,
+    ShaderStageFlags, Texture, TextureBarrier, TextureSubresourceRange, Viewport,
+};
+
+#[derive(Clone, Debug)]
+pub struct CommandListDesc<'a> {
+    /// The queue type the command list is going to be submitted to.
+    pub queue_type: QueueType,
+    pub label: Option<&'a str>,
+}
+
+impl Default for CommandListDesc<'_> {
+    fn default() -> Self {
+        Self {
+            queue_type: QueueType::Graphics,
+            label: None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum IndexFormat {
+    Uint16,
+    #[default]
+    Uint32,
+}
+
+impl IndexFormat {
+    #[inline]
+    pub fn size(&self) -> u64 {
+        match self {
+            IndexFormat::Uint16 => 2,
+            IndexFormat::Uint32 => 4,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ClearValue {
+    Color([f32; 4]),
+    DepthStencil { depth: f32, stencil: u32 },
+}
+
+/// Describes a copy between a buffer and a single mip level and array layer of a texture.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BufferTextureCopy {
+    pub buffer_offset: u64,
+    /// Bytes between consecutive block rows in the buffer, zero if tightly packed.
+    pub bytes_per_row: u32,
+    /// Block rows between consecutive depth slices in the buffer, zero if tightly packed.
+    pub rows_per_image: u32,
+    pub mip_level: u32,
+    pub array_layer: u32,
+    pub origin: Origin3d,
+    pub extent: Extent3d,
+}
+
+/// Describes a copy between single mip levels and array layers of two textures.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextureCopy {
+    pub src_mip_level: u32,
+    pub src_array_layer: u32,
+    pub src_origin: Origin3d,
+    pub dst_mip_level: u32,
+    pub dst_array_layer: u32,
+    pub dst_origin: Origin3d,
+    pub extent: Extent3d,
+}
+
+/// Records commands for a single queue type.
+///
+/// Buffers and textures created with `tracked` set remember how they were last accessed, and
+/// the command list inserts the barriers and layout transitions their next use needs. The
+/// states are updated while recording, so command lists using the same tracked resources must
+/// be submitted in the order they were recorded, with a semaphore between submissions to
+/// different queues. Untracked resources are left to explicit synchronization. Metal tracks
+/// hazards of every resource itself, so the flag only matters on Vulkan.
+pub enum CommandList {
+    #[cfg(feature = "metal")]
+    Metal(MetalCommandList),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanCommandList),
+    #[cfg(feature = "null")]
+    Null(NullCommandList),
+}
+
+impl CommandList {
+    #[inline]
+    pub fn queue_type(&self) -> QueueType {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.queue_type(),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.queue_type(),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.queue_type(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalCommandList {
+        match self {
+            CommandList::Metal(command_list) => command_list,
+            #[allow(unreachable_patterns)]
+            _ => panic!("CommandList belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanCommandList {
+        match self {
+            CommandList::Vulkan(command_list) => command_list,
+            #[allow(unreachable_patterns)]
+            _ => panic!("CommandList belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullCommandList {
+        match self {
+            CommandList::Null(command_list) => command_list,
+            #[allow(unreachable_patterns)]
+            _ => panic!("CommandList belongs to a different backend"),
+        }
+    }
+
+    /// Starts recording, discarding previously recorded commands.
+    ///
+    /// The caller must ensure earlier submissions of this command list have completed.
+    #[inline]
+    pub fn begin(&mut self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => Ok(command_list.begin()?),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => Ok(command_list.begin()?),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => Ok(command_list.begin()?),
+        }
+    }
+
+    /// Finishes recording, returning the first error encountered while recording.
+    #[inline]
+    pub fn end(&mut self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => Ok(command_list.end()?),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => Ok(command_list.end()?),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => Ok(command_list.end()?),
+        }
+    }
+
+    #[inline]
+    pub fn copy_buffer(
+        &mut self,
+        src: &Buffer,
+        src_offset: u64,
+        dst: &Buffer,
+        dst_offset: u64,
+        size: u64,
+    ) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.copy_buffer(src, src_offset, dst, dst_offset, size)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.copy_buffer(src, src_offset, dst, dst_offset, size)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.copy_buffer(src, src_offset, dst, dst_offset, size)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn copy_buffer_to_texture(
+        &mut self,
+        src: &Buffer,
+        dst: &Texture,
+        region: &BufferTextureCopy,
+    ) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.copy_buffer_to_texture(src, dst, region)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.copy_buffer_to_texture(src, dst, region)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.copy_buffer_to_texture(src, dst, region)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn copy_texture_to_buffer(
+        &mut self,
+        src: &Texture,
+        dst: &Buffer,
+        region: &BufferTextureCopy,
+    ) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.copy_texture_to_buffer(src, dst, region)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.copy_texture_to_buffer(src, dst, region)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.copy_texture_to_buffer(src, dst, region)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn copy_texture(&mut self, src: &Texture, dst: &Texture, region: &TextureCopy) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.copy_texture(src, dst, region),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.copy_texture(src, dst, region),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.copy_texture(src, dst, region),
+        }
+    }
+
+    /// Fills `size` bytes at `offset` with `value`, both must be multiples of four.
+    #[inline]
+    pub fn clear_buffer(&mut self, buffer: &Buffer, offset: u64, size: u64, value: u8) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.clear_buffer(buffer, offset, size, value)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.clear_buffer(buffer, offset, size, value)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.clear_buffer(buffer, offset, size, value)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn clear_texture(
+        &mut self,
+        texture: &Texture,
+        range: &TextureSubresourceRange,
+        value: ClearValue,
+    ) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.clear_texture(texture, range, value),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.clear_texture(texture, range, value),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.clear_texture(texture, range, value),
+        }
+    }
+
+    /// Records the barriers of untracked resources as a single batch, outside of render passes.
+    #[inline]
+    pub fn barrier(
+        &mut self,
+        buffer_barriers: &[BufferBarrier],
+        texture_barriers: &[TextureBarrier],
+        global_barriers: &[GlobalBarrier],
+    ) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.barrier(buffer_barriers, texture_barriers, global_barriers)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.barrier(buffer_barriers, texture_barriers, global_barriers)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.barrier(buffer_barriers, texture_barriers, global_barriers)
+            }
+        }
+    }
+
+    /// Begins rendering into the attachments of `desc`, draws are only valid inside a render
+    /// pass and copies, clears and dispatches only outside of one.
+    ///
+    /// Graphics pipelines, vertex and index buffers are bound inside the pass and do not carry
+    /// over to the next one.
+    #[inline]
+    pub fn begin_render_pass(&mut self, desc: &RenderPassDesc) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.begin_render_pass(desc),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.begin_render_pass(desc),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.begin_render_pass(desc),
+        }
+    }
+
+    #[inline]
+    pub fn end_render_pass(&mut self) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.end_render_pass(),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.end_render_pass(),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.end_render_pass(),
+        }
+    }
+
+    #[inline]
+    pub fn set_viewport(&mut self, viewport: &Viewport) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.set_viewport(viewport),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.set_viewport(viewport),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.set_viewport(viewport),
+        }
+    }
+
+    #[inline]
+    pub fn set_scissor(&mut self, scissor: &ScissorRect) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.set_scissor(scissor),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.set_scissor(scissor),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.set_scissor(scissor),
+        }
+    }
+
+    #[inline]
+    pub fn set_stencil_reference(&mut self, reference: u32) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.set_stencil_reference(reference),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.set_stencil_reference(reference),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.set_stencil_reference(reference),
+        }
+    }
+
+    /// Sets the color used by [`BlendFactor::Constant`](crate::BlendFactor::Constant).
+    #[inline]
+    pub fn set_blend_constants(&mut self, constants: [f32; 4]) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.set_blend_constants(constants),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.set_blend_constants(constants),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.set_blend_constants(constants),
+        }
+    }
+
+    #[inline]
+    pub fn bind_vertex_buffer(&mut self, slot: u32, buffer: &Buffer, offset: u64) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.bind_vertex_buffer(slot, buffer, offset)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.bind_vertex_buffer(slot, buffer, offset)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.bind_vertex_buffer(slot, buffer, offset)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn bind_index_buffer(&mut self, buffer: &Buffer, offset: u64, format: IndexFormat) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.bind_index_buffer(buffer, offset, format)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.bind_index_buffer(buffer, offset, format)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.bind_index_buffer(buffer, offset, format)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn draw(
+        &mut self,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.draw(vertex_count, instance_count, first_vertex, first_instance)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.draw(vertex_count, instance_count, first_vertex, first_instance)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.draw(vertex_count, instance_count, first_vertex, first_instance)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn draw_indexed(
+        &mut self,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.draw_indexed(
+                    index_count,
+                    instance_count,
+                    first_index,
+                    vertex_offset,
+                    first_instance,
+                )
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.draw_indexed(
+                    index_count,
+                    instance_count,
+                    first_index,
+                    vertex_offset,
+                    first_instance,
+                )
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.draw_indexed(
+                    index_count,
+                    instance_count,
+                    first_index,
+                    vertex_offset,
+                    first_instance,
+                )
+            }
+        }
+    }
+
+    /// Dispatches task or mesh shader workgroups, requires [`PhysicalDeviceFeatures::MESH_SHADER`].
+    ///
+    /// [`PhysicalDeviceFeatures::MESH_SHADER`]: crate::PhysicalDeviceFeatures::MESH_SHADER
+    #[inline]
+    pub fn draw_mesh_tasks(&mut self, x: u32, y: u32, z: u32) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.draw_mesh_tasks(x, y, z),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.draw_mesh_tasks(x, y, z),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.draw_mesh_tasks(x, y, z),
+        }
+    }
+
+    /// Draws `draw_count` mesh task dispatches read from `buffer` at `offset`, each three `u32`
+    /// workgroup counts placed `stride` bytes apart.
+    #[inline]
+    pub fn draw_mesh_tasks_indirect(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.draw_mesh_tasks_indirect(buffer, offset, draw_count, stride)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.draw_mesh_tasks_indirect(buffer, offset, draw_count, stride)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.draw_mesh_tasks_indirect(buffer, offset, draw_count, stride)
+            }
+        }
+    }
+
+    /// Like [`CommandList::draw_mesh_tasks_indirect`] with the draw count read as a `u32` from
+    /// `count_buffer` and clamped to `max_draw_count`, requires
+    /// [`PhysicalDeviceFeatures::DRAW_INDIRECT_COUNT`].
+    ///
+    /// [`PhysicalDeviceFeatures::DRAW_INDIRECT_COUNT`]: crate::PhysicalDeviceFeatures::DRAW_INDIRECT_COUNT
+    #[inline]
+    pub fn draw_mesh_tasks_indirect_count(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        count_buffer: &Buffer,
+        count_buffer_offset: u64,
+        max_draw_count: u32,
+        stride: u32,
+    ) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.draw_mesh_tasks_indirect_count(
+                    buffer,
+                    offset,
+                    count_buffer,
+                    count_buffer_offset,
+                    max_draw_count,
+                    stride,
+                )
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.draw_mesh_tasks_indirect_count(
+                    buffer,
+                    offset,
+                    count_buffer,
+                    count_buffer_offset,
+                    max_draw_count,
+                    stride,
+                )
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.draw_mesh_tasks_indirect_count(
+                    buffer,
+                    offset,
+                    count_buffer,
+                    count_buffer_offset,
+                    max_draw_count,
+                    stride,
+                )
+            }
+        }
+    }
+
+    #[inline]
+    pub fn bind_compute_pipeline(&mut self, pipeline: &ComputePipeline) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.bind_compute_pipeline(pipeline),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.bind_compute_pipeline(pipeline),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.bind_compute_pipeline(pipeline),
+        }
+    }
+
+    #[inline]
+    pub fn bind_graphics_pipeline(&mut self, pipeline: &GraphicsPipeline) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.bind_graphics_pipeline(pipeline),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.bind_graphics_pipeline(pipeline),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.bind_graphics_pipeline(pipeline),
+        }
+    }
+
+    /// Binds `bind_group` to `set` of the graphics pipeline inside a render pass and of the
+    /// compute pipeline outside of one, the group layout must match the pipeline layout's set.
+    ///
+    /// Tracked resources of the group are transitioned for the shader stages of their bindings.
+    #[inline]
+    pub fn bind_bind_group(&mut self, set: u32, bind_group: &BindGroup) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.bind_bind_group(set, bind_group),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.bind_bind_group(set, bind_group),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.bind_bind_group(set, bind_group),
+        }
+    }
+
+    /// Sets the push constants of `stages` at `offset` to `data`, for the graphics pipeline
+    /// inside a render pass and the compute pipeline outside of one.
+    ///
+    /// The bytes must lie within the push constant ranges of the pipeline layout, whose ranges
+    /// fit the device limit.
+    #[inline]
+    pub fn push_constants<T: Pod>(&mut self, stages: ShaderStageFlags, offset: u32, data: &T) {
+        let data = bytemuck::bytes_of(data);
+
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.push_constants(stages, offset, data),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.push_constants(stages, offset, data),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.push_constants(stages, offset, data),
+        }
+    }
+
+    /// Dispatches `x * y * z` workgroups of the bound compute pipeline.
+    #[inline]
+    pub fn dispatch(&mut self, x: u32, y: u32, z: u32) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.dispatch(x, y, z),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.dispatch(x, y, z),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.dispatch(x, y, z),
+        }
+    }
+
+    /// Dispatches with the workgroup counts read from three `u32` values in `buffer` at `offset`.
+    #[inline]
+    pub fn dispatch_indirect(&mut self, buffer: &Buffer, offset: u64) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.dispatch_indirect(buffer, offset),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.dispatch_indirect(buffer, offset),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.dispatch_indirect(buffer, offset),
+        }
+    }
+
+    /// Resets `count` queries of `query_pool` starting at `first`, outside of a render pass.
+    #[inline]
+    pub fn reset_queries(&mut self, query_pool: &QueryPool, first: u32, count: u32) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.reset_queries(query_pool, first, count)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.reset_queries(query_pool, first, count)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.reset_queries(query_pool, first, count),
+        }
+    }
+
+    /// Writes the GPU timestamp into the timestamp query `index` once all previous commands
+    /// have completed.
+    #[inline]
+    pub fn write_timestamp(&mut self, query_pool: &QueryPool, index: u32) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.write_timestamp(query_pool, index),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.write_timestamp(query_pool, index),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.write_timestamp(query_pool, index),
+        }
+    }
+
+    /// Begins the occlusion or pipeline statistics query `index`, occlusion queries belong to
+    /// the [`RenderPassDesc::occlusion_query_pool`] of the active render pass.
+    #[inline]
+    pub fn begin_query(&mut self, query_pool: &QueryPool, index: u32) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.begin_query(query_pool, index),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.begin_query(query_pool, index),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.begin_query(query_pool, index),
+        }
+    }
+
+    /// Ends the query begun by [`Self::begin_query`], in the same render pass if it was begun
+    /// inside one.
+    #[inline]
+    pub fn end_query(&mut self, query_pool: &QueryPool, index: u32) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.end_query(query_pool, index),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.end_query(query_pool, index),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.end_query(query_pool, index),
+        }
+    }
+
+    /// Copies the results of `count` queries starting at `first` into `dst` at `dst_offset`,
+    /// [`QueryType::result_size`](crate::QueryType::result_size) bytes each, waiting for
+    /// queries that are still in flight.
+    ///
+    /// `dst_offset` must be a multiple of 8 and the call must be made outside of a render pass.
+    #[inline]
+    pub fn resolve_queries(
+        &mut self,
+        query_pool: &QueryPool,
+        first: u32,
+        count: u32,
+        dst: &Buffer,
+        dst_offset: u64,
+    ) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => {
+                command_list.resolve_queries(query_pool, first, count, dst, dst_offset)
+            }
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => {
+                command_list.resolve_queries(query_pool, first, count, dst, dst_offset)
+            }
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => {
+                command_list.resolve_queries(query_pool, first, count, dst, dst_offset)
+            }
+        }
+    }
+
+    /// Opens a group of the following commands named `label` in validation messages and
+    /// captures, closed by [`Self::pop_debug_group`].
+    ///
+    /// Groups are closed in the command list that opened them, and in the render pass if they
+    /// were opened inside one.
+    #[inline]
+    pub fn push_debug_group(&mut self, label: &str) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.push_debug_group(label),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.push_debug_group(label),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.push_debug_group(label),
+        }
+    }
+
+    /// Closes the group opened by the last [`Self::push_debug_group`].
+    #[inline]
+    pub fn pop_debug_group(&mut self) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.pop_debug_group(),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.pop_debug_group(),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.pop_debug_group(),
+        }
+    }
+
+    /// Marks the current position in the command stream with `label`.
+    #[inline]
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        match self {
+            #[cfg(feature = "metal")]
+            CommandList::Metal(command_list) => command_list.insert_debug_marker(label),
+            #[cfg(feature = "vulkan")]
+            CommandList::Vulkan(command_list) => command_list.insert_debug_marker(label),
+            #[cfg(feature = "null")]
+            CommandList::Null(command_list) => command_list.insert_debug_marker(label),
+        }
+    }
+
+    /// Begins a scope timed by `profiler` that ends when the returned guard is dropped, commands
+    /// are recorded through the guard and nested scopes begun with
+    /// [`ProfileScope::profile_scope`].
+    ///
+    /// Scopes have to begin and end outside of render passes.
+    #[inline]
+    pub fn profile_scope<'a>(
+        &'a mut self,
+        profiler: &'a GpuProfiler,
+        label: &str,
+    ) -> ProfileScope<'a> {
+        ProfileScope::new(self, profiler, label)
+    }
+}
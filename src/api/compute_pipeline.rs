@@ -0,0 +1,72 @@
+#[cfg(feature = "metal")]
+use crate::metal::MetalComputePipeline;
+#[cfg(feature = "null")]
+use crate::null::NullComputePipeline;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanComputePipeline;
+use crate::{PipelineLayout, ShaderModule, SpecializationConstant};
+
+#[derive(Clone)]
+pub struct ComputePipelineDesc<'a> {
+    pub shader_module: &'a ShaderModule,
+    /// Name of a compute entry point of `shader_module`.
+    pub entry_point: &'a str,
+    pub specialization_constants: &'a [SpecializationConstant],
+    pub layout: &'a PipelineLayout,
+    pub label: Option<&'a str>,
+}
+
+#[derive(Clone)]
+pub enum ComputePipeline {
+    #[cfg(feature = "metal")]
+    Metal(MetalComputePipeline),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanComputePipeline),
+    #[cfg(feature = "null")]
+    Null(NullComputePipeline),
+}
+
+impl ComputePipeline {
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalComputePipeline {
+        match self {
+            ComputePipeline::Metal(compute_pipeline) => compute_pipeline,
+            #[allow(unreachable_patterns)]
+            _ => panic!("ComputePipeline belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanComputePipeline {
+        match self {
+            ComputePipeline::Vulkan(compute_pipeline) => compute_pipeline,
+            #[allow(unreachable_patterns)]
+            _ => panic!("ComputePipeline belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullComputePipeline {
+        match self {
+            ComputePipeline::Null(compute_pipeline) => compute_pipeline,
+            #[allow(unreachable_patterns)]
+            _ => panic!("ComputePipeline belongs to a different backend"),
+        }
+    }
+
+    /// Returns the workgroup size reflected from the entry point, before specialization.
+    #[inline]
+    pub fn workgroup_size(&self) -> [u32; 3] {
+        match self {
+            #[cfg(feature = "metal")]
+            ComputePipeline::Metal(compute_pipeline) => compute_pipeline.workgroup_size(),
+            #[cfg(feature = "vulkan")]
+            ComputePipeline::Vulkan(compute_pipeline) => compute_pipeline.workgroup_size(),
+            #[cfg(feature = "null")]
+            ComputePipeline::Null(compute_pipeline) => compute_pipeline.workgroup_size(),
+        }
+    }
+}
@@ -1,12 +1,60 @@
+use std::time::Duration;
+
 #[cfg(feature = "metal")]
-use crate::metal::MetalDevice;
+use crate::metal::{
+    MetalBindGroupLayout, MetalBindGroupPool, MetalBindlessHeap, MetalBuffer, MetalCommandList,
+    MetalComputePipeline, MetalDevice, MetalFence, MetalGraphicsPipeline, MetalPipelineLayout,
+    MetalQueryPool, MetalSampler, MetalSemaphore, MetalShaderModule, MetalTexture, MetalUploader,
+};
+#[cfg(feature = "null")]
+use crate::null::{
+    NullBindGroupLayout, NullBindGroupPool, NullBindlessHeap, NullBuffer, NullCommandList,
+    NullComputePipeline, NullDevice, NullFence, NullGraphicsPipeline, NullPipelineLayout,
+    NullQueryPool, NullSampler, NullSemaphore, NullShaderModule, NullTexture, NullUploader,
+};
 #[cfg(feature = "vulkan")]
-use crate::vulkan::VulkanDevice;
-use crate::PhysicalDevice;
+use crate::vulkan::{
+    VulkanBindGroupLayout, VulkanBindGroupPool, VulkanBindlessHeap, VulkanBuffer,
+    VulkanCommandList, VulkanComputePipeline, VulkanDevice, VulkanFence, VulkanGraphicsPipeline,
+    VulkanPipelineLayout, VulkanQueryPool, VulkanSampler, VulkanSemaphore, VulkanShaderModule,
+    VulkanTexture, VulkanUploader,
+};
+use crate::{
+    packed_texture_size, reflect_spirv, spirv_to_words, BindGroupLayout, BindGroupLayoutDesc,
+    BindGroupPool, BindGroupPoolDesc, Buffer, BufferDesc, BufferRead, BufferTextureCopy,
+    BufferUpload, BufferUsageFlags, CalibratedTimestamp, CommandList, CommandListDesc,
+    ComputePipeline, ComputePipelineDesc, Error, Extent3d, Fence, FenceDesc, GpuProfiler,
+    GpuProfilerDesc, GraphicsPipeline, GraphicsPipelineDesc, MemoryLocation, MeshPipelineDesc,
+    Origin3d, PhysicalDevice, PhysicalDeviceFeatures, PipelineLayout, PipelineLayoutDesc,
+    QueryPool, QueryPoolDesc, QueryType, Queue, QueueType, Readback, Sampler, SamplerDesc,
+    SamplerReductionMode, Semaphore, SemaphoreDesc, SemaphoreValue, ShaderModule, ShaderModuleDesc,
+    ShaderStage, ShaderStageFlags, SubmitDesc, Texture, TextureDesc, TextureRead, TextureUpload,
+    TextureUsageFlags, TextureView, READBACK_ROW_PITCH_ALIGNMENT,
+};
 
 #[derive(Clone)]
 pub struct DeviceDesc {
     pub physical_device: PhysicalDevice,
+    /// Features device creation fails without.
+    pub required_features: PhysicalDeviceFeatures,
+    /// Features enabled only if the physical device supports them.
+    pub optional_features: PhysicalDeviceFeatures,
+}
+
+impl DeviceDesc {
+    /// Returns the required features the physical device does not support.
+    #[inline]
+    pub fn missing_features(&self) -> PhysicalDeviceFeatures {
+        self.required_features
+            .difference(self.physical_device.get_supported_features())
+    }
+
+    /// Returns the required features plus every supported optional feature.
+    #[inline]
+    pub fn enabled_features(&self) -> PhysicalDeviceFeatures {
+        self.required_features
+            | (self.optional_features & self.physical_device.get_supported_features())
+    }
 }
 
 #[derive(Clone)]
@@ -15,4 +63,943 @@ pub enum Device {
     Metal(MetalDevice),
     #[cfg(feature = "vulkan")]
     Vulkan(VulkanDevice),
+    #[cfg(feature = "null")]
+    Null(NullDevice),
+}
+
+impl Device {
+    #[inline]
+    pub fn get_enabled_features(&self) -> PhysicalDeviceFeatures {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => device.get_enabled_features(),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => device.get_enabled_features(),
+            #[cfg(feature = "null")]
+            Device::Null(device) => device.get_enabled_features(),
+        }
+    }
+
+    /// Returns the number of nanoseconds per timestamp tick, see [`QueryType::Timestamp`].
+    #[inline]
+    pub fn get_timestamp_period(&self) -> f32 {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => device.get_timestamp_period(),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => device.get_timestamp_period(),
+            #[cfg(feature = "null")]
+            Device::Null(device) => device.get_timestamp_period(),
+        }
+    }
+
+    /// Samples a GPU timestamp together with the CPU clock, `None` unless the backend supports
+    /// calibrated timestamps (`VK_EXT_calibrated_timestamps` on Vulkan).
+    #[inline]
+    pub fn get_calibrated_timestamp(&self) -> Result<Option<CalibratedTimestamp>, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(device.get_calibrated_timestamp()?),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(device.get_calibrated_timestamp()?),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(device.get_calibrated_timestamp()?),
+        }
+    }
+
+    /// Converts a difference of timestamp ticks to nanoseconds.
+    #[inline]
+    pub fn ticks_to_nanoseconds(&self, ticks: u64) -> f64 {
+        ticks as f64 * f64::from(self.get_timestamp_period())
+    }
+
+    #[inline]
+    pub fn get_queue(&self, queue_type: QueueType) -> Queue {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Queue::Metal(device.get_queue(queue_type)),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Queue::Vulkan(device.get_queue(queue_type)),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Queue::Null(device.get_queue(queue_type)),
+        }
+    }
+
+    #[inline]
+    pub fn create_buffer(&self, desc: &BufferDesc) -> Result<Buffer, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(Buffer::Metal(MetalBuffer::new(device, desc)?)),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(Buffer::Vulkan(VulkanBuffer::new(device, desc)?)),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(Buffer::Null(NullBuffer::new(device, desc)?)),
+        }
+    }
+
+    #[inline]
+    pub fn create_texture(&self, desc: &TextureDesc) -> Result<Texture, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(Texture::Metal(MetalTexture::new(device, desc)?)),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(Texture::Vulkan(VulkanTexture::new(device, desc)?)),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(Texture::Null(NullTexture::new(device, desc)?)),
+        }
+    }
+
+    #[inline]
+    pub fn create_command_list(&self, desc: &CommandListDesc) -> Result<CommandList, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(CommandList::Metal(MetalCommandList::new(device, desc)?)),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => {
+                Ok(CommandList::Vulkan(VulkanCommandList::new(device, desc)?))
+            }
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(CommandList::Null(NullCommandList::new(device, desc)?)),
+        }
+    }
+
+    #[inline]
+    pub fn create_fence(&self, desc: &FenceDesc) -> Result<Fence, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(Fence::Metal(MetalFence::new(device, desc)?)),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(Fence::Vulkan(VulkanFence::new(device, desc)?)),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(Fence::Null(NullFence::new(device, desc)?)),
+        }
+    }
+
+    /// Creates a timeline semaphore, requires [`PhysicalDeviceFeatures::TIMELINE_SEMAPHORE`].
+    #[inline]
+    pub fn create_semaphore(&self, desc: &SemaphoreDesc) -> Result<Semaphore, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(Semaphore::Metal(MetalSemaphore::new(device, desc)?)),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(Semaphore::Vulkan(VulkanSemaphore::new(device, desc)?)),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(Semaphore::Null(NullSemaphore::new(device, desc)?)),
+        }
+    }
+
+    /// Creates a shader module from SPIR-V and reflects its interface.
+    #[inline]
+    pub fn create_shader_module(&self, desc: &ShaderModuleDesc) -> Result<ShaderModule, Error> {
+        let code = spirv_to_words(desc.code).map_err(Error::InvalidSpirv)?;
+        let reflection = reflect_spirv(&code, desc.entry_points).map_err(Error::InvalidSpirv)?;
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => {
+                Ok(ShaderModule::Metal(MetalShaderModule::new(
+                    device, desc, &code, reflection,
+                )?))
+            }
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => {
+                Ok(ShaderModule::Vulkan(VulkanShaderModule::new(
+                    device, desc, &code, reflection,
+                )?))
+            }
+            #[cfg(feature = "null")]
+            Device::Null(device) => {
+                Ok(ShaderModule::Null(NullShaderModule::new(
+                    device, desc, &code, reflection,
+                )?))
+            }
+        }
+    }
+
+    #[inline]
+    pub fn create_pipeline_layout(
+        &self,
+        desc: &PipelineLayoutDesc,
+    ) -> Result<PipelineLayout, Error> {
+        if desc.bindless_set.is_some() {
+            self.validate_bindless()?;
+        }
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => {
+                Ok(PipelineLayout::Metal(MetalPipelineLayout::new(
+                    device, desc,
+                )?))
+            }
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => {
+                Ok(PipelineLayout::Vulkan(VulkanPipelineLayout::new(
+                    device, desc,
+                )?))
+            }
+            #[cfg(feature = "null")]
+            Device::Null(device) => {
+                Ok(PipelineLayout::Null(NullPipelineLayout::new(device, desc)?))
+            }
+        }
+    }
+
+    /// Returns the layout for `desc.entries`, identical entries share one backend layout with
+    /// each other and with the sets of pipeline layouts.
+    #[inline]
+    pub fn create_bind_group_layout(
+        &self,
+        desc: &BindGroupLayoutDesc,
+    ) -> Result<BindGroupLayout, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => {
+                Ok(BindGroupLayout::Metal(MetalBindGroupLayout::new(
+                    device,
+                    desc.entries,
+                )?))
+            }
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => {
+                Ok(BindGroupLayout::Vulkan(VulkanBindGroupLayout::new(
+                    device,
+                    desc.entries,
+                )?))
+            }
+            #[cfg(feature = "null")]
+            Device::Null(device) => {
+                Ok(BindGroupLayout::Null(NullBindGroupLayout::new(
+                    device,
+                    desc.entries,
+                )?))
+            }
+        }
+    }
+
+    #[inline]
+    pub fn create_bind_group_pool(&self, desc: &BindGroupPoolDesc) -> Result<BindGroupPool, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => {
+                Ok(BindGroupPool::Metal(MetalBindGroupPool::new(device, desc)?))
+            }
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => {
+                Ok(BindGroupPool::Vulkan(VulkanBindGroupPool::new(
+                    device, desc,
+                )?))
+            }
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(BindGroupPool::Null(NullBindGroupPool::new(device, desc)?)),
+        }
+    }
+
+    /// Returns a sampler for `desc`, the same one for every identical descriptor since devices
+    /// limit the number of live samplers.
+    #[inline]
+    pub fn create_sampler(&self, desc: &SamplerDesc) -> Result<Sampler, Error> {
+        let mut required_features = PhysicalDeviceFeatures::empty();
+        if desc.max_anisotropy > 1 {
+            required_features |= PhysicalDeviceFeatures::SAMPLER_ANISOTROPY;
+        }
+        if desc.reduction_mode != SamplerReductionMode::WeightedAverage {
+            required_features |= PhysicalDeviceFeatures::SAMPLER_FILTER_MINMAX;
+        }
+
+        let missing_features = required_features.difference(self.get_enabled_features());
+        if !missing_features.is_empty() {
+            return Err(Error::MissingFeatures(missing_features));
+        }
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(Sampler::Metal(MetalSampler::new(device, desc)?)),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(Sampler::Vulkan(VulkanSampler::new(device, desc)?)),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(Sampler::Null(NullSampler::new(device, desc)?)),
+        }
+    }
+
+    #[inline]
+    pub fn create_query_pool(&self, desc: &QueryPoolDesc) -> Result<QueryPool, Error> {
+        if desc.count == 0 {
+            return Err(Error::InvalidQueryPool(
+                "Query count must not be zero".to_owned(),
+            ));
+        }
+        if let QueryType::PipelineStatistics(flags) = desc.query_type {
+            if flags.is_empty() {
+                return Err(Error::InvalidQueryPool(
+                    "Pipeline statistics queries require at least one counter".to_owned(),
+                ));
+            }
+            if !self
+                .get_enabled_features()
+                .contains(PhysicalDeviceFeatures::PIPELINE_STATISTICS_QUERY)
+            {
+                return Err(Error::MissingFeatures(
+                    PhysicalDeviceFeatures::PIPELINE_STATISTICS_QUERY,
+                ));
+            }
+        }
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(QueryPool::Metal(MetalQueryPool::new(device, desc)?)),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(QueryPool::Vulkan(VulkanQueryPool::new(device, desc)?)),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(QueryPool::Null(NullQueryPool::new(device, desc)?)),
+        }
+    }
+
+    /// Creates a profiler timing [`CommandList::profile_scope`] scopes with timestamp queries.
+    #[inline]
+    pub fn create_gpu_profiler(&self, desc: &GpuProfilerDesc) -> Result<GpuProfiler, Error> {
+        GpuProfiler::new(self, desc)
+    }
+
+    #[inline]
+    pub fn create_compute_pipeline(
+        &self,
+        desc: &ComputePipelineDesc,
+    ) -> Result<ComputePipeline, Error> {
+        let Some(entry_point) = desc
+            .shader_module
+            .reflection()
+            .entry_point(desc.entry_point)
+            .filter(|entry_point| entry_point.stage == ShaderStageFlags::COMPUTE)
+        else {
+            return Err(Error::InvalidEntryPoint(
+                desc.entry_point.to_owned(),
+                ShaderStageFlags::COMPUTE,
+            ));
+        };
+
+        let workgroup_size = entry_point.workgroup_size.unwrap_or([1, 1, 1]);
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => {
+                Ok(ComputePipeline::Metal(MetalComputePipeline::new(
+                    device,
+                    desc,
+                    workgroup_size,
+                )?))
+            }
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => {
+                Ok(ComputePipeline::Vulkan(VulkanComputePipeline::new(
+                    device,
+                    desc,
+                    workgroup_size,
+                )?))
+            }
+            #[cfg(feature = "null")]
+            Device::Null(device) => {
+                Ok(ComputePipeline::Null(NullComputePipeline::new(
+                    device,
+                    desc,
+                    workgroup_size,
+                )?))
+            }
+        }
+    }
+
+    #[inline]
+    pub fn create_graphics_pipeline(
+        &self,
+        desc: &GraphicsPipelineDesc,
+    ) -> Result<GraphicsPipeline, Error> {
+        validate_entry_points(&[
+            (Some(&desc.vertex_shader), ShaderStageFlags::VERTEX),
+            (desc.fragment_shader.as_ref(), ShaderStageFlags::FRAGMENT),
+        ])?;
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => {
+                Ok(GraphicsPipeline::Metal(MetalGraphicsPipeline::new(
+                    device, desc,
+                )?))
+            }
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => {
+                Ok(GraphicsPipeline::Vulkan(VulkanGraphicsPipeline::new(
+                    device, desc,
+                )?))
+            }
+            #[cfg(feature = "null")]
+            Device::Null(device) => {
+                Ok(GraphicsPipeline::Null(NullGraphicsPipeline::new(
+                    device, desc,
+                )?))
+            }
+        }
+    }
+
+    #[inline]
+    pub fn create_mesh_pipeline(&self, desc: &MeshPipelineDesc) -> Result<GraphicsPipeline, Error> {
+        if !self
+            .get_enabled_features()
+            .contains(PhysicalDeviceFeatures::MESH_SHADER)
+        {
+            return Err(Error::MissingFeatures(PhysicalDeviceFeatures::MESH_SHADER));
+        }
+
+        validate_entry_points(&[
+            (desc.task_shader.as_ref(), ShaderStageFlags::TASK),
+            (Some(&desc.mesh_shader), ShaderStageFlags::MESH),
+            (desc.fragment_shader.as_ref(), ShaderStageFlags::FRAGMENT),
+        ])?;
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => {
+                Ok(GraphicsPipeline::Metal(MetalGraphicsPipeline::new_mesh(
+                    device, desc,
+                )?))
+            }
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => {
+                Ok(GraphicsPipeline::Vulkan(VulkanGraphicsPipeline::new_mesh(
+                    device, desc,
+                )?))
+            }
+            #[cfg(feature = "null")]
+            Device::Null(device) => {
+                Ok(GraphicsPipeline::Null(NullGraphicsPipeline::new_mesh(
+                    device, desc,
+                )?))
+            }
+        }
+    }
+
+    /// Registers an untracked storage buffer in the bindless heap and returns its index into the
+    /// [`BINDLESS_STORAGE_BUFFER_BINDING`](crate::BINDLESS_STORAGE_BUFFER_BINDING) array.
+    ///
+    /// Requires [`PhysicalDeviceFeatures::DESCRIPTOR_INDEXING`]. The index stays valid until it
+    /// is released, shaders see the heap through the layout's
+    /// [`bindless_set`](PipelineLayoutDesc::bindless_set).
+    #[inline]
+    pub fn register_bindless_buffer(&self, buffer: &Buffer) -> Result<u32, Error> {
+        self.validate_bindless()?;
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(MetalBindlessHeap::register_buffer(device, buffer)?),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(VulkanBindlessHeap::register_buffer(device, buffer)?),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(NullBindlessHeap::register_buffer(device, buffer)?),
+        }
+    }
+
+    /// Registers a view of an untracked texture in the bindless heap and returns its index into
+    /// the [`BINDLESS_SAMPLED_TEXTURE_BINDING`](crate::BINDLESS_SAMPLED_TEXTURE_BINDING) and
+    /// [`BINDLESS_STORAGE_TEXTURE_BINDING`](crate::BINDLESS_STORAGE_TEXTURE_BINDING) arrays,
+    /// whichever the texture usage allows.
+    #[inline]
+    pub fn register_bindless_texture_view(&self, view: &TextureView) -> Result<u32, Error> {
+        self.validate_bindless()?;
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(MetalBindlessHeap::register_texture_view(device, view)?),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(VulkanBindlessHeap::register_texture_view(device, view)?),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(NullBindlessHeap::register_texture_view(device, view)?),
+        }
+    }
+
+    /// Releases a bindless index, the resource must stay alive until work already submitted no
+    /// longer uses it.
+    ///
+    /// The index is handed out again once every queue completed the work submitted before the
+    /// release, so it can be released right after the last submission that reads it.
+    #[inline]
+    pub fn release_bindless_index(&self, index: u32) -> Result<(), Error> {
+        self.validate_bindless()?;
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(MetalBindlessHeap::release(device, index)?),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(VulkanBindlessHeap::release(device, index)?),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(NullBindlessHeap::release(device, index)?),
+        }
+    }
+
+    fn validate_bindless(&self) -> Result<(), Error> {
+        if !self
+            .get_enabled_features()
+            .contains(PhysicalDeviceFeatures::DESCRIPTOR_INDEXING)
+        {
+            return Err(Error::MissingFeatures(
+                PhysicalDeviceFeatures::DESCRIPTOR_INDEXING,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Copies `upload.data` into the buffer on the transfer queue and returns the upload
+    /// timeline value reached once the copy has completed.
+    ///
+    /// The data is staged in host visible memory that is recycled once the copy has completed.
+    /// Submissions using the buffer wait for the value through
+    /// [`SubmitDesc::wait_upload`](crate::SubmitDesc::wait_upload), the CPU through
+    /// [`Self::wait_for_upload`]. Requires [`PhysicalDeviceFeatures::TIMELINE_SEMAPHORE`].
+    #[inline]
+    pub fn upload_buffer(&self, upload: &BufferUpload) -> Result<u64, Error> {
+        validate_buffer_range(
+            upload.buffer,
+            upload.offset,
+            upload.data.len() as u64,
+            BufferUsageFlags::TRANSFER_DST,
+        )?;
+        self.validate_uploads()?;
+
+        if upload.data.is_empty() {
+            return self.get_completed_upload_value();
+        }
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(MetalUploader::upload_buffer(device, upload)?),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(VulkanUploader::upload_buffer(device, upload)?),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(NullUploader::upload_buffer(device, upload)?),
+        }
+    }
+
+    /// Copies `upload.data` into the texture region on the transfer queue and returns the
+    /// upload timeline value reached once the copy has completed, see [`Self::upload_buffer`].
+    #[inline]
+    pub fn upload_texture(&self, upload: &TextureUpload) -> Result<u64, Error> {
+        let texture = upload.texture;
+
+        validate_texture_region(
+            texture,
+            upload.mip_level,
+            upload.array_layer,
+            upload.origin,
+            upload.extent,
+            TextureUsageFlags::TRANSFER_DST,
+        )?;
+
+        let size = packed_texture_size(texture.format(), upload.extent);
+        if upload.data.len() as u64 != size {
+            return Err(Error::InvalidCopy(format!(
+                "Upload of {} bytes to a region of {size} bytes",
+                upload.data.len()
+            )));
+        }
+
+        self.validate_uploads()?;
+
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(MetalUploader::upload_texture(device, upload)?),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(VulkanUploader::upload_texture(device, upload)?),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(NullUploader::upload_texture(device, upload)?),
+        }
+    }
+
+    /// Returns the upload timeline value of the last completed upload.
+    #[inline]
+    pub fn get_completed_upload_value(&self) -> Result<u64, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(MetalUploader::completed_value(device)),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(VulkanUploader::completed_value(device)?),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(NullUploader::completed_value(device)),
+        }
+    }
+
+    /// Blocks until the upload timeline reached `value`, returns `false` if `timeout` elapsed
+    /// first.
+    ///
+    /// A `timeout` of `None` waits indefinitely.
+    #[inline]
+    pub fn wait_for_upload(&self, value: u64, timeout: Option<Duration>) -> Result<bool, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(MetalUploader::wait(device, value, timeout)),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => Ok(VulkanUploader::wait(device, value, timeout)?),
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(NullUploader::wait(device, value, timeout)?),
+        }
+    }
+
+    fn validate_uploads(&self) -> Result<(), Error> {
+        if !self
+            .get_enabled_features()
+            .contains(PhysicalDeviceFeatures::TIMELINE_SEMAPHORE)
+        {
+            return Err(Error::MissingFeatures(
+                PhysicalDeviceFeatures::TIMELINE_SEMAPHORE,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Copies a buffer range into readback memory on `read.queue_type` and returns the pending
+    /// [`Readback`] holding the bytes.
+    ///
+    /// Submissions writing the buffer must have completed, e.g. by waiting for their fence.
+    pub fn read_buffer(&self, read: &BufferRead) -> Result<Readback, Error> {
+        validate_buffer_range(
+            read.buffer,
+            read.offset,
+            read.size,
+            BufferUsageFlags::TRANSFER_SRC,
+        )?;
+        if read.size == 0 {
+            return Err(Error::InvalidCopy(
+                "Readback size must not be zero".to_owned(),
+            ));
+        }
+
+        let size = read.size as usize;
+
+        self.read(read.queue_type, read.size, size, size, |cmd, dst| {
+            cmd.copy_buffer(read.buffer, read.offset, dst, 0, read.size)
+        })
+    }
+
+    /// Copies a texture region into readback memory on `read.queue_type` and returns the pending
+    /// [`Readback`] holding the bytes, see [`Self::read_buffer`].
+    ///
+    /// The rows are copied with a padded pitch and returned tightly packed.
+    pub fn read_texture(&self, read: &TextureRead) -> Result<Readback, Error> {
+        let texture = read.texture;
+        let format = texture.format();
+
+        validate_texture_region(
+            texture,
+            read.mip_level,
+            read.array_layer,
+            read.origin,
+            read.extent,
+            TextureUsageFlags::TRANSFER_SRC,
+        )?;
+
+        let (block_width, block_height) = format.block_extent();
+        let row_size = read.extent.width.div_ceil(block_width) * format.block_size();
+        let padded_row_size = row_size
+            .next_multiple_of(READBACK_ROW_PITCH_ALIGNMENT)
+            .next_multiple_of(format.block_size());
+        let row_count = read.extent.height.div_ceil(block_height) * read.extent.depth;
+
+        let region = BufferTextureCopy {
+            buffer_offset: 0,
+            bytes_per_row: padded_row_size,
+            rows_per_image: 0,
+            mip_level: read.mip_level,
+            array_layer: read.array_layer,
+            origin: read.origin,
+            extent: read.extent,
+        };
+
+        self.read(
+            read.queue_type,
+            u64::from(padded_row_size) * u64::from(row_count),
+            padded_row_size as usize,
+            row_size as usize,
+            |cmd, dst| cmd.copy_texture_to_buffer(texture, dst, &region),
+        )
+    }
+
+    /// Records the copy into a new readback buffer of `size` bytes with `record` and submits it.
+    fn read(
+        &self,
+        queue_type: QueueType,
+        size: u64,
+        padded_row_size: usize,
+        row_size: usize,
+        record: impl FnOnce(&mut CommandList, &Buffer),
+    ) -> Result<Readback, Error> {
+        let buffer = self.create_buffer(&BufferDesc {
+            size,
+            usage: BufferUsageFlags::TRANSFER_DST,
+            memory_location: MemoryLocation::Readback,
+            tracked: false,
+            label: Some("Readback"),
+        })?;
+        let fence = self.create_fence(&FenceDesc::default())?;
+
+        let mut command_list = self.create_command_list(&CommandListDesc {
+            queue_type,
+            label: Some("Readback"),
+        })?;
+        command_list.begin()?;
+        record(&mut command_list, &buffer);
+        command_list.end()?;
+
+        self.get_queue(queue_type).submit(&SubmitDesc {
+            command_lists: &[&command_list],
+            fence: Some(&fence),
+            ..Default::default()
+        })?;
+
+        Ok(Readback::new(
+            buffer,
+            fence,
+            command_list,
+            padded_row_size,
+            row_size,
+        ))
+    }
+
+    /// Blocks until every semaphore reached its value, returns `false` if `timeout` elapsed first.
+    ///
+    /// A `timeout` of `None` waits indefinitely.
+    #[inline]
+    pub fn wait_for_values(
+        &self,
+        values: &[SemaphoreValue],
+        timeout: Option<Duration>,
+    ) -> Result<bool, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Device::Metal(device) => Ok(MetalSemaphore::wait_for_values(device, values, timeout)?),
+            #[cfg(feature = "vulkan")]
+            Device::Vulkan(device) => {
+                Ok(VulkanSemaphore::wait_for_values(device, values, timeout)?)
+            }
+            #[cfg(feature = "null")]
+            Device::Null(device) => Ok(NullSemaphore::wait_for_values(device, values, timeout)?),
+        }
+    }
+}
+
+/// Checks that every given shader stage names an entry point of the expected stage.
+fn validate_entry_points(stages: &[(Option<&ShaderStage>, ShaderStageFlags)]) -> Result<(), Error> {
+    for &(shader_stage, stage) in stages {
+        let Some(shader_stage) = shader_stage else {
+            continue;
+        };
+
+        if !shader_stage
+            .shader_module
+            .reflection()
+            .entry_point(shader_stage.entry_point)
+            .is_some_and(|entry_point| entry_point.stage == stage)
+        {
+            return Err(Error::InvalidEntryPoint(
+                shader_stage.entry_point.to_owned(),
+                stage,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns an error unless `buffer` has `usage` and `size` bytes at `offset`.
+fn validate_buffer_range(
+    buffer: &Buffer,
+    offset: u64,
+    size: u64,
+    usage: BufferUsageFlags,
+) -> Result<(), Error> {
+    if !buffer.usage().contains(usage) {
+        return Err(Error::InvalidCopy(format!(
+            "Buffer usage {:?} does not contain {usage:?}",
+            buffer.usage()
+        )));
+    }
+    if offset
+        .checked_add(size)
+        .is_none_or(|end| end > buffer.size())
+    {
+        return Err(Error::InvalidCopy(format!(
+            "Range of {size} bytes at {offset} exceeds the buffer size of {}",
+            buffer.size()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns an error unless `texture` has `usage` and the region lies within the mip level.
+fn validate_texture_region(
+    texture: &Texture,
+    mip_level: u32,
+    array_layer: u32,
+    origin: Origin3d,
+    extent: Extent3d,
+    usage: TextureUsageFlags,
+) -> Result<(), Error> {
+    if !texture.usage().contains(usage) {
+        return Err(Error::InvalidCopy(format!(
+            "Texture usage {:?} does not contain {usage:?}",
+            texture.usage()
+        )));
+    }
+    if mip_level >= texture.mip_levels() || array_layer >= texture.array_layers() {
+        return Err(Error::InvalidCopy(format!(
+            "Mip level {mip_level} and array layer {array_layer} are outside of the texture"
+        )));
+    }
+
+    let mip_extent = texture.extent().mip_level(mip_level);
+    if extent.width == 0 || extent.height == 0 || extent.depth == 0 {
+        return Err(Error::InvalidCopy(
+            "Region extent must not be zero".to_owned(),
+        ));
+    }
+    if origin
+        .x
+        .checked_add(extent.width)
+        .is_none_or(|end| end > mip_extent.width)
+        || origin
+            .y
+            .checked_add(extent.height)
+            .is_none_or(|end| end > mip_extent.height)
+        || origin
+            .z
+            .checked_add(extent.depth)
+            .is_none_or(|end| end > mip_extent.depth)
+    {
+        return Err(Error::InvalidCopy(format!(
+            "Region {origin:?} {extent:?} exceeds the mip level extent {mip_extent:?}"
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(all(test, feature = "null"))]
+mod tests {
+    use super::*;
+    use crate::{
+        null::{
+            test_utils::{create_device, create_instance, null_device},
+            NullError,
+        },
+        BackendType, Instance, InstanceDesc,
+    };
+
+    #[test]
+    fn missing_required_features_fail_device_creation() {
+        let instance = create_instance(PhysicalDeviceFeatures::TIMELINE_SEMAPHORE);
+
+        let result = instance.create_device(&DeviceDesc {
+            physical_device: instance.get_physical_devices()[0].clone(),
+            required_features: PhysicalDeviceFeatures::TIMELINE_SEMAPHORE
+                | PhysicalDeviceFeatures::MESH_SHADER,
+            optional_features: PhysicalDeviceFeatures::empty(),
+        });
+
+        assert!(matches!(
+            result,
+            Err(Error::MissingFeatures(missing)) if missing == PhysicalDeviceFeatures::MESH_SHADER
+        ));
+    }
+
+    #[test]
+    fn unsupported_optional_features_are_skipped() {
+        let instance = create_instance(PhysicalDeviceFeatures::TIMELINE_SEMAPHORE);
+
+        let device = instance
+            .create_device(&DeviceDesc {
+                physical_device: instance.get_physical_devices()[0].clone(),
+                required_features: PhysicalDeviceFeatures::empty(),
+                optional_features: PhysicalDeviceFeatures::TIMELINE_SEMAPHORE
+                    | PhysicalDeviceFeatures::MESH_SHADER,
+            })
+            .unwrap();
+
+        assert_eq!(
+            device.get_enabled_features(),
+            PhysicalDeviceFeatures::TIMELINE_SEMAPHORE
+        );
+    }
+
+    #[test]
+    fn dropped_objects_are_untracked() {
+        let device = create_device(PhysicalDeviceFeatures::all());
+        let live_objects = null_device(&device).live_object_count();
+
+        let buffer = device
+            .create_buffer(&BufferDesc {
+                size: 256,
+                usage: BufferUsageFlags::STORAGE,
+                ..Default::default()
+            })
+            .unwrap();
+        let buffer_clone = buffer.clone();
+
+        assert_eq!(null_device(&device).live_object_count_of("Buffer"), 1);
+
+        drop(buffer);
+        assert_eq!(null_device(&device).live_object_count_of("Buffer"), 1);
+
+        drop(buffer_clone);
+        assert_eq!(null_device(&device).live_object_count_of("Buffer"), 0);
+        assert_eq!(null_device(&device).live_object_count(), live_objects);
+    }
+
+    #[test]
+    fn invalid_descriptors_fail_validation() {
+        let device = create_device(PhysicalDeviceFeatures::empty());
+
+        let result = device.create_buffer(&BufferDesc {
+            size: 0,
+            usage: BufferUsageFlags::STORAGE,
+            ..Default::default()
+        });
+        assert!(matches!(
+            result,
+            Err(Error::NullBackend(NullError::Validation(_)))
+        ));
+
+        let result = device.create_buffer(&BufferDesc {
+            size: 256,
+            usage: BufferUsageFlags::STORAGE | BufferUsageFlags::DEVICE_ADDRESS,
+            ..Default::default()
+        });
+        assert!(matches!(
+            result,
+            Err(Error::NullBackend(NullError::Validation(_)))
+        ));
+
+        let result = device.create_texture(&TextureDesc {
+            usage: TextureUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+            ..Default::default()
+        });
+        assert!(matches!(
+            result,
+            Err(Error::NullBackend(NullError::Validation(_)))
+        ));
+
+        assert_eq!(null_device(&device).live_object_count(), 0);
+    }
+
+    #[test]
+    fn invalid_null_physical_device_fails_instance_creation() {
+        let mut desc = InstanceDesc {
+            backend_type: BackendType::Null,
+            ..Default::default()
+        };
+        desc.null_physical_device.name.clear();
+
+        assert!(matches!(
+            unsafe { Instance::new(&desc) },
+            Err(Error::NullBackend(NullError::Validation(_)))
+        ));
+    }
 }
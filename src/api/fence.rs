@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+#[cfg(feature = "metal")]
+use crate::metal::MetalFence;
+#[cfg(feature = "null")]
+use crate::null::NullFence;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanFence;
+use crate::Error;
+
+#[derive(Clone, Debug, Default)]
+pub struct FenceDesc<'a> {
+    /// Creates the fence in the signaled state.
+    pub signaled: bool,
+    pub label: Option<&'a str>,
+}
+
+#[derive(Clone)]
+pub enum Fence {
+    #[cfg(feature = "metal")]
+    Metal(MetalFence),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanFence),
+    #[cfg(feature = "null")]
+    Null(NullFence),
+}
+
+impl Fence {
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalFence {
+        match self {
+            Fence::Metal(fence) => fence,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Fence belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanFence {
+        match self {
+            Fence::Vulkan(fence) => fence,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Fence belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullFence {
+        match self {
+            Fence::Null(fence) => fence,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Fence belongs to a different backend"),
+        }
+    }
+
+    #[inline]
+    pub fn is_signaled(&self) -> Result<bool, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Fence::Metal(fence) => Ok(fence.is_signaled()?),
+            #[cfg(feature = "vulkan")]
+            Fence::Vulkan(fence) => Ok(fence.is_signaled()?),
+            #[cfg(feature = "null")]
+            Fence::Null(fence) => Ok(fence.is_signaled()?),
+        }
+    }
+
+    /// Blocks until the fence is signaled, returns `false` if `timeout` elapsed first.
+    ///
+    /// A `timeout` of `None` waits indefinitely.
+    #[inline]
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<bool, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Fence::Metal(fence) => Ok(fence.wait(timeout)?),
+            #[cfg(feature = "vulkan")]
+            Fence::Vulkan(fence) => Ok(fence.wait(timeout)?),
+            #[cfg(feature = "null")]
+            Fence::Null(fence) => Ok(fence.wait(timeout)?),
+        }
+    }
+
+    /// Returns the fence to the unsignaled state so it can be passed to another submission.
+    #[inline]
+    pub fn reset(&self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Fence::Metal(fence) => Ok(fence.reset()?),
+            #[cfg(feature = "vulkan")]
+            Fence::Vulkan(fence) => Ok(fence.reset()?),
+            #[cfg(feature = "null")]
+            Fence::Null(fence) => Ok(fence.reset()?),
+        }
+    }
+}
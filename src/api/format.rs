@@ -0,0 +1,194 @@
+/// Texel format shared by all backends.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    R8Unorm,
+    R8Snorm,
+    R8Uint,
+    R8Sint,
+    Rg8Unorm,
+    Rg8Snorm,
+    Rg8Uint,
+    Rg8Sint,
+    Rgba8Unorm,
+    Rgba8UnormSrgb,
+    Rgba8Snorm,
+    Rgba8Uint,
+    Rgba8Sint,
+    Bgra8Unorm,
+    Bgra8UnormSrgb,
+    R16Unorm,
+    R16Snorm,
+    R16Uint,
+    R16Sint,
+    R16Float,
+    Rg16Unorm,
+    Rg16Snorm,
+    Rg16Uint,
+    Rg16Sint,
+    Rg16Float,
+    Rgba16Unorm,
+    Rgba16Snorm,
+    Rgba16Uint,
+    Rgba16Sint,
+    Rgba16Float,
+    R32Uint,
+    R32Sint,
+    R32Float,
+    Rg32Uint,
+    Rg32Sint,
+    Rg32Float,
+    Rgba32Uint,
+    Rgba32Sint,
+    Rgba32Float,
+    Rgb10a2Unorm,
+    Rgb10a2Uint,
+    Rg11b10Float,
+    Rgb9e5Float,
+    D16Unorm,
+    D32Float,
+    S8Uint,
+    D24UnormS8Uint,
+    D32FloatS8Uint,
+    Bc1RgbaUnorm,
+    Bc1RgbaUnormSrgb,
+    Bc2RgbaUnorm,
+    Bc2RgbaUnormSrgb,
+    Bc3RgbaUnorm,
+    Bc3RgbaUnormSrgb,
+    Bc4RUnorm,
+    Bc4RSnorm,
+    Bc5RgUnorm,
+    Bc5RgSnorm,
+    Bc6hRgbUfloat,
+    Bc6hRgbFloat,
+    Bc7RgbaUnorm,
+    Bc7RgbaUnormSrgb,
+}
+
+impl Format {
+    #[inline]
+    pub fn is_depth(&self) -> bool {
+        matches!(
+            self,
+            Self::D16Unorm | Self::D32Float | Self::D24UnormS8Uint | Self::D32FloatS8Uint
+        )
+    }
+
+    #[inline]
+    pub fn is_stencil(&self) -> bool {
+        matches!(
+            self,
+            Self::S8Uint | Self::D24UnormS8Uint | Self::D32FloatS8Uint
+        )
+    }
+
+    #[inline]
+    pub fn is_depth_stencil(&self) -> bool {
+        self.is_depth() || self.is_stencil()
+    }
+
+    #[inline]
+    pub fn is_srgb(&self) -> bool {
+        matches!(
+            self,
+            Self::Rgba8UnormSrgb
+                | Self::Bgra8UnormSrgb
+                | Self::Bc1RgbaUnormSrgb
+                | Self::Bc2RgbaUnormSrgb
+                | Self::Bc3RgbaUnormSrgb
+                | Self::Bc7RgbaUnormSrgb
+        )
+    }
+
+    #[inline]
+    pub fn is_compressed(&self) -> bool {
+        self.block_extent() != (1, 1)
+    }
+
+    /// Returns the width and height in texels of a single block.
+    #[inline]
+    pub fn block_extent(&self) -> (u32, u32) {
+        match self {
+            Self::Bc1RgbaUnorm
+            | Self::Bc1RgbaUnormSrgb
+            | Self::Bc2RgbaUnorm
+            | Self::Bc2RgbaUnormSrgb
+            | Self::Bc3RgbaUnorm
+            | Self::Bc3RgbaUnormSrgb
+            | Self::Bc4RUnorm
+            | Self::Bc4RSnorm
+            | Self::Bc5RgUnorm
+            | Self::Bc5RgSnorm
+            | Self::Bc6hRgbUfloat
+            | Self::Bc6hRgbFloat
+            | Self::Bc7RgbaUnorm
+            | Self::Bc7RgbaUnormSrgb => (4, 4),
+            _ => (1, 1),
+        }
+    }
+
+    /// Returns the size in bytes of a single block, which is one texel for uncompressed formats.
+    #[inline]
+    pub fn block_size(&self) -> u32 {
+        match self {
+            Self::R8Unorm | Self::R8Snorm | Self::R8Uint | Self::R8Sint | Self::S8Uint => 1,
+            Self::Rg8Unorm
+            | Self::Rg8Snorm
+            | Self::Rg8Uint
+            | Self::Rg8Sint
+            | Self::R16Unorm
+            | Self::R16Snorm
+            | Self::R16Uint
+            | Self::R16Sint
+            | Self::R16Float
+            | Self::D16Unorm => 2,
+            Self::Rgba8Unorm
+            | Self::Rgba8UnormSrgb
+            | Self::Rgba8Snorm
+            | Self::Rgba8Uint
+            | Self::Rgba8Sint
+            | Self::Bgra8Unorm
+            | Self::Bgra8UnormSrgb
+            | Self::Rg16Unorm
+            | Self::Rg16Snorm
+            | Self::Rg16Uint
+            | Self::Rg16Sint
+            | Self::Rg16Float
+            | Self::R32Uint
+            | Self::R32Sint
+            | Self::R32Float
+            | Self::Rgb10a2Unorm
+            | Self::Rgb10a2Uint
+            | Self::Rg11b10Float
+            | Self::Rgb9e5Float
+            | Self::D32Float
+            | Self::D24UnormS8Uint => 4,
+            Self::Rgba16Unorm
+            | Self::Rgba16Snorm
+            | Self::Rgba16Uint
+            | Self::Rgba16Sint
+            | Self::Rgba16Float
+            | Self::Rg32Uint
+            | Self::Rg32Sint
+            | Self::Rg32Float
+            | Self::D32FloatS8Uint
+            | Self::Bc1RgbaUnorm
+            | Self::Bc1RgbaUnormSrgb
+            | Self::Bc4RUnorm
+            | Self::Bc4RSnorm => 8,
+            Self::Rgba32Uint
+            | Self::Rgba32Sint
+            | Self::Rgba32Float
+            | Self::Bc2RgbaUnorm
+            | Self::Bc2RgbaUnormSrgb
+            | Self::Bc3RgbaUnorm
+            | Self::Bc3RgbaUnormSrgb
+            | Self::Bc5RgUnorm
+            | Self::Bc5RgSnorm
+            | Self::Bc6hRgbUfloat
+            | Self::Bc6hRgbFloat
+            | Self::Bc7RgbaUnorm
+            | Self::Bc7RgbaUnormSrgb => 16,
+        }
+    }
+}
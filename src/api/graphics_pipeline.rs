@@ -0,0 +1,450 @@
+use std::hash::{Hash, Hasher};
+
+use bitflags::bitflags;
+
+#[cfg(feature = "metal")]
+use crate::metal::MetalGraphicsPipeline;
+#[cfg(feature = "null")]
+use crate::null::NullGraphicsPipeline;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanGraphicsPipeline;
+use crate::{Format, PipelineLayout, ShaderModule, SpecializationConstant};
+
+/// An entry point of a shader module together with its specialization.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ShaderStage<'a> {
+    pub shader_module: &'a ShaderModule,
+    pub entry_point: &'a str,
+    pub specialization_constants: &'a [SpecializationConstant],
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VertexFormat {
+    Uint8x4,
+    Sint8x4,
+    Unorm8x4,
+    Snorm8x4,
+    Uint16x2,
+    Uint16x4,
+    Sint16x2,
+    Sint16x4,
+    Unorm16x2,
+    Unorm16x4,
+    Snorm16x2,
+    Snorm16x4,
+    Float16x2,
+    Float16x4,
+    Uint32,
+    Uint32x2,
+    Uint32x3,
+    Uint32x4,
+    Sint32,
+    Sint32x2,
+    Sint32x3,
+    Sint32x4,
+    Float32,
+    Float32x2,
+    Float32x3,
+    Float32x4,
+}
+
+impl VertexFormat {
+    /// Returns the size of one attribute in bytes.
+    #[inline]
+    pub fn size(&self) -> u32 {
+        match self {
+            Self::Uint8x4 | Self::Sint8x4 | Self::Unorm8x4 | Self::Snorm8x4 => 4,
+            Self::Uint16x2 | Self::Sint16x2 | Self::Unorm16x2 | Self::Snorm16x2 => 4,
+            Self::Float16x2 => 4,
+            Self::Uint16x4 | Self::Sint16x4 | Self::Unorm16x4 | Self::Snorm16x4 => 8,
+            Self::Float16x4 => 8,
+            Self::Uint32 | Self::Sint32 | Self::Float32 => 4,
+            Self::Uint32x2 | Self::Sint32x2 | Self::Float32x2 => 8,
+            Self::Uint32x3 | Self::Sint32x3 | Self::Float32x3 => 12,
+            Self::Uint32x4 | Self::Sint32x4 | Self::Float32x4 => 16,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum VertexStepMode {
+    #[default]
+    Vertex,
+    Instance,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VertexAttribute {
+    pub location: u32,
+    pub format: VertexFormat,
+    /// Offset from the start of a vertex in bytes.
+    pub offset: u32,
+}
+
+/// Layout of the vertex buffer bound to the slot matching its index in
+/// [`GraphicsPipelineDesc::vertex_buffers`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct VertexBufferLayout<'a> {
+    pub stride: u32,
+    pub step_mode: VertexStepMode,
+    pub attributes: &'a [VertexAttribute],
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PrimitiveTopology {
+    PointList,
+    LineList,
+    LineStrip,
+    #[default]
+    TriangleList,
+    TriangleStrip,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PolygonMode {
+    #[default]
+    Fill,
+    Line,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CullMode {
+    #[default]
+    None,
+    Front,
+    Back,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FrontFace {
+    #[default]
+    CounterClockwise,
+    Clockwise,
+}
+
+/// Depth bias applied to rasterized fragments, compared and hashed by the bits of its floats.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DepthBias {
+    pub constant: i32,
+    pub slope_scale: f32,
+    pub clamp: f32,
+}
+
+impl PartialEq for DepthBias {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.constant == other.constant
+            && self.slope_scale.to_bits() == other.slope_scale.to_bits()
+            && self.clamp.to_bits() == other.clamp.to_bits()
+    }
+}
+
+impl Eq for DepthBias {}
+
+impl Hash for DepthBias {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.constant.hash(state);
+        self.slope_scale.to_bits().hash(state);
+        self.clamp.to_bits().hash(state);
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RasterizerState {
+    pub polygon_mode: PolygonMode,
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+    /// Clamps depth instead of clipping primitives against the near and far planes.
+    pub depth_clamp: bool,
+    /// `None` disables depth bias.
+    pub depth_bias: Option<DepthBias>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CompareOp {
+    Never,
+    Less,
+    Equal,
+    LessEqual,
+    Greater,
+    NotEqual,
+    GreaterEqual,
+    #[default]
+    Always,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StencilOp {
+    #[default]
+    Keep,
+    Zero,
+    Replace,
+    IncrementClamp,
+    DecrementClamp,
+    Invert,
+    IncrementWrap,
+    DecrementWrap,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StencilFaceState {
+    pub fail_op: StencilOp,
+    pub depth_fail_op: StencilOp,
+    pub pass_op: StencilOp,
+    pub compare: CompareOp,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DepthStencilState {
+    pub depth_test: bool,
+    pub depth_write: bool,
+    pub depth_compare: CompareOp,
+    pub stencil_test: bool,
+    pub stencil_front: StencilFaceState,
+    pub stencil_back: StencilFaceState,
+    pub stencil_read_mask: u32,
+    pub stencil_write_mask: u32,
+}
+
+impl Default for DepthStencilState {
+    fn default() -> Self {
+        Self {
+            depth_test: false,
+            depth_write: false,
+            depth_compare: CompareOp::Always,
+            stencil_test: false,
+            stencil_front: StencilFaceState::default(),
+            stencil_back: StencilFaceState::default(),
+            stencil_read_mask: 0xff,
+            stencil_write_mask: 0xff,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MultisampleState {
+    pub sample_count: u32,
+    pub alpha_to_coverage: bool,
+}
+
+impl Default for MultisampleState {
+    fn default() -> Self {
+        Self {
+            sample_count: 1,
+            alpha_to_coverage: false,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    Src,
+    OneMinusSrc,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    Dst,
+    OneMinusDst,
+    DstAlpha,
+    OneMinusDstAlpha,
+    SrcAlphaSaturated,
+    Constant,
+    OneMinusConstant,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BlendOp {
+    #[default]
+    Add,
+    Subtract,
+    ReverseSubtract,
+    Min,
+    Max,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BlendComponent {
+    pub src_factor: BlendFactor,
+    pub dst_factor: BlendFactor,
+    pub operation: BlendOp,
+}
+
+impl BlendComponent {
+    pub const REPLACE: Self = Self {
+        src_factor: BlendFactor::One,
+        dst_factor: BlendFactor::Zero,
+        operation: BlendOp::Add,
+    };
+}
+
+impl Default for BlendComponent {
+    fn default() -> Self {
+        Self::REPLACE
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BlendState {
+    pub color: BlendComponent,
+    pub alpha: BlendComponent,
+}
+
+impl BlendState {
+    /// Standard `src * src_alpha + dst * (1 - src_alpha)` blending.
+    pub const ALPHA_BLENDING: Self = Self {
+        color: BlendComponent {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation: BlendOp::Add,
+        },
+        alpha: BlendComponent {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation: BlendOp::Add,
+        },
+    };
+}
+
+bitflags! {
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct ColorWriteFlags : u32 {
+        const RED = 1 << 0;
+        const GREEN = 1 << 1;
+        const BLUE = 1 << 2;
+        const ALPHA = 1 << 3;
+    }
+}
+
+impl Default for ColorWriteFlags {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ColorAttachmentState {
+    pub format: Format,
+    /// `None` disables blending.
+    pub blend: Option<BlendState>,
+    pub write_mask: ColorWriteFlags,
+}
+
+/// Describes a graphics pipeline rendering with dynamic rendering, attachments are given by
+/// format only.
+///
+/// Viewport, scissor, stencil reference and blend constants are dynamic state.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct GraphicsPipelineDesc<'a> {
+    pub vertex_shader: ShaderStage<'a>,
+    /// `None` renders depth and stencil only.
+    pub fragment_shader: Option<ShaderStage<'a>>,
+    pub layout: &'a PipelineLayout,
+    pub vertex_buffers: &'a [VertexBufferLayout<'a>],
+    pub primitive_topology: PrimitiveTopology,
+    pub rasterizer: RasterizerState,
+    pub depth_stencil: DepthStencilState,
+    pub multisample: MultisampleState,
+    pub color_attachments: &'a [ColorAttachmentState],
+    pub depth_stencil_format: Option<Format>,
+    pub label: Option<&'a str>,
+}
+
+/// Describes a graphics pipeline whose geometry is generated by task and mesh shaders instead of
+/// vertex input, requires [`PhysicalDeviceFeatures::MESH_SHADER`].
+///
+/// [`PhysicalDeviceFeatures::MESH_SHADER`]: crate::PhysicalDeviceFeatures::MESH_SHADER
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct MeshPipelineDesc<'a> {
+    /// `None` launches mesh shader workgroups directly from the draw.
+    pub task_shader: Option<ShaderStage<'a>>,
+    pub mesh_shader: ShaderStage<'a>,
+    /// `None` renders depth and stencil only.
+    pub fragment_shader: Option<ShaderStage<'a>>,
+    pub layout: &'a PipelineLayout,
+    pub rasterizer: RasterizerState,
+    pub depth_stencil: DepthStencilState,
+    pub multisample: MultisampleState,
+    pub color_attachments: &'a [ColorAttachmentState],
+    pub depth_stencil_format: Option<Format>,
+    pub label: Option<&'a str>,
+}
+
+/// The part of a graphics pipeline that does not depend on how geometry is generated.
+pub(crate) struct RasterStateDesc<'a> {
+    pub(crate) rasterizer: RasterizerState,
+    pub(crate) depth_stencil: DepthStencilState,
+    pub(crate) multisample: MultisampleState,
+    pub(crate) color_attachments: &'a [ColorAttachmentState],
+    pub(crate) depth_stencil_format: Option<Format>,
+}
+
+impl<'a> GraphicsPipelineDesc<'a> {
+    #[inline]
+    pub(crate) fn raster_state(&self) -> RasterStateDesc<'a> {
+        RasterStateDesc {
+            rasterizer: self.rasterizer,
+            depth_stencil: self.depth_stencil,
+            multisample: self.multisample,
+            color_attachments: self.color_attachments,
+            depth_stencil_format: self.depth_stencil_format,
+        }
+    }
+}
+
+impl<'a> MeshPipelineDesc<'a> {
+    #[inline]
+    pub(crate) fn raster_state(&self) -> RasterStateDesc<'a> {
+        RasterStateDesc {
+            rasterizer: self.rasterizer,
+            depth_stencil: self.depth_stencil,
+            multisample: self.multisample,
+            color_attachments: self.color_attachments,
+            depth_stencil_format: self.depth_stencil_format,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum GraphicsPipeline {
+    #[cfg(feature = "metal")]
+    Metal(MetalGraphicsPipeline),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanGraphicsPipeline),
+    #[cfg(feature = "null")]
+    Null(NullGraphicsPipeline),
+}
+
+impl GraphicsPipeline {
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalGraphicsPipeline {
+        match self {
+            GraphicsPipeline::Metal(graphics_pipeline) => graphics_pipeline,
+            #[allow(unreachable_patterns)]
+            _ => panic!("GraphicsPipeline belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanGraphicsPipeline {
+        match self {
+            GraphicsPipeline::Vulkan(graphics_pipeline) => graphics_pipeline,
+            #[allow(unreachable_patterns)]
+            _ => panic!("GraphicsPipeline belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullGraphicsPipeline {
+        match self {
+            GraphicsPipeline::Null(graphics_pipeline) => graphics_pipeline,
+            #[allow(unreachable_patterns)]
+            _ => panic!("GraphicsPipeline belongs to a different backend"),
+        }
+    }
+}
@@ -1,13 +1,12 @@
 use bitflags::bitflags;
 
 #[cfg(feature = "metal")]
-use crate::metal::MetalInstance;
+use crate::metal::{MetalDevice, MetalInstance};
+#[cfg(feature = "null")]
+use crate::null::{NullDevice, NullInstance, NullPhysicalDeviceDesc};
 #[cfg(feature = "vulkan")]
-use crate::vulkan::VulkanInstance;
-use crate::{
-    api::physical_device::PhysicalDevice, vulkan::VulkanDevice, Device, DeviceDesc, Error,
-};
-use crate::metal::MetalDevice;
+use crate::vulkan::{VulkanDevice, VulkanInstance};
+use crate::{api::physical_device::PhysicalDevice, Device, DeviceDesc, Error};
 
 bitflags! {
     #[repr(transparent)]
@@ -21,6 +20,7 @@ bitflags! {
 pub enum BackendType {
     Metal,
     Vulkan,
+    Null,
 }
 
 impl Default for BackendType {
@@ -30,7 +30,8 @@ impl Default for BackendType {
         } else if cfg!(all(feature = "vulkan", not(feature = "metal"))) {
             Self::Vulkan
         } else {
-            unimplemented!("Backend not implemented")
+            // Without any backend enabled `Instance::new` reports this as unsupported.
+            Self::Null
         }
     }
 }
@@ -39,6 +40,8 @@ impl Default for BackendType {
 pub struct InstanceDesc {
     pub flags: InstanceFlags,
     pub backend_type: BackendType,
+    #[cfg(feature = "null")]
+    pub null_physical_device: NullPhysicalDeviceDesc,
 }
 
 pub enum Instance {
@@ -46,9 +49,17 @@ pub enum Instance {
     Metal(MetalInstance),
     #[cfg(feature = "vulkan")]
     Vulkan(VulkanInstance),
+    #[cfg(feature = "null")]
+    Null(NullInstance),
 }
 
 impl Instance {
+    /// Creates an instance of `desc.backend_type`, failing with [`Error::UnsupportedBackend`]
+    /// if that backend was not compiled in.
+    ///
+    /// # Safety
+    ///
+    /// The Vulkan backend loads the Vulkan library at runtime, which runs arbitrary code.
     #[inline]
     pub unsafe fn new(desc: &InstanceDesc) -> Result<Self, Error> {
         match desc.backend_type {
@@ -56,7 +67,10 @@ impl Instance {
             BackendType::Metal => Ok(Self::Metal(MetalInstance::new(desc)?)),
             #[cfg(feature = "vulkan")]
             BackendType::Vulkan => Ok(Self::Vulkan(VulkanInstance::new(desc)?)),
-            _ => todo!(),
+            #[cfg(feature = "null")]
+            BackendType::Null => Ok(Self::Null(NullInstance::new(desc)?)),
+            #[allow(unreachable_patterns)]
+            backend_type => Err(Error::UnsupportedBackend(backend_type)),
         }
     }
 
@@ -67,16 +81,25 @@ impl Instance {
             Instance::Metal(instance) => instance.get_physical_devices(),
             #[cfg(feature = "vulkan")]
             Instance::Vulkan(instance) => instance.get_physical_devices(),
+            #[cfg(feature = "null")]
+            Instance::Null(instance) => instance.get_physical_devices(),
         }
     }
 
     #[inline]
     pub fn create_device(&self, desc: &DeviceDesc) -> Result<Device, Error> {
+        let missing_features = desc.missing_features();
+        if !missing_features.is_empty() {
+            return Err(Error::MissingFeatures(missing_features));
+        }
+
         match self {
             #[cfg(feature = "metal")]
             Instance::Metal(instance) => Ok(Device::Metal(MetalDevice::new(instance, desc)?)),
             #[cfg(feature = "vulkan")]
             Instance::Vulkan(instance) => Ok(Device::Vulkan(VulkanDevice::new(instance, desc)?)),
+            #[cfg(feature = "null")]
+            Instance::Null(instance) => Ok(Device::Null(NullDevice::new(instance, desc)?)),
         }
     }
 }
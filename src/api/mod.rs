@@ -1,23 +1,84 @@
+mod barrier;
+mod bind_group;
+mod bindless;
+mod buffer;
+mod command_list;
+mod compute_pipeline;
 mod device;
+mod fence;
+mod format;
+mod graphics_pipeline;
 mod instance;
 mod physical_device;
+mod pipeline_layout;
+mod profiler;
+mod query;
+mod queue;
+mod readback;
+mod render_pass;
+mod sampler;
+mod semaphore;
+mod shader;
+mod spirv;
+mod texture;
+mod upload;
 
+pub use barrier::*;
+pub use bind_group::*;
+pub use bindless::*;
+pub use buffer::*;
+pub use command_list::*;
+pub use compute_pipeline::*;
 pub use device::*;
+pub use fence::*;
+pub use format::*;
+pub use graphics_pipeline::*;
 pub use instance::*;
 pub use physical_device::*;
+pub use pipeline_layout::*;
+pub use profiler::*;
+pub use query::*;
+pub use queue::*;
+pub use readback::*;
+pub use render_pass::*;
+pub use sampler::*;
+pub use semaphore::*;
+pub use shader::*;
+pub(crate) use spirv::*;
+pub use texture::*;
 use thiserror::Error;
+pub use upload::*;
 
 #[cfg(feature = "metal")]
 use crate::metal::MetalError;
+#[cfg(feature = "null")]
+use crate::null::NullError;
 #[cfg(feature = "vulkan")]
 use crate::vulkan::VulkanError;
 
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("Backend {0:?} was not compiled in")]
+    UnsupportedBackend(BackendType),
+    #[error("Missing required features: {0:?}")]
+    MissingFeatures(PhysicalDeviceFeatures),
+    #[error("Invalid SPIR-V: {0}")]
+    InvalidSpirv(String),
+    #[error("Shader module has no {1:?} entry point named {0}")]
+    InvalidEntryPoint(String, ShaderStageFlags),
+    #[error("Invalid bind group: {0}")]
+    InvalidBindGroup(String),
+    #[error("Invalid copy: {0}")]
+    InvalidCopy(String),
+    #[error("Invalid query pool: {0}")]
+    InvalidQueryPool(String),
     #[cfg(feature = "metal")]
     #[error("Metal backend: {0}")]
     MetalBackend(#[from] MetalError),
     #[cfg(feature = "vulkan")]
     #[error("Vulkan backend: {0}")]
     VulkanBackend(#[from] VulkanError),
+    #[cfg(feature = "null")]
+    #[error("Null backend: {0}")]
+    NullBackend(#[from] NullError),
 }
@@ -1,5 +1,9 @@
+use bitflags::bitflags;
+
 #[cfg(feature = "metal")]
 use crate::metal::MetalPhysicalDevice;
+#[cfg(feature = "null")]
+use crate::null::NullPhysicalDevice;
 #[cfg(feature = "vulkan")]
 use crate::vulkan::VulkanPhysicalDevice;
 
@@ -9,9 +13,49 @@ pub enum PhysicalDevice {
     Metal(MetalPhysicalDevice),
     #[cfg(feature = "vulkan")]
     Vulkan(VulkanPhysicalDevice),
+    #[cfg(feature = "null")]
+    Null(NullPhysicalDevice),
 }
 
-pub struct PhysicalDeviceFeatures {}
+bitflags! {
+    /// Optional capabilities a physical device may support.
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct PhysicalDeviceFeatures : u64 {
+        /// Task and mesh shader stages.
+        const MESH_SHADER = 1 << 0;
+        /// Acceleration structures and ray tracing pipelines.
+        const RAY_TRACING_PIPELINE = 1 << 1;
+        /// Acceleration structures and inline ray queries from any stage.
+        const RAY_QUERY = 1 << 2;
+        /// Non-uniform indexing into partially bound, update-after-bind descriptor arrays.
+        const DESCRIPTOR_INDEXING = 1 << 3;
+        const TIMELINE_SEMAPHORE = 1 << 4;
+        const BUFFER_DEVICE_ADDRESS = 1 << 5;
+        const SHADER_FLOAT16 = 1 << 6;
+        const SHADER_INT16 = 1 << 7;
+        const SHADER_INT64 = 1 << 8;
+        const SHADER_FLOAT64 = 1 << 9;
+        /// 16-bit types in storage and uniform buffers.
+        const STORAGE_16BIT = 1 << 10;
+        /// Basic, vote, arithmetic, ballot and shuffle subgroup operations in compute shaders.
+        const SUBGROUP_OPERATIONS = 1 << 11;
+        /// Per-draw variable rate shading.
+        const VARIABLE_RATE_SHADING = 1 << 12;
+        const DRAW_INDIRECT_COUNT = 1 << 13;
+        const DYNAMIC_RENDERING = 1 << 14;
+        const SYNCHRONIZATION_2 = 1 << 15;
+        /// Samplers with a max anisotropy above one.
+        const SAMPLER_ANISOTROPY = 1 << 16;
+        /// Samplers with min and max reduction modes.
+        const SAMPLER_FILTER_MINMAX = 1 << 17;
+        /// Query pools of [`QueryType::PipelineStatistics`](crate::QueryType::PipelineStatistics).
+        const PIPELINE_STATISTICS_QUERY = 1 << 18;
+        /// Descriptors written to buffer memory through `VK_EXT_descriptor_buffer`, see
+        /// `VulkanDevice::ext_descriptor_buffer_device`.
+        const DESCRIPTOR_BUFFER = 1 << 19;
+    }
+}
 
 impl PhysicalDevice {
     #[inline]
@@ -21,10 +65,33 @@ impl PhysicalDevice {
             PhysicalDevice::Metal(physical_device) => physical_device.get_name(),
             #[cfg(feature = "vulkan")]
             PhysicalDevice::Vulkan(physcial_device) => physcial_device.get_name(),
+            #[cfg(feature = "null")]
+            PhysicalDevice::Null(physical_device) => physical_device.get_name(),
         }
     }
 
-    pub fn get_supported_features(&self) -> &PhysicalDeviceFeatures {
-        todo!()
+    #[inline]
+    pub fn get_supported_features(&self) -> PhysicalDeviceFeatures {
+        match self {
+            #[cfg(feature = "metal")]
+            PhysicalDevice::Metal(physical_device) => physical_device.get_supported_features(),
+            #[cfg(feature = "vulkan")]
+            PhysicalDevice::Vulkan(physical_device) => physical_device.get_supported_features(),
+            #[cfg(feature = "null")]
+            PhysicalDevice::Null(physical_device) => physical_device.get_supported_features(),
+        }
+    }
+
+    /// Returns the number of nanoseconds per timestamp tick.
+    #[inline]
+    pub fn get_timestamp_period(&self) -> f32 {
+        match self {
+            #[cfg(feature = "metal")]
+            PhysicalDevice::Metal(physical_device) => physical_device.get_timestamp_period(),
+            #[cfg(feature = "vulkan")]
+            PhysicalDevice::Vulkan(physical_device) => physical_device.get_timestamp_period(),
+            #[cfg(feature = "null")]
+            PhysicalDevice::Null(physical_device) => physical_device.get_timestamp_period(),
+        }
     }
 }
@@ -0,0 +1,297 @@
+#[cfg(feature = "metal")]
+use crate::metal::MetalPipelineLayout;
+#[cfg(feature = "null")]
+use crate::null::NullPipelineLayout;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanPipelineLayout;
+use crate::{BindGroupLayout, DescriptorBinding, PushConstantRange, ShaderStageFlags};
+
+#[derive(Clone, Debug, Default)]
+pub struct PipelineLayoutDesc<'a> {
+    /// Bindings of every descriptor set, usually taken from
+    /// [`ShaderReflection::descriptor_bindings`](crate::ShaderReflection::descriptor_bindings).
+    pub descriptor_bindings: &'a [DescriptorBinding],
+    /// Set the device's bindless heap is bound to, see
+    /// [`Device::register_bindless_buffer`](crate::Device::register_bindless_buffer). Bindings
+    /// declared for this set must match the `BINDLESS_*_BINDING` arrays and may be runtime sized.
+    pub bindless_set: Option<u32>,
+    /// Push constant ranges, usually taken from
+    /// [`ShaderReflection::push_constant_ranges`](crate::ShaderReflection::push_constant_ranges).
+    /// A stage may appear in one range only.
+    pub push_constant_ranges: &'a [PushConstantRange],
+    pub label: Option<&'a str>,
+}
+
+/// Checks that `ranges` are 4 byte aligned, fit in `max_size` bytes and give each stage at most
+/// one range.
+pub(crate) fn validate_push_constant_ranges(
+    ranges: &[PushConstantRange],
+    max_size: u32,
+) -> Result<(), String> {
+    for (i, range) in ranges.iter().enumerate() {
+        if range.size == 0 || !range.offset.is_multiple_of(4) || !range.size.is_multiple_of(4) {
+            return Err(format!(
+                "Push constant range at offset {} with size {} must be non-empty and 4 byte \
+                 aligned",
+                range.offset, range.size
+            ));
+        }
+        if range
+            .offset
+            .checked_add(range.size)
+            .is_none_or(|end| end > max_size)
+        {
+            return Err(format!(
+                "Push constant range at offset {} with size {} exceeds the {max_size} bytes the \
+                 device supports",
+                range.offset, range.size
+            ));
+        }
+        if ranges[..i]
+            .iter()
+            .any(|r| r.stages.intersects(range.stages))
+        {
+            return Err(format!(
+                "Stages {:?} appear in more than one push constant range",
+                range.stages
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that `size` bytes at `offset` lie within the range of every stage in `stages`, and
+/// that `stages` includes every stage of the ranges they overlap.
+pub(crate) fn validate_push_constants(
+    ranges: &[PushConstantRange],
+    stages: ShaderStageFlags,
+    offset: u32,
+    size: u32,
+) -> Result<(), String> {
+    if stages.is_empty() || size == 0 || !offset.is_multiple_of(4) || !size.is_multiple_of(4) {
+        return Err(format!(
+            "{size} bytes at offset {offset} must be non-empty, 4 byte aligned and used by at \
+             least one stage"
+        ));
+    }
+
+    let Some(end) = offset.checked_add(size) else {
+        return Err(format!("{size} bytes at offset {offset} are out of range"));
+    };
+
+    for stage in stages.iter() {
+        if !ranges.iter().any(|range| {
+            range.stages.contains(stage)
+                && range.offset <= offset
+                && end <= range.offset + range.size
+        }) {
+            return Err(format!(
+                "{size} bytes at offset {offset} exceed the push constant range of {stage:?}"
+            ));
+        }
+    }
+
+    if let Some(range) = ranges.iter().find(|range| {
+        range.offset < end && offset < range.offset + range.size && !stages.contains(range.stages)
+    }) {
+        return Err(format!(
+            "Push constants overlapping the range of {:?} must be set for all of its stages",
+            range.stages
+        ));
+    }
+
+    Ok(())
+}
+
+/// Compares and hashes by identity, clones of one pipeline layout are equal.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum PipelineLayout {
+    #[cfg(feature = "metal")]
+    Metal(MetalPipelineLayout),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanPipelineLayout),
+    #[cfg(feature = "null")]
+    Null(NullPipelineLayout),
+}
+
+impl PipelineLayout {
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalPipelineLayout {
+        match self {
+            PipelineLayout::Metal(pipeline_layout) => pipeline_layout,
+            #[allow(unreachable_patterns)]
+            _ => panic!("PipelineLayout belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanPipelineLayout {
+        match self {
+            PipelineLayout::Vulkan(pipeline_layout) => pipeline_layout,
+            #[allow(unreachable_patterns)]
+            _ => panic!("PipelineLayout belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullPipelineLayout {
+        match self {
+            PipelineLayout::Null(pipeline_layout) => pipeline_layout,
+            #[allow(unreachable_patterns)]
+            _ => panic!("PipelineLayout belongs to a different backend"),
+        }
+    }
+
+    #[inline]
+    pub fn descriptor_bindings(&self) -> &[DescriptorBinding] {
+        match self {
+            #[cfg(feature = "metal")]
+            PipelineLayout::Metal(pipeline_layout) => pipeline_layout.descriptor_bindings(),
+            #[cfg(feature = "vulkan")]
+            PipelineLayout::Vulkan(pipeline_layout) => pipeline_layout.descriptor_bindings(),
+            #[cfg(feature = "null")]
+            PipelineLayout::Null(pipeline_layout) => pipeline_layout.descriptor_bindings(),
+        }
+    }
+
+    #[inline]
+    pub fn push_constant_ranges(&self) -> &[PushConstantRange] {
+        match self {
+            #[cfg(feature = "metal")]
+            PipelineLayout::Metal(pipeline_layout) => pipeline_layout.push_constant_ranges(),
+            #[cfg(feature = "vulkan")]
+            PipelineLayout::Vulkan(pipeline_layout) => pipeline_layout.push_constant_ranges(),
+            #[cfg(feature = "null")]
+            PipelineLayout::Null(pipeline_layout) => pipeline_layout.push_constant_ranges(),
+        }
+    }
+
+    /// Returns the layout of `set`, shared with bind group layouts created from the same
+    /// bindings. `None` for the bindless set and sets past the last one.
+    #[inline]
+    pub fn bind_group_layout(&self, set: u32) -> Option<BindGroupLayout> {
+        match self {
+            #[cfg(feature = "metal")]
+            PipelineLayout::Metal(pipeline_layout) => {
+                pipeline_layout
+                    .bind_group_layout(set)
+                    .cloned()
+                    .map(BindGroupLayout::Metal)
+            }
+            #[cfg(feature = "vulkan")]
+            PipelineLayout::Vulkan(pipeline_layout) => {
+                pipeline_layout
+                    .bind_group_layout(set)
+                    .cloned()
+                    .map(BindGroupLayout::Vulkan)
+            }
+            #[cfg(feature = "null")]
+            PipelineLayout::Null(pipeline_layout) => {
+                pipeline_layout
+                    .bind_group_layout(set)
+                    .cloned()
+                    .map(BindGroupLayout::Null)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RANGES: [PushConstantRange; 2] = [
+        PushConstantRange {
+            stages: ShaderStageFlags::VERTEX,
+            offset: 0,
+            size: 16,
+        },
+        PushConstantRange {
+            stages: ShaderStageFlags::FRAGMENT,
+            offset: 16,
+            size: 16,
+        },
+    ];
+
+    #[test]
+    fn pushes_within_the_ranges_are_valid() {
+        assert!(validate_push_constants(&RANGES, ShaderStageFlags::VERTEX, 0, 16).is_ok());
+        assert!(validate_push_constants(&RANGES, ShaderStageFlags::VERTEX, 4, 8).is_ok());
+        assert!(validate_push_constants(&RANGES, ShaderStageFlags::FRAGMENT, 28, 4).is_ok());
+    }
+
+    #[test]
+    fn out_of_range_pushes_are_rejected() {
+        for (stages, offset, size) in [
+            (ShaderStageFlags::VERTEX, 12, 8),
+            (ShaderStageFlags::FRAGMENT, 32, 4),
+            (ShaderStageFlags::FRAGMENT, 12, 8),
+            (ShaderStageFlags::COMPUTE, 0, 4),
+            (ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT, 0, 32),
+            (ShaderStageFlags::FRAGMENT, u32::MAX - 3, 8),
+        ] {
+            assert!(
+                validate_push_constants(&RANGES, stages, offset, size).is_err(),
+                "{size} bytes at {offset} for {stages:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn misaligned_pushes_are_rejected() {
+        for (offset, size) in [(2, 4), (0, 6), (0, 0)] {
+            assert!(
+                validate_push_constants(&RANGES, ShaderStageFlags::VERTEX, offset, size).is_err(),
+                "{size} bytes at {offset}"
+            );
+        }
+        assert!(validate_push_constants(&RANGES, ShaderStageFlags::empty(), 0, 4).is_err());
+    }
+
+    #[test]
+    fn pushes_must_cover_every_stage_of_overlapped_ranges() {
+        let shared = [PushConstantRange {
+            stages: ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT,
+            offset: 0,
+            size: 16,
+        }];
+
+        let stages = ShaderStageFlags::VERTEX | ShaderStageFlags::FRAGMENT;
+        assert!(validate_push_constants(&shared, stages, 0, 16).is_ok());
+        assert!(validate_push_constants(&shared, ShaderStageFlags::VERTEX, 0, 16).is_err());
+    }
+
+    #[test]
+    fn invalid_ranges_are_rejected() {
+        assert!(validate_push_constant_ranges(&RANGES, 32).is_ok());
+        assert!(validate_push_constant_ranges(&RANGES, 28).is_err());
+
+        let range = |stages, offset, size| {
+            PushConstantRange {
+                stages,
+                offset,
+                size,
+            }
+        };
+        for ranges in [
+            [range(ShaderStageFlags::VERTEX, 2, 4)],
+            [range(ShaderStageFlags::VERTEX, 0, 6)],
+            [range(ShaderStageFlags::VERTEX, 0, 0)],
+            [range(ShaderStageFlags::VERTEX, u32::MAX - 3, 8)],
+        ] {
+            assert!(validate_push_constant_ranges(&ranges, 128).is_err());
+        }
+        assert!(validate_push_constant_ranges(
+            &[
+                range(ShaderStageFlags::VERTEX, 0, 4),
+                range(ShaderStageFlags::VERTEX, 4, 4)
+            ],
+            128
+        )
+        .is_err());
+    }
+}
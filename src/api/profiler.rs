@@ -0,0 +1,727 @@
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    ops::{Deref, DerefMut},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use log::warn;
+
+use crate::{
+    Buffer, BufferDesc, BufferUsageFlags, CalibratedTimestamp, CommandList, Device, Error, Fence,
+    FenceDesc, MemoryLocation, QueryPool, QueryPoolDesc, QueryType, QueueType, SubmitDesc,
+};
+
+const QUEUE_TYPES: [QueueType; 3] = [QueueType::Graphics, QueueType::Compute, QueueType::Transfer];
+
+/// Returns the position of `queue_type` in [`QUEUE_TYPES`].
+fn queue_slot(queue_type: QueueType) -> usize {
+    match queue_type {
+        QueueType::Graphics => 0,
+        QueueType::Compute => 1,
+        QueueType::Transfer => 2,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GpuProfilerDesc {
+    /// Scopes recorded per frame across every command list, further scopes are not timed.
+    pub max_scopes_per_frame: u32,
+    /// Frames recorded before [`GpuProfiler::end_frame`] waits for the oldest one to complete.
+    pub frames_in_flight: u32,
+}
+
+impl Default for GpuProfilerDesc {
+    fn default() -> Self {
+        Self {
+            max_scopes_per_frame: 1024,
+            frames_in_flight: 3,
+        }
+    }
+}
+
+/// A timed scope, placed on the CPU timeline.
+#[derive(Clone, Debug)]
+pub struct GpuScope {
+    pub label: String,
+    pub queue_type: QueueType,
+    /// Index of the enclosing scope in [`GpuFrame::scopes`].
+    pub parent: Option<usize>,
+    pub depth: u32,
+    pub begin: Instant,
+    pub end: Instant,
+}
+
+impl GpuScope {
+    #[inline]
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_duration_since(self.begin)
+    }
+}
+
+/// The scopes of a completed frame, in the order they were begun.
+#[derive(Clone, Debug)]
+pub struct GpuFrame {
+    pub index: u64,
+    pub scopes: Vec<GpuScope>,
+}
+
+/// A CPU span exported next to the GPU scopes by [`to_chrome_trace`].
+#[derive(Clone, Debug)]
+pub struct CpuSpan {
+    pub label: String,
+    pub thread: String,
+    pub begin: Instant,
+    pub end: Instant,
+}
+
+/// Identifies a scope begun in the frame with the given index.
+#[derive(Copy, Clone)]
+struct ScopeId {
+    frame_index: u64,
+    index: usize,
+    depth: u32,
+}
+
+struct PendingScope {
+    label: String,
+    queue_type: QueueType,
+    parent: Option<usize>,
+    depth: u32,
+    /// The CPU instant the scope was recorded at, the time origin without calibrated timestamps.
+    recorded_at: Instant,
+    ended: bool,
+}
+
+/// Scope `i` writes its begin and end timestamps to queries `2 * i` and `2 * i + 1`, which are
+/// resolved to the readback buffer at the same index.
+struct ProfilerFrame {
+    query_pool: QueryPool,
+    readback_buffer: Buffer,
+    /// One fence per queue type, signaled once the frame's work on that queue has completed.
+    fences: Vec<Fence>,
+    used_queues: Vec<QueueType>,
+    scopes: Vec<PendingScope>,
+    dropped_scopes: u32,
+    index: u64,
+    submitted: bool,
+}
+
+struct State {
+    frames: Vec<ProfilerFrame>,
+    current: usize,
+    frame_index: u64,
+    calibration: Option<CalibratedTimestamp>,
+    completed: Vec<GpuFrame>,
+}
+
+/// Times nested scopes of command lists across queues with timestamp queries, see
+/// [`CommandList::profile_scope`].
+///
+/// Every command list of a frame has to be submitted before [`Self::end_frame`]. Timestamps are
+/// placed on the CPU timeline with calibrated timestamps where available, otherwise the first one
+/// is anchored to the instant its scope was recorded at and GPU scopes only roughly line up with
+/// CPU spans.
+pub struct GpuProfiler {
+    device: Device,
+    timestamp_period: f64,
+    max_scopes_per_frame: u32,
+
+    state: Mutex<State>,
+}
+
+impl GpuProfiler {
+    pub(crate) fn new(device: &Device, desc: &GpuProfilerDesc) -> Result<Self, Error> {
+        let frames = (0..desc.frames_in_flight.max(1))
+            .map(|_| {
+                Ok(ProfilerFrame {
+                    query_pool: device.create_query_pool(&QueryPoolDesc {
+                        query_type: QueryType::Timestamp,
+                        count: desc.max_scopes_per_frame * 2,
+                        label: Some("GpuProfiler"),
+                    })?,
+                    readback_buffer: device.create_buffer(&BufferDesc {
+                        size: u64::from(desc.max_scopes_per_frame) * 16,
+                        usage: BufferUsageFlags::TRANSFER_DST,
+                        memory_location: MemoryLocation::Readback,
+                        tracked: false,
+                        label: Some("GpuProfiler"),
+                    })?,
+                    fences: QUEUE_TYPES
+                        .iter()
+                        .map(|_| device.create_fence(&FenceDesc::default()))
+                        .collect::<Result<_, _>>()?,
+                    used_queues: Vec::new(),
+                    scopes: Vec::new(),
+                    dropped_scopes: 0,
+                    index: 0,
+                    submitted: false,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self {
+            device: device.clone(),
+            timestamp_period: f64::from(device.get_timestamp_period()),
+            max_scopes_per_frame: desc.max_scopes_per_frame,
+
+            state: Mutex::new(State {
+                frames,
+                current: 0,
+                frame_index: 0,
+                calibration: device.get_calibrated_timestamp()?,
+                completed: Vec::new(),
+            }),
+        })
+    }
+
+    /// Returns the index of the frame scopes are currently recorded into.
+    #[inline]
+    pub fn frame_index(&self) -> u64 {
+        self.state.lock().unwrap().frame_index
+    }
+
+    fn begin_scope(
+        &self,
+        command_list: &mut CommandList,
+        label: &str,
+        parent: Option<ScopeId>,
+    ) -> Option<ScopeId> {
+        let mut state = self.state.lock().unwrap();
+        let frame_index = state.frame_index;
+        let current = state.current;
+        let frame = &mut state.frames[current];
+
+        if frame.scopes.len() >= self.max_scopes_per_frame as usize {
+            frame.dropped_scopes += 1;
+            return None;
+        }
+
+        let index = frame.scopes.len();
+        let query = index as u32 * 2;
+        let depth = parent.map_or(0, |parent| parent.depth + 1);
+
+        frame.scopes.push(PendingScope {
+            label: label.to_owned(),
+            queue_type: command_list.queue_type(),
+            parent: parent
+                .filter(|parent| parent.frame_index == frame_index)
+                .map(|parent| parent.index),
+            depth,
+            recorded_at: Instant::now(),
+            ended: false,
+        });
+        if !frame.used_queues.contains(&command_list.queue_type()) {
+            frame.used_queues.push(command_list.queue_type());
+        }
+
+        command_list.reset_queries(&frame.query_pool, query, 2);
+        command_list.write_timestamp(&frame.query_pool, query);
+
+        Some(ScopeId {
+            frame_index,
+            index,
+            depth,
+        })
+    }
+
+    fn end_scope(&self, command_list: &mut CommandList, scope: ScopeId) {
+        let mut state = self.state.lock().unwrap();
+
+        // Scopes left open across `end_frame` are not timed.
+        if scope.frame_index != state.frame_index {
+            return;
+        }
+
+        let current = state.current;
+        let frame = &mut state.frames[current];
+        let query = scope.index as u32 * 2;
+
+        command_list.write_timestamp(&frame.query_pool, query + 1);
+        command_list.resolve_queries(
+            &frame.query_pool,
+            query,
+            2,
+            &frame.readback_buffer,
+            u64::from(query) * 8,
+        );
+
+        frame.scopes[scope.index].ended = true;
+    }
+
+    /// Ends the current frame once its command lists have been submitted, waiting for the frame
+    /// recorded `frames_in_flight` frames ago if it has not completed yet.
+    pub fn end_frame(&self) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        let current = state.current;
+        let frame_index = state.frame_index;
+        let frame = &mut state.frames[current];
+
+        for queue_type in &frame.used_queues {
+            let fence = &frame.fences[queue_slot(*queue_type)];
+
+            // Signals once every earlier submission to the queue has completed.
+            self.device.get_queue(*queue_type).submit(&SubmitDesc {
+                fence: Some(fence),
+                ..Default::default()
+            })?;
+        }
+
+        if frame.dropped_scopes > 0 {
+            warn!(
+                "GpuProfiler dropped {} scopes of frame {frame_index}, max_scopes_per_frame is {}",
+                frame.dropped_scopes, self.max_scopes_per_frame
+            );
+        }
+
+        frame.index = frame_index;
+        frame.submitted = true;
+
+        if let Some(calibration) = self.device.get_calibrated_timestamp()? {
+            state.calibration = Some(calibration);
+        }
+
+        state.frame_index += 1;
+        state.current = (current + 1) % state.frames.len();
+
+        let next = state.current;
+        self.read_back(&mut state, next, true)?;
+
+        Ok(())
+    }
+
+    /// Returns the frames that completed since the last call, oldest first.
+    pub fn collect(&self) -> Result<Vec<GpuFrame>, Error> {
+        let mut state = self.state.lock().unwrap();
+
+        // Frames complete in order, starting with the one after the current frame.
+        for i in 1..state.frames.len() {
+            let slot = (state.current + i) % state.frames.len();
+
+            if !self.read_back(&mut state, slot, false)? {
+                break;
+            }
+        }
+
+        Ok(std::mem::take(&mut state.completed))
+    }
+
+    /// Reads the timings of a submitted frame into `completed` and makes the slot reusable,
+    /// returns `false` if the frame is still in flight and `wait` is `false`.
+    fn read_back(&self, state: &mut State, slot: usize, wait: bool) -> Result<bool, Error> {
+        let frame = &state.frames[slot];
+        if !frame.submitted {
+            return Ok(true);
+        }
+
+        for queue_type in &frame.used_queues {
+            let fence = &frame.fences[queue_slot(*queue_type)];
+
+            if wait {
+                fence.wait(None)?;
+            } else if !fence.is_signaled()? {
+                return Ok(false);
+            }
+        }
+
+        let mapped_ptr = frame
+            .readback_buffer
+            .mapped_ptr()
+            .expect("Readback buffers are host visible");
+        let timestamps = unsafe {
+            std::slice::from_raw_parts(mapped_ptr.as_ptr().cast::<u64>(), frame.scopes.len() * 2)
+        };
+
+        let calibration = *state.calibration.get_or_insert_with(|| {
+            let first = frame.scopes.first();
+
+            CalibratedTimestamp {
+                gpu_ticks: first.map_or(0, |_| timestamps[0]),
+                cpu_instant: first.map_or_else(Instant::now, |scope| scope.recorded_at),
+            }
+        });
+        let to_instant = |ticks: u64| {
+            let nanoseconds =
+                (ticks as i128 - calibration.gpu_ticks as i128) as f64 * self.timestamp_period;
+            let offset = Duration::from_nanos(nanoseconds.abs() as u64);
+
+            if nanoseconds >= 0.0 {
+                calibration.cpu_instant + offset
+            } else {
+                calibration
+                    .cpu_instant
+                    .checked_sub(offset)
+                    .unwrap_or(calibration.cpu_instant)
+            }
+        };
+
+        let scopes = frame
+            .scopes
+            .iter()
+            .zip(timestamps.chunks_exact(2))
+            .map(|(scope, timestamps)| {
+                GpuScope {
+                    label: scope.label.clone(),
+                    queue_type: scope.queue_type,
+                    parent: scope.parent,
+                    depth: scope.depth,
+                    begin: to_instant(timestamps[0]),
+                    end: if scope.ended {
+                        to_instant(timestamps[1].max(timestamps[0]))
+                    } else {
+                        to_instant(timestamps[0])
+                    },
+                }
+            })
+            .collect();
+
+        state.completed.push(GpuFrame {
+            index: frame.index,
+            scopes,
+        });
+
+        let frame = &mut state.frames[slot];
+        for queue_type in frame.used_queues.drain(..) {
+            frame.fences[queue_slot(queue_type)].reset()?;
+        }
+        frame.scopes.clear();
+        frame.dropped_scopes = 0;
+        frame.submitted = false;
+
+        Ok(true)
+    }
+}
+
+/// A profiled scope of a command list, returned by [`CommandList::profile_scope`].
+///
+/// Commands are recorded through it, the scope ends when it is dropped.
+pub struct ProfileScope<'a> {
+    command_list: &'a mut CommandList,
+    profiler: &'a GpuProfiler,
+    scope: Option<ScopeId>,
+}
+
+impl<'a> ProfileScope<'a> {
+    pub(crate) fn new(
+        command_list: &'a mut CommandList,
+        profiler: &'a GpuProfiler,
+        label: &str,
+    ) -> Self {
+        let scope = profiler.begin_scope(command_list, label, None);
+
+        Self {
+            command_list,
+            profiler,
+            scope,
+        }
+    }
+
+    /// Begins a scope nested in this one.
+    pub fn profile_scope(&mut self, label: &str) -> ProfileScope<'_> {
+        let scope = self
+            .profiler
+            .begin_scope(self.command_list, label, self.scope);
+
+        ProfileScope {
+            command_list: self.command_list,
+            profiler: self.profiler,
+            scope,
+        }
+    }
+}
+
+impl Deref for ProfileScope<'_> {
+    type Target = CommandList;
+
+    #[inline]
+    fn deref(&self) -> &CommandList {
+        self.command_list
+    }
+}
+
+impl DerefMut for ProfileScope<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut CommandList {
+        self.command_list
+    }
+}
+
+impl Drop for ProfileScope<'_> {
+    fn drop(&mut self) {
+        if let Some(scope) = self.scope {
+            self.profiler.end_scope(self.command_list, scope);
+        }
+    }
+}
+
+fn write_json_string(json: &mut String, value: &str) {
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// A complete event of a Chrome trace.
+struct TraceEvent<'a> {
+    label: &'a str,
+    category: &'static str,
+    tid: usize,
+    begin: Instant,
+    end: Instant,
+    frame: Option<u64>,
+}
+
+/// Exports GPU frames and CPU spans in the Chrome trace event format, which Perfetto loads too.
+///
+/// GPU scopes are placed on one track per queue, CPU spans on one track per thread name.
+pub fn to_chrome_trace(frames: &[GpuFrame], cpu_spans: &[CpuSpan]) -> String {
+    let mut events = Vec::new();
+    for frame in frames {
+        events.extend(frame.scopes.iter().map(|scope| {
+            TraceEvent {
+                label: &scope.label,
+                category: "gpu",
+                tid: queue_slot(scope.queue_type),
+                begin: scope.begin,
+                end: scope.end,
+                frame: Some(frame.index),
+            }
+        }));
+    }
+
+    let mut threads = HashMap::new();
+    for span in cpu_spans {
+        let next_tid = QUEUE_TYPES.len() + threads.len();
+
+        events.push(TraceEvent {
+            label: &span.label,
+            category: "cpu",
+            tid: *threads.entry(span.thread.as_str()).or_insert(next_tid),
+            begin: span.begin,
+            end: span.end,
+            frame: None,
+        });
+    }
+
+    let origin = events
+        .iter()
+        .map(|event| event.begin)
+        .min()
+        .unwrap_or_else(Instant::now);
+    let micros = |duration: Duration| duration.as_secs_f64() * 1e6;
+
+    let mut json = String::from("{\"traceEvents\":[");
+
+    for event in &events {
+        json.push_str("{\"name\":");
+        write_json_string(&mut json, event.label);
+        let _ = write!(
+            json,
+            ",\"cat\":\"{}\",\"ph\":\"X\",\"pid\":1,\"tid\":{},\"ts\":{:.3},\"dur\":{:.3}",
+            event.category,
+            event.tid,
+            micros(event.begin.saturating_duration_since(origin)),
+            micros(event.end.saturating_duration_since(event.begin))
+        );
+        if let Some(frame) = event.frame {
+            let _ = write!(json, ",\"args\":{{\"frame\":{frame}}}");
+        }
+        json.push_str("},");
+    }
+
+    let track_names = QUEUE_TYPES
+        .iter()
+        .enumerate()
+        .map(|(tid, queue_type)| (tid, format!("GPU {queue_type:?} queue")))
+        .chain(
+            threads
+                .into_iter()
+                .map(|(thread, tid)| (tid, thread.to_owned())),
+        );
+    for (tid, name) in track_names {
+        let _ = write!(
+            json,
+            "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":{tid},\"args\":{{\"name\":"
+        );
+        write_json_string(&mut json, &name);
+        json.push_str("}},");
+    }
+
+    // Every event is followed by a comma, the track names always end the list.
+    json.pop();
+    json.push_str("],\"displayTimeUnit\":\"ns\"}");
+    json
+}
+
+#[cfg(all(test, feature = "null"))]
+mod tests {
+    use super::*;
+    use crate::{null::test_utils::create_device, CommandListDesc, PhysicalDeviceFeatures};
+
+    fn create_profiler(device: &Device, max_scopes_per_frame: u32) -> GpuProfiler {
+        device
+            .create_gpu_profiler(&GpuProfilerDesc {
+                max_scopes_per_frame,
+                frames_in_flight: 2,
+            })
+            .unwrap()
+    }
+
+    fn submit(device: &Device, command_list: &mut CommandList) {
+        command_list.end().unwrap();
+        device
+            .get_queue(QueueType::Graphics)
+            .submit(&SubmitDesc {
+                command_lists: &[command_list],
+                ..Default::default()
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn chrome_trace_escapes_strings() {
+        let now = Instant::now();
+        let frames = [GpuFrame {
+            index: 7,
+            scopes: vec![GpuScope {
+                label: "say \"hi\"\\\n\t".to_owned(),
+                queue_type: QueueType::Compute,
+                parent: None,
+                depth: 0,
+                begin: now,
+                end: now + Duration::from_micros(2),
+            }],
+        }];
+        let cpu_spans = [CpuSpan {
+            label: "update".to_owned(),
+            thread: "main \"thread\"".to_owned(),
+            begin: now + Duration::from_micros(1),
+            end: now + Duration::from_micros(3),
+        }];
+
+        let trace = to_chrome_trace(&frames, &cpu_spans);
+
+        assert!(trace.starts_with("{\"traceEvents\":["));
+        assert!(trace.ends_with("],\"displayTimeUnit\":\"ns\"}"));
+        assert!(trace.contains(
+            "{\"name\":\"say \\\"hi\\\"\\\\\\n\\u0009\",\"cat\":\"gpu\",\"ph\":\"X\",\"pid\":1,\
+             \"tid\":1,\"ts\":0.000,\"dur\":2.000,\"args\":{\"frame\":7}}"
+        ));
+        assert!(trace.contains(
+            "{\"name\":\"update\",\"cat\":\"cpu\",\"ph\":\"X\",\"pid\":1,\"tid\":3,\
+             \"ts\":1.000,\"dur\":2.000}"
+        ));
+        assert!(trace.contains("\"tid\":3,\"args\":{\"name\":\"main \\\"thread\\\"\"}}"));
+        assert!(!trace.contains(",]"));
+    }
+
+    #[test]
+    fn nested_scopes_record_their_parents() {
+        let device = create_device(PhysicalDeviceFeatures::empty());
+        let profiler = create_profiler(&device, 4);
+        let mut command_list = device
+            .create_command_list(&CommandListDesc::default())
+            .unwrap();
+
+        command_list.begin().unwrap();
+        {
+            let mut outer = command_list.profile_scope(&profiler, "outer");
+            let _inner = outer.profile_scope("inner");
+        }
+        command_list.profile_scope(&profiler, "sibling");
+        submit(&device, &mut command_list);
+        profiler.end_frame().unwrap();
+
+        let frames = profiler.collect().unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].index, 0);
+
+        let scopes = &frames[0].scopes;
+        let hierarchy = scopes
+            .iter()
+            .map(|scope| (scope.label.as_str(), scope.parent, scope.depth))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hierarchy,
+            [
+                ("outer", None, 0),
+                ("inner", Some(0), 1),
+                ("sibling", None, 0)
+            ]
+        );
+        assert!(profiler.collect().unwrap().is_empty());
+    }
+
+    #[test]
+    fn scopes_open_across_end_frame_lose_their_parent() {
+        let device = create_device(PhysicalDeviceFeatures::empty());
+        let profiler = create_profiler(&device, 4);
+        let mut command_list = device
+            .create_command_list(&CommandListDesc::default())
+            .unwrap();
+
+        command_list.begin().unwrap();
+        {
+            let mut outer = command_list.profile_scope(&profiler, "outer");
+            profiler.end_frame().unwrap();
+            let _inner = outer.profile_scope("inner");
+        }
+        submit(&device, &mut command_list);
+        profiler.end_frame().unwrap();
+
+        let frames = profiler.collect().unwrap();
+        assert_eq!(
+            frames.iter().map(|frame| frame.index).collect::<Vec<_>>(),
+            [0, 1]
+        );
+
+        // The outer scope ends in the next frame and is not timed.
+        let outer = &frames[0].scopes[0];
+        assert_eq!(outer.label, "outer");
+        assert_eq!(outer.duration(), Duration::ZERO);
+
+        let inner = &frames[1].scopes[0];
+        assert_eq!(inner.label, "inner");
+        assert_eq!((inner.parent, inner.depth), (None, 1));
+    }
+
+    #[test]
+    fn scopes_beyond_the_limit_are_dropped() {
+        let device = create_device(PhysicalDeviceFeatures::empty());
+        let profiler = create_profiler(&device, 2);
+        let mut command_list = device
+            .create_command_list(&CommandListDesc::default())
+            .unwrap();
+        // The first frame is recorded into the first slot.
+        let dropped_scopes = || profiler.state.lock().unwrap().frames[0].dropped_scopes;
+
+        command_list.begin().unwrap();
+        for label in ["a", "b", "c", "d"] {
+            command_list.profile_scope(&profiler, label);
+        }
+        assert_eq!(dropped_scopes(), 2);
+        submit(&device, &mut command_list);
+        profiler.end_frame().unwrap();
+
+        let frames = profiler.collect().unwrap();
+        let labels = frames[0]
+            .scopes
+            .iter()
+            .map(|scope| scope.label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["a", "b"]);
+
+        // Reading the frame back resets its slot for reuse.
+        assert_eq!(dropped_scopes(), 0);
+    }
+}
@@ -0,0 +1,217 @@
+use std::time::Instant;
+
+use bitflags::bitflags;
+
+#[cfg(feature = "metal")]
+use crate::metal::MetalQueryPool;
+#[cfg(feature = "null")]
+use crate::null::NullQueryPool;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanQueryPool;
+
+bitflags! {
+    /// Counters of a pipeline statistics query, resolved in the order of the flags.
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct PipelineStatisticsFlags : u32 {
+        const INPUT_ASSEMBLY_VERTICES = 1 << 0;
+        const INPUT_ASSEMBLY_PRIMITIVES = 1 << 1;
+        const VERTEX_SHADER_INVOCATIONS = 1 << 2;
+        const CLIPPING_INVOCATIONS = 1 << 3;
+        const CLIPPING_PRIMITIVES = 1 << 4;
+        const FRAGMENT_SHADER_INVOCATIONS = 1 << 5;
+        const COMPUTE_SHADER_INVOCATIONS = 1 << 6;
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum QueryType {
+    /// GPU timestamps written by [`CommandList::write_timestamp`], in ticks of
+    /// [`Device::get_timestamp_period`] nanoseconds.
+    ///
+    /// [`CommandList::write_timestamp`]: crate::CommandList::write_timestamp
+    /// [`Device::get_timestamp_period`]: crate::Device::get_timestamp_period
+    #[default]
+    Timestamp,
+    /// Samples passing the depth and stencil tests between `begin_query` and `end_query` inside a
+    /// render pass. The count is only guaranteed to be non-zero if any sample passed.
+    Occlusion,
+    /// The selected counters between `begin_query` and `end_query`, requires
+    /// [`PhysicalDeviceFeatures::PIPELINE_STATISTICS_QUERY`].
+    ///
+    /// [`PhysicalDeviceFeatures::PIPELINE_STATISTICS_QUERY`]:
+    /// crate::PhysicalDeviceFeatures::PIPELINE_STATISTICS_QUERY
+    PipelineStatistics(PipelineStatisticsFlags),
+}
+
+impl QueryType {
+    /// Returns the size of the result of a single query, a `u64` per value.
+    #[inline]
+    pub fn result_size(&self) -> u64 {
+        match self {
+            Self::Timestamp | Self::Occlusion => 8,
+            Self::PipelineStatistics(flags) => 8 * u64::from(flags.bits().count_ones()),
+        }
+    }
+}
+
+/// A GPU timestamp and the CPU instant it was sampled at, relating timestamp queries to the CPU
+/// clock.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CalibratedTimestamp {
+    pub gpu_ticks: u64,
+    pub cpu_instant: Instant,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct QueryPoolDesc<'a> {
+    pub query_type: QueryType,
+    pub count: u32,
+    pub label: Option<&'a str>,
+}
+
+/// Queries have to be reset with [`CommandList::reset_queries`] before they are written, their
+/// results are copied to a buffer with [`CommandList::resolve_queries`].
+///
+/// [`CommandList::reset_queries`]: crate::CommandList::reset_queries
+/// [`CommandList::resolve_queries`]: crate::CommandList::resolve_queries
+#[derive(Clone)]
+pub enum QueryPool {
+    #[cfg(feature = "metal")]
+    Metal(MetalQueryPool),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanQueryPool),
+    #[cfg(feature = "null")]
+    Null(NullQueryPool),
+}
+
+impl QueryPool {
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalQueryPool {
+        match self {
+            QueryPool::Metal(query_pool) => query_pool,
+            #[allow(unreachable_patterns)]
+            _ => panic!("QueryPool belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanQueryPool {
+        match self {
+            QueryPool::Vulkan(query_pool) => query_pool,
+            #[allow(unreachable_patterns)]
+            _ => panic!("QueryPool belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullQueryPool {
+        match self {
+            QueryPool::Null(query_pool) => query_pool,
+            #[allow(unreachable_patterns)]
+            _ => panic!("QueryPool belongs to a different backend"),
+        }
+    }
+
+    #[inline]
+    pub fn query_type(&self) -> QueryType {
+        match self {
+            #[cfg(feature = "metal")]
+            QueryPool::Metal(query_pool) => query_pool.query_type(),
+            #[cfg(feature = "vulkan")]
+            QueryPool::Vulkan(query_pool) => query_pool.query_type(),
+            #[cfg(feature = "null")]
+            QueryPool::Null(query_pool) => query_pool.query_type(),
+        }
+    }
+
+    #[inline]
+    pub fn count(&self) -> u32 {
+        match self {
+            #[cfg(feature = "metal")]
+            QueryPool::Metal(query_pool) => query_pool.count(),
+            #[cfg(feature = "vulkan")]
+            QueryPool::Vulkan(query_pool) => query_pool.count(),
+            #[cfg(feature = "null")]
+            QueryPool::Null(query_pool) => query_pool.count(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "null"))]
+mod tests {
+    use super::*;
+    use crate::{
+        null::{test_utils::create_device, NullError},
+        CommandList, CommandListDesc, Error, PhysicalDeviceFeatures, QueueType,
+    };
+
+    #[test]
+    fn query_pools_are_validated() {
+        let device = create_device(PhysicalDeviceFeatures::empty());
+        let create_query_pool = |query_type, count| {
+            device.create_query_pool(&QueryPoolDesc {
+                query_type,
+                count,
+                label: None,
+            })
+        };
+
+        assert!(matches!(
+            create_query_pool(QueryType::Timestamp, 0),
+            Err(Error::InvalidQueryPool(_))
+        ));
+        assert!(matches!(
+            create_query_pool(
+                QueryType::PipelineStatistics(PipelineStatisticsFlags::empty()),
+                4
+            ),
+            Err(Error::InvalidQueryPool(_))
+        ));
+        assert!(matches!(
+            create_query_pool(
+                QueryType::PipelineStatistics(PipelineStatisticsFlags::VERTEX_SHADER_INVOCATIONS),
+                4
+            ),
+            Err(Error::MissingFeatures(
+                PhysicalDeviceFeatures::PIPELINE_STATISTICS_QUERY
+            ))
+        ));
+
+        let timestamps = create_query_pool(QueryType::Timestamp, 4).unwrap();
+        let occlusion = create_query_pool(QueryType::Occlusion, 4).unwrap();
+
+        let record = |commands: &dyn Fn(&mut CommandList)| {
+            let mut command_list = device
+                .create_command_list(&CommandListDesc {
+                    queue_type: QueueType::Graphics,
+                    label: None,
+                })
+                .unwrap();
+            command_list.begin().unwrap();
+            commands(&mut command_list);
+            command_list.end()
+        };
+
+        assert!(record(&|cmd| {
+            cmd.reset_queries(&timestamps, 0, 4);
+            cmd.write_timestamp(&timestamps, 3);
+        })
+        .is_ok());
+        assert!(matches!(
+            record(&|cmd| cmd.reset_queries(&timestamps, 2, 3)),
+            Err(Error::NullBackend(NullError::Validation(_)))
+        ));
+        assert!(matches!(
+            record(&|cmd| cmd.reset_queries(&timestamps, u32::MAX, 2)),
+            Err(Error::NullBackend(NullError::Validation(_)))
+        ));
+        assert!(matches!(
+            record(&|cmd| cmd.write_timestamp(&occlusion, 0)),
+            Err(Error::NullBackend(NullError::Validation(_)))
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+#[cfg(feature = "metal")]
+use crate::metal::MetalQueue;
+#[cfg(feature = "null")]
+use crate::null::NullQueue;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanQueue;
+use crate::{CommandList, Error, Fence, SemaphoreValue};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum QueueType {
+    /// The direct queue, supporting graphics, compute and transfer work.
+    Graphics,
+    Compute,
+    Transfer,
+}
+
+#[derive(Clone, Default)]
+pub struct SubmitDesc<'a> {
+    /// Semaphore values the GPU waits for before executing the command lists.
+    pub wait_semaphores: &'a [SemaphoreValue<'a>],
+    /// Upload timeline value the GPU waits for before executing the command lists, as returned
+    /// by [`Device::upload_buffer`](crate::Device::upload_buffer), zero waits for nothing.
+    pub wait_upload: u64,
+    /// Command lists executed in order, each must have been recorded for this queue type.
+    pub command_lists: &'a [&'a CommandList],
+    /// Semaphore values signaled once the command lists have completed.
+    pub signal_semaphores: &'a [SemaphoreValue<'a>],
+    /// Fence signaled once the command lists have completed, it must be unsignaled.
+    pub fence: Option<&'a Fence>,
+}
+
+#[derive(Clone)]
+pub enum Queue {
+    #[cfg(feature = "metal")]
+    Metal(MetalQueue),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanQueue),
+    #[cfg(feature = "null")]
+    Null(NullQueue),
+}
+
+impl Queue {
+    #[inline]
+    pub fn queue_type(&self) -> QueueType {
+        match self {
+            #[cfg(feature = "metal")]
+            Queue::Metal(queue) => queue.queue_type(),
+            #[cfg(feature = "vulkan")]
+            Queue::Vulkan(queue) => queue.queue_type(),
+            #[cfg(feature = "null")]
+            Queue::Null(queue) => queue.queue_type(),
+        }
+    }
+
+    /// Returns `false` if this compute or transfer queue is an alias of the graphics queue.
+    #[inline]
+    pub fn is_dedicated(&self) -> bool {
+        match self {
+            #[cfg(feature = "metal")]
+            Queue::Metal(queue) => queue.is_dedicated(),
+            #[cfg(feature = "vulkan")]
+            Queue::Vulkan(queue) => queue.is_dedicated(),
+            #[cfg(feature = "null")]
+            Queue::Null(queue) => queue.is_dedicated(),
+        }
+    }
+
+    #[inline]
+    pub fn submit(&self, desc: &SubmitDesc) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Queue::Metal(queue) => Ok(queue.submit(desc)?),
+            #[cfg(feature = "vulkan")]
+            Queue::Vulkan(queue) => Ok(queue.submit(desc)?),
+            #[cfg(feature = "null")]
+            Queue::Null(queue) => Ok(queue.submit(desc)?),
+        }
+    }
+
+    /// Blocks until all work submitted to this queue has completed.
+    #[inline]
+    pub fn wait_idle(&self) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Queue::Metal(queue) => Ok(queue.wait_idle()?),
+            #[cfg(feature = "vulkan")]
+            Queue::Vulkan(queue) => Ok(queue.wait_idle()?),
+            #[cfg(feature = "null")]
+            Queue::Null(queue) => Ok(queue.wait_idle()?),
+        }
+    }
+}
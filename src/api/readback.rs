@@ -0,0 +1,200 @@
+use std::time::Duration;
+
+use crate::{Buffer, CommandList, Error, Extent3d, Fence, Origin3d, QueueType, Texture};
+
+/// Alignment of the rows of texture readbacks in readback memory, which is the optimal buffer
+/// copy row pitch of every supported backend. The padding is removed before the bytes are
+/// returned.
+pub(crate) const READBACK_ROW_PITCH_ALIGNMENT: u32 = 256;
+
+/// Copies `size` bytes of `buffer` at `offset` into readback memory.
+#[derive(Clone)]
+pub struct BufferRead<'a> {
+    pub buffer: &'a Buffer,
+    pub offset: u64,
+    pub size: u64,
+    /// The queue the copy is submitted to. Untracked buffers have to be owned by its queue
+    /// family.
+    pub queue_type: QueueType,
+}
+
+/// Copies a region of a single mip level and array layer of `texture` into readback memory.
+#[derive(Clone)]
+pub struct TextureRead<'a> {
+    pub texture: &'a Texture,
+    pub mip_level: u32,
+    pub array_layer: u32,
+    pub origin: Origin3d,
+    pub extent: Extent3d,
+    /// The queue the copy is submitted to, see [`BufferRead::queue_type`].
+    pub queue_type: QueueType,
+}
+
+/// A pending copy into readback memory, returned by
+/// [`Device::read_buffer`](crate::Device::read_buffer) and
+/// [`Device::read_texture`](crate::Device::read_texture).
+///
+/// Dropping it waits for the copy to complete.
+pub struct Readback {
+    buffer: Buffer,
+    fence: Fence,
+    /// Kept alive until the copy has completed.
+    _command_list: CommandList,
+
+    /// Size of a row in `buffer` and of a tightly packed row, equal for buffer reads.
+    padded_row_size: usize,
+    row_size: usize,
+}
+
+impl Readback {
+    pub(crate) fn new(
+        buffer: Buffer,
+        fence: Fence,
+        command_list: CommandList,
+        padded_row_size: usize,
+        row_size: usize,
+    ) -> Self {
+        Self {
+            buffer,
+            fence,
+            _command_list: command_list,
+
+            padded_row_size,
+            row_size,
+        }
+    }
+
+    /// Returns `true` once the copy has completed.
+    #[inline]
+    pub fn is_ready(&self) -> Result<bool, Error> {
+        self.fence.is_signaled()
+    }
+
+    /// Blocks until the copy has completed, returns `false` if `timeout` elapsed first.
+    ///
+    /// A `timeout` of `None` waits indefinitely.
+    #[inline]
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<bool, Error> {
+        self.fence.wait(timeout)
+    }
+
+    /// Waits for the copy and returns the bytes read, texture rows are tightly packed.
+    pub fn into_bytes(self) -> Result<Vec<u8>, Error> {
+        self.wait(None)?;
+
+        let mapped_ptr = self
+            .buffer
+            .mapped_ptr()
+            .expect("Readback buffers are host visible");
+        let padded =
+            unsafe { std::slice::from_raw_parts(mapped_ptr.as_ptr(), self.buffer.size() as usize) };
+
+        if self.padded_row_size == self.row_size {
+            return Ok(padded.to_vec());
+        }
+
+        Ok(padded
+            .chunks_exact(self.padded_row_size)
+            .flat_map(|row| &row[..self.row_size])
+            .copied()
+            .collect())
+    }
+}
+
+impl Drop for Readback {
+    fn drop(&mut self) {
+        let _ = self.fence.wait(None);
+    }
+}
+
+#[cfg(all(test, feature = "null"))]
+mod tests {
+    use super::*;
+    use crate::{
+        null::test_utils::{create_device, null_device},
+        BufferDesc, BufferUsageFlags, PhysicalDeviceFeatures, TextureDesc, TextureUsageFlags,
+    };
+
+    #[test]
+    fn readback_ranges_are_validated() {
+        let device = create_device(PhysicalDeviceFeatures::empty());
+        let buffer = device
+            .create_buffer(&BufferDesc {
+                size: 256,
+                usage: BufferUsageFlags::TRANSFER_SRC,
+                ..Default::default()
+            })
+            .unwrap();
+        let texture = device
+            .create_texture(&TextureDesc {
+                extent: Extent3d {
+                    width: 16,
+                    height: 16,
+                    depth: 1,
+                },
+                usage: TextureUsageFlags::TRANSFER_SRC,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let read_buffer = |offset, size| {
+            device.read_buffer(&BufferRead {
+                buffer: &buffer,
+                offset,
+                size,
+                queue_type: QueueType::Graphics,
+            })
+        };
+
+        assert_eq!(
+            read_buffer(64, 128).unwrap().into_bytes().unwrap().len(),
+            128
+        );
+        assert!(matches!(read_buffer(0, 0), Err(Error::InvalidCopy(_))));
+        assert!(matches!(read_buffer(192, 128), Err(Error::InvalidCopy(_))));
+        assert!(matches!(
+            read_buffer(u64::MAX, 4),
+            Err(Error::InvalidCopy(_))
+        ));
+
+        let read_texture = |origin, extent| {
+            device.read_texture(&TextureRead {
+                texture: &texture,
+                mip_level: 0,
+                array_layer: 0,
+                origin,
+                extent,
+                queue_type: QueueType::Graphics,
+            })
+        };
+        let extent = Extent3d {
+            width: 3,
+            height: 2,
+            depth: 1,
+        };
+
+        let bytes = read_texture(Origin3d { x: 13, y: 14, z: 0 }, extent)
+            .unwrap()
+            .into_bytes()
+            .unwrap();
+        assert_eq!(bytes.len(), 24);
+        assert!(matches!(
+            read_texture(Origin3d { x: 14, y: 0, z: 0 }, extent),
+            Err(Error::InvalidCopy(_))
+        ));
+        assert!(matches!(
+            read_texture(
+                Origin3d {
+                    x: 0,
+                    y: u32::MAX,
+                    z: 0
+                },
+                extent
+            ),
+            Err(Error::InvalidCopy(_))
+        ));
+
+        drop((buffer, texture));
+        assert_eq!(null_device(&device).live_object_count(), 0);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{QueryPool, TextureView};
+
+/// What happens to the contents of an attachment at the start of a render pass.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LoadOp {
+    #[default]
+    Load,
+    Clear,
+    /// The previous contents are undefined, cheapest when every pixel is overwritten.
+    DontCare,
+}
+
+/// What happens to the rendered contents of an attachment at the end of a render pass.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum StoreOp {
+    #[default]
+    Store,
+    /// The rendered contents are discarded, for example after resolving them.
+    DontCare,
+}
+
+#[derive(Clone)]
+pub struct ColorAttachment<'a> {
+    pub view: &'a TextureView,
+    pub load_op: LoadOp,
+    pub store_op: StoreOp,
+    /// Used if `load_op` is [`LoadOp::Clear`].
+    pub clear_color: [f32; 4],
+    /// Single sampled view the multisampled contents are resolved into at the end of the pass.
+    pub resolve_target: Option<&'a TextureView>,
+}
+
+#[derive(Clone)]
+pub struct DepthStencilAttachment<'a> {
+    pub view: &'a TextureView,
+    pub depth_load_op: LoadOp,
+    pub depth_store_op: StoreOp,
+    /// Used if `depth_load_op` is [`LoadOp::Clear`].
+    pub clear_depth: f32,
+    pub stencil_load_op: LoadOp,
+    pub stencil_store_op: StoreOp,
+    /// Used if `stencil_load_op` is [`LoadOp::Clear`].
+    pub clear_stencil: u32,
+    /// Single sampled view sample zero of the multisampled contents is resolved into.
+    pub resolve_target: Option<&'a TextureView>,
+}
+
+/// Describes the attachments of a render pass, they must all have the same extent.
+///
+/// The viewport and scissor cover the whole render area when the pass begins.
+#[derive(Clone, Default)]
+pub struct RenderPassDesc<'a> {
+    pub color_attachments: &'a [ColorAttachment<'a>],
+    pub depth_stencil_attachment: Option<DepthStencilAttachment<'a>>,
+    /// Pool of the occlusion queries begun inside the pass, Metal binds its results up front.
+    pub occlusion_query_pool: Option<&'a QueryPool>,
+    pub label: Option<&'a str>,
+}
+
+impl RenderPassDesc<'_> {
+    /// Returns the view every attachment extent is taken from.
+    pub(crate) fn first_view(&self) -> Option<&TextureView> {
+        self.color_attachments
+            .first()
+            .map(|color_attachment| color_attachment.view)
+            .or_else(|| {
+                self.depth_stencil_attachment
+                    .as_ref()
+                    .map(|depth_stencil_attachment| depth_stencil_attachment.view)
+            })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub min_depth: f32,
+    pub max_depth: f32,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ScissorRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
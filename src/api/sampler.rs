@@ -0,0 +1,297 @@
+use std::hash::{Hash, Hasher};
+
+#[cfg(feature = "metal")]
+use crate::metal::MetalSampler;
+#[cfg(feature = "null")]
+use crate::null::NullSampler;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanSampler;
+use crate::CompareOp;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FilterMode {
+    #[default]
+    Nearest,
+    Linear,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MipmapMode {
+    #[default]
+    Nearest,
+    Linear,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AddressMode {
+    Repeat,
+    MirroredRepeat,
+    #[default]
+    ClampToEdge,
+    /// Coordinates outside the texture return the [`SamplerDesc::border_color`].
+    ClampToBorder,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BorderColor {
+    #[default]
+    TransparentBlack,
+    OpaqueBlack,
+    OpaqueWhite,
+}
+
+/// How the texels of a filter footprint are combined.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SamplerReductionMode {
+    #[default]
+    WeightedAverage,
+    /// Component-wise minimum, requires
+    /// [`SAMPLER_FILTER_MINMAX`](crate::PhysicalDeviceFeatures::SAMPLER_FILTER_MINMAX).
+    Min,
+    /// Component-wise maximum, requires
+    /// [`SAMPLER_FILTER_MINMAX`](crate::PhysicalDeviceFeatures::SAMPLER_FILTER_MINMAX).
+    Max,
+}
+
+/// Devices return the same sampler for identical descriptors, so samplers carry no label.
+#[derive(Copy, Clone, Debug)]
+pub struct SamplerDesc {
+    pub mag_filter: FilterMode,
+    pub min_filter: FilterMode,
+    pub mipmap_mode: MipmapMode,
+    pub address_mode_u: AddressMode,
+    pub address_mode_v: AddressMode,
+    pub address_mode_w: AddressMode,
+    /// Values above one enable anisotropic filtering, which requires
+    /// [`SAMPLER_ANISOTROPY`](crate::PhysicalDeviceFeatures::SAMPLER_ANISOTROPY).
+    pub max_anisotropy: u32,
+    /// Makes the sampler a comparison sampler for depth textures.
+    pub compare: Option<CompareOp>,
+    pub lod_min_clamp: f32,
+    pub lod_max_clamp: f32,
+    pub border_color: BorderColor,
+    pub reduction_mode: SamplerReductionMode,
+}
+
+impl Default for SamplerDesc {
+    fn default() -> Self {
+        Self {
+            mag_filter: FilterMode::Nearest,
+            min_filter: FilterMode::Nearest,
+            mipmap_mode: MipmapMode::Nearest,
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            max_anisotropy: 1,
+            compare: None,
+            lod_min_clamp: 0.0,
+            lod_max_clamp: f32::MAX,
+            border_color: BorderColor::TransparentBlack,
+            reduction_mode: SamplerReductionMode::WeightedAverage,
+        }
+    }
+}
+
+impl SamplerDesc {
+    /// Returns every field as comparable bits, LOD clamps by their representation.
+    fn key(&self) -> impl Eq + Hash {
+        (
+            (self.mag_filter, self.min_filter, self.mipmap_mode),
+            (
+                self.address_mode_u,
+                self.address_mode_v,
+                self.address_mode_w,
+            ),
+            (self.max_anisotropy, self.compare),
+            (self.lod_min_clamp.to_bits(), self.lod_max_clamp.to_bits()),
+            (self.border_color, self.reduction_mode),
+        )
+    }
+}
+
+impl PartialEq for SamplerDesc {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for SamplerDesc {}
+
+impl Hash for SamplerDesc {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+/// Checks the parts of `desc` every backend agrees on.
+pub(crate) fn validate_sampler_desc(desc: &SamplerDesc) -> Result<(), String> {
+    if desc.max_anisotropy == 0 || desc.max_anisotropy > 16 {
+        return Err(format!(
+            "Max anisotropy {} must be between 1 and 16",
+            desc.max_anisotropy
+        ));
+    }
+    if desc.max_anisotropy > 1
+        && (desc.mag_filter != FilterMode::Linear
+            || desc.min_filter != FilterMode::Linear
+            || desc.mipmap_mode != MipmapMode::Linear)
+    {
+        return Err("Anisotropic filtering requires linear filters and mipmaps".to_owned());
+    }
+    if desc.lod_min_clamp.is_nan()
+        || desc.lod_max_clamp.is_nan()
+        || desc.lod_min_clamp < 0.0
+        || desc.lod_min_clamp > desc.lod_max_clamp
+    {
+        return Err(format!(
+            "LOD clamp {}..{} must be a non-negative range",
+            desc.lod_min_clamp, desc.lod_max_clamp
+        ));
+    }
+    if desc.compare.is_some() && desc.reduction_mode != SamplerReductionMode::WeightedAverage {
+        return Err("Comparison samplers cannot use a min or max reduction".to_owned());
+    }
+
+    Ok(())
+}
+
+/// Compares and hashes by identity, samplers created from identical descriptors are equal.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum Sampler {
+    #[cfg(feature = "metal")]
+    Metal(MetalSampler),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanSampler),
+    #[cfg(feature = "null")]
+    Null(NullSampler),
+}
+
+impl Sampler {
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalSampler {
+        match self {
+            Sampler::Metal(sampler) => sampler,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Sampler belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanSampler {
+        match self {
+            Sampler::Vulkan(sampler) => sampler,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Sampler belongs to a different backend"),
+        }
+    }
+
+    #[inline]
+    pub fn desc(&self) -> &SamplerDesc {
+        match self {
+            #[cfg(feature = "metal")]
+            Sampler::Metal(sampler) => sampler.desc(),
+            #[cfg(feature = "vulkan")]
+            Sampler::Vulkan(sampler) => sampler.desc(),
+            #[cfg(feature = "null")]
+            Sampler::Null(sampler) => sampler.desc(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "null"))]
+mod tests {
+    use super::*;
+    use crate::{
+        null::{
+            test_utils::{create_device, null_device},
+            NullError,
+        },
+        BackendType, DeviceDesc, Error, Instance, InstanceDesc, PhysicalDeviceFeatures,
+    };
+
+    #[test]
+    fn identical_samplers_are_shared() {
+        let device = create_device(PhysicalDeviceFeatures::empty());
+        let desc = SamplerDesc {
+            lod_max_clamp: 4.0,
+            ..Default::default()
+        };
+
+        let sampler = device.create_sampler(&desc).unwrap();
+        assert!(sampler == device.create_sampler(&desc).unwrap());
+        assert!(sampler != device.create_sampler(&SamplerDesc::default()).unwrap());
+        assert_eq!(null_device(&device).live_object_count_of("Sampler"), 2);
+    }
+
+    #[test]
+    fn invalid_samplers_fail_validation() {
+        let device = create_device(PhysicalDeviceFeatures::SAMPLER_FILTER_MINMAX);
+
+        let result = device.create_sampler(&SamplerDesc {
+            lod_min_clamp: 2.0,
+            lod_max_clamp: 1.0,
+            ..Default::default()
+        });
+        assert!(matches!(
+            result,
+            Err(Error::NullBackend(NullError::Validation(_)))
+        ));
+
+        let result = device.create_sampler(&SamplerDesc {
+            compare: Some(CompareOp::Less),
+            reduction_mode: SamplerReductionMode::Min,
+            ..Default::default()
+        });
+        assert!(matches!(
+            result,
+            Err(Error::NullBackend(NullError::Validation(_)))
+        ));
+
+        let result = device.create_sampler(&SamplerDesc {
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_mode: MipmapMode::Linear,
+            max_anisotropy: 16,
+            ..Default::default()
+        });
+        assert!(matches!(
+            result,
+            Err(Error::MissingFeatures(
+                PhysicalDeviceFeatures::SAMPLER_ANISOTROPY
+            ))
+        ));
+    }
+
+    #[test]
+    fn sampler_count_is_limited() {
+        let mut desc = InstanceDesc {
+            backend_type: BackendType::Null,
+            ..Default::default()
+        };
+        desc.null_physical_device.max_sampler_count = 1;
+        let instance = unsafe { Instance::new(&desc) }.unwrap();
+        let device = instance
+            .create_device(&DeviceDesc {
+                physical_device: instance.get_physical_devices()[0].clone(),
+                required_features: PhysicalDeviceFeatures::empty(),
+                optional_features: PhysicalDeviceFeatures::empty(),
+            })
+            .unwrap();
+
+        device.create_sampler(&SamplerDesc::default()).unwrap();
+        device.create_sampler(&SamplerDesc::default()).unwrap();
+
+        let result = device.create_sampler(&SamplerDesc {
+            lod_max_clamp: 0.0,
+            ..Default::default()
+        });
+        assert!(matches!(
+            result,
+            Err(Error::NullBackend(NullError::Validation(_)))
+        ));
+    }
+}
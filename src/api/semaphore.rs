@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+#[cfg(feature = "metal")]
+use crate::metal::MetalSemaphore;
+#[cfg(feature = "null")]
+use crate::null::NullSemaphore;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanSemaphore;
+use crate::Error;
+
+#[derive(Clone, Debug, Default)]
+pub struct SemaphoreDesc<'a> {
+    pub initial_value: u64,
+    pub label: Option<&'a str>,
+}
+
+/// A timeline semaphore together with a value to wait for or signal.
+#[derive(Clone, Copy)]
+pub struct SemaphoreValue<'a> {
+    pub semaphore: &'a Semaphore,
+    pub value: u64,
+}
+
+/// A timeline semaphore, its counter only ever increases.
+#[derive(Clone)]
+pub enum Semaphore {
+    #[cfg(feature = "metal")]
+    Metal(MetalSemaphore),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanSemaphore),
+    #[cfg(feature = "null")]
+    Null(NullSemaphore),
+}
+
+impl Semaphore {
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalSemaphore {
+        match self {
+            Semaphore::Metal(semaphore) => semaphore,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Semaphore belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanSemaphore {
+        match self {
+            Semaphore::Vulkan(semaphore) => semaphore,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Semaphore belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullSemaphore {
+        match self {
+            Semaphore::Null(semaphore) => semaphore,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Semaphore belongs to a different backend"),
+        }
+    }
+
+    #[inline]
+    pub fn get_value(&self) -> Result<u64, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Semaphore::Metal(semaphore) => Ok(semaphore.get_value()?),
+            #[cfg(feature = "vulkan")]
+            Semaphore::Vulkan(semaphore) => Ok(semaphore.get_value()?),
+            #[cfg(feature = "null")]
+            Semaphore::Null(semaphore) => Ok(semaphore.get_value()?),
+        }
+    }
+
+    /// Sets the counter from the CPU, `value` must be greater than the current value.
+    #[inline]
+    pub fn signal(&self, value: u64) -> Result<(), Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Semaphore::Metal(semaphore) => Ok(semaphore.signal(value)?),
+            #[cfg(feature = "vulkan")]
+            Semaphore::Vulkan(semaphore) => Ok(semaphore.signal(value)?),
+            #[cfg(feature = "null")]
+            Semaphore::Null(semaphore) => Ok(semaphore.signal(value)?),
+        }
+    }
+
+    /// Blocks until the counter reaches `value`, returns `false` if `timeout` elapsed first.
+    ///
+    /// A `timeout` of `None` waits indefinitely.
+    #[inline]
+    pub fn wait(&self, value: u64, timeout: Option<Duration>) -> Result<bool, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Semaphore::Metal(semaphore) => Ok(semaphore.wait(value, timeout)?),
+            #[cfg(feature = "vulkan")]
+            Semaphore::Vulkan(semaphore) => Ok(semaphore.wait(value, timeout)?),
+            #[cfg(feature = "null")]
+            Semaphore::Null(semaphore) => Ok(semaphore.wait(value, timeout)?),
+        }
+    }
+}
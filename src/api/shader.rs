@@ -0,0 +1,203 @@
+use bitflags::bitflags;
+
+#[cfg(feature = "metal")]
+use crate::metal::MetalShaderModule;
+#[cfg(feature = "null")]
+use crate::null::NullShaderModule;
+#[cfg(feature = "vulkan")]
+use crate::vulkan::VulkanShaderModule;
+
+bitflags! {
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct ShaderStageFlags : u32 {
+        const VERTEX = 1 << 0;
+        const FRAGMENT = 1 << 1;
+        const COMPUTE = 1 << 2;
+        const TASK = 1 << 3;
+        const MESH = 1 << 4;
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ShaderModuleDesc<'a> {
+    /// SPIR-V binary, its length must be a multiple of four.
+    pub code: &'a [u8],
+    /// Entry points the module must contain, an empty slice keeps every entry point.
+    pub entry_points: &'a [&'a str],
+    pub label: Option<&'a str>,
+}
+
+/// A 32-bit specialization constant value, matched to the shader's `constant_id`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpecializationConstant {
+    pub id: u32,
+    /// Raw bits of the value.
+    pub value: u32,
+}
+
+impl SpecializationConstant {
+    #[inline]
+    pub fn bool(id: u32, value: bool) -> Self {
+        Self {
+            id,
+            value: value as u32,
+        }
+    }
+
+    #[inline]
+    pub fn i32(id: u32, value: i32) -> Self {
+        Self {
+            id,
+            value: value as u32,
+        }
+    }
+
+    #[inline]
+    pub fn u32(id: u32, value: u32) -> Self {
+        Self { id, value }
+    }
+
+    #[inline]
+    pub fn f32(id: u32, value: f32) -> Self {
+        Self {
+            id,
+            value: value.to_bits(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DescriptorType {
+    Sampler,
+    SampledTexture,
+    StorageTexture,
+    CombinedTextureSampler,
+    UniformTexelBuffer,
+    StorageTexelBuffer,
+    UniformBuffer,
+    StorageBuffer,
+    AccelerationStructure,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DescriptorBinding {
+    pub set: u32,
+    pub binding: u32,
+    pub descriptor_type: DescriptorType,
+    /// Number of array elements, zero for runtime sized arrays.
+    pub count: u32,
+    pub stages: ShaderStageFlags,
+    pub name: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PushConstantRange {
+    pub stages: ShaderStageFlags,
+    pub offset: u32,
+    pub size: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ShaderScalarType {
+    Float16,
+    Float32,
+    Float64,
+    Sint16,
+    Sint32,
+    Sint64,
+    Uint16,
+    Uint32,
+    Uint64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct VertexInput {
+    pub location: u32,
+    pub scalar_type: ShaderScalarType,
+    pub component_count: u32,
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EntryPointReflection {
+    pub name: String,
+    pub stage: ShaderStageFlags,
+    /// Workgroup size of compute, task and mesh entry points.
+    pub workgroup_size: Option<[u32; 3]>,
+    /// Vertex shader inputs sorted by location.
+    pub vertex_inputs: Vec<VertexInput>,
+}
+
+/// Backend neutral description of a shader module's interface.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ShaderReflection {
+    pub entry_points: Vec<EntryPointReflection>,
+    /// Descriptor bindings sorted by set and binding, visible to every entry point's stage.
+    pub descriptor_bindings: Vec<DescriptorBinding>,
+    pub push_constant_ranges: Vec<PushConstantRange>,
+}
+
+impl ShaderReflection {
+    #[inline]
+    pub fn entry_point(&self, name: &str) -> Option<&EntryPointReflection> {
+        self.entry_points
+            .iter()
+            .find(|entry_point| entry_point.name == name)
+    }
+
+    /// Returns the union of all entry point stages.
+    #[inline]
+    pub fn stages(&self) -> ShaderStageFlags {
+        self.entry_points
+            .iter()
+            .fold(ShaderStageFlags::empty(), |stages, entry_point| {
+                stages | entry_point.stage
+            })
+    }
+}
+
+/// Compares and hashes by identity, clones of one shader module are equal.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum ShaderModule {
+    #[cfg(feature = "metal")]
+    Metal(MetalShaderModule),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanShaderModule),
+    #[cfg(feature = "null")]
+    Null(NullShaderModule),
+}
+
+impl ShaderModule {
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalShaderModule {
+        match self {
+            ShaderModule::Metal(shader_module) => shader_module,
+            #[allow(unreachable_patterns)]
+            _ => panic!("ShaderModule belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanShaderModule {
+        match self {
+            ShaderModule::Vulkan(shader_module) => shader_module,
+            #[allow(unreachable_patterns)]
+            _ => panic!("ShaderModule belongs to a different backend"),
+        }
+    }
+
+    #[inline]
+    pub fn reflection(&self) -> &ShaderReflection {
+        match self {
+            #[cfg(feature = "metal")]
+            ShaderModule::Metal(shader_module) => shader_module.reflection(),
+            #[cfg(feature = "vulkan")]
+            ShaderModule::Vulkan(shader_module) => shader_module.reflection(),
+            #[cfg(feature = "null")]
+            ShaderModule::Null(shader_module) => shader_module.reflection(),
+        }
+    }
+}
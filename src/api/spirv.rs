@@ -0,0 +1,1060 @@
+use std::collections::HashMap;
+
+use crate::{
+    DescriptorBinding, DescriptorType, EntryPointReflection, PushConstantRange, ShaderReflection,
+    ShaderScalarType, ShaderStageFlags, VertexInput,
+};
+
+const MAGIC: u32 = 0x0723_0203;
+const HEADER_WORDS: usize = 5;
+
+const OP_NAME: u32 = 5;
+const OP_ENTRY_POINT: u32 = 15;
+const OP_EXECUTION_MODE: u32 = 16;
+const OP_TYPE_BOOL: u32 = 20;
+const OP_TYPE_INT: u32 = 21;
+const OP_TYPE_FLOAT: u32 = 22;
+const OP_TYPE_VECTOR: u32 = 23;
+const OP_TYPE_MATRIX: u32 = 24;
+const OP_TYPE_IMAGE: u32 = 25;
+const OP_TYPE_SAMPLER: u32 = 26;
+const OP_TYPE_SAMPLED_IMAGE: u32 = 27;
+const OP_TYPE_ARRAY: u32 = 28;
+const OP_TYPE_RUNTIME_ARRAY: u32 = 29;
+const OP_TYPE_STRUCT: u32 = 30;
+const OP_TYPE_POINTER: u32 = 32;
+const OP_CONSTANT: u32 = 43;
+const OP_CONSTANT_COMPOSITE: u32 = 44;
+const OP_SPEC_CONSTANT: u32 = 50;
+const OP_SPEC_CONSTANT_COMPOSITE: u32 = 51;
+const OP_FUNCTION: u32 = 54;
+const OP_VARIABLE: u32 = 59;
+const OP_DECORATE: u32 = 71;
+const OP_MEMBER_DECORATE: u32 = 72;
+const OP_EXECUTION_MODE_ID: u32 = 331;
+const OP_TYPE_ACCELERATION_STRUCTURE: u32 = 5341;
+
+const EXECUTION_MODEL_VERTEX: u32 = 0;
+const EXECUTION_MODEL_FRAGMENT: u32 = 4;
+const EXECUTION_MODEL_GL_COMPUTE: u32 = 5;
+const EXECUTION_MODEL_TASK_NV: u32 = 5267;
+const EXECUTION_MODEL_MESH_NV: u32 = 5268;
+const EXECUTION_MODEL_TASK_EXT: u32 = 5364;
+const EXECUTION_MODEL_MESH_EXT: u32 = 5365;
+
+const EXECUTION_MODE_LOCAL_SIZE: u32 = 17;
+const EXECUTION_MODE_LOCAL_SIZE_ID: u32 = 38;
+
+const DECORATION_BLOCK: u32 = 2;
+const DECORATION_BUFFER_BLOCK: u32 = 3;
+const DECORATION_ARRAY_STRIDE: u32 = 6;
+const DECORATION_MATRIX_STRIDE: u32 = 7;
+const DECORATION_BUILT_IN: u32 = 11;
+const DECORATION_LOCATION: u32 = 30;
+const DECORATION_BINDING: u32 = 33;
+const DECORATION_DESCRIPTOR_SET: u32 = 34;
+const DECORATION_OFFSET: u32 = 35;
+
+const BUILT_IN_WORKGROUP_SIZE: u32 = 25;
+
+const STORAGE_CLASS_UNIFORM_CONSTANT: u32 = 0;
+const STORAGE_CLASS_INPUT: u32 = 1;
+const STORAGE_CLASS_UNIFORM: u32 = 2;
+const STORAGE_CLASS_PUSH_CONSTANT: u32 = 9;
+const STORAGE_CLASS_STORAGE_BUFFER: u32 = 12;
+
+const DIM_BUFFER: u32 = 5;
+
+enum Type {
+    Bool,
+    Int { width: u32, signed: bool },
+    Float { width: u32 },
+    Vector { component: u32, count: u32 },
+    Matrix { column: u32, count: u32 },
+    Image { dim: u32, sampled: u32 },
+    Sampler,
+    SampledImage,
+    Array { element: u32, length: u32 },
+    RuntimeArray { element: u32 },
+    Struct { members: Vec<u32> },
+    Pointer { pointee: u32 },
+    AccelerationStructure,
+}
+
+#[derive(Default)]
+struct Decorations {
+    block: bool,
+    buffer_block: bool,
+    array_stride: Option<u32>,
+    matrix_stride: Option<u32>,
+    built_in: Option<u32>,
+    location: Option<u32>,
+    binding: Option<u32>,
+    descriptor_set: Option<u32>,
+    offset: Option<u32>,
+}
+
+impl Decorations {
+    fn apply(&mut self, decoration: u32, literals: &[u32]) {
+        let literal = literals.first().copied();
+
+        match decoration {
+            DECORATION_BLOCK => self.block = true,
+            DECORATION_BUFFER_BLOCK => self.buffer_block = true,
+            DECORATION_ARRAY_STRIDE => self.array_stride = literal,
+            DECORATION_MATRIX_STRIDE => self.matrix_stride = literal,
+            DECORATION_BUILT_IN => self.built_in = literal,
+            DECORATION_LOCATION => self.location = literal,
+            DECORATION_BINDING => self.binding = literal,
+            DECORATION_DESCRIPTOR_SET => self.descriptor_set = literal,
+            DECORATION_OFFSET => self.offset = literal,
+            _ => {}
+        }
+    }
+}
+
+struct EntryPoint {
+    execution_model: u32,
+    function: u32,
+    name: String,
+    interface: Vec<u32>,
+}
+
+struct Variable {
+    id: u32,
+    type_id: u32,
+    storage_class: u32,
+}
+
+#[derive(Default)]
+struct Module {
+    names: HashMap<u32, String>,
+    types: HashMap<u32, Type>,
+    constants: HashMap<u32, u32>,
+    composites: HashMap<u32, Vec<u32>>,
+    decorations: HashMap<u32, Decorations>,
+    member_decorations: HashMap<(u32, u32), Decorations>,
+    variables: Vec<Variable>,
+    entry_points: Vec<EntryPoint>,
+    local_sizes: HashMap<u32, [u32; 3]>,
+    local_size_ids: HashMap<u32, [u32; 3]>,
+}
+
+/// Decodes a nul terminated literal string, returning it and the number of words it occupies.
+fn parse_string(words: &[u32]) -> Result<(String, usize), String> {
+    let mut bytes = Vec::new();
+
+    for (i, word) in words.iter().enumerate() {
+        for byte in word.to_le_bytes() {
+            if byte == 0 {
+                let string = String::from_utf8(bytes)
+                    .map_err(|_| "Literal string is not valid UTF-8".to_owned())?;
+                return Ok((string, i + 1));
+            }
+
+            bytes.push(byte);
+        }
+    }
+
+    Err("Literal string is not nul terminated".to_owned())
+}
+
+fn operands_from(operands: &[u32], index: usize) -> &[u32] {
+    operands.get(index..).unwrap_or_default()
+}
+
+fn operand(operands: &[u32], index: usize) -> Result<u32, String> {
+    operands
+        .get(index)
+        .copied()
+        .ok_or_else(|| "Instruction is missing operands".to_owned())
+}
+
+/// Converts SPIR-V bytes into words, swapping the byte order if the module is big endian.
+pub(crate) fn spirv_to_words(code: &[u8]) -> Result<Vec<u32>, String> {
+    if !code.len().is_multiple_of(4) {
+        return Err("SPIR-V size must be a multiple of four".to_owned());
+    }
+
+    let mut words = code
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+        .collect::<Vec<_>>();
+
+    match words.first() {
+        Some(&MAGIC) => {}
+        Some(magic) if magic.swap_bytes() == MAGIC => {
+            for word in &mut words {
+                *word = word.swap_bytes();
+            }
+        }
+        _ => return Err("Missing SPIR-V magic number".to_owned()),
+    }
+
+    if words.len() < HEADER_WORDS {
+        return Err("Truncated SPIR-V header".to_owned());
+    }
+
+    Ok(words)
+}
+
+impl Module {
+    fn parse(words: &[u32]) -> Result<Self, String> {
+        let mut module = Self::default();
+
+        let mut offset = HEADER_WORDS;
+        while offset < words.len() {
+            let word_count = (words[offset] >> 16) as usize;
+            let opcode = words[offset] & 0xffff;
+
+            if word_count == 0 || offset + word_count > words.len() {
+                return Err(format!("Truncated instruction at word {offset}"));
+            }
+
+            let operands = &words[offset + 1..offset + word_count];
+            offset += word_count;
+
+            match opcode {
+                OP_NAME => {
+                    let (name, _) = parse_string(operands_from(operands, 1))?;
+                    module.names.insert(operand(operands, 0)?, name);
+                }
+                OP_ENTRY_POINT => {
+                    let (name, name_words) = parse_string(operands_from(operands, 2))?;
+
+                    module.entry_points.push(EntryPoint {
+                        execution_model: operand(operands, 0)?,
+                        function: operand(operands, 1)?,
+                        name,
+                        interface: operands[2 + name_words..].to_vec(),
+                    });
+                }
+                OP_EXECUTION_MODE | OP_EXECUTION_MODE_ID => {
+                    let mode = operand(operands, 1)?;
+                    let size = [
+                        operand(operands, 2).unwrap_or(1),
+                        operand(operands, 3).unwrap_or(1),
+                        operand(operands, 4).unwrap_or(1),
+                    ];
+
+                    match mode {
+                        EXECUTION_MODE_LOCAL_SIZE => {
+                            module.local_sizes.insert(operand(operands, 0)?, size);
+                        }
+                        EXECUTION_MODE_LOCAL_SIZE_ID => {
+                            module.local_size_ids.insert(operand(operands, 0)?, size);
+                        }
+                        _ => {}
+                    }
+                }
+                OP_TYPE_BOOL => {
+                    module.types.insert(operand(operands, 0)?, Type::Bool);
+                }
+                OP_TYPE_INT => {
+                    module.types.insert(
+                        operand(operands, 0)?,
+                        Type::Int {
+                            width: operand(operands, 1)?,
+                            signed: operand(operands, 2)? != 0,
+                        },
+                    );
+                }
+                OP_TYPE_FLOAT => {
+                    module.types.insert(
+                        operand(operands, 0)?,
+                        Type::Float {
+                            width: operand(operands, 1)?,
+                        },
+                    );
+                }
+                OP_TYPE_VECTOR => {
+                    module.types.insert(
+                        operand(operands, 0)?,
+                        Type::Vector {
+                            component: operand(operands, 1)?,
+                            count: operand(operands, 2)?,
+                        },
+                    );
+                }
+                OP_TYPE_MATRIX => {
+                    module.types.insert(
+                        operand(operands, 0)?,
+                        Type::Matrix {
+                            column: operand(operands, 1)?,
+                            count: operand(operands, 2)?,
+                        },
+                    );
+                }
+                OP_TYPE_IMAGE => {
+                    module.types.insert(
+                        operand(operands, 0)?,
+                        Type::Image {
+                            dim: operand(operands, 2)?,
+                            sampled: operand(operands, 6)?,
+                        },
+                    );
+                }
+                OP_TYPE_SAMPLER => {
+                    module.types.insert(operand(operands, 0)?, Type::Sampler);
+                }
+                OP_TYPE_SAMPLED_IMAGE => {
+                    module
+                        .types
+                        .insert(operand(operands, 0)?, Type::SampledImage);
+                }
+                OP_TYPE_ARRAY => {
+                    module.types.insert(
+                        operand(operands, 0)?,
+                        Type::Array {
+                            element: operand(operands, 1)?,
+                            length: operand(operands, 2)?,
+                        },
+                    );
+                }
+                OP_TYPE_RUNTIME_ARRAY => {
+                    module.types.insert(
+                        operand(operands, 0)?,
+                        Type::RuntimeArray {
+                            element: operand(operands, 1)?,
+                        },
+                    );
+                }
+                OP_TYPE_STRUCT => {
+                    module.types.insert(
+                        operand(operands, 0)?,
+                        Type::Struct {
+                            members: operands_from(operands, 1).to_vec(),
+                        },
+                    );
+                }
+                OP_TYPE_POINTER => {
+                    module.types.insert(
+                        operand(operands, 0)?,
+                        Type::Pointer {
+                            pointee: operand(operands, 2)?,
+                        },
+                    );
+                }
+                OP_TYPE_ACCELERATION_STRUCTURE => {
+                    module
+                        .types
+                        .insert(operand(operands, 0)?, Type::AccelerationStructure);
+                }
+                OP_CONSTANT | OP_SPEC_CONSTANT => {
+                    module
+                        .constants
+                        .insert(operand(operands, 1)?, operand(operands, 2)?);
+                }
+                OP_CONSTANT_COMPOSITE | OP_SPEC_CONSTANT_COMPOSITE => {
+                    module
+                        .composites
+                        .insert(operand(operands, 1)?, operands_from(operands, 2).to_vec());
+                }
+                OP_VARIABLE => {
+                    module.variables.push(Variable {
+                        id: operand(operands, 1)?,
+                        type_id: operand(operands, 0)?,
+                        storage_class: operand(operands, 2)?,
+                    });
+                }
+                OP_DECORATE => {
+                    module
+                        .decorations
+                        .entry(operand(operands, 0)?)
+                        .or_default()
+                        .apply(operand(operands, 1)?, operands_from(operands, 2));
+                }
+                OP_MEMBER_DECORATE => {
+                    module
+                        .member_decorations
+                        .entry((operand(operands, 0)?, operand(operands, 1)?))
+                        .or_default()
+                        .apply(operand(operands, 2)?, operands_from(operands, 3));
+                }
+                // Global declarations end where the first function begins.
+                OP_FUNCTION => break,
+                _ => {}
+            }
+        }
+
+        Ok(module)
+    }
+
+    fn get_type(&self, id: u32) -> Result<&Type, String> {
+        self.types
+            .get(&id)
+            .ok_or_else(|| format!("Unknown type %{id}"))
+    }
+
+    fn get_constant(&self, id: u32) -> Result<u32, String> {
+        self.constants
+            .get(&id)
+            .copied()
+            .ok_or_else(|| format!("Unknown constant %{id}"))
+    }
+
+    fn get_name(&self, id: u32) -> Option<String> {
+        self.names.get(&id).filter(|name| !name.is_empty()).cloned()
+    }
+
+    fn decorations(&self, id: u32) -> Option<&Decorations> {
+        self.decorations.get(&id)
+    }
+
+    /// Returns the size in bytes of a type as laid out in a buffer block.
+    fn size_of(&self, id: u32, matrix_stride: Option<u32>) -> Result<u32, String> {
+        Ok(match self.get_type(id)? {
+            Type::Bool => 4,
+            Type::Int { width, .. } | Type::Float { width } => width / 8,
+            Type::Vector { component, count } => self.size_of(*component, None)? * count,
+            Type::Matrix { column, count } => {
+                match matrix_stride {
+                    Some(stride) => stride * count,
+                    None => self.size_of(*column, None)? * count,
+                }
+            }
+            Type::Array { element, length } => {
+                let stride = match self.decorations(id).and_then(|d| d.array_stride) {
+                    Some(stride) => stride,
+                    None => self.size_of(*element, matrix_stride)?,
+                };
+
+                stride * self.get_constant(*length)?
+            }
+            Type::RuntimeArray { .. } => 0,
+            Type::Struct { members } => {
+                let mut size = 0;
+
+                for (i, member) in members.iter().enumerate() {
+                    let decorations = self.member_decorations.get(&(id, i as u32));
+                    let offset = decorations.and_then(|d| d.offset).unwrap_or(size);
+                    let member_size =
+                        self.size_of(*member, decorations.and_then(|d| d.matrix_stride))?;
+
+                    size = size.max(offset + member_size);
+                }
+
+                size
+            }
+            _ => return Err(format!("Type %{id} has no size")),
+        })
+    }
+
+    fn scalar_type(&self, id: u32) -> Result<(ShaderScalarType, u32), String> {
+        let scalar_type = match self.get_type(id)? {
+            Type::Vector { component, count } => {
+                let (scalar_type, _) = self.scalar_type(*component)?;
+                return Ok((scalar_type, *count));
+            }
+            Type::Float { width: 16 } => ShaderScalarType::Float16,
+            Type::Float { width: 32 } => ShaderScalarType::Float32,
+            Type::Float { width: 64 } => ShaderScalarType::Float64,
+            Type::Int {
+                width: 16,
+                signed: true,
+            } => ShaderScalarType::Sint16,
+            Type::Int {
+                width: 32,
+                signed: true,
+            } => ShaderScalarType::Sint32,
+            Type::Int {
+                width: 64,
+                signed: true,
+            } => ShaderScalarType::Sint64,
+            Type::Int {
+                width: 16,
+                signed: false,
+            } => ShaderScalarType::Uint16,
+            Type::Int {
+                width: 32,
+                signed: false,
+            } => ShaderScalarType::Uint32,
+            Type::Int {
+                width: 64,
+                signed: false,
+            } => ShaderScalarType::Uint64,
+            _ => return Err(format!("Type %{id} is not a scalar or vector")),
+        };
+
+        Ok((scalar_type, 1))
+    }
+
+    fn workgroup_size(&self, entry_point: &EntryPoint) -> Result<Option<[u32; 3]>, String> {
+        // A constant decorated with the WorkgroupSize built-in overrides the execution mode.
+        for (id, decorations) in &self.decorations {
+            if decorations.built_in == Some(BUILT_IN_WORKGROUP_SIZE) {
+                if let Some(constituents) = self.composites.get(id) {
+                    return Ok(Some([
+                        self.get_constant(operand(constituents, 0)?)?,
+                        self.get_constant(operand(constituents, 1)?)?,
+                        self.get_constant(operand(constituents, 2)?)?,
+                    ]));
+                }
+            }
+        }
+
+        if let Some(size) = self.local_sizes.get(&entry_point.function) {
+            return Ok(Some(*size));
+        }
+
+        if let Some(ids) = self.local_size_ids.get(&entry_point.function) {
+            return Ok(Some([
+                self.get_constant(ids[0])?,
+                self.get_constant(ids[1])?,
+                self.get_constant(ids[2])?,
+            ]));
+        }
+
+        Ok(None)
+    }
+
+    fn vertex_inputs(&self, entry_point: &EntryPoint) -> Result<Vec<VertexInput>, String> {
+        let mut vertex_inputs = Vec::new();
+
+        for variable in &self.variables {
+            if variable.storage_class != STORAGE_CLASS_INPUT
+                || !entry_point.interface.contains(&variable.id)
+            {
+                continue;
+            }
+
+            let Some(location) = self.decorations(variable.id).and_then(|d| d.location) else {
+                continue;
+            };
+
+            let Type::Pointer { pointee, .. } = self.get_type(variable.type_id)? else {
+                return Err(format!("Variable %{} is not a pointer", variable.id));
+            };
+
+            let (scalar_type, component_count) = self.scalar_type(*pointee)?;
+
+            vertex_inputs.push(VertexInput {
+                location,
+                scalar_type,
+                component_count,
+                name: self.get_name(variable.id),
+            });
+        }
+
+        vertex_inputs.sort_by_key(|vertex_input| vertex_input.location);
+
+        Ok(vertex_inputs)
+    }
+
+    fn descriptor_type(
+        &self,
+        id: u32,
+        storage_class: u32,
+    ) -> Result<Option<DescriptorType>, String> {
+        Ok(Some(match self.get_type(id)? {
+            Type::Sampler => DescriptorType::Sampler,
+            Type::SampledImage => DescriptorType::CombinedTextureSampler,
+            Type::Image {
+                dim: DIM_BUFFER,
+                sampled: 2,
+            } => DescriptorType::StorageTexelBuffer,
+            Type::Image {
+                dim: DIM_BUFFER, ..
+            } => DescriptorType::UniformTexelBuffer,
+            Type::Image { sampled: 2, .. } => DescriptorType::StorageTexture,
+            Type::Image { .. } => DescriptorType::SampledTexture,
+            Type::AccelerationStructure => DescriptorType::AccelerationStructure,
+            Type::Struct { .. } if storage_class == STORAGE_CLASS_STORAGE_BUFFER => {
+                DescriptorType::StorageBuffer
+            }
+            Type::Struct { .. }
+                if self
+                    .decorations(id)
+                    .is_some_and(|decorations| decorations.buffer_block) =>
+            {
+                DescriptorType::StorageBuffer
+            }
+            Type::Struct { .. } => DescriptorType::UniformBuffer,
+            _ => return Ok(None),
+        }))
+    }
+
+    fn descriptor_bindings(
+        &self,
+        stages: ShaderStageFlags,
+    ) -> Result<Vec<DescriptorBinding>, String> {
+        let mut descriptor_bindings: Vec<DescriptorBinding> = Vec::new();
+
+        for variable in &self.variables {
+            if !matches!(
+                variable.storage_class,
+                STORAGE_CLASS_UNIFORM_CONSTANT
+                    | STORAGE_CLASS_UNIFORM
+                    | STORAGE_CLASS_STORAGE_BUFFER
+            ) {
+                continue;
+            }
+
+            let Some(decorations) = self.decorations(variable.id) else {
+                continue;
+            };
+            let (Some(set), Some(binding)) = (decorations.descriptor_set, decorations.binding)
+            else {
+                continue;
+            };
+
+            let Type::Pointer { pointee, .. } = self.get_type(variable.type_id)? else {
+                return Err(format!("Variable %{} is not a pointer", variable.id));
+            };
+
+            let (element, count) = match self.get_type(*pointee)? {
+                Type::Array { element, length } => (*element, self.get_constant(*length)?),
+                Type::RuntimeArray { element } => (*element, 0),
+                _ => (*pointee, 1),
+            };
+
+            let Some(descriptor_type) = self.descriptor_type(element, variable.storage_class)?
+            else {
+                continue;
+            };
+
+            // Aliased variables share a binding, the first declaration wins.
+            if descriptor_bindings
+                .iter()
+                .any(|b| b.set == set && b.binding == binding)
+            {
+                continue;
+            }
+
+            descriptor_bindings.push(DescriptorBinding {
+                set,
+                binding,
+                descriptor_type,
+                count,
+                stages,
+                name: self
+                    .get_name(variable.id)
+                    .or_else(|| self.get_name(element)),
+            });
+        }
+
+        descriptor_bindings.sort_by_key(|b| (b.set, b.binding));
+
+        Ok(descriptor_bindings)
+    }
+
+    fn push_constant_ranges(
+        &self,
+        stages: ShaderStageFlags,
+    ) -> Result<Vec<PushConstantRange>, String> {
+        let mut push_constant_ranges = Vec::new();
+
+        for variable in &self.variables {
+            if variable.storage_class != STORAGE_CLASS_PUSH_CONSTANT {
+                continue;
+            }
+
+            let Type::Pointer { pointee, .. } = self.get_type(variable.type_id)? else {
+                return Err(format!("Variable %{} is not a pointer", variable.id));
+            };
+            let Type::Struct { members } = self.get_type(*pointee)? else {
+                return Err(format!("Push constant %{} is not a struct", variable.id));
+            };
+
+            let offset = (0..members.len() as u32)
+                .filter_map(|i| {
+                    self.member_decorations
+                        .get(&(*pointee, i))
+                        .and_then(|d| d.offset)
+                })
+                .min()
+                .unwrap_or(0);
+            let size = self.size_of(*pointee, None)?;
+
+            push_constant_ranges.push(PushConstantRange {
+                stages,
+                offset,
+                size: size - offset,
+            });
+        }
+
+        Ok(push_constant_ranges)
+    }
+}
+
+fn to_shader_stage(execution_model: u32) -> Option<ShaderStageFlags> {
+    Some(match execution_model {
+        EXECUTION_MODEL_VERTEX => ShaderStageFlags::VERTEX,
+        EXECUTION_MODEL_FRAGMENT => ShaderStageFlags::FRAGMENT,
+        EXECUTION_MODEL_GL_COMPUTE => ShaderStageFlags::COMPUTE,
+        EXECUTION_MODEL_TASK_NV | EXECUTION_MODEL_TASK_EXT => ShaderStageFlags::TASK,
+        EXECUTION_MODEL_MESH_NV | EXECUTION_MODEL_MESH_EXT => ShaderStageFlags::MESH,
+        _ => return None,
+    })
+}
+
+/// Reflects the interface of a SPIR-V module, keeping only `entry_points` unless it is empty.
+///
+/// Without `entry_points`, entry points of unsupported execution models such as geometry or ray
+/// tracing shaders are skipped, requesting one of them is an error.
+pub(crate) fn reflect_spirv(
+    words: &[u32],
+    entry_points: &[&str],
+) -> Result<ShaderReflection, String> {
+    let module = Module::parse(words)?;
+
+    for name in entry_points {
+        if !module.entry_points.iter().any(|e| e.name == *name) {
+            return Err(format!("Entry point {name} not found"));
+        }
+    }
+
+    let mut reflected_entry_points = Vec::new();
+
+    for entry_point in &module.entry_points {
+        if !entry_points.is_empty() && !entry_points.contains(&entry_point.name.as_str()) {
+            continue;
+        }
+
+        let Some(stage) = to_shader_stage(entry_point.execution_model) else {
+            if entry_points.is_empty() {
+                continue;
+            }
+
+            return Err(format!(
+                "Entry point {} has unsupported execution model {}",
+                entry_point.name, entry_point.execution_model
+            ));
+        };
+
+        let workgroup_size = if stage
+            .intersects(ShaderStageFlags::COMPUTE | ShaderStageFlags::TASK | ShaderStageFlags::MESH)
+        {
+            module.workgroup_size(entry_point)?
+        } else {
+            None
+        };
+
+        let vertex_inputs = if stage == ShaderStageFlags::VERTEX {
+            module.vertex_inputs(entry_point)?
+        } else {
+            Vec::new()
+        };
+
+        reflected_entry_points.push(EntryPointReflection {
+            name: entry_point.name.clone(),
+            stage,
+            workgroup_size,
+            vertex_inputs,
+        });
+    }
+
+    let stages = reflected_entry_points
+        .iter()
+        .fold(ShaderStageFlags::empty(), |stages, e| stages | e.stage);
+
+    Ok(ShaderReflection {
+        entry_points: reflected_entry_points,
+        descriptor_bindings: module.descriptor_bindings(stages)?,
+        push_constant_ranges: module.push_constant_ranges(stages)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OP_CAPABILITY: u32 = 17;
+    const OP_MEMORY_MODEL: u32 = 14;
+    const OP_TYPE_VOID: u32 = 19;
+    const OP_TYPE_FUNCTION: u32 = 33;
+    const OP_FUNCTION_END: u32 = 56;
+    const OP_LABEL: u32 = 248;
+    const OP_RETURN: u32 = 253;
+
+    const EXECUTION_MODEL_GEOMETRY: u32 = 3;
+    const DECORATION_BUILT_IN_VERTEX_INDEX: u32 = 42;
+
+    fn instruction(opcode: u32, operands: &[u32]) -> Vec<u32> {
+        let word_count = operands.len() as u32 + 1;
+
+        [&[word_count << 16 | opcode], operands].concat()
+    }
+
+    /// Encodes a nul terminated literal string padded to whole words.
+    fn string(value: &str) -> Vec<u32> {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize(value.len() / 4 * 4 + 4, 0);
+
+        bytes
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap()))
+            .collect()
+    }
+
+    fn name(id: u32, value: &str) -> Vec<u32> {
+        instruction(OP_NAME, &[&[id], &string(value)[..]].concat())
+    }
+
+    fn entry_point(
+        execution_model: u32,
+        function: u32,
+        value: &str,
+        interface: &[u32],
+    ) -> Vec<u32> {
+        instruction(
+            OP_ENTRY_POINT,
+            &[&[execution_model, function], &string(value)[..], interface].concat(),
+        )
+    }
+
+    fn decorate(id: u32, decoration: u32, literals: &[u32]) -> Vec<u32> {
+        instruction(OP_DECORATE, &[&[id, decoration], literals].concat())
+    }
+
+    /// Assembles a module around `declarations`, with an empty function `%1` and the ids from
+    /// `%100` on reserved for it.
+    fn module(declarations: &[Vec<u32>]) -> Vec<u32> {
+        let mut words = vec![MAGIC, 0x0001_0500, 0, 200, 0];
+
+        words.extend(instruction(OP_CAPABILITY, &[1]));
+        words.extend(instruction(OP_MEMORY_MODEL, &[0, 1]));
+        words.extend(declarations.concat());
+        words.extend(instruction(OP_TYPE_VOID, &[100]));
+        words.extend(instruction(OP_TYPE_FUNCTION, &[101, 100]));
+        words.extend(instruction(OP_FUNCTION, &[100, 1, 0, 101]));
+        words.extend(instruction(OP_LABEL, &[102]));
+        words.extend(instruction(OP_RETURN, &[]));
+        words.extend(instruction(OP_FUNCTION_END, &[]));
+
+        words
+    }
+
+    fn compute_module(declarations: &[Vec<u32>]) -> Vec<u32> {
+        module(
+            &[
+                &[entry_point(EXECUTION_MODEL_GL_COMPUTE, 1, "main", &[])][..],
+                declarations,
+            ]
+            .concat(),
+        )
+    }
+
+    fn to_bytes(words: &[u32], encode: fn(u32) -> [u8; 4]) -> Vec<u8> {
+        words.iter().flat_map(|word| encode(*word)).collect()
+    }
+
+    #[test]
+    fn descriptor_bindings_are_reflected() {
+        let words = compute_module(&[
+            instruction(OP_EXECUTION_MODE, &[1, EXECUTION_MODE_LOCAL_SIZE, 8, 4, 1]),
+            name(8, "textures"),
+            name(11, "bindless"),
+            name(12, "Globals"),
+            decorate(8, DECORATION_DESCRIPTOR_SET, &[0]),
+            decorate(8, DECORATION_BINDING, &[1]),
+            decorate(11, DECORATION_DESCRIPTOR_SET, &[1]),
+            decorate(11, DECORATION_BINDING, &[0]),
+            decorate(12, DECORATION_BLOCK, &[]),
+            decorate(14, DECORATION_DESCRIPTOR_SET, &[0]),
+            decorate(14, DECORATION_BINDING, &[0]),
+            decorate(15, DECORATION_ARRAY_STRIDE, &[4]),
+            decorate(16, DECORATION_BLOCK, &[]),
+            decorate(18, DECORATION_DESCRIPTOR_SET, &[0]),
+            decorate(18, DECORATION_BINDING, &[2]),
+            instruction(OP_TYPE_FLOAT, &[2, 32]),
+            instruction(OP_TYPE_INT, &[3, 32, 0]),
+            instruction(OP_CONSTANT, &[3, 4, 4]),
+            // A sampled 2D image.
+            instruction(OP_TYPE_IMAGE, &[5, 2, 1, 0, 0, 0, 1, 0]),
+            instruction(OP_TYPE_ARRAY, &[6, 5, 4]),
+            instruction(OP_TYPE_POINTER, &[7, STORAGE_CLASS_UNIFORM_CONSTANT, 6]),
+            instruction(OP_VARIABLE, &[7, 8, STORAGE_CLASS_UNIFORM_CONSTANT]),
+            instruction(OP_TYPE_RUNTIME_ARRAY, &[9, 5]),
+            instruction(OP_TYPE_POINTER, &[10, STORAGE_CLASS_UNIFORM_CONSTANT, 9]),
+            instruction(OP_VARIABLE, &[10, 11, STORAGE_CLASS_UNIFORM_CONSTANT]),
+            instruction(OP_TYPE_STRUCT, &[12, 2, 3]),
+            instruction(OP_TYPE_POINTER, &[13, STORAGE_CLASS_UNIFORM, 12]),
+            instruction(OP_VARIABLE, &[13, 14, STORAGE_CLASS_UNIFORM]),
+            instruction(OP_TYPE_RUNTIME_ARRAY, &[15, 2]),
+            instruction(OP_TYPE_STRUCT, &[16, 15]),
+            instruction(OP_TYPE_POINTER, &[17, STORAGE_CLASS_STORAGE_BUFFER, 16]),
+            instruction(OP_VARIABLE, &[17, 18, STORAGE_CLASS_STORAGE_BUFFER]),
+        ]);
+
+        let reflection = reflect_spirv(&words, &[]).unwrap();
+        let binding = |set, binding, descriptor_type, count, name: Option<&str>| {
+            DescriptorBinding {
+                set,
+                binding,
+                descriptor_type,
+                count,
+                stages: ShaderStageFlags::COMPUTE,
+                name: name.map(str::to_owned),
+            }
+        };
+
+        assert_eq!(
+            reflection.descriptor_bindings,
+            [
+                binding(0, 0, DescriptorType::UniformBuffer, 1, Some("Globals")),
+                binding(0, 1, DescriptorType::SampledTexture, 4, Some("textures")),
+                binding(0, 2, DescriptorType::StorageBuffer, 1, None),
+                binding(1, 0, DescriptorType::SampledTexture, 0, Some("bindless")),
+            ]
+        );
+        assert_eq!(reflection.entry_points[0].workgroup_size, Some([8, 4, 1]));
+    }
+
+    #[test]
+    fn push_constant_ranges_start_at_the_first_member() {
+        let words = compute_module(&[
+            decorate(5, DECORATION_BLOCK, &[]),
+            instruction(OP_MEMBER_DECORATE, &[5, 0, DECORATION_OFFSET, 16]),
+            instruction(OP_MEMBER_DECORATE, &[5, 1, DECORATION_OFFSET, 32]),
+            instruction(OP_TYPE_FLOAT, &[2, 32]),
+            instruction(OP_TYPE_INT, &[3, 32, 0]),
+            instruction(OP_TYPE_VECTOR, &[4, 2, 4]),
+            instruction(OP_TYPE_STRUCT, &[5, 4, 3]),
+            instruction(OP_TYPE_POINTER, &[6, STORAGE_CLASS_PUSH_CONSTANT, 5]),
+            instruction(OP_VARIABLE, &[6, 7, STORAGE_CLASS_PUSH_CONSTANT]),
+        ]);
+
+        let reflection = reflect_spirv(&words, &[]).unwrap();
+
+        assert_eq!(
+            reflection.push_constant_ranges,
+            [PushConstantRange {
+                stages: ShaderStageFlags::COMPUTE,
+                offset: 16,
+                size: 20,
+            }]
+        );
+    }
+
+    #[test]
+    fn workgroup_size_built_in_overrides_execution_modes() {
+        let constants = [
+            instruction(OP_TYPE_INT, &[2, 32, 0]),
+            instruction(OP_CONSTANT, &[2, 3, 2]),
+            instruction(OP_CONSTANT, &[2, 4, 3]),
+            instruction(OP_CONSTANT, &[2, 5, 1]),
+            instruction(OP_TYPE_VECTOR, &[6, 2, 3]),
+        ];
+        let workgroup_size = |declarations: &[Vec<u32>]| {
+            let words = compute_module(&[declarations, &constants[..]].concat());
+
+            reflect_spirv(&words, &[]).unwrap().entry_points[0].workgroup_size
+        };
+
+        let local_size = instruction(OP_EXECUTION_MODE, &[1, EXECUTION_MODE_LOCAL_SIZE, 8, 4, 1]);
+        let local_size_id = instruction(
+            OP_EXECUTION_MODE_ID,
+            &[1, EXECUTION_MODE_LOCAL_SIZE_ID, 3, 4, 5],
+        );
+        let built_in = [
+            decorate(7, DECORATION_BUILT_IN, &[BUILT_IN_WORKGROUP_SIZE]),
+            instruction(OP_SPEC_CONSTANT_COMPOSITE, &[6, 7, 4, 5, 5]),
+        ];
+
+        assert_eq!(workgroup_size(&[]), None);
+        assert_eq!(
+            workgroup_size(std::slice::from_ref(&local_size)),
+            Some([8, 4, 1])
+        );
+        assert_eq!(
+            workgroup_size(std::slice::from_ref(&local_size_id)),
+            Some([2, 3, 1])
+        );
+        assert_eq!(
+            workgroup_size(&[&[local_size, local_size_id][..], &built_in].concat()),
+            Some([3, 1, 1])
+        );
+    }
+
+    #[test]
+    fn vertex_inputs_are_sorted_by_location() {
+        let words = module(&[
+            entry_point(EXECUTION_MODEL_VERTEX, 1, "main", &[5, 7, 8]),
+            name(5, "normal"),
+            name(7, "bone_ids"),
+            decorate(5, DECORATION_LOCATION, &[1]),
+            decorate(7, DECORATION_LOCATION, &[0]),
+            decorate(8, DECORATION_BUILT_IN, &[DECORATION_BUILT_IN_VERTEX_INDEX]),
+            decorate(9, DECORATION_LOCATION, &[2]),
+            instruction(OP_TYPE_FLOAT, &[2, 32]),
+            instruction(OP_TYPE_VECTOR, &[3, 2, 3]),
+            instruction(OP_TYPE_POINTER, &[4, STORAGE_CLASS_INPUT, 3]),
+            instruction(OP_VARIABLE, &[4, 5, STORAGE_CLASS_INPUT]),
+            instruction(OP_TYPE_INT, &[10, 32, 1]),
+            instruction(OP_TYPE_VECTOR, &[11, 10, 2]),
+            instruction(OP_TYPE_POINTER, &[6, STORAGE_CLASS_INPUT, 11]),
+            instruction(OP_VARIABLE, &[6, 7, STORAGE_CLASS_INPUT]),
+            instruction(OP_TYPE_POINTER, &[12, STORAGE_CLASS_INPUT, 10]),
+            instruction(OP_VARIABLE, &[12, 8, STORAGE_CLASS_INPUT]),
+            // Not part of the entry point's interface.
+            instruction(OP_VARIABLE, &[4, 9, STORAGE_CLASS_INPUT]),
+        ]);
+
+        let reflection = reflect_spirv(&words, &[]).unwrap();
+
+        assert_eq!(
+            reflection.entry_points[0].vertex_inputs,
+            [
+                VertexInput {
+                    location: 0,
+                    scalar_type: ShaderScalarType::Sint32,
+                    component_count: 2,
+                    name: Some("bone_ids".to_owned()),
+                },
+                VertexInput {
+                    location: 1,
+                    scalar_type: ShaderScalarType::Float32,
+                    component_count: 3,
+                    name: Some("normal".to_owned()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unsupported_execution_models_are_skipped_unless_requested() {
+        let words = module(&[
+            entry_point(EXECUTION_MODEL_VERTEX, 1, "vs", &[]),
+            entry_point(EXECUTION_MODEL_GEOMETRY, 1, "gs", &[]),
+        ]);
+
+        let reflection = reflect_spirv(&words, &[]).unwrap();
+        assert_eq!(reflection.entry_points.len(), 1);
+        assert_eq!(reflection.entry_points[0].name, "vs");
+
+        assert!(reflect_spirv(&words, &["gs"]).is_err());
+    }
+
+    #[test]
+    fn invalid_modules_fail_reflection() {
+        let words = compute_module(&[]);
+
+        assert!(reflect_spirv(&words, &["main"]).is_ok());
+        assert!(reflect_spirv(&words, &["missing"]).is_err());
+
+        // Cut off in the middle of the entry point, after the capability and memory model.
+        assert!(reflect_spirv(&words[..HEADER_WORDS + 6], &[]).is_err());
+
+        assert!(spirv_to_words(&to_bytes(&[MAGIC, 0, 0], u32::to_le_bytes)).is_err());
+        assert!(spirv_to_words(&to_bytes(&[0, 0, 0, 0, 0], u32::to_le_bytes)).is_err());
+        assert!(spirv_to_words(&[0x03, 0x02, 0x23]).is_err());
+    }
+
+    #[test]
+    fn big_endian_modules_are_swapped() {
+        let words = compute_module(&[instruction(
+            OP_EXECUTION_MODE,
+            &[1, EXECUTION_MODE_LOCAL_SIZE, 64, 1, 1],
+        )]);
+
+        assert_eq!(
+            spirv_to_words(&to_bytes(&words, u32::to_be_bytes)).unwrap(),
+            words
+        );
+        assert_eq!(
+            spirv_to_words(&to_bytes(&words, u32::to_le_bytes)).unwrap(),
+            words
+        );
+    }
+}
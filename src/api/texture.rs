@@ -0,0 +1,455 @@
+use bitflags::bitflags;
+
+#[cfg(feature = "metal")]
+use crate::metal::{MetalTexture, MetalTextureView};
+#[cfg(feature = "null")]
+use crate::null::{NullTexture, NullTextureView};
+#[cfg(feature = "vulkan")]
+use crate::vulkan::{VulkanTexture, VulkanTextureView};
+use crate::{Error, Format};
+
+bitflags! {
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct TextureUsageFlags : u32 {
+        const TRANSFER_SRC = 1 << 0;
+        const TRANSFER_DST = 1 << 1;
+        const SAMPLED = 1 << 2;
+        const STORAGE = 1 << 3;
+        const COLOR_ATTACHMENT = 1 << 4;
+        const DEPTH_STENCIL_ATTACHMENT = 1 << 5;
+    }
+}
+
+bitflags! {
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct TextureAspectFlags : u32 {
+        const COLOR = 1 << 0;
+        const DEPTH = 1 << 1;
+        const STENCIL = 1 << 2;
+    }
+}
+
+impl TextureAspectFlags {
+    /// Returns every aspect contained in `format`.
+    pub fn from_format(format: Format) -> Self {
+        let mut result = Self::empty();
+
+        if format.is_depth() {
+            result |= Self::DEPTH;
+        }
+        if format.is_stencil() {
+            result |= Self::STENCIL;
+        }
+
+        if result.is_empty() {
+            Self::COLOR
+        } else {
+            result
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextureDimension {
+    D1,
+    #[default]
+    D2,
+    D3,
+    /// A 2D texture with six faces per cube, `array_layers` must be a multiple of six.
+    Cube,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Extent3d {
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+}
+
+impl Default for Extent3d {
+    fn default() -> Self {
+        Self {
+            width: 1,
+            height: 1,
+            depth: 1,
+        }
+    }
+}
+
+impl Extent3d {
+    /// Returns the extent of the given mip level, clamped to one texel.
+    #[inline]
+    pub fn mip_level(&self, level: u32) -> Self {
+        Self {
+            width: (self.width >> level).max(1),
+            height: (self.height >> level).max(1),
+            depth: (self.depth >> level).max(1),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Origin3d {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct TextureDesc<'a> {
+    pub dimension: TextureDimension,
+    pub format: Format,
+    pub extent: Extent3d,
+    pub mip_levels: u32,
+    pub array_layers: u32,
+    pub sample_count: u32,
+    pub usage: TextureUsageFlags,
+    /// Lets command lists insert the barriers and layout transitions for the texture
+    /// automatically, see [`CommandList`](crate::CommandList).
+    ///
+    /// Untracked textures are created with undefined contents and layout, the first use of each
+    /// subresource has to be a [`TextureBarrier`](crate::TextureBarrier) or
+    /// [`TextureUpload`](crate::TextureUpload) with `discard` set.
+    pub tracked: bool,
+    pub label: Option<&'a str>,
+}
+
+impl Default for TextureDesc<'_> {
+    fn default() -> Self {
+        Self {
+            dimension: TextureDimension::D2,
+            format: Format::Rgba8Unorm,
+            extent: Extent3d::default(),
+            mip_levels: 1,
+            array_layers: 1,
+            sample_count: 1,
+            usage: TextureUsageFlags::empty(),
+            tracked: false,
+            label: None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TextureViewDimension {
+    D1,
+    D1Array,
+    D2,
+    D2Array,
+    D3,
+    Cube,
+    CubeArray,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextureSubresourceRange {
+    /// Aspects to include, an empty set selects every aspect of the view format.
+    pub aspect: TextureAspectFlags,
+    pub base_mip_level: u32,
+    /// Number of mip levels, `None` selects all remaining levels.
+    pub mip_level_count: Option<u32>,
+    pub base_array_layer: u32,
+    /// Number of array layers, `None` selects all remaining layers.
+    pub array_layer_count: Option<u32>,
+}
+
+impl Default for TextureSubresourceRange {
+    fn default() -> Self {
+        Self {
+            aspect: TextureAspectFlags::empty(),
+            base_mip_level: 0,
+            mip_level_count: None,
+            base_array_layer: 0,
+            array_layer_count: None,
+        }
+    }
+}
+
+impl TextureSubresourceRange {
+    /// Resolves `None` counts and empty aspects against the texture the range refers to.
+    pub fn resolve(&self, texture: &Texture, format: Format) -> Self {
+        Self {
+            aspect: if self.aspect.is_empty() {
+                TextureAspectFlags::from_format(format)
+            } else {
+                self.aspect
+            },
+            base_mip_level: self.base_mip_level,
+            mip_level_count: Some(
+                self.mip_level_count
+                    .unwrap_or(texture.mip_levels().saturating_sub(self.base_mip_level)),
+            ),
+            base_array_layer: self.base_array_layer,
+            array_layer_count: Some(
+                self.array_layer_count
+                    .unwrap_or(texture.array_layers().saturating_sub(self.base_array_layer)),
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TextureViewDesc<'a> {
+    /// View dimension, `None` derives it from the texture.
+    pub dimension: Option<TextureViewDimension>,
+    /// View format, `None` uses the texture format.
+    pub format: Option<Format>,
+    pub range: TextureSubresourceRange,
+    pub label: Option<&'a str>,
+}
+
+#[derive(Clone)]
+pub enum Texture {
+    #[cfg(feature = "metal")]
+    Metal(MetalTexture),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanTexture),
+    #[cfg(feature = "null")]
+    Null(NullTexture),
+}
+
+impl Texture {
+    #[inline]
+    pub fn dimension(&self) -> TextureDimension {
+        match self {
+            #[cfg(feature = "metal")]
+            Texture::Metal(texture) => texture.dimension(),
+            #[cfg(feature = "vulkan")]
+            Texture::Vulkan(texture) => texture.dimension(),
+            #[cfg(feature = "null")]
+            Texture::Null(texture) => texture.dimension(),
+        }
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        match self {
+            #[cfg(feature = "metal")]
+            Texture::Metal(texture) => texture.format(),
+            #[cfg(feature = "vulkan")]
+            Texture::Vulkan(texture) => texture.format(),
+            #[cfg(feature = "null")]
+            Texture::Null(texture) => texture.format(),
+        }
+    }
+
+    #[inline]
+    pub fn extent(&self) -> Extent3d {
+        match self {
+            #[cfg(feature = "metal")]
+            Texture::Metal(texture) => texture.extent(),
+            #[cfg(feature = "vulkan")]
+            Texture::Vulkan(texture) => texture.extent(),
+            #[cfg(feature = "null")]
+            Texture::Null(texture) => texture.extent(),
+        }
+    }
+
+    #[inline]
+    pub fn mip_levels(&self) -> u32 {
+        match self {
+            #[cfg(feature = "metal")]
+            Texture::Metal(texture) => texture.mip_levels(),
+            #[cfg(feature = "vulkan")]
+            Texture::Vulkan(texture) => texture.mip_levels(),
+            #[cfg(feature = "null")]
+            Texture::Null(texture) => texture.mip_levels(),
+        }
+    }
+
+    #[inline]
+    pub fn array_layers(&self) -> u32 {
+        match self {
+            #[cfg(feature = "metal")]
+            Texture::Metal(texture) => texture.array_layers(),
+            #[cfg(feature = "vulkan")]
+            Texture::Vulkan(texture) => texture.array_layers(),
+            #[cfg(feature = "null")]
+            Texture::Null(texture) => texture.array_layers(),
+        }
+    }
+
+    #[inline]
+    pub fn sample_count(&self) -> u32 {
+        match self {
+            #[cfg(feature = "metal")]
+            Texture::Metal(texture) => texture.sample_count(),
+            #[cfg(feature = "vulkan")]
+            Texture::Vulkan(texture) => texture.sample_count(),
+            #[cfg(feature = "null")]
+            Texture::Null(texture) => texture.sample_count(),
+        }
+    }
+
+    #[inline]
+    pub fn usage(&self) -> TextureUsageFlags {
+        match self {
+            #[cfg(feature = "metal")]
+            Texture::Metal(texture) => texture.usage(),
+            #[cfg(feature = "vulkan")]
+            Texture::Vulkan(texture) => texture.usage(),
+            #[cfg(feature = "null")]
+            Texture::Null(texture) => texture.usage(),
+        }
+    }
+
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalTexture {
+        match self {
+            Texture::Metal(texture) => texture,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Texture belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanTexture {
+        match self {
+            Texture::Vulkan(texture) => texture,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Texture belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "null")]
+    #[inline]
+    pub(crate) fn as_null(&self) -> &NullTexture {
+        match self {
+            Texture::Null(texture) => texture,
+            #[allow(unreachable_patterns)]
+            _ => panic!("Texture belongs to a different backend"),
+        }
+    }
+
+    /// Returns the view dimension matching the whole texture.
+    pub fn default_view_dimension(&self) -> TextureViewDimension {
+        let array = self.array_layers() > 1;
+
+        match self.dimension() {
+            TextureDimension::D1 if array => TextureViewDimension::D1Array,
+            TextureDimension::D1 => TextureViewDimension::D1,
+            TextureDimension::D2 if array => TextureViewDimension::D2Array,
+            TextureDimension::D2 => TextureViewDimension::D2,
+            TextureDimension::D3 => TextureViewDimension::D3,
+            TextureDimension::Cube if self.array_layers() > 6 => TextureViewDimension::CubeArray,
+            TextureDimension::Cube => TextureViewDimension::Cube,
+        }
+    }
+
+    #[inline]
+    pub fn create_view(&self, desc: &TextureViewDesc) -> Result<TextureView, Error> {
+        match self {
+            #[cfg(feature = "metal")]
+            Texture::Metal(texture) => {
+                Ok(TextureView::Metal(MetalTextureView::new(
+                    self, texture, desc,
+                )?))
+            }
+            #[cfg(feature = "vulkan")]
+            Texture::Vulkan(texture) => {
+                Ok(TextureView::Vulkan(VulkanTextureView::new(
+                    self, texture, desc,
+                )?))
+            }
+            #[cfg(feature = "null")]
+            Texture::Null(texture) => {
+                Ok(TextureView::Null(NullTextureView::new(
+                    self, texture, desc,
+                )?))
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum TextureView {
+    #[cfg(feature = "metal")]
+    Metal(MetalTextureView),
+    #[cfg(feature = "vulkan")]
+    Vulkan(VulkanTextureView),
+    #[cfg(feature = "null")]
+    Null(NullTextureView),
+}
+
+impl TextureView {
+    #[inline]
+    pub fn texture(&self) -> &Texture {
+        match self {
+            #[cfg(feature = "metal")]
+            TextureView::Metal(view) => view.texture(),
+            #[cfg(feature = "vulkan")]
+            TextureView::Vulkan(view) => view.texture(),
+            #[cfg(feature = "null")]
+            TextureView::Null(view) => view.texture(),
+        }
+    }
+
+    #[inline]
+    pub fn dimension(&self) -> TextureViewDimension {
+        match self {
+            #[cfg(feature = "metal")]
+            TextureView::Metal(view) => view.dimension(),
+            #[cfg(feature = "vulkan")]
+            TextureView::Vulkan(view) => view.dimension(),
+            #[cfg(feature = "null")]
+            TextureView::Null(view) => view.dimension(),
+        }
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        match self {
+            #[cfg(feature = "metal")]
+            TextureView::Metal(view) => view.format(),
+            #[cfg(feature = "vulkan")]
+            TextureView::Vulkan(view) => view.format(),
+            #[cfg(feature = "null")]
+            TextureView::Null(view) => view.format(),
+        }
+    }
+
+    /// Returns the subresource range with all counts and aspects resolved.
+    #[inline]
+    pub fn range(&self) -> &TextureSubresourceRange {
+        match self {
+            #[cfg(feature = "metal")]
+            TextureView::Metal(view) => view.range(),
+            #[cfg(feature = "vulkan")]
+            TextureView::Vulkan(view) => view.range(),
+            #[cfg(feature = "null")]
+            TextureView::Null(view) => view.range(),
+        }
+    }
+
+    /// Returns the extent of the base mip level of the view.
+    #[inline]
+    pub fn extent(&self) -> Extent3d {
+        self.texture()
+            .extent()
+            .mip_level(self.range().base_mip_level)
+    }
+
+    #[cfg(feature = "metal")]
+    #[inline]
+    pub(crate) fn as_metal(&self) -> &MetalTextureView {
+        match self {
+            TextureView::Metal(view) => view,
+            #[allow(unreachable_patterns)]
+            _ => panic!("TextureView belongs to a different backend"),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline]
+    pub(crate) fn as_vulkan(&self) -> &VulkanTextureView {
+        match self {
+            TextureView::Vulkan(view) => view,
+            #[allow(unreachable_patterns)]
+            _ => panic!("TextureView belongs to a different backend"),
+        }
+    }
+}
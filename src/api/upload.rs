@@ -0,0 +1,283 @@
+#[cfg(any(feature = "vulkan", feature = "metal"))]
+use std::collections::VecDeque;
+
+use crate::{Buffer, Extent3d, Format, Origin3d, QueueType, Texture};
+
+/// Size of the staging buffers uploads are suballocated from, larger uploads get a staging
+/// buffer of their own.
+#[cfg(any(feature = "vulkan", feature = "metal"))]
+pub(crate) const STAGING_BUFFER_SIZE: u64 = 8 << 20;
+
+/// Writes `data` to `buffer` at `offset` through the transfer queue.
+#[derive(Clone)]
+pub struct BufferUpload<'a> {
+    pub buffer: &'a Buffer,
+    pub offset: u64,
+    pub data: &'a [u8],
+    /// The queue the buffer is used on afterwards. Untracked buffers are released to it and
+    /// have to be acquired with a [`QueueTransfer`](crate::QueueTransfer) from
+    /// [`QueueType::Transfer`] before their first use.
+    pub queue_type: QueueType,
+}
+
+/// Writes `data` to a region of a single mip level and array layer of `texture` through the
+/// transfer queue, the rows of blocks in `data` are tightly packed.
+#[derive(Clone)]
+pub struct TextureUpload<'a> {
+    pub texture: &'a Texture,
+    pub mip_level: u32,
+    pub array_layer: u32,
+    pub origin: Origin3d,
+    pub extent: Extent3d,
+    pub data: &'a [u8],
+    /// The queue the texture is used on afterwards, see [`BufferUpload::queue_type`].
+    pub queue_type: QueueType,
+    /// The previous contents of the subresource are not needed, which is required for the first
+    /// upload to an untracked texture.
+    pub discard: bool,
+}
+
+/// Returns the size of `extent` texels of `format` with tightly packed rows of blocks.
+pub(crate) fn packed_texture_size(format: Format, extent: Extent3d) -> u64 {
+    let (block_width, block_height) = format.block_extent();
+
+    u64::from(extent.width.div_ceil(block_width))
+        * u64::from(format.block_size())
+        * u64::from(extent.height.div_ceil(block_height))
+        * u64::from(extent.depth)
+}
+
+#[cfg(any(feature = "vulkan", feature = "metal"))]
+struct StagingChunk<C> {
+    buffer: C,
+    size: u64,
+    /// End of the last allocation.
+    offset: u64,
+    /// Upload timeline value of the last upload reading the buffer.
+    value: u64,
+}
+
+/// Suballocates uploads from a ring of host visible staging buffers of type `C`.
+///
+/// Allocations are made linearly from the current buffer, which is retired once an upload no
+/// longer fits and reused after the upload timeline passed the value of its last upload.
+/// Buffers larger than [`STAGING_BUFFER_SIZE`] are destroyed instead of reused.
+#[cfg(any(feature = "vulkan", feature = "metal"))]
+pub(crate) struct StagingRing<C> {
+    current: Option<StagingChunk<C>>,
+    retired: VecDeque<StagingChunk<C>>,
+    free: Vec<C>,
+}
+
+#[cfg(any(feature = "vulkan", feature = "metal"))]
+impl<C> Default for StagingRing<C> {
+    fn default() -> Self {
+        Self {
+            current: None,
+            retired: VecDeque::new(),
+            free: Vec::new(),
+        }
+    }
+}
+
+#[cfg(any(feature = "vulkan", feature = "metal"))]
+impl<C> StagingRing<C> {
+    /// Returns a staging buffer and the offset of `size` bytes in it aligned to `alignment`.
+    ///
+    /// Retired buffers whose last upload is at most `completed_value` are recycled first, new
+    /// buffers are made by `create` with the size passed to it and dropped ones handed to
+    /// `destroy`.
+    pub fn allocate<E>(
+        &mut self,
+        size: u64,
+        alignment: u64,
+        completed_value: u64,
+        create: impl FnOnce(u64) -> Result<C, E>,
+        mut destroy: impl FnMut(C),
+    ) -> Result<(&C, u64), E> {
+        while self
+            .retired
+            .front()
+            .is_some_and(|chunk| chunk.value <= completed_value)
+        {
+            let chunk = self.retired.pop_front().unwrap();
+
+            if chunk.size == STAGING_BUFFER_SIZE {
+                self.free.push(chunk.buffer);
+            } else {
+                destroy(chunk.buffer);
+            }
+        }
+
+        let fits = self
+            .current
+            .as_ref()
+            .is_some_and(|chunk| chunk.offset.next_multiple_of(alignment) + size <= chunk.size);
+
+        if !fits {
+            let buffer_size = size.max(STAGING_BUFFER_SIZE);
+            let free_buffer = if buffer_size == STAGING_BUFFER_SIZE {
+                self.free.pop()
+            } else {
+                None
+            };
+            let buffer = match free_buffer {
+                Some(buffer) => buffer,
+                None => create(buffer_size)?,
+            };
+
+            let chunk = StagingChunk {
+                buffer,
+                size: buffer_size,
+                offset: 0,
+                value: 0,
+            };
+
+            if let Some(current) = self.current.replace(chunk) {
+                self.retired.push_back(current);
+            }
+        }
+
+        let chunk = self.current.as_mut().unwrap();
+        let offset = chunk.offset.next_multiple_of(alignment);
+        chunk.offset = offset + size;
+
+        Ok((&chunk.buffer, offset))
+    }
+
+    /// Marks the current staging buffer as read by the upload with `value`.
+    pub fn submit(&mut self, value: u64) {
+        if let Some(chunk) = &mut self.current {
+            chunk.value = value;
+        }
+    }
+
+    /// Takes every staging buffer out of the ring, for the device to destroy them.
+    pub fn drain(&mut self) -> impl Iterator<Item = C> + '_ {
+        self.current
+            .take()
+            .into_iter()
+            .chain(self.retired.drain(..))
+            .map(|chunk| chunk.buffer)
+            .chain(self.free.drain(..))
+    }
+}
+
+#[cfg(all(test, feature = "null"))]
+mod tests {
+    use super::*;
+    use crate::{
+        null::test_utils::create_device, BufferDesc, BufferUsageFlags, Error,
+        PhysicalDeviceFeatures, TextureDesc, TextureUsageFlags,
+    };
+
+    #[test]
+    fn upload_ranges_are_validated() {
+        let device = create_device(PhysicalDeviceFeatures::TIMELINE_SEMAPHORE);
+        let buffer = device
+            .create_buffer(&BufferDesc {
+                size: 256,
+                usage: BufferUsageFlags::TRANSFER_DST,
+                ..Default::default()
+            })
+            .unwrap();
+        let texture = device
+            .create_texture(&TextureDesc {
+                extent: Extent3d {
+                    width: 16,
+                    height: 16,
+                    depth: 1,
+                },
+                usage: TextureUsageFlags::TRANSFER_DST,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let buffer_upload = |offset, data| {
+            device.upload_buffer(&BufferUpload {
+                buffer: &buffer,
+                offset,
+                data,
+                queue_type: QueueType::Graphics,
+            })
+        };
+
+        let value = buffer_upload(0, &[0; 256]).unwrap();
+        assert!(device.wait_for_upload(value, None).unwrap());
+        assert!(matches!(
+            buffer_upload(192, &[0; 128]),
+            Err(Error::InvalidCopy(_))
+        ));
+        assert!(matches!(
+            buffer_upload(u64::MAX, &[0; 4]),
+            Err(Error::InvalidCopy(_))
+        ));
+
+        let texture_upload = |origin, extent, data: &[u8]| {
+            device.upload_texture(&TextureUpload {
+                texture: &texture,
+                mip_level: 0,
+                array_layer: 0,
+                origin,
+                extent,
+                data,
+                queue_type: QueueType::Graphics,
+                discard: true,
+            })
+        };
+        let extent = Extent3d {
+            width: 8,
+            height: 8,
+            depth: 1,
+        };
+
+        let value = texture_upload(Origin3d { x: 8, y: 8, z: 0 }, extent, &[0; 256]).unwrap();
+        assert!(device.wait_for_upload(value, None).unwrap());
+        assert!(matches!(
+            texture_upload(Origin3d { x: 12, y: 0, z: 0 }, extent, &[0; 256]),
+            Err(Error::InvalidCopy(_))
+        ));
+        assert!(matches!(
+            texture_upload(
+                Origin3d {
+                    x: u32::MAX,
+                    y: 0,
+                    z: 0
+                },
+                extent,
+                &[0; 256]
+            ),
+            Err(Error::InvalidCopy(_))
+        ));
+        assert!(matches!(
+            texture_upload(Origin3d::default(), extent, &[0; 128]),
+            Err(Error::InvalidCopy(_))
+        ));
+    }
+
+    #[test]
+    fn uploads_require_timeline_semaphores() {
+        let device = create_device(PhysicalDeviceFeatures::empty());
+        let buffer = device
+            .create_buffer(&BufferDesc {
+                size: 256,
+                usage: BufferUsageFlags::TRANSFER_DST,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let result = device.upload_buffer(&BufferUpload {
+            buffer: &buffer,
+            offset: 0,
+            data: &[0; 256],
+            queue_type: QueueType::Graphics,
+        });
+
+        assert!(matches!(
+            result,
+            Err(Error::MissingFeatures(
+                PhysicalDeviceFeatures::TIMELINE_SEMAPHORE
+            ))
+        ));
+    }
+}
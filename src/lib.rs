@@ -1,7 +1,11 @@
 mod api;
 #[cfg(feature = "metal")]
 pub(crate) mod metal;
+#[cfg(feature = "null")]
+pub(crate) mod null;
 #[cfg(feature = "vulkan")]
 pub(crate) mod vulkan;
 
 pub use api::*;
+#[cfg(feature = "null")]
+pub use null::NullPhysicalDeviceDesc;
@@ -0,0 +1,316 @@
+use std::sync::{Arc, Mutex};
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_foundation::NSString;
+use objc2_metal::{
+    MTLBuffer, MTLDevice, MTLResource, MTLResourceID, MTLResourceOptions, MTLResourceUsage,
+    MTLSamplerState, MTLTexture,
+};
+
+use crate::{
+    metal::{retain_resource, MetalDevice, MetalError, ResidentResource},
+    sort_bind_group_layout_entries, BindGroupDesc, BindGroupLayoutEntry, BindGroupPoolDesc,
+    BindingResource, DescriptorType,
+};
+
+/// Kinds of descriptors a pool block reserves `max_descriptors` entries for.
+const BIND_GROUP_DESCRIPTOR_TYPE_COUNT: usize = 5;
+
+/// Offsets of argument buffers bound to an encoder must be aligned to this.
+const ARGUMENT_BUFFER_ALIGNMENT: usize = 256;
+
+/// Argument buffers hold one array of 8 byte entries per binding in binding order, GPU addresses
+/// for buffers and resource IDs for textures and samplers, the same encoding as the bindless heap.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct MetalBindGroupLayout(Arc<[BindGroupLayoutEntry]>);
+
+impl MetalBindGroupLayout {
+    pub fn new(
+        _device: &MetalDevice,
+        entries: &[BindGroupLayoutEntry],
+    ) -> Result<Self, MetalError> {
+        let entries = sort_bind_group_layout_entries(entries).map_err(MetalError::Custom)?;
+
+        Ok(Self(entries.into()))
+    }
+
+    #[inline]
+    pub fn entries(&self) -> &[BindGroupLayoutEntry] {
+        &self.0
+    }
+
+    /// Returns the index of the argument buffer entry of `binding` at `array_element`.
+    fn entry_index(&self, binding: u32, array_element: u32) -> usize {
+        let preceding_count = self
+            .0
+            .iter()
+            .take_while(|entry| entry.binding < binding)
+            .map(|entry| entry.count as usize)
+            .sum::<usize>();
+
+        preceding_count + array_element as usize
+    }
+
+    fn argument_buffer_length(&self) -> usize {
+        self.0
+            .iter()
+            .map(|entry| entry.count as usize)
+            .sum::<usize>()
+            * size_of::<u64>()
+    }
+}
+
+/// A suballocated argument buffer, its offset and the pool generation it belongs to.
+type ArgumentBufferAllocation = (Retained<ProtocolObject<dyn MTLBuffer>>, usize, u64);
+
+struct PoolState {
+    blocks: Vec<Retained<ProtocolObject<dyn MTLBuffer>>>,
+    /// Index of the block argument buffers are suballocated from, earlier ones are full.
+    current: usize,
+    offset: usize,
+    /// Incremented by every reset, bind groups of earlier generations are freed.
+    generation: u64,
+}
+
+struct PoolInner {
+    device: MetalDevice,
+
+    max_descriptors: u32,
+    block_length: usize,
+    /// Applied to every block, which are created lazily.
+    label: Option<String>,
+
+    state: Mutex<PoolState>,
+}
+
+unsafe impl Send for PoolInner {}
+unsafe impl Sync for PoolInner {}
+
+impl PoolInner {
+    /// Suballocates `length` bytes, moving on to the next block, created if needed, when the
+    /// current one is full.
+    fn allocate(&self, length: usize) -> Result<ArgumentBufferAllocation, MetalError> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.current < state.blocks.len() && state.offset + length > self.block_length {
+            state.current += 1;
+            state.offset = 0;
+        }
+
+        if state.current == state.blocks.len() {
+            let block = self
+                .device
+                .mtl_device()
+                .newBufferWithLength_options(
+                    self.block_length,
+                    MTLResourceOptions::MTLResourceStorageModeShared
+                        | MTLResourceOptions::MTLResourceCPUCacheModeWriteCombined,
+                )
+                .ok_or_else(|| {
+                    MetalError::Custom("Failed to create bind group block".to_owned())
+                })?;
+            if let Some(label) = &self.label {
+                block.setLabel(Some(&NSString::from_str(label)));
+            }
+
+            state.blocks.push(block);
+        }
+
+        let offset = state.offset;
+        state.offset = (offset + length).next_multiple_of(ARGUMENT_BUFFER_ALIGNMENT);
+
+        Ok((
+            state.blocks[state.current].clone(),
+            offset,
+            state.generation,
+        ))
+    }
+
+    #[inline]
+    fn generation(&self) -> u64 {
+        self.state.lock().unwrap().generation
+    }
+}
+
+/// Shared buffers used as blocks, argument buffers are suballocated linearly until a reset.
+pub struct MetalBindGroupPool(Arc<PoolInner>);
+
+impl MetalBindGroupPool {
+    pub fn new(device: &MetalDevice, desc: &BindGroupPoolDesc) -> Result<Self, MetalError> {
+        if desc.max_bind_groups == 0 || desc.max_descriptors == 0 {
+            return Err(MetalError::Custom(
+                "Bind group pool blocks cannot be empty".to_owned(),
+            ));
+        }
+
+        Ok(Self(Arc::new(PoolInner {
+            device: device.clone(),
+
+            max_descriptors: desc.max_descriptors,
+            block_length: BIND_GROUP_DESCRIPTOR_TYPE_COUNT
+                * desc.max_descriptors as usize
+                * size_of::<u64>(),
+            label: desc.label.map(str::to_owned),
+
+            state: Mutex::new(PoolState {
+                blocks: Vec::new(),
+                current: 0,
+                offset: 0,
+                generation: 0,
+            }),
+        })))
+    }
+
+    pub fn create_bind_group(&self, desc: &BindGroupDesc) -> Result<MetalBindGroup, MetalError> {
+        let layout = desc.layout.as_metal();
+
+        for entry in layout.entries() {
+            let count = layout
+                .entries()
+                .iter()
+                .filter(|e| e.descriptor_type == entry.descriptor_type)
+                .map(|e| e.count)
+                .sum::<u32>();
+
+            if count > self.0.max_descriptors {
+                return Err(MetalError::Custom(format!(
+                    "Bind group holds {count} {:?} descriptors, the pool blocks hold {}",
+                    entry.descriptor_type, self.0.max_descriptors
+                )));
+            }
+        }
+
+        let (mtl_argument_buffer, offset, generation) =
+            self.0.allocate(layout.argument_buffer_length())?;
+        let contents = unsafe {
+            mtl_argument_buffer
+                .contents()
+                .cast::<u8>()
+                .as_ptr()
+                .add(offset)
+                .cast::<u64>()
+        };
+
+        let mut resources = Vec::new();
+
+        for entry in desc.entries {
+            let descriptor_type = layout
+                .entries()
+                .iter()
+                .find(|layout_entry| layout_entry.binding == entry.binding)
+                .unwrap()
+                .descriptor_type;
+            let usage = match descriptor_type {
+                DescriptorType::StorageBuffer | DescriptorType::StorageTexture => {
+                    MTLResourceUsage::Read | MTLResourceUsage::Write
+                }
+                _ => MTLResourceUsage::Read,
+            };
+            let argument =
+                unsafe { contents.add(layout.entry_index(entry.binding, entry.array_element)) };
+
+            match entry.resource {
+                BindingResource::Buffer { buffer, offset, .. } => {
+                    let mtl_buffer = buffer.as_metal().mtl_buffer();
+
+                    unsafe { argument.write(mtl_buffer.gpuAddress() + offset) };
+                    resources.push((retain_resource(ProtocolObject::from_ref(mtl_buffer)), usage));
+                }
+                BindingResource::TextureView(view) => {
+                    let mtl_texture = view.as_metal().mtl_texture();
+
+                    unsafe {
+                        argument
+                            .cast::<MTLResourceID>()
+                            .write(mtl_texture.gpuResourceID())
+                    };
+                    resources.push((
+                        retain_resource(ProtocolObject::from_ref(mtl_texture)),
+                        usage,
+                    ));
+                }
+                BindingResource::Sampler(sampler) => {
+                    // Sampler states are no resources and live in the device cache, nothing has
+                    // to be made resident or kept alive.
+                    unsafe {
+                        argument
+                            .cast::<MTLResourceID>()
+                            .write(sampler.as_metal().mtl_sampler_state().gpuResourceID())
+                    };
+                }
+            }
+        }
+
+        Ok(MetalBindGroup(Arc::new(BindGroupInner {
+            pool: self.0.clone(),
+            generation,
+
+            layout: layout.clone(),
+            mtl_argument_buffer,
+            offset,
+
+            resources,
+        })))
+    }
+
+    pub fn reset(&mut self) -> Result<(), MetalError> {
+        let mut state = self.0.state.lock().unwrap();
+
+        state.current = 0;
+        state.offset = 0;
+        state.generation += 1;
+
+        Ok(())
+    }
+}
+
+struct BindGroupInner {
+    pool: Arc<PoolInner>,
+    generation: u64,
+
+    layout: MetalBindGroupLayout,
+    mtl_argument_buffer: Retained<ProtocolObject<dyn MTLBuffer>>,
+    offset: usize,
+
+    /// Resources referenced by the argument buffer, encoders have to make them resident.
+    resources: Vec<ResidentResource>,
+}
+
+unsafe impl Send for BindGroupInner {}
+unsafe impl Sync for BindGroupInner {}
+
+#[derive(Clone)]
+pub struct MetalBindGroup(Arc<BindGroupInner>);
+
+impl MetalBindGroup {
+    #[inline]
+    pub fn layout(&self) -> &MetalBindGroupLayout {
+        &self.0.layout
+    }
+
+    #[inline]
+    pub fn mtl_argument_buffer(&self) -> &ProtocolObject<dyn MTLBuffer> {
+        &self.0.mtl_argument_buffer
+    }
+
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.0.offset
+    }
+
+    /// Returns `false` once the pool was reset and the argument buffer may be overwritten.
+    #[inline]
+    pub(crate) fn is_allocated(&self) -> bool {
+        self.0.pool.generation() == self.0.generation
+    }
+
+    #[inline]
+    pub(crate) fn resources(
+        &self,
+    ) -> impl Iterator<Item = (&ProtocolObject<dyn MTLResource>, MTLResourceUsage)> {
+        self.0
+            .resources
+            .iter()
+            .map(|(resource, usage)| (&**resource, *usage))
+    }
+}
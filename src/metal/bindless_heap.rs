@@ -0,0 +1,181 @@
+use std::sync::Mutex;
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_metal::{
+    MTLBuffer, MTLDevice, MTLResource, MTLResourceID, MTLResourceOptions, MTLResourceUsage,
+    MTLTexture,
+};
+
+use crate::{
+    metal::{MetalDevice, MetalError},
+    BindlessSlots, Buffer, BufferUsageFlags, TextureUsageFlags, TextureView,
+    BINDLESS_HEAP_CAPACITY, BINDLESS_SAMPLED_TEXTURE_BINDING, BINDLESS_STORAGE_BUFFER_BINDING,
+    BINDLESS_STORAGE_TEXTURE_BINDING,
+};
+
+/// A resource encoders have to make resident while it is referenced by an argument buffer.
+pub(crate) type ResidentResource = (Retained<ProtocolObject<dyn MTLResource>>, MTLResourceUsage);
+
+pub(crate) fn retain_resource(
+    resource: &ProtocolObject<dyn MTLResource>,
+) -> Retained<ProtocolObject<dyn MTLResource>> {
+    unsafe { Retained::retain(resource as *const _ as *mut _) }.unwrap()
+}
+
+/// A tier 2 argument buffer holding one array of GPU addresses or resource IDs per bindless
+/// binding, every entry is 8 bytes.
+///
+/// Argument buffer contents are invisible to Metal, so the heap retains registered resources
+/// and encoders declare them with `useResource`. Released indices carry the submission value of
+/// the device at release time.
+pub(crate) struct MetalBindlessHeap {
+    mtl_argument_buffer: Retained<ProtocolObject<dyn MTLBuffer>>,
+
+    slots: Mutex<BindlessSlots<ResidentResource, u64>>,
+}
+
+unsafe impl Send for MetalBindlessHeap {}
+unsafe impl Sync for MetalBindlessHeap {}
+
+impl MetalBindlessHeap {
+    pub(crate) fn new(mtl_device: &ProtocolObject<dyn MTLDevice>) -> Result<Self, MetalError> {
+        let length = 3 * BINDLESS_HEAP_CAPACITY as usize * size_of::<u64>();
+
+        let mtl_argument_buffer = mtl_device
+            .newBufferWithLength_options(
+                length,
+                MTLResourceOptions::MTLResourceStorageModeShared
+                    | MTLResourceOptions::MTLResourceCPUCacheModeWriteCombined,
+            )
+            .ok_or_else(|| MetalError::Custom("Failed to create bindless heap".to_owned()))?;
+
+        Ok(Self {
+            mtl_argument_buffer,
+
+            slots: Mutex::new(BindlessSlots::new(BINDLESS_HEAP_CAPACITY)),
+        })
+    }
+
+    #[inline]
+    pub(crate) fn mtl_argument_buffer(&self) -> &ProtocolObject<dyn MTLBuffer> {
+        &self.mtl_argument_buffer
+    }
+
+    /// Calls `use_resource` for every live resource, encoders must do so before reading the heap.
+    pub(crate) fn use_resources(
+        &self,
+        mut use_resource: impl FnMut(&ProtocolObject<dyn MTLResource>, MTLResourceUsage),
+    ) {
+        for (resource, usage) in self.slots.lock().unwrap().live_resources() {
+            use_resource(resource, *usage);
+        }
+    }
+
+    fn heap(device: &MetalDevice) -> Result<&Self, MetalError> {
+        device.bindless_heap().ok_or_else(|| {
+            MetalError::Custom("The bindless heap requires tier 2 argument buffers".to_owned())
+        })
+    }
+
+    /// Returns a pointer to the entry at `index` of the array of `binding`.
+    fn entry(&self, binding: u32, index: u32) -> *mut u64 {
+        let contents = self.mtl_argument_buffer.contents().cast::<u64>().as_ptr();
+
+        unsafe { contents.add((binding * BINDLESS_HEAP_CAPACITY + index) as usize) }
+    }
+
+    /// Takes a free index for `resource` and writes its entries with `write`.
+    fn insert(
+        device: &MetalDevice,
+        resource: ResidentResource,
+        write: impl FnOnce(&Self, u32),
+    ) -> Result<u32, MetalError> {
+        let heap = Self::heap(device)?;
+        let mut slots = heap.slots.lock().unwrap();
+
+        let completed_value = device.completed_submission_value();
+        let Some(index) = slots.insert(resource, |value| *value <= completed_value) else {
+            return Err(MetalError::Custom(format!(
+                "Bindless heap is full, it holds {} resources",
+                slots.capacity()
+            )));
+        };
+
+        write(heap, index);
+
+        Ok(index)
+    }
+
+    pub fn register_buffer(device: &MetalDevice, buffer: &Buffer) -> Result<u32, MetalError> {
+        if !buffer.usage().contains(BufferUsageFlags::STORAGE) {
+            return Err(MetalError::Custom(
+                "Bindless buffers require storage usage".to_owned(),
+            ));
+        }
+
+        let mtl_buffer = buffer.as_metal().mtl_buffer();
+        let resource = (
+            retain_resource(ProtocolObject::from_ref(mtl_buffer)),
+            MTLResourceUsage::Read | MTLResourceUsage::Write,
+        );
+
+        Self::insert(device, resource, |heap, index| unsafe {
+            heap.entry(BINDLESS_STORAGE_BUFFER_BINDING, index)
+                .write(mtl_buffer.gpuAddress());
+        })
+    }
+
+    pub fn register_texture_view(
+        device: &MetalDevice,
+        view: &TextureView,
+    ) -> Result<u32, MetalError> {
+        let usage = view.texture().usage();
+
+        if !usage.intersects(TextureUsageFlags::SAMPLED | TextureUsageFlags::STORAGE) {
+            return Err(MetalError::Custom(
+                "Bindless texture views require sampled or storage usage".to_owned(),
+            ));
+        }
+
+        let mtl_texture = view.as_metal().mtl_texture();
+        let mut resource_usage = MTLResourceUsage::Read;
+        if usage.contains(TextureUsageFlags::STORAGE) {
+            resource_usage |= MTLResourceUsage::Write;
+        }
+
+        let resource = (
+            retain_resource(ProtocolObject::from_ref(mtl_texture)),
+            resource_usage,
+        );
+
+        Self::insert(device, resource, |heap, index| {
+            let resource_id = unsafe { mtl_texture.gpuResourceID() };
+
+            for (required_usage, binding) in [
+                (TextureUsageFlags::SAMPLED, BINDLESS_SAMPLED_TEXTURE_BINDING),
+                (TextureUsageFlags::STORAGE, BINDLESS_STORAGE_TEXTURE_BINDING),
+            ] {
+                if usage.contains(required_usage) {
+                    unsafe {
+                        heap.entry(binding, index)
+                            .cast::<MTLResourceID>()
+                            .write(resource_id)
+                    };
+                }
+            }
+        })
+    }
+
+    pub fn release(device: &MetalDevice, index: u32) -> Result<(), MetalError> {
+        let heap = Self::heap(device)?;
+        let value = device.submission_value();
+
+        if !heap.slots.lock().unwrap().release(index, value) {
+            return Err(MetalError::Custom(format!(
+                "Bindless index {index} is not registered"
+            )));
+        }
+
+        Ok(())
+    }
+}
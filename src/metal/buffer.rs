@@ -0,0 +1,97 @@
+use std::{ptr::NonNull, sync::Arc};
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_foundation::NSString;
+use objc2_metal::{MTLBuffer, MTLDevice, MTLResource, MTLResourceOptions};
+
+use crate::{
+    metal::{MetalDevice, MetalError},
+    BufferDesc, BufferUsageFlags, MemoryLocation,
+};
+
+struct Inner {
+    mtl_buffer: Retained<ProtocolObject<dyn MTLBuffer>>,
+
+    usage: BufferUsageFlags,
+    memory_location: MemoryLocation,
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+#[derive(Clone)]
+pub struct MetalBuffer(Arc<Inner>);
+
+fn to_mtl_resource_options(memory_location: MemoryLocation) -> MTLResourceOptions {
+    match memory_location {
+        MemoryLocation::GpuOnly => MTLResourceOptions::MTLResourceStorageModePrivate,
+        MemoryLocation::Upload => {
+            MTLResourceOptions::MTLResourceStorageModeShared
+                | MTLResourceOptions::MTLResourceCPUCacheModeWriteCombined
+        }
+        MemoryLocation::Readback => {
+            MTLResourceOptions::MTLResourceStorageModeShared
+                | MTLResourceOptions::MTLResourceCPUCacheModeDefaultCache
+        }
+    }
+}
+
+impl MetalBuffer {
+    pub fn new(device: &MetalDevice, desc: &BufferDesc) -> Result<Self, MetalError> {
+        let mtl_buffer = device
+            .mtl_device()
+            .newBufferWithLength_options(
+                desc.size as _,
+                to_mtl_resource_options(desc.memory_location),
+            )
+            .ok_or_else(|| MetalError::Custom("Failed to create buffer".to_owned()))?;
+        if let Some(label) = desc.label {
+            mtl_buffer.setLabel(Some(&NSString::from_str(label)));
+        }
+
+        Ok(Self(Arc::new(Inner {
+            mtl_buffer,
+
+            usage: desc.usage,
+            memory_location: desc.memory_location,
+        })))
+    }
+
+    #[inline]
+    pub fn mtl_buffer(&self) -> &ProtocolObject<dyn MTLBuffer> {
+        &self.0.mtl_buffer
+    }
+
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.0.mtl_buffer.length() as _
+    }
+
+    #[inline]
+    pub fn usage(&self) -> BufferUsageFlags {
+        self.0.usage
+    }
+
+    #[inline]
+    pub fn memory_location(&self) -> MemoryLocation {
+        self.0.memory_location
+    }
+
+    #[inline]
+    pub fn mapped_ptr(&self) -> Option<NonNull<u8>> {
+        if self.0.memory_location.is_host_visible() {
+            Some(self.0.mtl_buffer.contents().cast())
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn device_address(&self) -> Option<u64> {
+        if self.0.usage.contains(BufferUsageFlags::DEVICE_ADDRESS) {
+            Some(self.0.mtl_buffer.gpuAddress())
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,1411 @@
+use std::ptr::NonNull;
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_foundation::{NSRange, NSString};
+use objc2_metal::{
+    MTLBarrierScope, MTLBlitCommandEncoder, MTLClearColor, MTLCommandBuffer,
+    MTLCommandBufferStatus, MTLCommandEncoder, MTLCommandQueue, MTLComputeCommandEncoder,
+    MTLCounterSamplingPoint, MTLDevice, MTLIndexType, MTLLoadAction, MTLOrigin, MTLPrimitiveType,
+    MTLRenderCommandEncoder, MTLRenderPassDescriptor, MTLRenderStages, MTLScissorRect, MTLSize,
+    MTLStoreAction, MTLViewport, MTLVisibilityResultMode,
+};
+
+use crate::{
+    metal::{
+        MetalBindGroup, MetalComputePipeline, MetalDevice, MetalError, MetalGraphicsPipeline,
+        MetalMeshWorkgroupSizes, PUSH_CONSTANTS_BUFFER_INDEX,
+    },
+    validate_push_constants, BindGroup, Buffer, BufferBarrier, BufferTextureCopy, ClearValue,
+    CommandListDesc, ComputePipeline, Extent3d, GlobalBarrier, GraphicsPipeline, IndexFormat,
+    LoadOp, Origin3d, QueryPool, QueryType, QueueType, RenderPassDesc, ScissorRect,
+    ShaderStageFlags, StoreOp, Texture, TextureBarrier, TextureCopy, TextureDimension,
+    TextureSubresourceRange, TextureUsageFlags, Viewport,
+};
+
+pub struct MetalCommandList {
+    device: MetalDevice,
+
+    queue_type: QueueType,
+    /// Applied to the command buffer of every recording.
+    label: Option<String>,
+
+    command_buffer: Option<Retained<ProtocolObject<dyn MTLCommandBuffer>>>,
+    blit_encoder: Option<Retained<ProtocolObject<dyn MTLBlitCommandEncoder>>>,
+    compute_encoder: Option<Retained<ProtocolObject<dyn MTLComputeCommandEncoder>>>,
+    render_encoder: Option<Retained<ProtocolObject<dyn MTLRenderCommandEncoder>>>,
+
+    compute_pipeline: Option<MetalComputePipeline>,
+    /// Compute encoders are started lazily, so bind groups are set on each of them by set.
+    compute_bind_groups: Vec<(u32, MetalBindGroup)>,
+    graphics_pipeline: Option<MetalGraphicsPipeline>,
+    index_buffer: Option<(Buffer, u64, IndexFormat)>,
+    /// Push constant data as laid out in the shaders, set as bytes on every encoder that uses it.
+    push_constants: Vec<u8>,
+    /// Debug groups open on the command buffer, and on the render encoder of the active pass.
+    debug_group_depth: u32,
+    render_pass_debug_group_depth: u32,
+
+    error: Option<MetalError>,
+}
+
+pub(crate) fn to_mtl_origin(origin: Origin3d) -> MTLOrigin {
+    MTLOrigin {
+        x: origin.x as _,
+        y: origin.y as _,
+        z: origin.z as _,
+    }
+}
+
+pub(crate) fn to_mtl_size(extent: Extent3d) -> MTLSize {
+    MTLSize {
+        width: extent.width as _,
+        height: extent.height as _,
+        depth: extent.depth as _,
+    }
+}
+
+fn to_mtl_workgroup_size([x, y, z]: [u32; 3]) -> MTLSize {
+    MTLSize {
+        width: x as _,
+        height: y as _,
+        depth: z as _,
+    }
+}
+
+fn to_mtl_load_action(load_op: LoadOp) -> MTLLoadAction {
+    match load_op {
+        LoadOp::Load => MTLLoadAction::Load,
+        LoadOp::Clear => MTLLoadAction::Clear,
+        LoadOp::DontCare => MTLLoadAction::DontCare,
+    }
+}
+
+fn to_mtl_store_action(store_op: StoreOp, resolve: bool) -> MTLStoreAction {
+    match (store_op, resolve) {
+        (StoreOp::Store, false) => MTLStoreAction::Store,
+        (StoreOp::Store, true) => MTLStoreAction::StoreAndMultisampleResolve,
+        (StoreOp::DontCare, false) => MTLStoreAction::DontCare,
+        (StoreOp::DontCare, true) => MTLStoreAction::MultisampleResolve,
+    }
+}
+
+/// Returns the buffer row and image pitch of `region`, resolving tightly packed zero values.
+pub(crate) fn to_mtl_bytes_per_row_and_image(
+    texture: &Texture,
+    region: &BufferTextureCopy,
+) -> (usize, usize) {
+    let format = texture.format();
+    let (block_width, block_height) = format.block_extent();
+
+    let bytes_per_row = if region.bytes_per_row == 0 {
+        region.extent.width.div_ceil(block_width) * format.block_size()
+    } else {
+        region.bytes_per_row
+    };
+    let rows_per_image = if region.rows_per_image == 0 {
+        region.extent.height.div_ceil(block_height)
+    } else {
+        region.rows_per_image
+    };
+
+    (
+        bytes_per_row as usize,
+        bytes_per_row as usize * rows_per_image as usize,
+    )
+}
+
+impl MetalCommandList {
+    pub fn new(device: &MetalDevice, desc: &CommandListDesc) -> Result<Self, MetalError> {
+        Ok(Self {
+            device: device.clone(),
+
+            queue_type: desc.queue_type,
+            label: desc.label.map(str::to_owned),
+
+            command_buffer: None,
+            blit_encoder: None,
+            compute_encoder: None,
+            render_encoder: None,
+
+            compute_pipeline: None,
+            compute_bind_groups: Vec::new(),
+            graphics_pipeline: None,
+            index_buffer: None,
+            push_constants: Vec::new(),
+            debug_group_depth: 0,
+            render_pass_debug_group_depth: 0,
+
+            error: None,
+        })
+    }
+
+    /// Returns the command buffer of the current recording, `None` before the first `begin`.
+    #[inline]
+    pub fn mtl_command_buffer(&self) -> Option<&ProtocolObject<dyn MTLCommandBuffer>> {
+        self.command_buffer.as_deref()
+    }
+
+    #[inline]
+    pub fn queue_type(&self) -> QueueType {
+        self.queue_type
+    }
+
+    pub fn begin(&mut self) -> Result<(), MetalError> {
+        self.end_encoding();
+        if let Some(render_encoder) = self.render_encoder.take() {
+            render_encoder.endEncoding();
+        }
+        self.compute_pipeline = None;
+        self.compute_bind_groups.clear();
+        self.graphics_pipeline = None;
+        self.index_buffer = None;
+        self.push_constants.clear();
+        self.debug_group_depth = 0;
+        self.render_pass_debug_group_depth = 0;
+        self.error = None;
+
+        // Metal command buffers are single use, every recording gets a fresh one.
+        let command_buffer = self
+            .device
+            .mtl_command_queue()
+            .commandBuffer()
+            .ok_or_else(|| MetalError::Custom("Failed to create command buffer".to_owned()))?;
+        if let Some(label) = &self.label {
+            command_buffer.setLabel(Some(&NSString::from_str(label)));
+        }
+        self.command_buffer = Some(command_buffer);
+
+        Ok(())
+    }
+
+    pub fn end(&mut self) -> Result<(), MetalError> {
+        if self.render_encoder.is_some() {
+            self.record_error(MetalError::Custom(
+                "Command list ended inside a render pass".to_owned(),
+            ));
+            self.end_render_pass();
+        }
+        self.end_encoding();
+
+        if self.debug_group_depth > 0 {
+            self.record_error(MetalError::Custom(
+                "Command list ended with an open debug group".to_owned(),
+            ));
+            if let Some(command_buffer) = &self.command_buffer {
+                for _ in 0..self.debug_group_depth {
+                    command_buffer.popDebugGroup();
+                }
+            }
+            self.debug_group_depth = 0;
+        }
+
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Keeps the first recording error so it can be reported by [`Self::end`].
+    fn record_error(&mut self, error: MetalError) {
+        self.error.get_or_insert(error);
+    }
+
+    fn end_encoding(&mut self) {
+        if let Some(blit_encoder) = self.blit_encoder.take() {
+            blit_encoder.endEncoding();
+        }
+        if let Some(compute_encoder) = self.compute_encoder.take() {
+            compute_encoder.endEncoding();
+        }
+    }
+
+    /// Records an error if a render pass is active, Metal encodes one pass at a time.
+    fn validate_outside_render_pass(&mut self, command: &str) -> bool {
+        if self.render_encoder.is_some() {
+            self.record_error(MetalError::Custom(format!(
+                "{command} is not allowed inside a render pass"
+            )));
+            return false;
+        }
+
+        true
+    }
+
+    fn blit_encoder(
+        &mut self,
+        command: &str,
+    ) -> Option<&ProtocolObject<dyn MTLBlitCommandEncoder>> {
+        if !self.validate_outside_render_pass(command) {
+            return None;
+        }
+
+        if self.blit_encoder.is_none() {
+            self.end_encoding();
+
+            let Some(command_buffer) = &self.command_buffer else {
+                self.record_error(MetalError::Custom(
+                    "Command recorded outside of begin and end".to_owned(),
+                ));
+                return None;
+            };
+
+            self.blit_encoder = command_buffer.blitCommandEncoder();
+        }
+
+        self.blit_encoder.as_deref()
+    }
+
+    /// Returns the compute encoder with the bound pipeline set, starting a new one if needed.
+    fn compute_encoder(
+        &mut self,
+        command: &str,
+    ) -> Option<(&ProtocolObject<dyn MTLComputeCommandEncoder>, [u32; 3])> {
+        let Some(compute_pipeline) = &self.compute_pipeline else {
+            self.record_error(MetalError::Custom(format!(
+                "{command} requires a bound compute pipeline"
+            )));
+            return None;
+        };
+        let compute_pipeline = compute_pipeline.clone();
+
+        if !self.validate_outside_render_pass(command) {
+            return None;
+        }
+
+        if self.compute_encoder.is_none() {
+            self.end_encoding();
+
+            let Some(command_buffer) = &self.command_buffer else {
+                self.record_error(MetalError::Custom(
+                    "Command recorded outside of begin and end".to_owned(),
+                ));
+                return None;
+            };
+
+            self.compute_encoder = command_buffer.computeCommandEncoder();
+
+            if let (Some(compute_encoder), Some(bindless_heap)) =
+                (&self.compute_encoder, self.device.bindless_heap())
+            {
+                bindless_heap.use_resources(|resource, usage| {
+                    compute_encoder.useResource_usage(resource, usage)
+                });
+            }
+        }
+
+        let compute_encoder = self.compute_encoder.as_deref()?;
+        compute_encoder.setComputePipelineState(compute_pipeline.mtl_pipeline_state());
+
+        if let (Some(set), Some(bindless_heap)) = (
+            compute_pipeline.layout().bindless_set(),
+            self.device.bindless_heap(),
+        ) {
+            unsafe {
+                compute_encoder.setBuffer_offset_atIndex(
+                    Some(bindless_heap.mtl_argument_buffer()),
+                    0,
+                    set as _,
+                )
+            };
+        }
+
+        for (set, bind_group) in &self.compute_bind_groups {
+            unsafe {
+                compute_encoder.setBuffer_offset_atIndex(
+                    Some(bind_group.mtl_argument_buffer()),
+                    bind_group.offset(),
+                    *set as _,
+                )
+            };
+
+            for (resource, usage) in bind_group.resources() {
+                compute_encoder.useResource_usage(resource, usage);
+            }
+        }
+
+        if !self.push_constants.is_empty() {
+            unsafe {
+                compute_encoder.setBytes_length_atIndex(
+                    NonNull::new_unchecked(self.push_constants.as_ptr().cast_mut().cast()),
+                    self.push_constants.len(),
+                    PUSH_CONSTANTS_BUFFER_INDEX,
+                )
+            };
+        }
+
+        Some((compute_encoder, compute_pipeline.workgroup_size()))
+    }
+
+    pub fn copy_buffer(
+        &mut self,
+        src: &Buffer,
+        src_offset: u64,
+        dst: &Buffer,
+        dst_offset: u64,
+        size: u64,
+    ) {
+        let Some(blit_encoder) = self.blit_encoder("copy_buffer") else {
+            return;
+        };
+
+        unsafe {
+            blit_encoder.copyFromBuffer_sourceOffset_toBuffer_destinationOffset_size(
+                src.as_metal().mtl_buffer(),
+                src_offset as _,
+                dst.as_metal().mtl_buffer(),
+                dst_offset as _,
+                size as _,
+            );
+        }
+    }
+
+    pub fn copy_buffer_to_texture(
+        &mut self,
+        src: &Buffer,
+        dst: &Texture,
+        region: &BufferTextureCopy,
+    ) {
+        let (bytes_per_row, bytes_per_image) = to_mtl_bytes_per_row_and_image(dst, region);
+
+        let Some(blit_encoder) = self.blit_encoder("copy_buffer_to_texture") else {
+            return;
+        };
+
+        unsafe {
+            blit_encoder.copyFromBuffer_sourceOffset_sourceBytesPerRow_sourceBytesPerImage_sourceSize_toTexture_destinationSlice_destinationLevel_destinationOrigin(
+                src.as_metal().mtl_buffer(),
+                region.buffer_offset as _,
+                bytes_per_row,
+                bytes_per_image,
+                to_mtl_size(region.extent),
+                dst.as_metal().mtl_texture(),
+                region.array_layer as _,
+                region.mip_level as _,
+                to_mtl_origin(region.origin),
+            );
+        }
+    }
+
+    pub fn copy_texture_to_buffer(
+        &mut self,
+        src: &Texture,
+        dst: &Buffer,
+        region: &BufferTextureCopy,
+    ) {
+        let (bytes_per_row, bytes_per_image) = to_mtl_bytes_per_row_and_image(src, region);
+
+        let Some(blit_encoder) = self.blit_encoder("copy_texture_to_buffer") else {
+            return;
+        };
+
+        unsafe {
+            blit_encoder.copyFromTexture_sourceSlice_sourceLevel_sourceOrigin_sourceSize_toBuffer_destinationOffset_destinationBytesPerRow_destinationBytesPerImage(
+                src.as_metal().mtl_texture(),
+                region.array_layer as _,
+                region.mip_level as _,
+                to_mtl_origin(region.origin),
+                to_mtl_size(region.extent),
+                dst.as_metal().mtl_buffer(),
+                region.buffer_offset as _,
+                bytes_per_row,
+                bytes_per_image,
+            );
+        }
+    }
+
+    pub fn copy_texture(&mut self, src: &Texture, dst: &Texture, region: &TextureCopy) {
+        let Some(blit_encoder) = self.blit_encoder("copy_texture") else {
+            return;
+        };
+
+        unsafe {
+            blit_encoder.copyFromTexture_sourceSlice_sourceLevel_sourceOrigin_sourceSize_toTexture_destinationSlice_destinationLevel_destinationOrigin(
+                src.as_metal().mtl_texture(),
+                region.src_array_layer as _,
+                region.src_mip_level as _,
+                to_mtl_origin(region.src_origin),
+                to_mtl_size(region.extent),
+                dst.as_metal().mtl_texture(),
+                region.dst_array_layer as _,
+                region.dst_mip_level as _,
+                to_mtl_origin(region.dst_origin),
+            );
+        }
+    }
+
+    pub fn clear_buffer(&mut self, buffer: &Buffer, offset: u64, size: u64, value: u8) {
+        let Some(blit_encoder) = self.blit_encoder("clear_buffer") else {
+            return;
+        };
+
+        blit_encoder.fillBuffer_range_value(
+            buffer.as_metal().mtl_buffer(),
+            NSRange::new(offset as _, size as _),
+            value,
+        );
+    }
+
+    /// Metal has no clear command, every subresource is cleared by an empty render pass instead.
+    pub fn clear_texture(
+        &mut self,
+        texture: &Texture,
+        range: &TextureSubresourceRange,
+        value: ClearValue,
+    ) {
+        if !texture.usage().intersects(
+            TextureUsageFlags::COLOR_ATTACHMENT | TextureUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+        ) {
+            self.record_error(MetalError::Custom(
+                "clear_texture requires an attachment usage on Metal".to_owned(),
+            ));
+            return;
+        }
+
+        if !self.validate_outside_render_pass("clear_texture") {
+            return;
+        }
+
+        self.end_encoding();
+
+        let Some(command_buffer) = &self.command_buffer else {
+            self.record_error(MetalError::Custom(
+                "Command recorded outside of begin and end".to_owned(),
+            ));
+            return;
+        };
+
+        let mtl_texture = texture.as_metal().mtl_texture();
+        let format = texture.format();
+        let range = range.resolve(texture, format);
+
+        let base_mip_level = range.base_mip_level;
+        let mip_level_count = range.mip_level_count.unwrap_or(0);
+
+        for mip_level in base_mip_level..base_mip_level + mip_level_count {
+            // 3D textures are cleared slice by slice through their depth planes.
+            let (slices, depth_planes) = if texture.dimension() == TextureDimension::D3 {
+                (0..1, 0..texture.extent().mip_level(mip_level).depth)
+            } else {
+                let base_array_layer = range.base_array_layer;
+                let array_layer_count = range.array_layer_count.unwrap_or(0);
+
+                (base_array_layer..base_array_layer + array_layer_count, 0..1)
+            };
+
+            for slice in slices {
+                for depth_plane in depth_planes.clone() {
+                    let render_pass_descriptor = MTLRenderPassDescriptor::renderPassDescriptor();
+
+                    match value {
+                        ClearValue::Color([red, green, blue, alpha]) => {
+                            let attachment = unsafe {
+                                render_pass_descriptor
+                                    .colorAttachments()
+                                    .objectAtIndexedSubscript(0)
+                            };
+
+                            attachment.setTexture(Some(mtl_texture));
+                            attachment.setLevel(mip_level as _);
+                            attachment.setSlice(slice as _);
+                            attachment.setDepthPlane(depth_plane as _);
+                            attachment.setLoadAction(MTLLoadAction::Clear);
+                            attachment.setStoreAction(MTLStoreAction::Store);
+                            attachment.setClearColor(MTLClearColor {
+                                red: red as _,
+                                green: green as _,
+                                blue: blue as _,
+                                alpha: alpha as _,
+                            });
+                        }
+                        ClearValue::DepthStencil { depth, stencil } => {
+                            if format.is_depth() {
+                                let attachment = render_pass_descriptor.depthAttachment();
+
+                                attachment.setTexture(Some(mtl_texture));
+                                attachment.setLevel(mip_level as _);
+                                attachment.setSlice(slice as _);
+                                attachment.setLoadAction(MTLLoadAction::Clear);
+                                attachment.setStoreAction(MTLStoreAction::Store);
+                                attachment.setClearDepth(depth as _);
+                            }
+                            if format.is_stencil() {
+                                let attachment = render_pass_descriptor.stencilAttachment();
+
+                                attachment.setTexture(Some(mtl_texture));
+                                attachment.setLevel(mip_level as _);
+                                attachment.setSlice(slice as _);
+                                attachment.setLoadAction(MTLLoadAction::Clear);
+                                attachment.setStoreAction(MTLStoreAction::Store);
+                                attachment.setClearStencil(stencil);
+                            }
+                        }
+                    }
+
+                    if let Some(render_encoder) =
+                        command_buffer.renderCommandEncoderWithDescriptor(&render_pass_descriptor)
+                    {
+                        render_encoder.endEncoding();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Metal tracks hazards between encoders itself, so only the dispatches of the open compute
+    /// encoder need a memory barrier. Queue transfers need nothing, all queues share resources.
+    pub fn barrier(
+        &mut self,
+        buffer_barriers: &[BufferBarrier],
+        texture_barriers: &[TextureBarrier],
+        global_barriers: &[GlobalBarrier],
+    ) {
+        if !self.validate_outside_render_pass("barrier") {
+            return;
+        }
+
+        let Some(compute_encoder) = &self.compute_encoder else {
+            return;
+        };
+
+        let mut scope = MTLBarrierScope::empty();
+        if !buffer_barriers.is_empty() || !global_barriers.is_empty() {
+            scope |= MTLBarrierScope::Buffers;
+        }
+        if !texture_barriers.is_empty() || !global_barriers.is_empty() {
+            scope |= MTLBarrierScope::Textures;
+        }
+
+        if !scope.is_empty() {
+            unsafe { compute_encoder.memoryBarrierWithScope(scope) };
+        }
+    }
+
+    pub fn begin_render_pass(&mut self, desc: &RenderPassDesc) {
+        if self.render_encoder.is_some() {
+            self.record_error(MetalError::Custom(
+                "begin_render_pass called inside a render pass".to_owned(),
+            ));
+            return;
+        }
+
+        self.end_encoding();
+
+        let Some(command_buffer) = &self.command_buffer else {
+            self.record_error(MetalError::Custom(
+                "Command recorded outside of begin and end".to_owned(),
+            ));
+            return;
+        };
+
+        let descriptor = MTLRenderPassDescriptor::renderPassDescriptor();
+
+        for (i, color_attachment) in desc.color_attachments.iter().enumerate() {
+            let [red, green, blue, alpha] = color_attachment.clear_color;
+            let attachment = unsafe { descriptor.colorAttachments().objectAtIndexedSubscript(i) };
+
+            attachment.setTexture(Some(color_attachment.view.as_metal().mtl_texture()));
+            attachment.setLoadAction(to_mtl_load_action(color_attachment.load_op));
+            attachment.setStoreAction(to_mtl_store_action(
+                color_attachment.store_op,
+                color_attachment.resolve_target.is_some(),
+            ));
+            attachment.setClearColor(MTLClearColor {
+                red: red as _,
+                green: green as _,
+                blue: blue as _,
+                alpha: alpha as _,
+            });
+            if let Some(resolve_target) = color_attachment.resolve_target {
+                attachment.setResolveTexture(Some(resolve_target.as_metal().mtl_texture()));
+            }
+        }
+
+        if let Some(depth_stencil_attachment) = &desc.depth_stencil_attachment {
+            let view = depth_stencil_attachment.view;
+            let resolve_target = depth_stencil_attachment.resolve_target;
+
+            if view.format().is_depth() {
+                let attachment = descriptor.depthAttachment();
+
+                attachment.setTexture(Some(view.as_metal().mtl_texture()));
+                attachment
+                    .setLoadAction(to_mtl_load_action(depth_stencil_attachment.depth_load_op));
+                attachment.setStoreAction(to_mtl_store_action(
+                    depth_stencil_attachment.depth_store_op,
+                    resolve_target.is_some(),
+                ));
+                attachment.setClearDepth(depth_stencil_attachment.clear_depth as _);
+                if let Some(resolve_target) = resolve_target {
+                    attachment.setResolveTexture(Some(resolve_target.as_metal().mtl_texture()));
+                }
+            }
+            if view.format().is_stencil() {
+                let attachment = descriptor.stencilAttachment();
+
+                attachment.setTexture(Some(view.as_metal().mtl_texture()));
+                attachment
+                    .setLoadAction(to_mtl_load_action(depth_stencil_attachment.stencil_load_op));
+                attachment.setStoreAction(to_mtl_store_action(
+                    depth_stencil_attachment.stencil_store_op,
+                    resolve_target.is_some(),
+                ));
+                attachment.setClearStencil(depth_stencil_attachment.clear_stencil);
+                if let Some(resolve_target) = resolve_target {
+                    attachment.setResolveTexture(Some(resolve_target.as_metal().mtl_texture()));
+                }
+            }
+        }
+
+        if let Some(query_pool) = desc.occlusion_query_pool {
+            descriptor.setVisibilityResultBuffer(query_pool.as_metal().mtl_visibility_buffer());
+        }
+
+        if let Some(first_view) = desc.first_view() {
+            let layer_count = first_view.range().array_layer_count.unwrap_or(1);
+            if layer_count > 1 {
+                unsafe { descriptor.setRenderTargetArrayLength(layer_count as _) };
+            }
+        }
+
+        self.render_encoder = command_buffer.renderCommandEncoderWithDescriptor(&descriptor);
+        if self.render_encoder.is_none() {
+            self.record_error(MetalError::Custom(
+                "Failed to create render command encoder".to_owned(),
+            ));
+        }
+        self.render_pass_debug_group_depth = 0;
+
+        if let (Some(render_encoder), Some(label)) = (&self.render_encoder, desc.label) {
+            render_encoder.setLabel(Some(&NSString::from_str(label)));
+        }
+
+        if let (Some(render_encoder), Some(bindless_heap)) =
+            (&self.render_encoder, self.device.bindless_heap())
+        {
+            bindless_heap.use_resources(|resource, usage| {
+                render_encoder.useResource_usage_stages(resource, usage, MTLRenderStages::all())
+            });
+        }
+    }
+
+    pub fn end_render_pass(&mut self) {
+        let Some(render_encoder) = self.render_encoder.take() else {
+            self.record_error(MetalError::Custom(
+                "end_render_pass called outside of a render pass".to_owned(),
+            ));
+            return;
+        };
+
+        if self.render_pass_debug_group_depth > 0 {
+            self.record_error(MetalError::Custom(
+                "end_render_pass called with an open debug group of the render pass".to_owned(),
+            ));
+            for _ in 0..self.render_pass_debug_group_depth {
+                render_encoder.popDebugGroup();
+            }
+            self.render_pass_debug_group_depth = 0;
+        }
+
+        render_encoder.endEncoding();
+
+        // Render encoder state does not outlive the encoder.
+        self.graphics_pipeline = None;
+        self.index_buffer = None;
+    }
+
+    /// Returns the render encoder of the active render pass.
+    fn render_encoder(
+        &mut self,
+        command: &str,
+    ) -> Option<&ProtocolObject<dyn MTLRenderCommandEncoder>> {
+        if self.render_encoder.is_none() {
+            self.record_error(MetalError::Custom(format!(
+                "{command} requires an active render pass"
+            )));
+        }
+
+        self.render_encoder.as_deref()
+    }
+
+    /// Returns the render encoder of the active render pass and the bound graphics pipeline.
+    fn draw_state(
+        &mut self,
+        command: &str,
+    ) -> Option<(
+        &ProtocolObject<dyn MTLRenderCommandEncoder>,
+        &MetalGraphicsPipeline,
+    )> {
+        self.render_encoder(command)?;
+
+        if self.graphics_pipeline.is_none() {
+            self.record_error(MetalError::Custom(format!(
+                "{command} requires a bound graphics pipeline"
+            )));
+        }
+
+        Some((
+            self.render_encoder.as_deref()?,
+            self.graphics_pipeline.as_ref()?,
+        ))
+    }
+
+    /// Returns the draw state if a vertex pipeline is bound.
+    fn vertex_draw_state(
+        &mut self,
+        command: &str,
+    ) -> Option<(
+        &ProtocolObject<dyn MTLRenderCommandEncoder>,
+        MTLPrimitiveType,
+    )> {
+        let (_, pipeline) = self.draw_state(command)?;
+        let is_mesh_pipeline = pipeline.mesh_workgroup_sizes().is_some();
+        let primitive_type = pipeline.mtl_primitive_type();
+
+        if is_mesh_pipeline {
+            self.record_error(MetalError::Custom(format!(
+                "{command} requires a pipeline with vertex input"
+            )));
+            return None;
+        }
+
+        Some((self.render_encoder.as_deref()?, primitive_type))
+    }
+
+    /// Returns the draw state if a mesh pipeline is bound.
+    fn mesh_draw_state(
+        &mut self,
+        command: &str,
+    ) -> Option<(
+        &ProtocolObject<dyn MTLRenderCommandEncoder>,
+        MetalMeshWorkgroupSizes,
+    )> {
+        let (_, pipeline) = self.draw_state(command)?;
+
+        let Some(mesh_workgroup_sizes) = pipeline.mesh_workgroup_sizes() else {
+            self.record_error(MetalError::Custom(format!(
+                "{command} requires a mesh pipeline"
+            )));
+            return None;
+        };
+
+        Some((self.render_encoder.as_deref()?, mesh_workgroup_sizes))
+    }
+
+    pub fn set_viewport(&mut self, viewport: &Viewport) {
+        let Some(render_encoder) = self.render_encoder("set_viewport") else {
+            return;
+        };
+
+        render_encoder.setViewport(MTLViewport {
+            originX: viewport.x as _,
+            originY: viewport.y as _,
+            width: viewport.width as _,
+            height: viewport.height as _,
+            znear: viewport.min_depth as _,
+            zfar: viewport.max_depth as _,
+        });
+    }
+
+    pub fn set_scissor(&mut self, scissor: &ScissorRect) {
+        let Some(render_encoder) = self.render_encoder("set_scissor") else {
+            return;
+        };
+
+        render_encoder.setScissorRect(MTLScissorRect {
+            x: scissor.x as _,
+            y: scissor.y as _,
+            width: scissor.width as _,
+            height: scissor.height as _,
+        });
+    }
+
+    pub fn set_stencil_reference(&mut self, reference: u32) {
+        let Some(render_encoder) = self.render_encoder("set_stencil_reference") else {
+            return;
+        };
+
+        render_encoder.setStencilReferenceValue(reference);
+    }
+
+    pub fn set_blend_constants(&mut self, [red, green, blue, alpha]: [f32; 4]) {
+        let Some(render_encoder) = self.render_encoder("set_blend_constants") else {
+            return;
+        };
+
+        render_encoder.setBlendColorRed_green_blue_alpha(red, green, blue, alpha);
+    }
+
+    pub fn bind_vertex_buffer(&mut self, slot: u32, buffer: &Buffer, offset: u64) {
+        let Some(render_encoder) = self.render_encoder("bind_vertex_buffer") else {
+            return;
+        };
+
+        unsafe {
+            render_encoder.setVertexBuffer_offset_atIndex(
+                Some(buffer.as_metal().mtl_buffer()),
+                offset as _,
+                slot as _,
+            );
+        }
+    }
+
+    /// Metal takes the index buffer with every indexed draw, so it is only remembered here.
+    pub fn bind_index_buffer(&mut self, buffer: &Buffer, offset: u64, format: IndexFormat) {
+        if self.render_encoder("bind_index_buffer").is_none() {
+            return;
+        }
+
+        self.index_buffer = Some((buffer.clone(), offset, format));
+    }
+
+    pub fn draw(
+        &mut self,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) {
+        let Some((render_encoder, primitive_type)) = self.vertex_draw_state("draw") else {
+            return;
+        };
+
+        unsafe {
+            render_encoder.drawPrimitives_vertexStart_vertexCount_instanceCount_baseInstance(
+                primitive_type,
+                first_vertex as _,
+                vertex_count as _,
+                instance_count as _,
+                first_instance as _,
+            );
+        }
+    }
+
+    pub fn draw_indexed(
+        &mut self,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) {
+        let Some((buffer, offset, format)) = self.index_buffer.clone() else {
+            self.record_error(MetalError::Custom(
+                "draw_indexed requires a bound index buffer".to_owned(),
+            ));
+            return;
+        };
+
+        let Some((render_encoder, primitive_type)) = self.vertex_draw_state("draw_indexed") else {
+            return;
+        };
+
+        unsafe {
+            render_encoder.drawIndexedPrimitives_indexCount_indexType_indexBuffer_indexBufferOffset_instanceCount_baseVertex_baseInstance(
+                primitive_type,
+                index_count as _,
+                match format {
+                    IndexFormat::Uint16 => MTLIndexType::UInt16,
+                    IndexFormat::Uint32 => MTLIndexType::UInt32,
+                },
+                buffer.as_metal().mtl_buffer(),
+                (offset + first_index as u64 * format.size()) as _,
+                instance_count as _,
+                vertex_offset as _,
+                first_instance as _,
+            );
+        }
+    }
+
+    pub fn draw_mesh_tasks(&mut self, x: u32, y: u32, z: u32) {
+        let Some((render_encoder, mesh_workgroup_sizes)) = self.mesh_draw_state("draw_mesh_tasks")
+        else {
+            return;
+        };
+
+        unsafe {
+            render_encoder
+                .drawMeshThreadgroups_threadsPerObjectThreadgroup_threadsPerMeshThreadgroup(
+                    to_mtl_size(Extent3d {
+                        width: x,
+                        height: y,
+                        depth: z,
+                    }),
+                    to_mtl_workgroup_size(mesh_workgroup_sizes.object),
+                    to_mtl_workgroup_size(mesh_workgroup_sizes.mesh),
+                );
+        }
+    }
+
+    /// Metal has no multi draw for mesh threadgroups, every draw is issued separately.
+    pub fn draw_mesh_tasks_indirect(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        let Some((render_encoder, mesh_workgroup_sizes)) =
+            self.mesh_draw_state("draw_mesh_tasks_indirect")
+        else {
+            return;
+        };
+
+        for i in 0..draw_count {
+            unsafe {
+                render_encoder.drawMeshThreadgroupsWithIndirectBuffer_indirectBufferOffset_threadsPerObjectThreadgroup_threadsPerMeshThreadgroup(
+                    buffer.as_metal().mtl_buffer(),
+                    (offset + i as u64 * stride as u64) as _,
+                    to_mtl_workgroup_size(mesh_workgroup_sizes.object),
+                    to_mtl_workgroup_size(mesh_workgroup_sizes.mesh),
+                );
+            }
+        }
+    }
+
+    pub fn draw_mesh_tasks_indirect_count(
+        &mut self,
+        _buffer: &Buffer,
+        _offset: u64,
+        _count_buffer: &Buffer,
+        _count_buffer_offset: u64,
+        _max_draw_count: u32,
+        _stride: u32,
+    ) {
+        self.record_error(MetalError::Custom(
+            "draw_mesh_tasks_indirect_count is not supported on Metal".to_owned(),
+        ));
+    }
+
+    pub fn bind_graphics_pipeline(&mut self, pipeline: &GraphicsPipeline) {
+        let pipeline = pipeline.as_metal();
+        let device = self.device.clone();
+
+        let Some(render_encoder) = self.render_encoder("bind_graphics_pipeline") else {
+            return;
+        };
+
+        render_encoder.setRenderPipelineState(pipeline.mtl_pipeline_state());
+        render_encoder.setDepthStencilState(Some(pipeline.mtl_depth_stencil_state()));
+        render_encoder.setCullMode(pipeline.mtl_cull_mode());
+        render_encoder.setFrontFacingWinding(pipeline.mtl_winding());
+        render_encoder.setTriangleFillMode(pipeline.mtl_fill_mode());
+        render_encoder.setDepthClipMode(pipeline.mtl_depth_clip_mode());
+
+        let depth_bias = pipeline.depth_bias().unwrap_or_default();
+        render_encoder.setDepthBias_slopeScale_clamp(
+            depth_bias.constant as f32,
+            depth_bias.slope_scale,
+            depth_bias.clamp,
+        );
+
+        if let (Some(set), Some(bindless_heap)) =
+            (pipeline.layout().bindless_set(), device.bindless_heap())
+        {
+            let argument_buffer = Some(bindless_heap.mtl_argument_buffer());
+
+            unsafe {
+                if pipeline.mesh_workgroup_sizes().is_some() {
+                    render_encoder.setObjectBuffer_offset_atIndex(argument_buffer, 0, set as _);
+                    render_encoder.setMeshBuffer_offset_atIndex(argument_buffer, 0, set as _);
+                } else {
+                    render_encoder.setVertexBuffer_offset_atIndex(argument_buffer, 0, set as _);
+                }
+                render_encoder.setFragmentBuffer_offset_atIndex(argument_buffer, 0, set as _);
+            }
+        }
+
+        self.graphics_pipeline = Some(pipeline.clone());
+    }
+
+    pub fn bind_compute_pipeline(&mut self, pipeline: &ComputePipeline) {
+        self.compute_pipeline = Some(pipeline.as_metal().clone());
+    }
+
+    pub fn bind_bind_group(&mut self, set: u32, bind_group: &BindGroup) {
+        let bind_group = bind_group.as_metal();
+
+        let layout = match &self.render_encoder {
+            Some(_) => self.graphics_pipeline.as_ref().map(|p| p.layout()),
+            None => self.compute_pipeline.as_ref().map(|p| p.layout()),
+        };
+        let Some(layout) = layout else {
+            self.record_error(MetalError::Custom(
+                "bind_bind_group requires a bound pipeline".to_owned(),
+            ));
+            return;
+        };
+
+        if layout.bind_group_layout(set) != Some(bind_group.layout()) {
+            self.record_error(MetalError::Custom(format!(
+                "Bind group layout does not match set {set} of the pipeline layout"
+            )));
+            return;
+        }
+        if !bind_group.is_allocated() {
+            self.record_error(MetalError::Custom(
+                "Bind group used after its pool was reset".to_owned(),
+            ));
+            return;
+        }
+
+        let (Some(render_encoder), Some(graphics_pipeline)) =
+            (&self.render_encoder, &self.graphics_pipeline)
+        else {
+            self.compute_bind_groups.retain(|(s, _)| *s != set);
+            self.compute_bind_groups.push((set, bind_group.clone()));
+            return;
+        };
+
+        let argument_buffer = Some(bind_group.mtl_argument_buffer());
+        let offset = bind_group.offset();
+
+        unsafe {
+            if graphics_pipeline.mesh_workgroup_sizes().is_some() {
+                render_encoder.setObjectBuffer_offset_atIndex(argument_buffer, offset, set as _);
+                render_encoder.setMeshBuffer_offset_atIndex(argument_buffer, offset, set as _);
+            } else {
+                render_encoder.setVertexBuffer_offset_atIndex(argument_buffer, offset, set as _);
+            }
+            render_encoder.setFragmentBuffer_offset_atIndex(argument_buffer, offset, set as _);
+        }
+
+        for (resource, usage) in bind_group.resources() {
+            render_encoder.useResource_usage_stages(resource, usage, MTLRenderStages::all());
+        }
+    }
+
+    pub fn push_constants(&mut self, stages: ShaderStageFlags, offset: u32, data: &[u8]) {
+        let layout = match &self.render_encoder {
+            Some(_) => self.graphics_pipeline.as_ref().map(|p| p.layout()),
+            None => self.compute_pipeline.as_ref().map(|p| p.layout()),
+        };
+        let Some(layout) = layout else {
+            self.record_error(MetalError::Custom(
+                "push_constants requires a bound pipeline".to_owned(),
+            ));
+            return;
+        };
+
+        if let Err(e) = validate_push_constants(
+            layout.push_constant_ranges(),
+            stages,
+            offset,
+            data.len() as u32,
+        ) {
+            self.record_error(MetalError::Custom(e));
+            return;
+        }
+
+        let end = offset as usize + data.len();
+        if self.push_constants.len() < end {
+            self.push_constants.resize(end, 0);
+        }
+        self.push_constants[offset as usize..end].copy_from_slice(data);
+
+        // Compute encoders pick the data up when the next dispatch starts or reuses them.
+        let Some(render_encoder) = &self.render_encoder else {
+            return;
+        };
+
+        let bytes =
+            unsafe { NonNull::new_unchecked(self.push_constants.as_ptr().cast_mut().cast()) };
+        let length = self.push_constants.len();
+
+        unsafe {
+            if stages.contains(ShaderStageFlags::VERTEX) {
+                render_encoder.setVertexBytes_length_atIndex(
+                    bytes,
+                    length,
+                    PUSH_CONSTANTS_BUFFER_INDEX,
+                );
+            }
+            if stages.contains(ShaderStageFlags::TASK) {
+                render_encoder.setObjectBytes_length_atIndex(
+                    bytes,
+                    length,
+                    PUSH_CONSTANTS_BUFFER_INDEX,
+                );
+            }
+            if stages.contains(ShaderStageFlags::MESH) {
+                render_encoder.setMeshBytes_length_atIndex(
+                    bytes,
+                    length,
+                    PUSH_CONSTANTS_BUFFER_INDEX,
+                );
+            }
+            if stages.contains(ShaderStageFlags::FRAGMENT) {
+                render_encoder.setFragmentBytes_length_atIndex(
+                    bytes,
+                    length,
+                    PUSH_CONSTANTS_BUFFER_INDEX,
+                );
+            }
+        }
+    }
+
+    pub fn dispatch(&mut self, x: u32, y: u32, z: u32) {
+        let Some((compute_encoder, workgroup_size)) = self.compute_encoder("dispatch") else {
+            return;
+        };
+
+        compute_encoder.dispatchThreadgroups_threadsPerThreadgroup(
+            to_mtl_size(Extent3d {
+                width: x,
+                height: y,
+                depth: z,
+            }),
+            to_mtl_workgroup_size(workgroup_size),
+        );
+    }
+
+    pub fn dispatch_indirect(&mut self, buffer: &Buffer, offset: u64) {
+        let Some((compute_encoder, workgroup_size)) = self.compute_encoder("dispatch_indirect")
+        else {
+            return;
+        };
+
+        unsafe {
+            compute_encoder
+                .dispatchThreadgroupsWithIndirectBuffer_indirectBufferOffset_threadsPerThreadgroup(
+                    buffer.as_metal().mtl_buffer(),
+                    offset as _,
+                    to_mtl_workgroup_size(workgroup_size),
+                );
+        }
+    }
+
+    /// Counter sample buffers need no reset, only visibility results are cleared.
+    pub fn reset_queries(&mut self, query_pool: &QueryPool, first: u32, count: u32) {
+        let Some(visibility_buffer) = query_pool.as_metal().mtl_visibility_buffer() else {
+            self.validate_outside_render_pass("reset_queries");
+            return;
+        };
+
+        let Some(blit_encoder) = self.blit_encoder("reset_queries") else {
+            return;
+        };
+
+        blit_encoder.fillBuffer_range_value(
+            visibility_buffer,
+            NSRange::new(first as usize * 8, count as usize * 8),
+            0,
+        );
+    }
+
+    /// Samples the timestamp at the boundary of the current draw or blit, which not every device
+    /// supports.
+    pub fn write_timestamp(&mut self, query_pool: &QueryPool, index: u32) {
+        let Some(sample_buffer) = query_pool.as_metal().mtl_counter_sample_buffer() else {
+            self.record_error(MetalError::Custom(
+                "write_timestamp requires a timestamp query pool".to_owned(),
+            ));
+            return;
+        };
+
+        let (sampling_point, location) = if self.render_encoder.is_some() {
+            (MTLCounterSamplingPoint::AtDrawBoundary, "inside")
+        } else {
+            (MTLCounterSamplingPoint::AtBlitBoundary, "outside")
+        };
+        if !self
+            .device
+            .mtl_device()
+            .supportsCounterSampling(sampling_point)
+        {
+            self.record_error(MetalError::Custom(format!(
+                "write_timestamp {location} a render pass is not supported by this device"
+            )));
+            return;
+        }
+
+        if let Some(render_encoder) = &self.render_encoder {
+            unsafe {
+                render_encoder.sampleCountersInBuffer_atSampleIndex_withBarrier(
+                    sample_buffer,
+                    index as _,
+                    true,
+                );
+            }
+            return;
+        }
+
+        let Some(blit_encoder) = self.blit_encoder("write_timestamp") else {
+            return;
+        };
+
+        unsafe {
+            blit_encoder.sampleCountersInBuffer_atSampleIndex_withBarrier(
+                sample_buffer,
+                index as _,
+                true,
+            );
+        }
+    }
+
+    /// Only occlusion queries are supported, counting into the visibility result buffer of the
+    /// render pass.
+    pub fn begin_query(&mut self, query_pool: &QueryPool, index: u32) {
+        if query_pool.query_type() != QueryType::Occlusion {
+            self.record_error(MetalError::Custom(format!(
+                "begin_query is not supported for {:?} queries",
+                query_pool.query_type()
+            )));
+            return;
+        }
+
+        let Some(render_encoder) = self.render_encoder("begin_query") else {
+            return;
+        };
+
+        render_encoder
+            .setVisibilityResultMode_offset(MTLVisibilityResultMode::Counting, index as usize * 8);
+    }
+
+    pub fn end_query(&mut self, query_pool: &QueryPool, index: u32) {
+        if query_pool.query_type() != QueryType::Occlusion {
+            self.record_error(MetalError::Custom(format!(
+                "end_query is not supported for {:?} queries",
+                query_pool.query_type()
+            )));
+            return;
+        }
+
+        let Some(render_encoder) = self.render_encoder("end_query") else {
+            return;
+        };
+
+        render_encoder
+            .setVisibilityResultMode_offset(MTLVisibilityResultMode::Disabled, index as usize * 8);
+    }
+
+    pub fn resolve_queries(
+        &mut self,
+        query_pool: &QueryPool,
+        first: u32,
+        count: u32,
+        dst: &Buffer,
+        dst_offset: u64,
+    ) {
+        let query_pool = query_pool.as_metal();
+
+        let Some(blit_encoder) = self.blit_encoder("resolve_queries") else {
+            return;
+        };
+
+        if let Some(sample_buffer) = query_pool.mtl_counter_sample_buffer() {
+            unsafe {
+                blit_encoder.resolveCounters_inRange_destinationBuffer_destinationOffset(
+                    sample_buffer,
+                    NSRange::new(first as _, count as _),
+                    dst.as_metal().mtl_buffer(),
+                    dst_offset as _,
+                );
+            }
+        } else if let Some(visibility_buffer) = query_pool.mtl_visibility_buffer() {
+            unsafe {
+                blit_encoder.copyFromBuffer_sourceOffset_toBuffer_destinationOffset_size(
+                    visibility_buffer,
+                    first as usize * 8,
+                    dst.as_metal().mtl_buffer(),
+                    dst_offset as _,
+                    count as usize * 8,
+                );
+            }
+        }
+    }
+
+    /// Debug groups inside a render pass belong to its encoder, the ones outside to the command
+    /// buffer, which ends the open blit or compute encoder.
+    pub fn push_debug_group(&mut self, label: &str) {
+        let label = NSString::from_str(label);
+
+        if let Some(render_encoder) = &self.render_encoder {
+            render_encoder.pushDebugGroup(&label);
+            self.render_pass_debug_group_depth += 1;
+            return;
+        }
+
+        self.end_encoding();
+
+        let Some(command_buffer) = &self.command_buffer else {
+            self.record_error(MetalError::Custom(
+                "Command recorded outside of begin and end".to_owned(),
+            ));
+            return;
+        };
+
+        command_buffer.pushDebugGroup(&label);
+        self.debug_group_depth += 1;
+    }
+
+    pub fn pop_debug_group(&mut self) {
+        if let Some(render_encoder) = &self.render_encoder {
+            if self.render_pass_debug_group_depth == 0 {
+                self.record_error(MetalError::Custom(
+                    "pop_debug_group called without a debug group of the render pass".to_owned(),
+                ));
+                return;
+            }
+
+            render_encoder.popDebugGroup();
+            self.render_pass_debug_group_depth -= 1;
+            return;
+        }
+
+        if self.debug_group_depth == 0 {
+            self.record_error(MetalError::Custom(
+                "pop_debug_group called without an open debug group".to_owned(),
+            ));
+            return;
+        }
+
+        self.end_encoding();
+
+        if let Some(command_buffer) = &self.command_buffer {
+            command_buffer.popDebugGroup();
+            self.debug_group_depth -= 1;
+        }
+    }
+
+    /// Markers are signposts of the current encoder, outside of render passes a blit encoder is
+    /// started if no compute encoder is open.
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        let label = NSString::from_str(label);
+
+        if let Some(render_encoder) = &self.render_encoder {
+            render_encoder.insertDebugSignpost(&label);
+        } else if let Some(compute_encoder) = &self.compute_encoder {
+            compute_encoder.insertDebugSignpost(&label);
+        } else if let Some(blit_encoder) = self.blit_encoder("insert_debug_marker") {
+            blit_encoder.insertDebugSignpost(&label);
+        }
+    }
+
+    /// Commits the recorded command buffer, Metal command buffers can only be committed once.
+    pub(crate) fn commit(&self) -> Result<(), MetalError> {
+        let Some(command_buffer) = &self.command_buffer else {
+            return Err(MetalError::Custom(
+                "Command list submitted without being recorded".to_owned(),
+            ));
+        };
+
+        if command_buffer.status() != MTLCommandBufferStatus::NotEnqueued {
+            return Err(MetalError::Custom(
+                "Command list submitted twice without being recorded again".to_owned(),
+            ));
+        }
+
+        command_buffer.commit();
+
+        Ok(())
+    }
+}
+
+impl Drop for MetalCommandList {
+    fn drop(&mut self) {
+        self.end_encoding();
+        if let Some(render_encoder) = self.render_encoder.take() {
+            render_encoder.endEncoding();
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::{ptr, sync::Arc};
+
+use objc2::{
+    msg_send_id,
+    rc::Retained,
+    runtime::{AnyObject, ProtocolObject},
+};
+use objc2_foundation::{NSError, NSString};
+use objc2_metal::{
+    MTLComputePipelineDescriptor, MTLComputePipelineState, MTLDevice, MTLLibrary, MTLPipelineOption,
+};
+
+use crate::{
+    metal::{MetalDevice, MetalError, MetalPipelineLayout},
+    ComputePipelineDesc,
+};
+
+struct Inner {
+    pipeline_state: Retained<ProtocolObject<dyn MTLComputePipelineState>>,
+    layout: MetalPipelineLayout,
+    workgroup_size: [u32; 3],
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+#[derive(Clone)]
+pub struct MetalComputePipeline(Arc<Inner>);
+
+impl MetalComputePipeline {
+    pub fn new(
+        device: &MetalDevice,
+        desc: &ComputePipelineDesc,
+        workgroup_size: [u32; 3],
+    ) -> Result<Self, MetalError> {
+        let Some(library) = desc.shader_module.as_metal().mtl_library() else {
+            return Err(MetalError::Custom(
+                "Shader module has no Metal library, SPIR-V to MSL translation is unavailable"
+                    .to_owned(),
+            ));
+        };
+
+        if !desc.specialization_constants.is_empty() {
+            return Err(MetalError::Custom(
+                "Specialization constants are not supported on Metal".to_owned(),
+            ));
+        }
+
+        let function = library
+            .newFunctionWithName(&NSString::from_str(desc.entry_point))
+            .ok_or_else(|| {
+                MetalError::Custom(format!("Failed to find function {}", desc.entry_point))
+            })?;
+
+        // Only pipelines created from a descriptor carry a label.
+        let descriptor = MTLComputePipelineDescriptor::new();
+        descriptor.setComputeFunction(Some(&function));
+        if let Some(label) = desc.label {
+            descriptor.setLabel(Some(&NSString::from_str(label)));
+        }
+
+        let pipeline_state: Result<Retained<ProtocolObject<dyn MTLComputePipelineState>>, _> = unsafe {
+            msg_send_id![
+                device.mtl_device(),
+                newComputePipelineStateWithDescriptor: &*descriptor,
+                options: MTLPipelineOption::None,
+                reflection: ptr::null_mut::<*mut AnyObject>(),
+                error: _
+            ]
+        };
+        let pipeline_state = pipeline_state.map_err(|e: Retained<NSError>| {
+            MetalError::Custom(e.localizedDescription().to_string())
+        })?;
+
+        Ok(Self(Arc::new(Inner {
+            pipeline_state,
+            layout: desc.layout.as_metal().clone(),
+            workgroup_size,
+        })))
+    }
+
+    #[inline]
+    pub fn mtl_pipeline_state(&self) -> &ProtocolObject<dyn MTLComputePipelineState> {
+        &self.0.pipeline_state
+    }
+
+    #[inline]
+    pub fn layout(&self) -> &MetalPipelineLayout {
+        &self.0.layout
+    }
+
+    #[inline]
+    pub fn workgroup_size(&self) -> [u32; 3] {
+        self.0.workgroup_size
+    }
+}
@@ -1,12 +1,28 @@
-use std::sync::Arc;
-use objc2::rc::Retained;
-use objc2::runtime::ProtocolObject;
-use objc2_metal::MTLDevice;
-use crate::{DeviceDesc, PhysicalDevice};
-use crate::metal::{MetalError, MetalInstance};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_metal::{MTLCommandQueue, MTLDevice, MTLSharedEvent};
+
+use crate::{
+    metal::{
+        MetalBindlessHeap, MetalError, MetalInstance, MetalQueue, MetalUploader, SamplerCache,
+    },
+    CalibratedTimestamp, DeviceDesc, PhysicalDevice, PhysicalDeviceFeatures, QueueType,
+};
 
 struct Inner {
-    mtl_device: Retained<ProtocolObject<dyn MTLDevice>>
+    mtl_device: Retained<ProtocolObject<dyn MTLDevice>>,
+    mtl_command_queue: Retained<ProtocolObject<dyn MTLCommandQueue>>,
+    enabled_features: PhysicalDeviceFeatures,
+
+    /// Signaled with the value of every submission once it completes.
+    mtl_submission_event: Retained<ProtocolObject<dyn MTLSharedEvent>>,
+    /// Holds the value of the last submission.
+    submission_value: Mutex<u64>,
+
+    bindless_heap: Option<MetalBindlessHeap>,
+    sampler_cache: SamplerCache,
+    uploader: MetalUploader,
 }
 
 #[derive(Clone)]
@@ -18,8 +34,106 @@ impl MetalDevice {
             return Err(MetalError::Custom("Invalid physical device".to_owned()));
         };
 
+        let mtl_device = physical_device.get_mtl_device();
+        let mtl_command_queue = mtl_device
+            .newCommandQueue()
+            .ok_or_else(|| MetalError::Custom("Failed to create command queue".to_owned()))?;
+        let mtl_submission_event = mtl_device
+            .newSharedEvent()
+            .ok_or_else(|| MetalError::Custom("Failed to create submission event".to_owned()))?;
+
+        let enabled_features = desc.enabled_features();
+
+        let bindless_heap =
+            if enabled_features.contains(PhysicalDeviceFeatures::DESCRIPTOR_INDEXING) {
+                Some(MetalBindlessHeap::new(&mtl_device)?)
+            } else {
+                None
+            };
+
         Ok(Self(Arc::new(Inner {
-            mtl_device: physical_device.get_mtl_device(),
+            mtl_device,
+            mtl_command_queue,
+            enabled_features,
+
+            mtl_submission_event,
+            submission_value: Mutex::new(0),
+
+            bindless_heap,
+            sampler_cache: Default::default(),
+            uploader: Default::default(),
         })))
     }
+
+    #[inline]
+    pub fn get_enabled_features(&self) -> PhysicalDeviceFeatures {
+        self.0.enabled_features
+    }
+
+    #[inline]
+    pub fn get_timestamp_period(&self) -> f32 {
+        1.0
+    }
+
+    /// Counter timestamps are not calibrated against the CPU clock.
+    #[inline]
+    pub fn get_calibrated_timestamp(&self) -> Result<Option<CalibratedTimestamp>, MetalError> {
+        Ok(None)
+    }
+
+    #[inline]
+    pub fn get_queue(&self, queue_type: QueueType) -> MetalQueue {
+        MetalQueue::new(self, queue_type)
+    }
+
+    #[inline]
+    pub fn mtl_device(&self) -> &ProtocolObject<dyn MTLDevice> {
+        &self.0.mtl_device
+    }
+
+    #[inline]
+    pub fn mtl_command_queue(&self) -> &ProtocolObject<dyn MTLCommandQueue> {
+        &self.0.mtl_command_queue
+    }
+
+    /// Locks the submission counter, submissions hold it so their values signal in order.
+    #[inline]
+    pub(crate) fn lock_submissions(&self) -> MutexGuard<'_, u64> {
+        self.0.submission_value.lock().unwrap()
+    }
+
+    #[inline]
+    pub(crate) fn mtl_submission_event(&self) -> &ProtocolObject<dyn MTLSharedEvent> {
+        &self.0.mtl_submission_event
+    }
+
+    /// Returns the value of the last submission.
+    #[inline]
+    pub(crate) fn submission_value(&self) -> u64 {
+        *self.lock_submissions()
+    }
+
+    /// Returns the value of the last completed submission.
+    #[inline]
+    pub(crate) fn completed_submission_value(&self) -> u64 {
+        unsafe { self.0.mtl_submission_event.signaledValue() }
+    }
+
+    /// Returns the bindless heap, `None` unless descriptor indexing is enabled.
+    #[inline]
+    pub(crate) fn bindless_heap(&self) -> Option<&MetalBindlessHeap> {
+        self.0.bindless_heap.as_ref()
+    }
+
+    /// Returns the sampler states shared by every identical sampler descriptor.
+    #[inline]
+    pub(crate) fn sampler_cache(&self) -> &SamplerCache {
+        &self.0.sampler_cache
+    }
+
+    /// Returns the staging buffers of uploads.
+    #[inline]
+    pub(crate) fn uploader(&self) -> &MetalUploader {
+        &self.0.uploader
+    }
 }
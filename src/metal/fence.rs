@@ -0,0 +1,80 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_foundation::NSString;
+use objc2_metal::{MTLDevice, MTLEvent, MTLSharedEvent};
+
+use crate::{
+    metal::{to_mtl_timeout_ms, MetalDevice, MetalError},
+    FenceDesc,
+};
+
+struct Inner {
+    shared_event: Retained<ProtocolObject<dyn MTLSharedEvent>>,
+    /// The event value at which the fence counts as signaled.
+    target_value: AtomicU64,
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+/// A fence emulated with an `MTLSharedEvent`, resetting moves the target value past the current
+/// event value.
+#[derive(Clone)]
+pub struct MetalFence(Arc<Inner>);
+
+impl MetalFence {
+    pub fn new(device: &MetalDevice, desc: &FenceDesc) -> Result<Self, MetalError> {
+        let shared_event = device
+            .mtl_device()
+            .newSharedEvent()
+            .ok_or_else(|| MetalError::Custom("Failed to create shared event".to_owned()))?;
+        if let Some(label) = desc.label {
+            shared_event.setLabel(Some(&NSString::from_str(label)));
+        }
+
+        Ok(Self(Arc::new(Inner {
+            shared_event,
+            target_value: AtomicU64::new(if desc.signaled { 0 } else { 1 }),
+        })))
+    }
+
+    #[inline]
+    pub fn mtl_shared_event(&self) -> &ProtocolObject<dyn MTLSharedEvent> {
+        &self.0.shared_event
+    }
+
+    /// Returns the value a submission signals the event with.
+    #[inline]
+    pub(crate) fn target_value(&self) -> u64 {
+        self.0.target_value.load(Ordering::Acquire)
+    }
+
+    pub fn is_signaled(&self) -> Result<bool, MetalError> {
+        Ok(unsafe { self.0.shared_event.signaledValue() } >= self.target_value())
+    }
+
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<bool, MetalError> {
+        Ok(unsafe {
+            self.0
+                .shared_event
+                .waitUntilSignaledValue_timeoutMS(self.target_value(), to_mtl_timeout_ms(timeout))
+        })
+    }
+
+    pub fn reset(&self) -> Result<(), MetalError> {
+        let signaled_value = unsafe { self.0.shared_event.signaledValue() };
+
+        self.0
+            .target_value
+            .store(signaled_value + 1, Ordering::Release);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use objc2_metal::MTLPixelFormat;
+
+use crate::Format;
+
+pub(crate) fn to_mtl_pixel_format(format: Format) -> MTLPixelFormat {
+    match format {
+        Format::R8Unorm => MTLPixelFormat::R8Unorm,
+        Format::R8Snorm => MTLPixelFormat::R8Snorm,
+        Format::R8Uint => MTLPixelFormat::R8Uint,
+        Format::R8Sint => MTLPixelFormat::R8Sint,
+        Format::Rg8Unorm => MTLPixelFormat::RG8Unorm,
+        Format::Rg8Snorm => MTLPixelFormat::RG8Snorm,
+        Format::Rg8Uint => MTLPixelFormat::RG8Uint,
+        Format::Rg8Sint => MTLPixelFormat::RG8Sint,
+        Format::Rgba8Unorm => MTLPixelFormat::RGBA8Unorm,
+        Format::Rgba8UnormSrgb => MTLPixelFormat::RGBA8Unorm_sRGB,
+        Format::Rgba8Snorm => MTLPixelFormat::RGBA8Snorm,
+        Format::Rgba8Uint => MTLPixelFormat::RGBA8Uint,
+        Format::Rgba8Sint => MTLPixelFormat::RGBA8Sint,
+        Format::Bgra8Unorm => MTLPixelFormat::BGRA8Unorm,
+        Format::Bgra8UnormSrgb => MTLPixelFormat::BGRA8Unorm_sRGB,
+        Format::R16Unorm => MTLPixelFormat::R16Unorm,
+        Format::R16Snorm => MTLPixelFormat::R16Snorm,
+        Format::R16Uint => MTLPixelFormat::R16Uint,
+        Format::R16Sint => MTLPixelFormat::R16Sint,
+        Format::R16Float => MTLPixelFormat::R16Float,
+        Format::Rg16Unorm => MTLPixelFormat::RG16Unorm,
+        Format::Rg16Snorm => MTLPixelFormat::RG16Snorm,
+        Format::Rg16Uint => MTLPixelFormat::RG16Uint,
+        Format::Rg16Sint => MTLPixelFormat::RG16Sint,
+        Format::Rg16Float => MTLPixelFormat::RG16Float,
+        Format::Rgba16Unorm => MTLPixelFormat::RGBA16Unorm,
+        Format::Rgba16Snorm => MTLPixelFormat::RGBA16Snorm,
+        Format::Rgba16Uint => MTLPixelFormat::RGBA16Uint,
+        Format::Rgba16Sint => MTLPixelFormat::RGBA16Sint,
+        Format::Rgba16Float => MTLPixelFormat::RGBA16Float,
+        Format::R32Uint => MTLPixelFormat::R32Uint,
+        Format::R32Sint => MTLPixelFormat::R32Sint,
+        Format::R32Float => MTLPixelFormat::R32Float,
+        Format::Rg32Uint => MTLPixelFormat::RG32Uint,
+        Format::Rg32Sint => MTLPixelFormat::RG32Sint,
+        Format::Rg32Float => MTLPixelFormat::RG32Float,
+        Format::Rgba32Uint => MTLPixelFormat::RGBA32Uint,
+        Format::Rgba32Sint => MTLPixelFormat::RGBA32Sint,
+        Format::Rgba32Float => MTLPixelFormat::RGBA32Float,
+        Format::Rgb10a2Unorm => MTLPixelFormat::RGB10A2Unorm,
+        Format::Rgb10a2Uint => MTLPixelFormat::RGB10A2Uint,
+        Format::Rg11b10Float => MTLPixelFormat::RG11B10Float,
+        Format::Rgb9e5Float => MTLPixelFormat::RGB9E5Float,
+        Format::D16Unorm => MTLPixelFormat::Depth16Unorm,
+        Format::D32Float => MTLPixelFormat::Depth32Float,
+        Format::S8Uint => MTLPixelFormat::Stencil8,
+        Format::D24UnormS8Uint => MTLPixelFormat::Depth24Unorm_Stencil8,
+        Format::D32FloatS8Uint => MTLPixelFormat::Depth32Float_Stencil8,
+        Format::Bc1RgbaUnorm => MTLPixelFormat::BC1_RGBA,
+        Format::Bc1RgbaUnormSrgb => MTLPixelFormat::BC1_RGBA_sRGB,
+        Format::Bc2RgbaUnorm => MTLPixelFormat::BC2_RGBA,
+        Format::Bc2RgbaUnormSrgb => MTLPixelFormat::BC2_RGBA_sRGB,
+        Format::Bc3RgbaUnorm => MTLPixelFormat::BC3_RGBA,
+        Format::Bc3RgbaUnormSrgb => MTLPixelFormat::BC3_RGBA_sRGB,
+        Format::Bc4RUnorm => MTLPixelFormat::BC4_RUnorm,
+        Format::Bc4RSnorm => MTLPixelFormat::BC4_RSnorm,
+        Format::Bc5RgUnorm => MTLPixelFormat::BC5_RGUnorm,
+        Format::Bc5RgSnorm => MTLPixelFormat::BC5_RGSnorm,
+        Format::Bc6hRgbUfloat => MTLPixelFormat::BC6H_RGBUfloat,
+        Format::Bc6hRgbFloat => MTLPixelFormat::BC6H_RGBFloat,
+        Format::Bc7RgbaUnorm => MTLPixelFormat::BC7_RGBAUnorm,
+        Format::Bc7RgbaUnormSrgb => MTLPixelFormat::BC7_RGBAUnorm_sRGB,
+    }
+}
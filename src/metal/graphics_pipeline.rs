@@ -0,0 +1,510 @@
+use std::{ptr, sync::Arc};
+
+use objc2::{
+    msg_send_id,
+    rc::Retained,
+    runtime::{AnyObject, ProtocolObject},
+};
+use objc2_foundation::{NSError, NSString};
+use objc2_metal::{
+    MTLBlendFactor, MTLBlendOperation, MTLColorWriteMask, MTLCompareFunction, MTLCullMode,
+    MTLDepthClipMode, MTLDepthStencilDescriptor, MTLDepthStencilState, MTLDevice, MTLFunction,
+    MTLLibrary, MTLMeshRenderPipelineDescriptor, MTLPipelineOption, MTLPixelFormat,
+    MTLPrimitiveType, MTLRenderPipelineColorAttachmentDescriptorArray, MTLRenderPipelineDescriptor,
+    MTLRenderPipelineState, MTLStencilDescriptor, MTLStencilOperation, MTLTriangleFillMode,
+    MTLVertexDescriptor, MTLVertexFormat, MTLVertexStepFunction, MTLWinding,
+};
+
+use crate::{
+    metal::{to_mtl_pixel_format, MetalDevice, MetalError, MetalPipelineLayout},
+    BlendFactor, BlendOp, ColorAttachmentState, ColorWriteFlags, CompareOp, CullMode, DepthBias,
+    DepthStencilState, Format, FrontFace, GraphicsPipelineDesc, MeshPipelineDesc, PolygonMode,
+    PrimitiveTopology, RasterStateDesc, ShaderStage, StencilFaceState, StencilOp, VertexFormat,
+    VertexStepMode,
+};
+
+struct Inner {
+    pipeline_state: Retained<ProtocolObject<dyn MTLRenderPipelineState>>,
+    depth_stencil_state: Retained<ProtocolObject<dyn MTLDepthStencilState>>,
+    layout: MetalPipelineLayout,
+
+    primitive_type: MTLPrimitiveType,
+    cull_mode: MTLCullMode,
+    winding: MTLWinding,
+    fill_mode: MTLTriangleFillMode,
+    depth_clip_mode: MTLDepthClipMode,
+    depth_bias: Option<DepthBias>,
+    mesh_workgroup_sizes: Option<MetalMeshWorkgroupSizes>,
+}
+
+/// Threads per object and mesh threadgroup, Metal takes these with every mesh draw.
+#[derive(Copy, Clone, Debug)]
+pub struct MetalMeshWorkgroupSizes {
+    pub object: [u32; 3],
+    pub mesh: [u32; 3],
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+/// A render pipeline state plus the fixed function state Metal sets on the render encoder.
+#[derive(Clone)]
+pub struct MetalGraphicsPipeline(Arc<Inner>);
+
+/// Looks up the function of `shader_stage` in the library of its shader module.
+pub(crate) fn to_mtl_function(
+    shader_stage: &ShaderStage,
+) -> Result<Retained<ProtocolObject<dyn MTLFunction>>, MetalError> {
+    let Some(library) = shader_stage.shader_module.as_metal().mtl_library() else {
+        return Err(MetalError::Custom(
+            "Shader module has no Metal library, SPIR-V to MSL translation is unavailable"
+                .to_owned(),
+        ));
+    };
+
+    if !shader_stage.specialization_constants.is_empty() {
+        return Err(MetalError::Custom(
+            "Specialization constants are not supported on Metal".to_owned(),
+        ));
+    }
+
+    library
+        .newFunctionWithName(&NSString::from_str(shader_stage.entry_point))
+        .ok_or_else(|| {
+            MetalError::Custom(format!(
+                "Failed to find function {}",
+                shader_stage.entry_point
+            ))
+        })
+}
+
+fn to_mtl_vertex_format(format: VertexFormat) -> MTLVertexFormat {
+    match format {
+        VertexFormat::Uint8x4 => MTLVertexFormat::UChar4,
+        VertexFormat::Sint8x4 => MTLVertexFormat::Char4,
+        VertexFormat::Unorm8x4 => MTLVertexFormat::UChar4Normalized,
+        VertexFormat::Snorm8x4 => MTLVertexFormat::Char4Normalized,
+        VertexFormat::Uint16x2 => MTLVertexFormat::UShort2,
+        VertexFormat::Uint16x4 => MTLVertexFormat::UShort4,
+        VertexFormat::Sint16x2 => MTLVertexFormat::Short2,
+        VertexFormat::Sint16x4 => MTLVertexFormat::Short4,
+        VertexFormat::Unorm16x2 => MTLVertexFormat::UShort2Normalized,
+        VertexFormat::Unorm16x4 => MTLVertexFormat::UShort4Normalized,
+        VertexFormat::Snorm16x2 => MTLVertexFormat::Short2Normalized,
+        VertexFormat::Snorm16x4 => MTLVertexFormat::Short4Normalized,
+        VertexFormat::Float16x2 => MTLVertexFormat::Half2,
+        VertexFormat::Float16x4 => MTLVertexFormat::Half4,
+        VertexFormat::Uint32 => MTLVertexFormat::UInt,
+        VertexFormat::Uint32x2 => MTLVertexFormat::UInt2,
+        VertexFormat::Uint32x3 => MTLVertexFormat::UInt3,
+        VertexFormat::Uint32x4 => MTLVertexFormat::UInt4,
+        VertexFormat::Sint32 => MTLVertexFormat::Int,
+        VertexFormat::Sint32x2 => MTLVertexFormat::Int2,
+        VertexFormat::Sint32x3 => MTLVertexFormat::Int3,
+        VertexFormat::Sint32x4 => MTLVertexFormat::Int4,
+        VertexFormat::Float32 => MTLVertexFormat::Float,
+        VertexFormat::Float32x2 => MTLVertexFormat::Float2,
+        VertexFormat::Float32x3 => MTLVertexFormat::Float3,
+        VertexFormat::Float32x4 => MTLVertexFormat::Float4,
+    }
+}
+
+fn to_mtl_primitive_type(topology: PrimitiveTopology) -> MTLPrimitiveType {
+    match topology {
+        PrimitiveTopology::PointList => MTLPrimitiveType::Point,
+        PrimitiveTopology::LineList => MTLPrimitiveType::Line,
+        PrimitiveTopology::LineStrip => MTLPrimitiveType::LineStrip,
+        PrimitiveTopology::TriangleList => MTLPrimitiveType::Triangle,
+        PrimitiveTopology::TriangleStrip => MTLPrimitiveType::TriangleStrip,
+    }
+}
+
+pub(crate) fn to_mtl_compare_function(compare_op: CompareOp) -> MTLCompareFunction {
+    match compare_op {
+        CompareOp::Never => MTLCompareFunction::Never,
+        CompareOp::Less => MTLCompareFunction::Less,
+        CompareOp::Equal => MTLCompareFunction::Equal,
+        CompareOp::LessEqual => MTLCompareFunction::LessEqual,
+        CompareOp::Greater => MTLCompareFunction::Greater,
+        CompareOp::NotEqual => MTLCompareFunction::NotEqual,
+        CompareOp::GreaterEqual => MTLCompareFunction::GreaterEqual,
+        CompareOp::Always => MTLCompareFunction::Always,
+    }
+}
+
+fn to_mtl_stencil_operation(stencil_op: StencilOp) -> MTLStencilOperation {
+    match stencil_op {
+        StencilOp::Keep => MTLStencilOperation::Keep,
+        StencilOp::Zero => MTLStencilOperation::Zero,
+        StencilOp::Replace => MTLStencilOperation::Replace,
+        StencilOp::IncrementClamp => MTLStencilOperation::IncrementClamp,
+        StencilOp::DecrementClamp => MTLStencilOperation::DecrementClamp,
+        StencilOp::Invert => MTLStencilOperation::Invert,
+        StencilOp::IncrementWrap => MTLStencilOperation::IncrementWrap,
+        StencilOp::DecrementWrap => MTLStencilOperation::DecrementWrap,
+    }
+}
+
+fn to_mtl_stencil_descriptor(
+    state: &StencilFaceState,
+    read_mask: u32,
+    write_mask: u32,
+) -> Retained<MTLStencilDescriptor> {
+    let stencil_descriptor = unsafe { MTLStencilDescriptor::new() };
+
+    stencil_descriptor.setStencilCompareFunction(to_mtl_compare_function(state.compare));
+    stencil_descriptor.setStencilFailureOperation(to_mtl_stencil_operation(state.fail_op));
+    stencil_descriptor.setDepthFailureOperation(to_mtl_stencil_operation(state.depth_fail_op));
+    stencil_descriptor.setDepthStencilPassOperation(to_mtl_stencil_operation(state.pass_op));
+    stencil_descriptor.setReadMask(read_mask);
+    stencil_descriptor.setWriteMask(write_mask);
+
+    stencil_descriptor
+}
+
+fn to_mtl_blend_factor(blend_factor: BlendFactor) -> MTLBlendFactor {
+    match blend_factor {
+        BlendFactor::Zero => MTLBlendFactor::Zero,
+        BlendFactor::One => MTLBlendFactor::One,
+        BlendFactor::Src => MTLBlendFactor::SourceColor,
+        BlendFactor::OneMinusSrc => MTLBlendFactor::OneMinusSourceColor,
+        BlendFactor::SrcAlpha => MTLBlendFactor::SourceAlpha,
+        BlendFactor::OneMinusSrcAlpha => MTLBlendFactor::OneMinusSourceAlpha,
+        BlendFactor::Dst => MTLBlendFactor::DestinationColor,
+        BlendFactor::OneMinusDst => MTLBlendFactor::OneMinusDestinationColor,
+        BlendFactor::DstAlpha => MTLBlendFactor::DestinationAlpha,
+        BlendFactor::OneMinusDstAlpha => MTLBlendFactor::OneMinusDestinationAlpha,
+        BlendFactor::SrcAlphaSaturated => MTLBlendFactor::SourceAlphaSaturated,
+        BlendFactor::Constant => MTLBlendFactor::BlendColor,
+        BlendFactor::OneMinusConstant => MTLBlendFactor::OneMinusBlendColor,
+    }
+}
+
+fn to_mtl_blend_operation(blend_op: BlendOp) -> MTLBlendOperation {
+    match blend_op {
+        BlendOp::Add => MTLBlendOperation::Add,
+        BlendOp::Subtract => MTLBlendOperation::Subtract,
+        BlendOp::ReverseSubtract => MTLBlendOperation::ReverseSubtract,
+        BlendOp::Min => MTLBlendOperation::Min,
+        BlendOp::Max => MTLBlendOperation::Max,
+    }
+}
+
+fn to_mtl_color_write_mask(write_mask: ColorWriteFlags) -> MTLColorWriteMask {
+    let mut mask = MTLColorWriteMask::None;
+
+    if write_mask.contains(ColorWriteFlags::RED) {
+        mask |= MTLColorWriteMask::Red;
+    }
+    if write_mask.contains(ColorWriteFlags::GREEN) {
+        mask |= MTLColorWriteMask::Green;
+    }
+    if write_mask.contains(ColorWriteFlags::BLUE) {
+        mask |= MTLColorWriteMask::Blue;
+    }
+    if write_mask.contains(ColorWriteFlags::ALPHA) {
+        mask |= MTLColorWriteMask::Alpha;
+    }
+
+    mask
+}
+
+fn set_color_attachments(
+    attachments: &MTLRenderPipelineColorAttachmentDescriptorArray,
+    color_attachments: &[ColorAttachmentState],
+) {
+    for (i, color_attachment) in color_attachments.iter().enumerate() {
+        let attachment = unsafe { attachments.objectAtIndexedSubscript(i) };
+
+        attachment.setPixelFormat(to_mtl_pixel_format(color_attachment.format));
+        attachment.setWriteMask(to_mtl_color_write_mask(color_attachment.write_mask));
+
+        if let Some(blend) = &color_attachment.blend {
+            attachment.setBlendingEnabled(true);
+            attachment.setSourceRGBBlendFactor(to_mtl_blend_factor(blend.color.src_factor));
+            attachment.setDestinationRGBBlendFactor(to_mtl_blend_factor(blend.color.dst_factor));
+            attachment.setRgbBlendOperation(to_mtl_blend_operation(blend.color.operation));
+            attachment.setSourceAlphaBlendFactor(to_mtl_blend_factor(blend.alpha.src_factor));
+            attachment.setDestinationAlphaBlendFactor(to_mtl_blend_factor(blend.alpha.dst_factor));
+            attachment.setAlphaBlendOperation(to_mtl_blend_operation(blend.alpha.operation));
+        }
+    }
+}
+
+/// Returns the depth and stencil attachment pixel formats, `Invalid` for absent aspects.
+fn to_mtl_depth_stencil_pixel_formats(format: Option<Format>) -> (MTLPixelFormat, MTLPixelFormat) {
+    match format {
+        Some(format) => {
+            (
+                if format.is_depth() {
+                    to_mtl_pixel_format(format)
+                } else {
+                    MTLPixelFormat::Invalid
+                },
+                if format.is_stencil() {
+                    to_mtl_pixel_format(format)
+                } else {
+                    MTLPixelFormat::Invalid
+                },
+            )
+        }
+        None => (MTLPixelFormat::Invalid, MTLPixelFormat::Invalid),
+    }
+}
+
+fn new_depth_stencil_state(
+    device: &MetalDevice,
+    depth_stencil: &DepthStencilState,
+) -> Result<Retained<ProtocolObject<dyn MTLDepthStencilState>>, MetalError> {
+    let depth_stencil_descriptor = unsafe { MTLDepthStencilDescriptor::new() };
+
+    // Disabled tests are expressed as an always passing compare function.
+    if depth_stencil.depth_test {
+        depth_stencil_descriptor
+            .setDepthCompareFunction(to_mtl_compare_function(depth_stencil.depth_compare));
+        depth_stencil_descriptor.setDepthWriteEnabled(depth_stencil.depth_write);
+    }
+    if depth_stencil.stencil_test {
+        depth_stencil_descriptor.setFrontFaceStencil(Some(&to_mtl_stencil_descriptor(
+            &depth_stencil.stencil_front,
+            depth_stencil.stencil_read_mask,
+            depth_stencil.stencil_write_mask,
+        )));
+        depth_stencil_descriptor.setBackFaceStencil(Some(&to_mtl_stencil_descriptor(
+            &depth_stencil.stencil_back,
+            depth_stencil.stencil_read_mask,
+            depth_stencil.stencil_write_mask,
+        )));
+    }
+
+    device
+        .mtl_device()
+        .newDepthStencilStateWithDescriptor(&depth_stencil_descriptor)
+        .ok_or_else(|| MetalError::Custom("Failed to create depth stencil state".to_owned()))
+}
+
+/// Returns the workgroup size the entry point of `shader_stage` declares.
+fn workgroup_size(shader_stage: &ShaderStage) -> [u32; 3] {
+    shader_stage
+        .shader_module
+        .reflection()
+        .entry_point(shader_stage.entry_point)
+        .and_then(|entry_point| entry_point.workgroup_size)
+        .unwrap_or([1, 1, 1])
+}
+
+impl MetalGraphicsPipeline {
+    pub fn new(device: &MetalDevice, desc: &GraphicsPipelineDesc) -> Result<Self, MetalError> {
+        let raster_state = desc.raster_state();
+        let descriptor = MTLRenderPipelineDescriptor::new();
+        if let Some(label) = desc.label {
+            descriptor.setLabel(Some(&NSString::from_str(label)));
+        }
+
+        descriptor.setVertexFunction(Some(&*to_mtl_function(&desc.vertex_shader)?));
+        if let Some(fragment_shader) = &desc.fragment_shader {
+            descriptor.setFragmentFunction(Some(&*to_mtl_function(fragment_shader)?));
+        }
+
+        // Vertex buffer slots map directly to Metal buffer indices.
+        let vertex_descriptor = MTLVertexDescriptor::vertexDescriptor();
+        for (slot, vertex_buffer) in desc.vertex_buffers.iter().enumerate() {
+            unsafe {
+                let layout = vertex_descriptor.layouts().objectAtIndexedSubscript(slot);
+                layout.setStride(vertex_buffer.stride as _);
+                layout.setStepFunction(match vertex_buffer.step_mode {
+                    VertexStepMode::Vertex => MTLVertexStepFunction::PerVertex,
+                    VertexStepMode::Instance => MTLVertexStepFunction::PerInstance,
+                });
+
+                for attribute in vertex_buffer.attributes {
+                    let descriptor = vertex_descriptor
+                        .attributes()
+                        .objectAtIndexedSubscript(attribute.location as _);
+                    descriptor.setFormat(to_mtl_vertex_format(attribute.format));
+                    descriptor.setOffset(attribute.offset as _);
+                    descriptor.setBufferIndex(slot);
+                }
+            }
+        }
+        descriptor.setVertexDescriptor(Some(&vertex_descriptor));
+
+        set_color_attachments(
+            &descriptor.colorAttachments(),
+            raster_state.color_attachments,
+        );
+
+        let (depth_pixel_format, stencil_pixel_format) =
+            to_mtl_depth_stencil_pixel_formats(raster_state.depth_stencil_format);
+        descriptor.setDepthAttachmentPixelFormat(depth_pixel_format);
+        descriptor.setStencilAttachmentPixelFormat(stencil_pixel_format);
+
+        descriptor.setRasterSampleCount(raster_state.multisample.sample_count as _);
+        descriptor.setAlphaToCoverageEnabled(raster_state.multisample.alpha_to_coverage);
+
+        let pipeline_state = device
+            .mtl_device()
+            .newRenderPipelineStateWithDescriptor_error(&descriptor)
+            .map_err(|e| MetalError::Custom(e.localizedDescription().to_string()))?;
+
+        Self::create(
+            device,
+            pipeline_state,
+            desc.layout.as_metal(),
+            to_mtl_primitive_type(desc.primitive_topology),
+            &raster_state,
+            None,
+        )
+    }
+
+    pub fn new_mesh(device: &MetalDevice, desc: &MeshPipelineDesc) -> Result<Self, MetalError> {
+        let raster_state = desc.raster_state();
+        let descriptor = unsafe { MTLMeshRenderPipelineDescriptor::new() };
+
+        unsafe {
+            if let Some(label) = desc.label {
+                descriptor.setLabel(Some(&NSString::from_str(label)));
+            }
+            if let Some(task_shader) = &desc.task_shader {
+                descriptor.setObjectFunction(Some(&*to_mtl_function(task_shader)?));
+            }
+            descriptor.setMeshFunction(Some(&*to_mtl_function(&desc.mesh_shader)?));
+            if let Some(fragment_shader) = &desc.fragment_shader {
+                descriptor.setFragmentFunction(Some(&*to_mtl_function(fragment_shader)?));
+            }
+
+            set_color_attachments(
+                &descriptor.colorAttachments(),
+                raster_state.color_attachments,
+            );
+
+            let (depth_pixel_format, stencil_pixel_format) =
+                to_mtl_depth_stencil_pixel_formats(raster_state.depth_stencil_format);
+            descriptor.setDepthAttachmentPixelFormat(depth_pixel_format);
+            descriptor.setStencilAttachmentPixelFormat(stencil_pixel_format);
+
+            descriptor.setRasterSampleCount(raster_state.multisample.sample_count as _);
+            descriptor.setAlphaToCoverageEnabled(raster_state.multisample.alpha_to_coverage);
+        }
+
+        // objc2-metal only binds the asynchronous variant of this constructor.
+        let pipeline_state: Result<Retained<ProtocolObject<dyn MTLRenderPipelineState>>, _> = unsafe {
+            msg_send_id![
+                device.mtl_device(),
+                newRenderPipelineStateWithMeshDescriptor: &*descriptor,
+                options: MTLPipelineOption::None,
+                reflection: ptr::null_mut::<*mut AnyObject>(),
+                error: _
+            ]
+        };
+        let pipeline_state = pipeline_state.map_err(|e: Retained<NSError>| {
+            MetalError::Custom(e.localizedDescription().to_string())
+        })?;
+
+        let mesh_workgroup_sizes = MetalMeshWorkgroupSizes {
+            object: desc.task_shader.as_ref().map_or([1, 1, 1], workgroup_size),
+            mesh: workgroup_size(&desc.mesh_shader),
+        };
+
+        Self::create(
+            device,
+            pipeline_state,
+            desc.layout.as_metal(),
+            MTLPrimitiveType::Triangle,
+            &raster_state,
+            Some(mesh_workgroup_sizes),
+        )
+    }
+
+    fn create(
+        device: &MetalDevice,
+        pipeline_state: Retained<ProtocolObject<dyn MTLRenderPipelineState>>,
+        layout: &MetalPipelineLayout,
+        primitive_type: MTLPrimitiveType,
+        raster_state: &RasterStateDesc,
+        mesh_workgroup_sizes: Option<MetalMeshWorkgroupSizes>,
+    ) -> Result<Self, MetalError> {
+        let depth_stencil_state = new_depth_stencil_state(device, &raster_state.depth_stencil)?;
+
+        let rasterizer = &raster_state.rasterizer;
+
+        Ok(Self(Arc::new(Inner {
+            pipeline_state,
+            depth_stencil_state,
+            layout: layout.clone(),
+
+            primitive_type,
+            cull_mode: match rasterizer.cull_mode {
+                CullMode::None => MTLCullMode::None,
+                CullMode::Front => MTLCullMode::Front,
+                CullMode::Back => MTLCullMode::Back,
+            },
+            winding: match rasterizer.front_face {
+                FrontFace::CounterClockwise => MTLWinding::CounterClockwise,
+                FrontFace::Clockwise => MTLWinding::Clockwise,
+            },
+            fill_mode: match rasterizer.polygon_mode {
+                PolygonMode::Fill => MTLTriangleFillMode::Fill,
+                PolygonMode::Line => MTLTriangleFillMode::Lines,
+            },
+            depth_clip_mode: if rasterizer.depth_clamp {
+                MTLDepthClipMode::Clamp
+            } else {
+                MTLDepthClipMode::Clip
+            },
+            depth_bias: rasterizer.depth_bias,
+            mesh_workgroup_sizes,
+        })))
+    }
+
+    #[inline]
+    pub fn mtl_pipeline_state(&self) -> &ProtocolObject<dyn MTLRenderPipelineState> {
+        &self.0.pipeline_state
+    }
+
+    #[inline]
+    pub fn mtl_depth_stencil_state(&self) -> &ProtocolObject<dyn MTLDepthStencilState> {
+        &self.0.depth_stencil_state
+    }
+
+    #[inline]
+    pub fn layout(&self) -> &MetalPipelineLayout {
+        &self.0.layout
+    }
+
+    #[inline]
+    pub fn mtl_primitive_type(&self) -> MTLPrimitiveType {
+        self.0.primitive_type
+    }
+
+    #[inline]
+    pub fn mtl_cull_mode(&self) -> MTLCullMode {
+        self.0.cull_mode
+    }
+
+    #[inline]
+    pub fn mtl_winding(&self) -> MTLWinding {
+        self.0.winding
+    }
+
+    #[inline]
+    pub fn mtl_fill_mode(&self) -> MTLTriangleFillMode {
+        self.0.fill_mode
+    }
+
+    #[inline]
+    pub fn mtl_depth_clip_mode(&self) -> MTLDepthClipMode {
+        self.0.depth_clip_mode
+    }
+
+    #[inline]
+    pub fn depth_bias(&self) -> Option<DepthBias> {
+        self.0.depth_bias
+    }
+
+    /// Returns `None` for pipelines created from a [`GraphicsPipelineDesc`].
+    #[inline]
+    pub fn mesh_workgroup_sizes(&self) -> Option<MetalMeshWorkgroupSizes> {
+        self.0.mesh_workgroup_sizes
+    }
+}
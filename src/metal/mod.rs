@@ -1,11 +1,43 @@
+mod bind_group;
+mod bindless_heap;
+mod buffer;
+mod command_list;
+mod compute_pipeline;
 mod device;
+mod fence;
+mod format;
+mod graphics_pipeline;
 mod instance;
 mod physical_device;
+mod pipeline_layout;
+mod query_pool;
+mod queue;
+mod sampler;
+mod semaphore;
+mod shader_module;
+mod texture;
+mod upload;
 
+pub use bind_group::*;
+pub(crate) use bindless_heap::*;
+pub use buffer::*;
+pub use command_list::*;
+pub use compute_pipeline::*;
 pub use device::*;
+pub use fence::*;
+pub(crate) use format::*;
+pub use graphics_pipeline::*;
 pub use instance::*;
 pub use physical_device::*;
+pub use pipeline_layout::*;
+pub use query_pool::*;
+pub use queue::*;
+pub use sampler::*;
+pub use semaphore::*;
+pub use shader_module::*;
+pub use texture::*;
 use thiserror::Error;
+pub(crate) use upload::*;
 
 #[derive(Debug, Error)]
 pub enum MetalError {
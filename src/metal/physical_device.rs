@@ -1,13 +1,55 @@
 use std::sync::Arc;
 
 use objc2::{rc::Retained, runtime::ProtocolObject};
-use objc2_metal::MTLDevice;
+use objc2_metal::{MTLArgumentBuffersTier, MTLDevice, MTLGPUFamily};
 
 use crate::{Error, PhysicalDeviceFeatures};
 
 struct Inner {
     mtl_device: Retained<ProtocolObject<dyn MTLDevice>>,
     name: String,
+    supported_features: PhysicalDeviceFeatures,
+}
+
+fn query_supported_features(mtl_device: &ProtocolObject<dyn MTLDevice>) -> PhysicalDeviceFeatures {
+    let metal_3 = mtl_device.supportsFamily(MTLGPUFamily::Metal3);
+    let apple_7 = mtl_device.supportsFamily(MTLGPUFamily::Apple7);
+    let mac_2 = mtl_device.supportsFamily(MTLGPUFamily::Mac2);
+
+    // Shared events, render pass descriptors, explicit fences, 16-bit types and anisotropic
+    // filtering are available on every family we support. Samplers have no min or max reduction.
+    let mut result = PhysicalDeviceFeatures::TIMELINE_SEMAPHORE
+        | PhysicalDeviceFeatures::DYNAMIC_RENDERING
+        | PhysicalDeviceFeatures::SYNCHRONIZATION_2
+        | PhysicalDeviceFeatures::SHADER_FLOAT16
+        | PhysicalDeviceFeatures::SHADER_INT16
+        | PhysicalDeviceFeatures::STORAGE_16BIT
+        | PhysicalDeviceFeatures::SAMPLER_ANISOTROPY;
+
+    result.set(PhysicalDeviceFeatures::MESH_SHADER, metal_3);
+    result.set(
+        PhysicalDeviceFeatures::RAY_TRACING_PIPELINE,
+        mtl_device.supportsRaytracing(),
+    );
+    result.set(
+        PhysicalDeviceFeatures::RAY_QUERY,
+        mtl_device.supportsRaytracing(),
+    );
+    result.set(
+        PhysicalDeviceFeatures::DESCRIPTOR_INDEXING,
+        mtl_device.argumentBuffersSupport() == MTLArgumentBuffersTier::MTLArgumentBuffersTier2,
+    );
+    result.set(PhysicalDeviceFeatures::BUFFER_DEVICE_ADDRESS, metal_3);
+    result.set(PhysicalDeviceFeatures::SHADER_INT64, apple_7 || mac_2);
+    result.set(
+        PhysicalDeviceFeatures::SUBGROUP_OPERATIONS,
+        apple_7 || mac_2,
+    );
+    result.set(PhysicalDeviceFeatures::VARIABLE_RATE_SHADING, unsafe {
+        mtl_device.supportsRasterizationRateMapWithLayerCount(1)
+    });
+
+    result
 }
 
 #[derive(Clone)]
@@ -16,8 +58,13 @@ pub struct MetalPhysicalDevice(Arc<Inner>);
 impl MetalPhysicalDevice {
     pub fn new(mtl_device: Retained<ProtocolObject<dyn MTLDevice>>) -> Result<Self, Error> {
         let name = mtl_device.name().to_string();
+        let supported_features = query_supported_features(&mtl_device);
 
-        Ok(Self(Arc::new(Inner { mtl_device, name })))
+        Ok(Self(Arc::new(Inner {
+            mtl_device,
+            name,
+            supported_features,
+        })))
     }
 
     #[inline]
@@ -25,8 +72,15 @@ impl MetalPhysicalDevice {
         &self.0.name
     }
 
-    pub fn get_supported_features(&self) -> &PhysicalDeviceFeatures {
-        todo!()
+    #[inline]
+    pub fn get_supported_features(&self) -> PhysicalDeviceFeatures {
+        self.0.supported_features
+    }
+
+    /// Counter sample buffers resolve timestamps in nanoseconds.
+    #[inline]
+    pub fn get_timestamp_period(&self) -> f32 {
+        1.0
     }
 
     pub fn get_mtl_device(&self) -> Retained<ProtocolObject<dyn MTLDevice>> {
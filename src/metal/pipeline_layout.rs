@@ -0,0 +1,113 @@
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use crate::{
+    bindless_descriptor_type,
+    metal::{MetalBindGroupLayout, MetalDevice, MetalError},
+    validate_push_constant_ranges, BindGroupLayoutDesc, DescriptorBinding, PipelineLayoutDesc,
+    PushConstantRange,
+};
+
+/// Largest amount of data the `set*Bytes` encoder methods accept.
+const MAX_PUSH_CONSTANTS_SIZE: u32 = 4096;
+
+/// Buffer index push constants are bound to in every stage, after the ones used for sets.
+pub(crate) const PUSH_CONSTANTS_BUFFER_INDEX: usize = 30;
+
+struct Inner {
+    descriptor_bindings: Vec<DescriptorBinding>,
+    /// Layout of each set, `None` for the bindless set.
+    bind_group_layouts: Vec<Option<MetalBindGroupLayout>>,
+    push_constant_ranges: Vec<PushConstantRange>,
+    bindless_set: Option<u32>,
+}
+
+/// Metal has no pipeline layout object, the bindings are kept to map sets to argument buffers.
+#[derive(Clone)]
+pub struct MetalPipelineLayout(Arc<Inner>);
+
+impl PartialEq for MetalPipelineLayout {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for MetalPipelineLayout {}
+
+impl Hash for MetalPipelineLayout {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+impl MetalPipelineLayout {
+    pub fn new(device: &MetalDevice, desc: &PipelineLayoutDesc) -> Result<Self, MetalError> {
+        validate_push_constant_ranges(desc.push_constant_ranges, MAX_PUSH_CONSTANTS_SIZE)
+            .map_err(MetalError::Custom)?;
+
+        for binding in desc.descriptor_bindings {
+            if Some(binding.set) == desc.bindless_set
+                && bindless_descriptor_type(binding.binding) != Some(binding.descriptor_type)
+            {
+                return Err(MetalError::Custom(format!(
+                    "Binding {} of the bindless set {} does not match the bindless heap",
+                    binding.binding, binding.set
+                )));
+            }
+        }
+
+        let set_count = desc
+            .descriptor_bindings
+            .iter()
+            .map(|binding| binding.set)
+            .max()
+            .max(desc.bindless_set)
+            .map_or(0, |set| set + 1);
+
+        let bind_group_layouts = (0..set_count)
+            .map(|set| {
+                if Some(set) == desc.bindless_set {
+                    return Ok(None);
+                }
+
+                let entries = BindGroupLayoutDesc::entries_of_set(desc.descriptor_bindings, set);
+                MetalBindGroupLayout::new(device, &entries).map(Some)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self(Arc::new(Inner {
+            descriptor_bindings: desc.descriptor_bindings.to_vec(),
+            bind_group_layouts,
+            push_constant_ranges: desc.push_constant_ranges.to_vec(),
+            bindless_set: desc.bindless_set,
+        })))
+    }
+
+    #[inline]
+    pub fn bind_group_layout(&self, set: u32) -> Option<&MetalBindGroupLayout> {
+        self.0
+            .bind_group_layouts
+            .get(set as usize)
+            .and_then(Option::as_ref)
+    }
+
+    #[inline]
+    pub fn descriptor_bindings(&self) -> &[DescriptorBinding] {
+        &self.0.descriptor_bindings
+    }
+
+    #[inline]
+    pub fn push_constant_ranges(&self) -> &[PushConstantRange] {
+        &self.0.push_constant_ranges
+    }
+
+    /// Returns the set whose argument buffer index the bindless heap is bound to.
+    #[inline]
+    pub fn bindless_set(&self) -> Option<u32> {
+        self.0.bindless_set
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_foundation::NSString;
+use objc2_metal::{
+    MTLBuffer, MTLCommonCounterSetTimestamp, MTLCounterSampleBuffer,
+    MTLCounterSampleBufferDescriptor, MTLCounterSet, MTLDevice, MTLResource, MTLResourceOptions,
+    MTLStorageMode,
+};
+
+use crate::{
+    metal::{MetalDevice, MetalError},
+    QueryPoolDesc, QueryType,
+};
+
+/// Timestamps are sampled into a counter sample buffer, occlusion queries count into the
+/// visibility result buffer of a render pass.
+enum Storage {
+    Timestamp(Retained<ProtocolObject<dyn MTLCounterSampleBuffer>>),
+    Occlusion(Retained<ProtocolObject<dyn MTLBuffer>>),
+}
+
+struct Inner {
+    storage: Storage,
+
+    query_type: QueryType,
+    count: u32,
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+#[derive(Clone)]
+pub struct MetalQueryPool(Arc<Inner>);
+
+fn timestamp_counter_set(
+    mtl_device: &ProtocolObject<dyn MTLDevice>,
+) -> Option<Retained<ProtocolObject<dyn MTLCounterSet>>> {
+    let counter_sets = mtl_device.counterSets()?;
+
+    counter_sets
+        .iter()
+        .find(|counter_set| unsafe {
+            counter_set
+                .name()
+                .isEqualToString(MTLCommonCounterSetTimestamp)
+        })
+        .map(|counter_set| counter_set.retain())
+}
+
+impl MetalQueryPool {
+    pub fn new(device: &MetalDevice, desc: &QueryPoolDesc) -> Result<Self, MetalError> {
+        let storage = match desc.query_type {
+            QueryType::Timestamp => {
+                let counter_set = timestamp_counter_set(device.mtl_device()).ok_or_else(|| {
+                    MetalError::Custom("Device has no timestamp counter set".to_owned())
+                })?;
+
+                let descriptor = MTLCounterSampleBufferDescriptor::new();
+                descriptor.setCounterSet(Some(&counter_set));
+                descriptor.setSampleCount(desc.count as _);
+                descriptor.setStorageMode(MTLStorageMode::Shared);
+                if let Some(label) = desc.label {
+                    descriptor.setLabel(&NSString::from_str(label));
+                }
+
+                let sample_buffer = device
+                    .mtl_device()
+                    .newCounterSampleBufferWithDescriptor_error(&descriptor)
+                    .map_err(|e| {
+                        MetalError::Custom(format!(
+                            "Failed to create counter sample buffer: {}",
+                            e.localizedDescription()
+                        ))
+                    })?;
+
+                Storage::Timestamp(sample_buffer)
+            }
+            QueryType::Occlusion => {
+                let visibility_buffer = device
+                    .mtl_device()
+                    .newBufferWithLength_options(
+                        desc.count as usize * 8,
+                        MTLResourceOptions::MTLResourceStorageModePrivate,
+                    )
+                    .ok_or_else(|| {
+                        MetalError::Custom("Failed to create visibility result buffer".to_owned())
+                    })?;
+                if let Some(label) = desc.label {
+                    visibility_buffer.setLabel(Some(&NSString::from_str(label)));
+                }
+
+                Storage::Occlusion(visibility_buffer)
+            }
+            QueryType::PipelineStatistics(_) => {
+                return Err(MetalError::Custom(
+                    "Pipeline statistics queries are not supported".to_owned(),
+                ));
+            }
+        };
+
+        Ok(Self(Arc::new(Inner {
+            storage,
+
+            query_type: desc.query_type,
+            count: desc.count,
+        })))
+    }
+
+    #[inline]
+    pub(crate) fn mtl_counter_sample_buffer(
+        &self,
+    ) -> Option<&ProtocolObject<dyn MTLCounterSampleBuffer>> {
+        match &self.0.storage {
+            Storage::Timestamp(sample_buffer) => Some(sample_buffer),
+            Storage::Occlusion(_) => None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn mtl_visibility_buffer(&self) -> Option<&ProtocolObject<dyn MTLBuffer>> {
+        match &self.0.storage {
+            Storage::Timestamp(_) => None,
+            Storage::Occlusion(visibility_buffer) => Some(visibility_buffer),
+        }
+    }
+
+    #[inline]
+    pub fn query_type(&self) -> QueryType {
+        self.0.query_type
+    }
+
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.0.count
+    }
+}
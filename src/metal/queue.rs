@@ -0,0 +1,113 @@
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_metal::{MTLCommandBuffer, MTLCommandQueue};
+
+use crate::{
+    metal::{MetalDevice, MetalError},
+    QueueType, SubmitDesc,
+};
+
+/// Metal exposes a single command queue per device, every [`QueueType`] is backed by it.
+#[derive(Clone)]
+pub struct MetalQueue {
+    device: MetalDevice,
+
+    queue_type: QueueType,
+}
+
+impl MetalQueue {
+    pub(crate) fn new(device: &MetalDevice, queue_type: QueueType) -> Self {
+        Self {
+            device: device.clone(),
+
+            queue_type,
+        }
+    }
+
+    #[inline]
+    pub fn mtl_command_queue(&self) -> &ProtocolObject<dyn MTLCommandQueue> {
+        self.device.mtl_command_queue()
+    }
+
+    #[inline]
+    pub fn queue_type(&self) -> QueueType {
+        self.queue_type
+    }
+
+    #[inline]
+    pub fn is_dedicated(&self) -> bool {
+        self.queue_type == QueueType::Graphics
+    }
+
+    /// Commits every command list in order, Metal has a single queue so the queue type is ignored.
+    ///
+    /// Semaphore waits and signals are encoded into separate command buffers committed before
+    /// and after the command lists, the latter also signals the device's submission event.
+    /// Uploads are committed to the same queue, so `wait_upload` needs no wait.
+    pub fn submit(&self, desc: &SubmitDesc) -> Result<(), MetalError> {
+        let mut submission_value = self.device.lock_submissions();
+
+        if !desc.wait_semaphores.is_empty() {
+            let command_buffer = self.new_command_buffer()?;
+
+            for value in desc.wait_semaphores {
+                command_buffer.encodeWaitForEvent_value(
+                    ProtocolObject::from_ref(value.semaphore.as_metal().mtl_shared_event()),
+                    value.value,
+                );
+            }
+
+            command_buffer.commit();
+        }
+
+        for command_list in desc.command_lists {
+            command_list.as_metal().commit()?;
+        }
+
+        let command_buffer = self.new_command_buffer()?;
+
+        command_buffer.encodeSignalEvent_value(
+            ProtocolObject::from_ref(self.device.mtl_submission_event()),
+            *submission_value + 1,
+        );
+
+        for value in desc.signal_semaphores {
+            command_buffer.encodeSignalEvent_value(
+                ProtocolObject::from_ref(value.semaphore.as_metal().mtl_shared_event()),
+                value.value,
+            );
+        }
+
+        if let Some(fence) = desc.fence {
+            let fence = fence.as_metal();
+
+            command_buffer.encodeSignalEvent_value(
+                ProtocolObject::from_ref(fence.mtl_shared_event()),
+                fence.target_value(),
+            );
+        }
+
+        command_buffer.commit();
+
+        *submission_value += 1;
+
+        Ok(())
+    }
+
+    fn new_command_buffer(
+        &self,
+    ) -> Result<Retained<ProtocolObject<dyn MTLCommandBuffer>>, MetalError> {
+        self.mtl_command_queue()
+            .commandBuffer()
+            .ok_or_else(|| MetalError::Custom("Failed to create command buffer".to_owned()))
+    }
+
+    pub fn wait_idle(&self) -> Result<(), MetalError> {
+        // Command buffers complete in commit order, so waiting on an empty one drains the queue.
+        let command_buffer = self.new_command_buffer()?;
+
+        command_buffer.commit();
+        unsafe { command_buffer.waitUntilCompleted() };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_metal::{
+    MTLDevice, MTLSamplerAddressMode, MTLSamplerBorderColor, MTLSamplerDescriptor,
+    MTLSamplerMinMagFilter, MTLSamplerMipFilter, MTLSamplerState,
+};
+
+use crate::{
+    metal::{to_mtl_compare_function, MetalDevice, MetalError},
+    validate_sampler_desc, AddressMode, BorderColor, FilterMode, MipmapMode, SamplerDesc,
+    SamplerReductionMode,
+};
+
+fn to_mtl_sampler_min_mag_filter(filter: FilterMode) -> MTLSamplerMinMagFilter {
+    match filter {
+        FilterMode::Nearest => MTLSamplerMinMagFilter::Nearest,
+        FilterMode::Linear => MTLSamplerMinMagFilter::Linear,
+    }
+}
+
+fn to_mtl_sampler_mip_filter(mipmap_mode: MipmapMode) -> MTLSamplerMipFilter {
+    match mipmap_mode {
+        MipmapMode::Nearest => MTLSamplerMipFilter::Nearest,
+        MipmapMode::Linear => MTLSamplerMipFilter::Linear,
+    }
+}
+
+fn to_mtl_sampler_address_mode(address_mode: AddressMode) -> MTLSamplerAddressMode {
+    match address_mode {
+        AddressMode::Repeat => MTLSamplerAddressMode::Repeat,
+        AddressMode::MirroredRepeat => MTLSamplerAddressMode::MirrorRepeat,
+        AddressMode::ClampToEdge => MTLSamplerAddressMode::ClampToEdge,
+        AddressMode::ClampToBorder => MTLSamplerAddressMode::ClampToBorderColor,
+    }
+}
+
+fn to_mtl_sampler_border_color(border_color: BorderColor) -> MTLSamplerBorderColor {
+    match border_color {
+        BorderColor::TransparentBlack => MTLSamplerBorderColor::TransparentBlack,
+        BorderColor::OpaqueBlack => MTLSamplerBorderColor::OpaqueBlack,
+        BorderColor::OpaqueWhite => MTLSamplerBorderColor::OpaqueWhite,
+    }
+}
+
+/// Sampler states of the device by descriptor.
+pub(crate) type SamplerCache = Mutex<HashMap<SamplerDesc, MetalSampler>>;
+
+struct Inner {
+    mtl_sampler_state: Retained<ProtocolObject<dyn MTLSamplerState>>,
+
+    desc: SamplerDesc,
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+#[derive(Clone)]
+pub struct MetalSampler(Arc<Inner>);
+
+impl PartialEq for MetalSampler {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for MetalSampler {}
+
+impl Hash for MetalSampler {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+impl MetalSampler {
+    pub fn new(device: &MetalDevice, desc: &SamplerDesc) -> Result<Self, MetalError> {
+        validate_sampler_desc(desc).map_err(MetalError::Custom)?;
+
+        if desc.reduction_mode != SamplerReductionMode::WeightedAverage {
+            return Err(MetalError::Custom(
+                "Metal samplers do not support min and max reductions".to_owned(),
+            ));
+        }
+
+        let mut cache = device.sampler_cache().lock().unwrap();
+
+        if let Some(sampler) = cache.get(desc) {
+            return Ok(sampler.clone());
+        }
+
+        let descriptor = MTLSamplerDescriptor::new();
+        descriptor.setMagFilter(to_mtl_sampler_min_mag_filter(desc.mag_filter));
+        descriptor.setMinFilter(to_mtl_sampler_min_mag_filter(desc.min_filter));
+        descriptor.setMipFilter(to_mtl_sampler_mip_filter(desc.mipmap_mode));
+        descriptor.setSAddressMode(to_mtl_sampler_address_mode(desc.address_mode_u));
+        descriptor.setTAddressMode(to_mtl_sampler_address_mode(desc.address_mode_v));
+        descriptor.setRAddressMode(to_mtl_sampler_address_mode(desc.address_mode_w));
+        descriptor.setMaxAnisotropy(desc.max_anisotropy as _);
+        descriptor.setLodMinClamp(desc.lod_min_clamp);
+        descriptor.setLodMaxClamp(desc.lod_max_clamp);
+        descriptor.setBorderColor(to_mtl_sampler_border_color(desc.border_color));
+        if let Some(compare) = desc.compare {
+            descriptor.setCompareFunction(to_mtl_compare_function(compare));
+        }
+        // Bind groups and the bindless heap reference samplers by resource ID.
+        descriptor.setSupportArgumentBuffers(true);
+
+        let mtl_sampler_state = device
+            .mtl_device()
+            .newSamplerStateWithDescriptor(&descriptor)
+            .ok_or_else(|| MetalError::Custom("Failed to create sampler state".to_owned()))?;
+
+        let sampler = Self(Arc::new(Inner {
+            mtl_sampler_state,
+
+            desc: *desc,
+        }));
+
+        cache.insert(*desc, sampler.clone());
+
+        Ok(sampler)
+    }
+
+    #[inline]
+    pub fn mtl_sampler_state(&self) -> &ProtocolObject<dyn MTLSamplerState> {
+        &self.0.mtl_sampler_state
+    }
+
+    #[inline]
+    pub fn desc(&self) -> &SamplerDesc {
+        &self.0.desc
+    }
+}
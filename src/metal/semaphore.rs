@@ -0,0 +1,89 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_foundation::NSString;
+use objc2_metal::{MTLDevice, MTLEvent, MTLSharedEvent};
+
+use crate::{
+    metal::{MetalDevice, MetalError},
+    SemaphoreDesc, SemaphoreValue,
+};
+
+struct Inner {
+    shared_event: Retained<ProtocolObject<dyn MTLSharedEvent>>,
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+/// A timeline semaphore backed by an `MTLSharedEvent`.
+#[derive(Clone)]
+pub struct MetalSemaphore(Arc<Inner>);
+
+/// Converts a timeout to milliseconds, `None` waits indefinitely.
+pub(crate) fn to_mtl_timeout_ms(timeout: Option<Duration>) -> u64 {
+    timeout.map_or(u64::MAX, |timeout| {
+        timeout.as_millis().try_into().unwrap_or(u64::MAX)
+    })
+}
+
+impl MetalSemaphore {
+    pub fn new(device: &MetalDevice, desc: &SemaphoreDesc) -> Result<Self, MetalError> {
+        let shared_event = device
+            .mtl_device()
+            .newSharedEvent()
+            .ok_or_else(|| MetalError::Custom("Failed to create shared event".to_owned()))?;
+        if let Some(label) = desc.label {
+            shared_event.setLabel(Some(&NSString::from_str(label)));
+        }
+
+        unsafe { shared_event.setSignaledValue(desc.initial_value) };
+
+        Ok(Self(Arc::new(Inner { shared_event })))
+    }
+
+    #[inline]
+    pub fn mtl_shared_event(&self) -> &ProtocolObject<dyn MTLSharedEvent> {
+        &self.0.shared_event
+    }
+
+    pub fn get_value(&self) -> Result<u64, MetalError> {
+        Ok(unsafe { self.0.shared_event.signaledValue() })
+    }
+
+    pub fn signal(&self, value: u64) -> Result<(), MetalError> {
+        unsafe { self.0.shared_event.setSignaledValue(value) };
+
+        Ok(())
+    }
+
+    pub fn wait(&self, value: u64, timeout: Option<Duration>) -> Result<bool, MetalError> {
+        Ok(unsafe {
+            self.0
+                .shared_event
+                .waitUntilSignaledValue_timeoutMS(value, to_mtl_timeout_ms(timeout))
+        })
+    }
+
+    pub fn wait_for_values(
+        _device: &MetalDevice,
+        values: &[SemaphoreValue],
+        timeout: Option<Duration>,
+    ) -> Result<bool, MetalError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        for value in values {
+            let remaining =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+
+            if !value.semaphore.as_metal().wait(value.value, remaining)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
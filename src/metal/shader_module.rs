@@ -0,0 +1,74 @@
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_metal::MTLLibrary;
+
+use crate::{
+    metal::{MetalDevice, MetalError},
+    ShaderModuleDesc, ShaderReflection,
+};
+
+struct Inner {
+    code: Vec<u32>,
+    library: Option<Retained<ProtocolObject<dyn MTLLibrary>>>,
+    reflection: ShaderReflection,
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+/// Keeps the SPIR-V words around, Metal libraries are created when a pipeline is built.
+#[derive(Clone)]
+pub struct MetalShaderModule(Arc<Inner>);
+
+impl PartialEq for MetalShaderModule {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for MetalShaderModule {}
+
+impl Hash for MetalShaderModule {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+impl MetalShaderModule {
+    pub fn new(
+        _device: &MetalDevice,
+        _desc: &ShaderModuleDesc,
+        code: &[u32],
+        reflection: ShaderReflection,
+    ) -> Result<Self, MetalError> {
+        Ok(Self(Arc::new(Inner {
+            code: code.to_vec(),
+            // SPIR-V has to be translated to MSL before Metal can compile it, which this backend
+            // does not do yet.
+            library: None,
+            reflection,
+        })))
+    }
+
+    #[inline]
+    pub fn code(&self) -> &[u32] {
+        &self.0.code
+    }
+
+    /// Returns the compiled library, `None` until the module has been translated to MSL.
+    #[inline]
+    pub fn mtl_library(&self) -> Option<&ProtocolObject<dyn MTLLibrary>> {
+        self.0.library.as_deref()
+    }
+
+    #[inline]
+    pub fn reflection(&self) -> &ShaderReflection {
+        &self.0.reflection
+    }
+}
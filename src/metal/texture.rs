@@ -0,0 +1,257 @@
+use std::sync::Arc;
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_foundation::{NSRange, NSString};
+use objc2_metal::{
+    MTLDevice, MTLResource, MTLStorageMode, MTLTexture, MTLTextureDescriptor, MTLTextureType,
+    MTLTextureUsage,
+};
+
+use crate::{
+    metal::{to_mtl_pixel_format, MetalDevice, MetalError},
+    Extent3d, Format, Texture, TextureDesc, TextureDimension, TextureSubresourceRange,
+    TextureUsageFlags, TextureViewDesc, TextureViewDimension,
+};
+
+struct Inner {
+    mtl_texture: Retained<ProtocolObject<dyn MTLTexture>>,
+
+    dimension: TextureDimension,
+    format: Format,
+    extent: Extent3d,
+    mip_levels: u32,
+    array_layers: u32,
+    sample_count: u32,
+    usage: TextureUsageFlags,
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+#[derive(Clone)]
+pub struct MetalTexture(Arc<Inner>);
+
+fn to_mtl_texture_usage(usage: TextureUsageFlags) -> MTLTextureUsage {
+    let mut result = MTLTextureUsage::empty();
+
+    if usage.contains(TextureUsageFlags::SAMPLED) {
+        result |= MTLTextureUsage::ShaderRead;
+    }
+    if usage.contains(TextureUsageFlags::STORAGE) {
+        result |= MTLTextureUsage::ShaderRead | MTLTextureUsage::ShaderWrite;
+    }
+    if usage.intersects(
+        TextureUsageFlags::COLOR_ATTACHMENT | TextureUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+    ) {
+        result |= MTLTextureUsage::RenderTarget;
+    }
+
+    result
+}
+
+fn to_mtl_texture_type(desc: &TextureDesc) -> MTLTextureType {
+    let array = desc.array_layers > 1;
+
+    match desc.dimension {
+        TextureDimension::D1 if array => MTLTextureType::MTLTextureType1DArray,
+        TextureDimension::D1 => MTLTextureType::MTLTextureType1D,
+        TextureDimension::D2 if desc.sample_count > 1 && array => {
+            MTLTextureType::MTLTextureType2DMultisampleArray
+        }
+        TextureDimension::D2 if desc.sample_count > 1 => {
+            MTLTextureType::MTLTextureType2DMultisample
+        }
+        TextureDimension::D2 if array => MTLTextureType::MTLTextureType2DArray,
+        TextureDimension::D2 => MTLTextureType::MTLTextureType2D,
+        TextureDimension::D3 => MTLTextureType::MTLTextureType3D,
+        TextureDimension::Cube if desc.array_layers > 6 => MTLTextureType::CubeArray,
+        TextureDimension::Cube => MTLTextureType::Cube,
+    }
+}
+
+fn to_mtl_view_texture_type(dimension: TextureViewDimension, sample_count: u32) -> MTLTextureType {
+    match dimension {
+        TextureViewDimension::D1 => MTLTextureType::MTLTextureType1D,
+        TextureViewDimension::D1Array => MTLTextureType::MTLTextureType1DArray,
+        TextureViewDimension::D2 if sample_count > 1 => MTLTextureType::MTLTextureType2DMultisample,
+        TextureViewDimension::D2 => MTLTextureType::MTLTextureType2D,
+        TextureViewDimension::D2Array if sample_count > 1 => {
+            MTLTextureType::MTLTextureType2DMultisampleArray
+        }
+        TextureViewDimension::D2Array => MTLTextureType::MTLTextureType2DArray,
+        TextureViewDimension::D3 => MTLTextureType::MTLTextureType3D,
+        TextureViewDimension::Cube => MTLTextureType::Cube,
+        TextureViewDimension::CubeArray => MTLTextureType::CubeArray,
+    }
+}
+
+impl MetalTexture {
+    pub fn new(device: &MetalDevice, desc: &TextureDesc) -> Result<Self, MetalError> {
+        let array_length = if desc.dimension == TextureDimension::Cube {
+            desc.array_layers / 6
+        } else {
+            desc.array_layers
+        };
+
+        let texture_descriptor = unsafe { MTLTextureDescriptor::new() };
+
+        texture_descriptor.setTextureType(to_mtl_texture_type(desc));
+        texture_descriptor.setPixelFormat(to_mtl_pixel_format(desc.format));
+        unsafe {
+            texture_descriptor.setWidth(desc.extent.width as _);
+            texture_descriptor.setHeight(desc.extent.height as _);
+            texture_descriptor.setDepth(desc.extent.depth as _);
+            texture_descriptor.setMipmapLevelCount(desc.mip_levels as _);
+            texture_descriptor.setSampleCount(desc.sample_count as _);
+            texture_descriptor.setArrayLength(array_length as _);
+        }
+        texture_descriptor.setStorageMode(MTLStorageMode::Private);
+        texture_descriptor.setUsage(to_mtl_texture_usage(desc.usage));
+
+        let mtl_texture = device
+            .mtl_device()
+            .newTextureWithDescriptor(&texture_descriptor)
+            .ok_or_else(|| MetalError::Custom("Failed to create texture".to_owned()))?;
+        if let Some(label) = desc.label {
+            mtl_texture.setLabel(Some(&NSString::from_str(label)));
+        }
+
+        Ok(Self(Arc::new(Inner {
+            mtl_texture,
+
+            dimension: desc.dimension,
+            format: desc.format,
+            extent: desc.extent,
+            mip_levels: desc.mip_levels,
+            array_layers: desc.array_layers,
+            sample_count: desc.sample_count,
+            usage: desc.usage,
+        })))
+    }
+
+    #[inline]
+    pub fn mtl_texture(&self) -> &ProtocolObject<dyn MTLTexture> {
+        &self.0.mtl_texture
+    }
+
+    #[inline]
+    pub fn dimension(&self) -> TextureDimension {
+        self.0.dimension
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        self.0.format
+    }
+
+    #[inline]
+    pub fn extent(&self) -> Extent3d {
+        self.0.extent
+    }
+
+    #[inline]
+    pub fn mip_levels(&self) -> u32 {
+        self.0.mip_levels
+    }
+
+    #[inline]
+    pub fn array_layers(&self) -> u32 {
+        self.0.array_layers
+    }
+
+    #[inline]
+    pub fn sample_count(&self) -> u32 {
+        self.0.sample_count
+    }
+
+    #[inline]
+    pub fn usage(&self) -> TextureUsageFlags {
+        self.0.usage
+    }
+}
+
+struct ViewInner {
+    texture: Texture,
+
+    mtl_texture: Retained<ProtocolObject<dyn MTLTexture>>,
+
+    dimension: TextureViewDimension,
+    format: Format,
+    range: TextureSubresourceRange,
+}
+
+unsafe impl Send for ViewInner {}
+unsafe impl Sync for ViewInner {}
+
+#[derive(Clone)]
+pub struct MetalTextureView(Arc<ViewInner>);
+
+impl MetalTextureView {
+    pub fn new(
+        texture: &Texture,
+        metal_texture: &MetalTexture,
+        desc: &TextureViewDesc,
+    ) -> Result<Self, MetalError> {
+        let dimension = desc
+            .dimension
+            .unwrap_or_else(|| texture.default_view_dimension());
+        let format = desc.format.unwrap_or(metal_texture.format());
+        let range = desc.range.resolve(texture, format);
+
+        let mtl_texture = unsafe {
+            metal_texture
+                .mtl_texture()
+                .newTextureViewWithPixelFormat_textureType_levels_slices(
+                    to_mtl_pixel_format(format),
+                    to_mtl_view_texture_type(dimension, metal_texture.sample_count()),
+                    NSRange::new(
+                        range.base_mip_level as _,
+                        range.mip_level_count.unwrap_or_default() as _,
+                    ),
+                    NSRange::new(
+                        range.base_array_layer as _,
+                        range.array_layer_count.unwrap_or_default() as _,
+                    ),
+                )
+        }
+        .ok_or_else(|| MetalError::Custom("Failed to create texture view".to_owned()))?;
+        if let Some(label) = desc.label {
+            mtl_texture.setLabel(Some(&NSString::from_str(label)));
+        }
+
+        Ok(Self(Arc::new(ViewInner {
+            texture: texture.clone(),
+
+            mtl_texture,
+
+            dimension,
+            format,
+            range,
+        })))
+    }
+
+    #[inline]
+    pub fn mtl_texture(&self) -> &ProtocolObject<dyn MTLTexture> {
+        &self.0.mtl_texture
+    }
+
+    #[inline]
+    pub fn texture(&self) -> &Texture {
+        &self.0.texture
+    }
+
+    #[inline]
+    pub fn dimension(&self) -> TextureViewDimension {
+        self.0.dimension
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        self.0.format
+    }
+
+    #[inline]
+    pub fn range(&self) -> &TextureSubresourceRange {
+        &self.0.range
+    }
+}
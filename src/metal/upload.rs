@@ -0,0 +1,163 @@
+use std::{ptr::NonNull, sync::Mutex, time::Duration};
+
+use objc2::{rc::Retained, runtime::ProtocolObject};
+use objc2_metal::{
+    MTLBlitCommandEncoder, MTLBuffer, MTLCommandBuffer, MTLCommandEncoder, MTLCommandQueue,
+    MTLDevice, MTLResourceOptions, MTLSharedEvent,
+};
+
+use crate::{
+    metal::{
+        to_mtl_bytes_per_row_and_image, to_mtl_origin, to_mtl_size, to_mtl_timeout_ms, MetalDevice,
+        MetalError,
+    },
+    BufferTextureCopy, BufferUpload, StagingRing, TextureUpload,
+};
+
+/// Records uploads into blit command buffers on the device's queue, whose submission event is the
+/// upload timeline.
+///
+/// Metal has a single queue, so later submissions are ordered after uploads without waiting.
+#[derive(Default)]
+pub(crate) struct MetalUploader {
+    staging_ring: Mutex<StagingRing<Retained<ProtocolObject<dyn MTLBuffer>>>>,
+}
+
+unsafe impl Send for MetalUploader {}
+unsafe impl Sync for MetalUploader {}
+
+impl MetalUploader {
+    pub fn completed_value(device: &MetalDevice) -> u64 {
+        device.completed_submission_value()
+    }
+
+    pub fn wait(device: &MetalDevice, value: u64, timeout: Option<Duration>) -> bool {
+        unsafe {
+            device
+                .mtl_submission_event()
+                .waitUntilSignaledValue_timeoutMS(value, to_mtl_timeout_ms(timeout))
+        }
+    }
+
+    /// Stages `data`, encodes the copy with `encode` and commits it.
+    ///
+    /// `encode` receives the blit encoder and the staging buffer and offset of the data.
+    fn upload(
+        device: &MetalDevice,
+        data: &[u8],
+        alignment: u64,
+        encode: impl FnOnce(
+            &ProtocolObject<dyn MTLBlitCommandEncoder>,
+            &ProtocolObject<dyn MTLBuffer>,
+            u64,
+        ),
+    ) -> Result<u64, MetalError> {
+        let completed_value = device.completed_submission_value();
+
+        let mut staging_ring = device.uploader().staging_ring.lock().unwrap();
+
+        let (staging_buffer, offset) = staging_ring.allocate(
+            data.len() as u64,
+            alignment,
+            completed_value,
+            |size| {
+                device
+                    .mtl_device()
+                    .newBufferWithLength_options(
+                        size as _,
+                        MTLResourceOptions::MTLResourceStorageModeShared
+                            | MTLResourceOptions::MTLResourceCPUCacheModeWriteCombined,
+                    )
+                    .ok_or_else(|| MetalError::Custom("Failed to create staging buffer".to_owned()))
+            },
+            drop,
+        )?;
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                staging_buffer
+                    .contents()
+                    .cast::<u8>()
+                    .as_ptr()
+                    .add(offset as usize),
+                data.len(),
+            );
+        }
+
+        let mut submission_value = device.lock_submissions();
+
+        let command_buffer = device
+            .mtl_command_queue()
+            .commandBuffer()
+            .ok_or_else(|| MetalError::Custom("Failed to create command buffer".to_owned()))?;
+        let blit_encoder = command_buffer
+            .blitCommandEncoder()
+            .ok_or_else(|| MetalError::Custom("Failed to create blit encoder".to_owned()))?;
+
+        encode(&blit_encoder, staging_buffer, offset);
+        blit_encoder.endEncoding();
+
+        command_buffer.encodeSignalEvent_value(
+            ProtocolObject::from_ref(device.mtl_submission_event()),
+            *submission_value + 1,
+        );
+        command_buffer.commit();
+
+        *submission_value += 1;
+        staging_ring.submit(*submission_value);
+
+        Ok(*submission_value)
+    }
+
+    pub fn upload_buffer(device: &MetalDevice, upload: &BufferUpload) -> Result<u64, MetalError> {
+        Self::upload(
+            device,
+            upload.data,
+            16,
+            |blit_encoder, staging_buffer, offset| unsafe {
+                blit_encoder.copyFromBuffer_sourceOffset_toBuffer_destinationOffset_size(
+                    staging_buffer,
+                    offset as _,
+                    upload.buffer.as_metal().mtl_buffer(),
+                    upload.offset as _,
+                    upload.data.len() as _,
+                );
+            },
+        )
+    }
+
+    pub fn upload_texture(device: &MetalDevice, upload: &TextureUpload) -> Result<u64, MetalError> {
+        let region = BufferTextureCopy {
+            buffer_offset: 0,
+            bytes_per_row: 0,
+            rows_per_image: 0,
+            mip_level: upload.mip_level,
+            array_layer: upload.array_layer,
+            origin: upload.origin,
+            extent: upload.extent,
+        };
+        let (bytes_per_row, bytes_per_image) =
+            to_mtl_bytes_per_row_and_image(upload.texture, &region);
+
+        let alignment = u64::from(upload.texture.format().block_size());
+
+        let encode = |blit_encoder: &ProtocolObject<dyn MTLBlitCommandEncoder>,
+                      staging_buffer: &ProtocolObject<dyn MTLBuffer>,
+                      offset: u64| unsafe {
+            blit_encoder.copyFromBuffer_sourceOffset_sourceBytesPerRow_sourceBytesPerImage_sourceSize_toTexture_destinationSlice_destinationLevel_destinationOrigin(
+                staging_buffer,
+                offset as _,
+                bytes_per_row,
+                bytes_per_image,
+                to_mtl_size(upload.extent),
+                upload.texture.as_metal().mtl_texture(),
+                upload.array_layer as _,
+                upload.mip_level as _,
+                to_mtl_origin(upload.origin),
+            );
+        };
+
+        Self::upload(device, upload.data, alignment, encode)
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    null::{NullDevice, NullError, NullObjectId},
+    sort_bind_group_layout_entries, BindGroupDesc, BindGroupLayoutEntry, BindGroupPoolDesc,
+};
+
+/// Layouts need no backend object, identical entries make identical layouts.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct NullBindGroupLayout(Arc<[BindGroupLayoutEntry]>);
+
+impl NullBindGroupLayout {
+    pub fn new(_device: &NullDevice, entries: &[BindGroupLayoutEntry]) -> Result<Self, NullError> {
+        let entries = sort_bind_group_layout_entries(entries).map_err(NullError::Validation)?;
+
+        Ok(Self(entries.into()))
+    }
+
+    #[inline]
+    pub fn entries(&self) -> &[BindGroupLayoutEntry] {
+        &self.0
+    }
+}
+
+struct PoolInner {
+    device: NullDevice,
+    id: NullObjectId,
+
+    max_descriptors: u32,
+    /// Incremented by every reset, bind groups of earlier generations are freed.
+    generation: Mutex<u64>,
+}
+
+impl Drop for PoolInner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
+
+pub struct NullBindGroupPool(Arc<PoolInner>);
+
+impl NullBindGroupPool {
+    pub fn new(device: &NullDevice, desc: &BindGroupPoolDesc) -> Result<Self, NullError> {
+        if desc.max_bind_groups == 0 || desc.max_descriptors == 0 {
+            return Err(NullError::Validation(
+                "Bind group pool blocks cannot be empty".to_owned(),
+            ));
+        }
+
+        let id = device.register_object("BindGroupPool");
+
+        Ok(Self(Arc::new(PoolInner {
+            device: device.clone(),
+            id,
+
+            max_descriptors: desc.max_descriptors,
+            generation: Mutex::new(0),
+        })))
+    }
+
+    pub fn create_bind_group(&self, desc: &BindGroupDesc) -> Result<NullBindGroup, NullError> {
+        let layout = desc.layout.as_null();
+
+        for entry in layout.entries() {
+            let count = layout
+                .entries()
+                .iter()
+                .filter(|e| e.descriptor_type == entry.descriptor_type)
+                .map(|e| e.count)
+                .sum::<u32>();
+
+            if count > self.0.max_descriptors {
+                return Err(NullError::Validation(format!(
+                    "Bind group holds {count} {:?} descriptors, the pool blocks hold {}",
+                    entry.descriptor_type, self.0.max_descriptors
+                )));
+            }
+        }
+
+        Ok(NullBindGroup(Arc::new(BindGroupInner {
+            pool: self.0.clone(),
+            generation: *self.0.generation.lock().unwrap(),
+
+            layout: layout.clone(),
+        })))
+    }
+
+    pub fn reset(&mut self) -> Result<(), NullError> {
+        *self.0.generation.lock().unwrap() += 1;
+
+        Ok(())
+    }
+}
+
+struct BindGroupInner {
+    pool: Arc<PoolInner>,
+    generation: u64,
+
+    layout: NullBindGroupLayout,
+}
+
+#[derive(Clone)]
+pub struct NullBindGroup(Arc<BindGroupInner>);
+
+impl NullBindGroup {
+    #[inline]
+    pub fn layout(&self) -> &NullBindGroupLayout {
+        &self.0.layout
+    }
+
+    /// Returns `false` once the pool was reset.
+    #[inline]
+    pub(crate) fn is_allocated(&self) -> bool {
+        *self.0.pool.generation.lock().unwrap() == self.0.generation
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::Mutex;
+
+use crate::{
+    null::{NullDevice, NullError},
+    BindlessSlots, Buffer, BufferUsageFlags, TextureUsageFlags, TextureView,
+    BINDLESS_HEAP_CAPACITY,
+};
+
+/// Validates registrations, released indices carry the last submission serial at release time.
+pub(crate) struct NullBindlessHeap {
+    slots: Mutex<BindlessSlots<(), u64>>,
+}
+
+impl NullBindlessHeap {
+    pub(crate) fn new() -> Self {
+        Self {
+            slots: Mutex::new(BindlessSlots::new(BINDLESS_HEAP_CAPACITY)),
+        }
+    }
+
+    fn heap(device: &NullDevice) -> Result<&Self, NullError> {
+        device.bindless_heap().ok_or_else(|| {
+            NullError::Validation("The bindless heap requires descriptor indexing".to_owned())
+        })
+    }
+
+    fn insert(device: &NullDevice) -> Result<u32, NullError> {
+        let mut slots = Self::heap(device)?.slots.lock().unwrap();
+
+        slots
+            .insert((), |serial| device.is_submission_complete(*serial))
+            .ok_or_else(|| {
+                NullError::Validation(format!(
+                    "Bindless heap is full, it holds {} resources",
+                    slots.capacity()
+                ))
+            })
+    }
+
+    pub fn register_buffer(device: &NullDevice, buffer: &Buffer) -> Result<u32, NullError> {
+        if !buffer.usage().contains(BufferUsageFlags::STORAGE) {
+            return Err(NullError::Validation(
+                "Bindless buffers require storage usage".to_owned(),
+            ));
+        }
+        if buffer.as_null().tracked() {
+            return Err(NullError::Validation(
+                "Tracked buffers cannot be used bindless".to_owned(),
+            ));
+        }
+
+        Self::insert(device)
+    }
+
+    pub fn register_texture_view(
+        device: &NullDevice,
+        view: &TextureView,
+    ) -> Result<u32, NullError> {
+        let texture = view.texture();
+
+        if !texture
+            .usage()
+            .intersects(TextureUsageFlags::SAMPLED | TextureUsageFlags::STORAGE)
+        {
+            return Err(NullError::Validation(
+                "Bindless texture views require sampled or storage usage".to_owned(),
+            ));
+        }
+        if texture.as_null().tracked() {
+            return Err(NullError::Validation(
+                "Tracked textures cannot be used bindless".to_owned(),
+            ));
+        }
+
+        Self::insert(device)
+    }
+
+    pub fn release(device: &NullDevice, index: u32) -> Result<(), NullError> {
+        let serial = device.submission_serial();
+
+        if !Self::heap(device)?
+            .slots
+            .lock()
+            .unwrap()
+            .release(index, serial)
+        {
+            return Err(NullError::Validation(format!(
+                "Bindless index {index} is not registered"
+            )));
+        }
+
+        Ok(())
+    }
+}
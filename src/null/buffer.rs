@@ -0,0 +1,116 @@
+use std::{cell::UnsafeCell, ptr::NonNull, sync::Arc};
+
+use crate::{
+    null::{NullDevice, NullError, NullObjectId},
+    BufferDesc, BufferUsageFlags, MemoryLocation, PhysicalDeviceFeatures,
+};
+
+struct Inner {
+    device: NullDevice,
+    id: NullObjectId,
+
+    memory: Vec<UnsafeCell<u8>>,
+
+    size: u64,
+    usage: BufferUsageFlags,
+    memory_location: MemoryLocation,
+    device_address: Option<u64>,
+    tracked: bool,
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+#[derive(Clone)]
+pub struct NullBuffer(Arc<Inner>);
+
+impl NullBuffer {
+    pub fn new(device: &NullDevice, desc: &BufferDesc) -> Result<Self, NullError> {
+        if desc.size == 0 {
+            return Err(NullError::Validation(format!(
+                "Buffer {:?}: size must be greater than zero",
+                desc.label
+            )));
+        }
+
+        if desc.usage.is_empty() {
+            return Err(NullError::Validation(format!(
+                "Buffer {:?}: usage must not be empty",
+                desc.label
+            )));
+        }
+
+        if desc.usage.contains(BufferUsageFlags::DEVICE_ADDRESS) {
+            device.validate_features(
+                PhysicalDeviceFeatures::BUFFER_DEVICE_ADDRESS,
+                "BufferUsageFlags::DEVICE_ADDRESS",
+            )?;
+        }
+
+        let memory = if desc.memory_location.is_host_visible() {
+            (0..desc.size).map(|_| UnsafeCell::new(0)).collect()
+        } else {
+            Vec::new()
+        };
+
+        let id = device.register_object("Buffer");
+
+        let device_address = desc
+            .usage
+            .contains(BufferUsageFlags::DEVICE_ADDRESS)
+            .then(|| device.allocate_address_range(desc.size));
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+            id,
+
+            memory,
+
+            size: desc.size,
+            usage: desc.usage,
+            memory_location: desc.memory_location,
+            device_address,
+            tracked: desc.tracked,
+        })))
+    }
+
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.0.size
+    }
+
+    #[inline]
+    pub fn usage(&self) -> BufferUsageFlags {
+        self.0.usage
+    }
+
+    #[inline]
+    pub fn memory_location(&self) -> MemoryLocation {
+        self.0.memory_location
+    }
+
+    #[inline]
+    pub fn mapped_ptr(&self) -> Option<NonNull<u8>> {
+        if self.0.memory.is_empty() {
+            None
+        } else {
+            NonNull::new(UnsafeCell::raw_get(self.0.memory.as_ptr()))
+        }
+    }
+
+    #[inline]
+    pub fn device_address(&self) -> Option<u64> {
+        self.0.device_address
+    }
+
+    #[inline]
+    pub fn tracked(&self) -> bool {
+        self.0.tracked
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
@@ -0,0 +1,1240 @@
+use crate::{
+    null::{
+        NullComputePipeline, NullDevice, NullError, NullGraphicsPipeline, NullObjectId,
+        NullQueryPool,
+    },
+    validate_push_constants, Access, BindGroup, Buffer, BufferBarrier, BufferTextureCopy,
+    BufferUsageFlags, ClearValue, CommandListDesc, ComputePipeline, Extent3d, Format,
+    GlobalBarrier, GraphicsPipeline, IndexFormat, Origin3d, PhysicalDeviceFeatures, QueryPool,
+    QueryType, QueueTransfer, QueueType, RenderPassDesc, ScissorRect, ShaderStageFlags, Texture,
+    TextureAspectFlags, TextureBarrier, TextureCopy, TextureSubresourceRange, TextureUsageFlags,
+    TextureView, Viewport,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum State {
+    Initial,
+    Recording,
+    Executable,
+}
+
+/// Attachment layout of the active render pass, which bound graphics pipelines must match.
+struct RenderPass {
+    color_formats: Vec<Format>,
+    depth_stencil_format: Option<Format>,
+    sample_count: u32,
+    occlusion_query_pool: Option<NullQueryPool>,
+}
+
+/// A query between `begin_query` and `end_query`.
+struct ActiveQuery {
+    query_pool: NullQueryPool,
+    index: u32,
+    /// Queries begun inside a render pass have to end in it.
+    in_render_pass: bool,
+}
+
+/// Records command names instead of executing anything, validating each command on the way.
+pub struct NullCommandList {
+    device: NullDevice,
+    id: NullObjectId,
+
+    queue_type: QueueType,
+    state: State,
+    commands: Vec<&'static str>,
+    compute_pipeline: Option<NullComputePipeline>,
+    render_pass: Option<RenderPass>,
+    graphics_pipeline: Option<NullGraphicsPipeline>,
+    active_query: Option<ActiveQuery>,
+    /// Open debug groups, `true` for the ones pushed inside the render pass.
+    debug_groups: Vec<bool>,
+
+    error: Option<NullError>,
+}
+
+fn validate_buffer_range(
+    buffer: &Buffer,
+    offset: u64,
+    size: u64,
+    usage: BufferUsageFlags,
+) -> Result<(), String> {
+    if !buffer.usage().contains(usage) {
+        return Err(format!("Buffer is missing usage {usage:?}"));
+    }
+
+    if offset
+        .checked_add(size)
+        .is_none_or(|end| end > buffer.size())
+    {
+        return Err(format!(
+            "Range {offset}..{} exceeds the buffer size of {}",
+            offset.saturating_add(size),
+            buffer.size()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Validates `draw_count` indirect mesh task commands of 12 bytes each, `stride` bytes apart.
+fn validate_indirect_draws(
+    buffer: &Buffer,
+    offset: u64,
+    draw_count: u32,
+    stride: u32,
+) -> Result<(), String> {
+    if !offset.is_multiple_of(4) {
+        return Err("offset must be a multiple of 4".to_owned());
+    }
+
+    if draw_count == 0 {
+        return Ok(());
+    }
+
+    if draw_count > 1 && (stride < 12 || !stride.is_multiple_of(4)) {
+        return Err(format!(
+            "stride {stride} must be a multiple of 4 and at least 12"
+        ));
+    }
+
+    let size = (draw_count as u64 - 1) * stride as u64 + 12;
+    validate_buffer_range(buffer, offset, size, BufferUsageFlags::INDIRECT)
+}
+
+/// Validates that `view` can be rendered to with `usage` and matches the `extent` and
+/// `sample_count` of the other attachments.
+fn validate_attachment(
+    view: &TextureView,
+    usage: TextureUsageFlags,
+    extent: Extent3d,
+    sample_count: u32,
+    resolve_target: Option<&TextureView>,
+) -> Result<(), String> {
+    if !view.texture().usage().contains(usage) {
+        return Err(format!("Attachment is missing usage {usage:?}"));
+    }
+
+    if view.extent() != extent {
+        return Err(format!(
+            "Attachment extent {:?} differs from {extent:?}",
+            view.extent()
+        ));
+    }
+
+    if view.texture().sample_count() != sample_count {
+        return Err("Attachments must have the same sample count".to_owned());
+    }
+
+    let Some(resolve_target) = resolve_target else {
+        return Ok(());
+    };
+
+    if sample_count == 1 {
+        return Err("Resolve target given for a single sampled attachment".to_owned());
+    }
+
+    if resolve_target.texture().sample_count() != 1 {
+        return Err("Resolve target must be single sampled".to_owned());
+    }
+
+    if resolve_target.format() != view.format() || resolve_target.extent() != extent {
+        return Err("Resolve target must match the format and extent of the attachment".to_owned());
+    }
+
+    if !resolve_target.texture().usage().contains(usage) {
+        return Err(format!("Resolve target is missing usage {usage:?}"));
+    }
+
+    Ok(())
+}
+
+fn validate_render_pass(desc: &RenderPassDesc) -> Result<RenderPass, String> {
+    let Some(first_view) = desc.first_view() else {
+        return Err("requires at least one attachment".to_owned());
+    };
+
+    let extent = first_view.extent();
+    let sample_count = first_view.texture().sample_count();
+
+    if let Some(query_pool) = desc.occlusion_query_pool {
+        if query_pool.query_type() != QueryType::Occlusion {
+            return Err(format!(
+                "occlusion query pool has query type {:?}",
+                query_pool.query_type()
+            ));
+        }
+    }
+
+    for color_attachment in desc.color_attachments {
+        if color_attachment.view.format().is_depth_stencil() {
+            return Err(format!(
+                "{:?} is not a color format",
+                color_attachment.view.format()
+            ));
+        }
+
+        validate_attachment(
+            color_attachment.view,
+            TextureUsageFlags::COLOR_ATTACHMENT,
+            extent,
+            sample_count,
+            color_attachment.resolve_target,
+        )?;
+    }
+
+    if let Some(depth_stencil_attachment) = &desc.depth_stencil_attachment {
+        if !depth_stencil_attachment.view.format().is_depth_stencil() {
+            return Err(format!(
+                "{:?} is not a depth stencil format",
+                depth_stencil_attachment.view.format()
+            ));
+        }
+
+        validate_attachment(
+            depth_stencil_attachment.view,
+            TextureUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+            extent,
+            sample_count,
+            depth_stencil_attachment.resolve_target,
+        )?;
+    }
+
+    Ok(RenderPass {
+        color_formats: desc
+            .color_attachments
+            .iter()
+            .map(|color_attachment| color_attachment.view.format())
+            .collect(),
+        depth_stencil_format: desc
+            .depth_stencil_attachment
+            .as_ref()
+            .map(|depth_stencil_attachment| depth_stencil_attachment.view.format()),
+        sample_count,
+        occlusion_query_pool: desc
+            .occlusion_query_pool
+            .map(|query_pool| query_pool.as_null().clone()),
+    })
+}
+
+fn validate_subresource_range(
+    texture: &Texture,
+    range: &TextureSubresourceRange,
+) -> Result<(), String> {
+    if !TextureAspectFlags::from_format(texture.format()).contains(range.aspect) {
+        return Err(format!(
+            "Aspects {:?} are not present in {:?}",
+            range.aspect,
+            texture.format()
+        ));
+    }
+
+    let range = range.resolve(texture, texture.format());
+    let mip_level_end = range.base_mip_level + range.mip_level_count.unwrap_or(0);
+    let array_layer_end = range.base_array_layer + range.array_layer_count.unwrap_or(0);
+
+    if range.mip_level_count == Some(0)
+        || range.array_layer_count == Some(0)
+        || mip_level_end > texture.mip_levels()
+        || array_layer_end > texture.array_layers()
+    {
+        return Err(format!("Invalid subresource range {range:?}"));
+    }
+
+    Ok(())
+}
+
+/// Validates that `access` can happen on `queue_type`.
+fn validate_queue_access(queue_type: QueueType, access: Access) -> Result<(), String> {
+    let supported = match access {
+        Access::IndexBuffer
+        | Access::VertexBuffer
+        | Access::ColorAttachmentRead
+        | Access::ColorAttachmentWrite
+        | Access::DepthStencilAttachmentRead
+        | Access::DepthStencilAttachmentWrite => queue_type == QueueType::Graphics,
+        Access::IndirectCommand | Access::ShaderRead | Access::ShaderWrite => {
+            queue_type != QueueType::Transfer
+        }
+        Access::TransferSrc | Access::TransferDst | Access::HostRead | Access::HostWrite => true,
+    };
+
+    if !supported {
+        return Err(format!(
+            "{access:?} is not supported on {queue_type:?} queues"
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_buffer_access(buffer: &Buffer, access: Access) -> Result<(), String> {
+    let usage = match access {
+        Access::IndirectCommand => BufferUsageFlags::INDIRECT,
+        Access::IndexBuffer => BufferUsageFlags::INDEX,
+        Access::VertexBuffer => BufferUsageFlags::VERTEX,
+        Access::ShaderRead => BufferUsageFlags::UNIFORM | BufferUsageFlags::STORAGE,
+        Access::ShaderWrite => BufferUsageFlags::STORAGE,
+        Access::TransferSrc => BufferUsageFlags::TRANSFER_SRC,
+        Access::TransferDst => BufferUsageFlags::TRANSFER_DST,
+        Access::HostRead | Access::HostWrite => {
+            if !buffer.memory_location().is_host_visible() {
+                return Err(format!("{access:?} requires host visible memory"));
+            }
+            return Ok(());
+        }
+        _ => return Err(format!("{access:?} is not a buffer access")),
+    };
+
+    if !buffer.usage().intersects(usage) {
+        return Err(format!("{access:?} requires buffer usage {usage:?}"));
+    }
+
+    Ok(())
+}
+
+fn validate_texture_access(texture: &Texture, access: Access) -> Result<(), String> {
+    let usage = match access {
+        Access::ShaderRead => TextureUsageFlags::SAMPLED | TextureUsageFlags::STORAGE,
+        Access::ShaderWrite => TextureUsageFlags::STORAGE,
+        Access::ColorAttachmentRead | Access::ColorAttachmentWrite => {
+            TextureUsageFlags::COLOR_ATTACHMENT
+        }
+        Access::DepthStencilAttachmentRead | Access::DepthStencilAttachmentWrite => {
+            TextureUsageFlags::DEPTH_STENCIL_ATTACHMENT
+        }
+        Access::TransferSrc => TextureUsageFlags::TRANSFER_SRC,
+        Access::TransferDst => TextureUsageFlags::TRANSFER_DST,
+        _ => return Err(format!("{access:?} is not a texture access")),
+    };
+
+    if !texture.usage().intersects(usage) {
+        return Err(format!("{access:?} requires texture usage {usage:?}"));
+    }
+
+    Ok(())
+}
+
+/// Validates the `before` accesses against the queue releasing the resource and the `after`
+/// accesses against the queue acquiring it.
+fn validate_barrier_accesses(
+    queue_type: QueueType,
+    queue_transfer: Option<QueueTransfer>,
+    before: &[Access],
+    after: &[Access],
+    validate_access: impl Fn(Access) -> Result<(), String>,
+) -> Result<(), String> {
+    let (src_queue_type, dst_queue_type) = match queue_transfer {
+        Some(queue_transfer) => {
+            if queue_transfer.src == queue_transfer.dst {
+                return Err("Queue transfer between the same queue".to_owned());
+            }
+
+            if queue_type != queue_transfer.src && queue_type != queue_transfer.dst {
+                return Err(format!(
+                    "Queue transfer from {:?} to {:?} recorded for a {queue_type:?} queue",
+                    queue_transfer.src, queue_transfer.dst
+                ));
+            }
+
+            (queue_transfer.src, queue_transfer.dst)
+        }
+        None => (queue_type, queue_type),
+    };
+
+    for access in before {
+        validate_queue_access(src_queue_type, *access)?;
+        validate_access(*access)?;
+    }
+    for access in after {
+        validate_queue_access(dst_queue_type, *access)?;
+        validate_access(*access)?;
+    }
+
+    Ok(())
+}
+
+fn validate_texture_region(
+    texture: &Texture,
+    mip_level: u32,
+    array_layer: u32,
+    origin: Origin3d,
+    extent: Extent3d,
+    usage: TextureUsageFlags,
+) -> Result<(), String> {
+    if !texture.usage().contains(usage) {
+        return Err(format!("Texture is missing usage {usage:?}"));
+    }
+
+    if extent.width == 0 || extent.height == 0 || extent.depth == 0 {
+        return Err("Region extent must not be zero".to_owned());
+    }
+
+    if mip_level >= texture.mip_levels() {
+        return Err(format!(
+            "Mip level {mip_level} exceeds the {} mip levels of the texture",
+            texture.mip_levels()
+        ));
+    }
+
+    if array_layer >= texture.array_layers() {
+        return Err(format!(
+            "Array layer {array_layer} exceeds the {} array layers of the texture",
+            texture.array_layers()
+        ));
+    }
+
+    let mip_extent = texture.extent().mip_level(mip_level);
+
+    if origin.x + extent.width > mip_extent.width
+        || origin.y + extent.height > mip_extent.height
+        || origin.z + extent.depth > mip_extent.depth
+    {
+        return Err(format!(
+            "Region {origin:?} {extent:?} exceeds the mip level extent {mip_extent:?}"
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_buffer_texture_copy(
+    buffer: &Buffer,
+    buffer_usage: BufferUsageFlags,
+    texture: &Texture,
+    texture_usage: TextureUsageFlags,
+    region: &BufferTextureCopy,
+) -> Result<(), String> {
+    validate_texture_region(
+        texture,
+        region.mip_level,
+        region.array_layer,
+        region.origin,
+        region.extent,
+        texture_usage,
+    )?;
+
+    let format = texture.format();
+    let (block_width, block_height) = format.block_extent();
+
+    let row_size = region.extent.width.div_ceil(block_width) * format.block_size();
+    let bytes_per_row = if region.bytes_per_row == 0 {
+        row_size
+    } else {
+        region.bytes_per_row
+    };
+
+    if bytes_per_row < row_size {
+        return Err(format!(
+            "bytes_per_row {bytes_per_row} is smaller than a row of {row_size} bytes"
+        ));
+    }
+
+    let rows = region.extent.height.div_ceil(block_height);
+    let rows_per_image = if region.rows_per_image == 0 {
+        rows
+    } else {
+        region.rows_per_image
+    };
+
+    if rows_per_image < rows {
+        return Err(format!(
+            "rows_per_image {rows_per_image} is smaller than the {rows} rows of the region"
+        ));
+    }
+
+    let size =
+        u64::from(bytes_per_row) * u64::from(rows_per_image) * u64::from(region.extent.depth - 1)
+            + u64::from(bytes_per_row) * u64::from(rows - 1)
+            + u64::from(row_size);
+
+    validate_buffer_range(buffer, region.buffer_offset, size, buffer_usage)
+}
+
+impl NullCommandList {
+    pub fn new(device: &NullDevice, desc: &CommandListDesc) -> Result<Self, NullError> {
+        let id = device.register_object("CommandList");
+
+        Ok(Self {
+            device: device.clone(),
+            id,
+
+            queue_type: desc.queue_type,
+            state: State::Initial,
+            commands: Vec::new(),
+            compute_pipeline: None,
+            render_pass: None,
+            graphics_pipeline: None,
+            active_query: None,
+            debug_groups: Vec::new(),
+
+            error: None,
+        })
+    }
+
+    #[inline]
+    pub fn queue_type(&self) -> QueueType {
+        self.queue_type
+    }
+
+    /// Returns the names of the commands recorded since the last `begin`.
+    #[inline]
+    pub fn commands(&self) -> &[&'static str] {
+        &self.commands
+    }
+
+    #[inline]
+    pub(crate) fn is_executable(&self) -> bool {
+        self.state == State::Executable
+    }
+
+    pub fn begin(&mut self) -> Result<(), NullError> {
+        if self.state == State::Recording {
+            return Err(NullError::Validation(
+                "Command list is already recording".to_owned(),
+            ));
+        }
+
+        self.state = State::Recording;
+        self.commands.clear();
+        self.compute_pipeline = None;
+        self.render_pass = None;
+        self.graphics_pipeline = None;
+        self.active_query = None;
+        self.debug_groups.clear();
+        self.error = None;
+
+        Ok(())
+    }
+
+    pub fn end(&mut self) -> Result<(), NullError> {
+        if self.state != State::Recording {
+            return Err(NullError::Validation(
+                "Command list ended without begin".to_owned(),
+            ));
+        }
+
+        self.state = State::Executable;
+
+        if self.render_pass.take().is_some() {
+            self.error.get_or_insert(NullError::Validation(
+                "Command list ended inside a render pass".to_owned(),
+            ));
+        }
+        if self.active_query.take().is_some() {
+            self.error.get_or_insert(NullError::Validation(
+                "Command list ended with an active query".to_owned(),
+            ));
+        }
+        if !self.debug_groups.is_empty() {
+            self.error.get_or_insert(NullError::Validation(
+                "Command list ended with an open debug group".to_owned(),
+            ));
+            self.debug_groups.clear();
+        }
+
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Records `command` if `validate` passes, otherwise keeps the first error for [`Self::end`].
+    fn record(&mut self, command: &'static str, validate: impl FnOnce() -> Result<(), String>) {
+        let result = if self.state != State::Recording {
+            Err("recorded outside of begin and end".to_owned())
+        } else {
+            validate()
+        };
+
+        match result {
+            Ok(()) => self.commands.push(command),
+            Err(e) => {
+                self.error
+                    .get_or_insert(NullError::Validation(format!("{command}: {e}")));
+            }
+        }
+    }
+
+    fn validate_queue_type(&self, allowed: &[QueueType]) -> Result<(), String> {
+        if allowed.contains(&self.queue_type) {
+            Ok(())
+        } else {
+            Err(format!("not supported on {:?} queues", self.queue_type))
+        }
+    }
+
+    fn validate_outside_render_pass(&self) -> Result<(), String> {
+        if self.render_pass.is_some() {
+            return Err("not allowed inside a render pass".to_owned());
+        }
+
+        Ok(())
+    }
+
+    fn validate_inside_render_pass(&self) -> Result<(), String> {
+        if self.render_pass.is_none() {
+            return Err("requires an active render pass".to_owned());
+        }
+
+        Ok(())
+    }
+
+    pub fn copy_buffer(
+        &mut self,
+        src: &Buffer,
+        src_offset: u64,
+        dst: &Buffer,
+        dst_offset: u64,
+        size: u64,
+    ) {
+        let result = self.validate_outside_render_pass();
+
+        self.record("copy_buffer", || {
+            result?;
+
+            validate_buffer_range(src, src_offset, size, BufferUsageFlags::TRANSFER_SRC)?;
+            validate_buffer_range(dst, dst_offset, size, BufferUsageFlags::TRANSFER_DST)
+        });
+    }
+
+    pub fn copy_buffer_to_texture(
+        &mut self,
+        src: &Buffer,
+        dst: &Texture,
+        region: &BufferTextureCopy,
+    ) {
+        let result = self.validate_outside_render_pass();
+
+        self.record("copy_buffer_to_texture", || {
+            result?;
+
+            validate_buffer_texture_copy(
+                src,
+                BufferUsageFlags::TRANSFER_SRC,
+                dst,
+                TextureUsageFlags::TRANSFER_DST,
+                region,
+            )
+        });
+    }
+
+    pub fn copy_texture_to_buffer(
+        &mut self,
+        src: &Texture,
+        dst: &Buffer,
+        region: &BufferTextureCopy,
+    ) {
+        let result = self.validate_outside_render_pass();
+
+        self.record("copy_texture_to_buffer", || {
+            result?;
+
+            validate_buffer_texture_copy(
+                dst,
+                BufferUsageFlags::TRANSFER_DST,
+                src,
+                TextureUsageFlags::TRANSFER_SRC,
+                region,
+            )
+        });
+    }
+
+    pub fn copy_texture(&mut self, src: &Texture, dst: &Texture, region: &TextureCopy) {
+        let result = self.validate_outside_render_pass();
+
+        self.record("copy_texture", || {
+            result?;
+
+            if src.format().block_size() != dst.format().block_size() {
+                return Err(format!(
+                    "{:?} and {:?} are not copy compatible",
+                    src.format(),
+                    dst.format()
+                ));
+            }
+
+            validate_texture_region(
+                src,
+                region.src_mip_level,
+                region.src_array_layer,
+                region.src_origin,
+                region.extent,
+                TextureUsageFlags::TRANSFER_SRC,
+            )?;
+            validate_texture_region(
+                dst,
+                region.dst_mip_level,
+                region.dst_array_layer,
+                region.dst_origin,
+                region.extent,
+                TextureUsageFlags::TRANSFER_DST,
+            )
+        });
+    }
+
+    pub fn clear_buffer(&mut self, buffer: &Buffer, offset: u64, size: u64, _value: u8) {
+        let result = self.validate_outside_render_pass();
+
+        self.record("clear_buffer", || {
+            result?;
+
+            if !offset.is_multiple_of(4) || !size.is_multiple_of(4) {
+                return Err("offset and size must be multiples of four".to_owned());
+            }
+
+            validate_buffer_range(buffer, offset, size, BufferUsageFlags::TRANSFER_DST)
+        });
+    }
+
+    pub fn clear_texture(
+        &mut self,
+        texture: &Texture,
+        range: &TextureSubresourceRange,
+        value: ClearValue,
+    ) {
+        let queue_type = self.queue_type;
+        let result = self.validate_outside_render_pass();
+
+        self.record("clear_texture", || {
+            result?;
+
+            if queue_type == QueueType::Transfer {
+                return Err("not supported on Transfer queues".to_owned());
+            }
+
+            if !texture.usage().contains(TextureUsageFlags::TRANSFER_DST) {
+                return Err("Texture is missing usage TRANSFER_DST".to_owned());
+            }
+
+            let is_depth_stencil = texture.format().is_depth_stencil();
+            match value {
+                ClearValue::Color(_) if is_depth_stencil => {
+                    return Err("Color clear value used for a depth stencil texture".to_owned());
+                }
+                ClearValue::DepthStencil { .. } if !is_depth_stencil => {
+                    return Err("Depth stencil clear value used for a color texture".to_owned());
+                }
+                _ => {}
+            }
+
+            validate_subresource_range(texture, range)
+        });
+    }
+
+    pub fn barrier(
+        &mut self,
+        buffer_barriers: &[BufferBarrier],
+        texture_barriers: &[TextureBarrier],
+        global_barriers: &[GlobalBarrier],
+    ) {
+        let queue_type = self.queue_type;
+        let result = self.validate_outside_render_pass();
+
+        self.record("barrier", || {
+            result?;
+
+            for barrier in global_barriers {
+                validate_barrier_accesses(queue_type, None, barrier.before, barrier.after, |_| {
+                    Ok(())
+                })?;
+            }
+
+            for barrier in buffer_barriers {
+                if barrier.buffer.as_null().tracked() {
+                    return Err("Tracked buffers are synchronized automatically".to_owned());
+                }
+
+                validate_barrier_accesses(
+                    queue_type,
+                    barrier.queue_transfer,
+                    barrier.before,
+                    barrier.after,
+                    |access| validate_buffer_access(barrier.buffer, access),
+                )?;
+            }
+
+            for barrier in texture_barriers {
+                if barrier.texture.as_null().tracked() {
+                    return Err("Tracked textures are synchronized automatically".to_owned());
+                }
+
+                validate_subresource_range(barrier.texture, &barrier.range)?;
+                validate_barrier_accesses(
+                    queue_type,
+                    barrier.queue_transfer,
+                    barrier.before,
+                    barrier.after,
+                    |access| validate_texture_access(barrier.texture, access),
+                )?;
+            }
+
+            Ok(())
+        });
+    }
+
+    pub fn begin_render_pass(&mut self, desc: &RenderPassDesc) {
+        let result = self
+            .validate_queue_type(&[QueueType::Graphics])
+            .and_then(|()| self.validate_outside_render_pass())
+            .and_then(|()| {
+                self.device
+                    .validate_features(PhysicalDeviceFeatures::DYNAMIC_RENDERING, "Render passes")
+                    .map_err(|e| e.to_string())
+            })
+            .and_then(|()| validate_render_pass(desc));
+
+        let mut render_pass = None;
+        self.record("begin_render_pass", || {
+            render_pass = Some(result?);
+            Ok(())
+        });
+
+        // A failed begin still opens a pass, so the matching end is not reported as well.
+        self.render_pass = render_pass.or(self.render_pass.take()).or(Some(RenderPass {
+            color_formats: Vec::new(),
+            depth_stencil_format: None,
+            sample_count: 1,
+            occlusion_query_pool: None,
+        }));
+        self.graphics_pipeline = None;
+    }
+
+    pub fn end_render_pass(&mut self) {
+        let result = self.validate_inside_render_pass().and_then(|()| {
+            if self
+                .active_query
+                .as_ref()
+                .is_some_and(|active_query| active_query.in_render_pass)
+            {
+                return Err("a query begun inside the render pass is still active".to_owned());
+            }
+
+            if self.debug_groups.last() == Some(&true) {
+                return Err("a debug group pushed inside the render pass is still open".to_owned());
+            }
+
+            Ok(())
+        });
+
+        self.record("end_render_pass", || result);
+        self.render_pass = None;
+        while self.debug_groups.last() == Some(&true) {
+            self.debug_groups.pop();
+        }
+        self.graphics_pipeline = None;
+    }
+
+    pub fn set_viewport(&mut self, viewport: &Viewport) {
+        let result = self.validate_inside_render_pass();
+
+        self.record("set_viewport", || {
+            result?;
+
+            if !(0.0..=1.0).contains(&viewport.min_depth)
+                || !(0.0..=1.0).contains(&viewport.max_depth)
+            {
+                return Err("Viewport depth range must be within 0..=1".to_owned());
+            }
+
+            if viewport.width <= 0.0 || viewport.height <= 0.0 {
+                return Err("Viewport extent must be positive".to_owned());
+            }
+
+            Ok(())
+        });
+    }
+
+    pub fn set_scissor(&mut self, _scissor: &ScissorRect) {
+        let result = self.validate_inside_render_pass();
+
+        self.record("set_scissor", || result);
+    }
+
+    pub fn set_stencil_reference(&mut self, _reference: u32) {
+        let result = self.validate_inside_render_pass();
+
+        self.record("set_stencil_reference", || result);
+    }
+
+    pub fn set_blend_constants(&mut self, _constants: [f32; 4]) {
+        let result = self.validate_inside_render_pass();
+
+        self.record("set_blend_constants", || result);
+    }
+
+    pub fn bind_vertex_buffer(&mut self, _slot: u32, buffer: &Buffer, offset: u64) {
+        let result = self.validate_inside_render_pass();
+
+        self.record("bind_vertex_buffer", || {
+            result?;
+            validate_buffer_range(buffer, offset, 0, BufferUsageFlags::VERTEX)
+        });
+    }
+
+    pub fn bind_index_buffer(&mut self, buffer: &Buffer, offset: u64, format: IndexFormat) {
+        let result = self.validate_inside_render_pass();
+
+        self.record("bind_index_buffer", || {
+            result?;
+
+            if !offset.is_multiple_of(format.size()) {
+                return Err(format!("offset must be a multiple of {}", format.size()));
+            }
+
+            validate_buffer_range(buffer, offset, 0, BufferUsageFlags::INDEX)
+        });
+    }
+
+    /// Validates that a draw is recorded in a render pass with a pipeline of the matching kind.
+    fn validate_draw(&self, mesh: bool) -> Result<(), String> {
+        self.validate_inside_render_pass()?;
+
+        let Some(graphics_pipeline) = &self.graphics_pipeline else {
+            return Err("requires a bound graphics pipeline".to_owned());
+        };
+
+        match (mesh, graphics_pipeline.is_mesh()) {
+            (true, false) => Err("requires a mesh pipeline".to_owned()),
+            (false, true) => Err("requires a pipeline with vertex input".to_owned()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        _vertex_count: u32,
+        _instance_count: u32,
+        _first_vertex: u32,
+        _first_instance: u32,
+    ) {
+        let result = self.validate_draw(false);
+
+        self.record("draw", || result);
+    }
+
+    pub fn draw_indexed(
+        &mut self,
+        _index_count: u32,
+        _instance_count: u32,
+        _first_index: u32,
+        _vertex_offset: i32,
+        _first_instance: u32,
+    ) {
+        let result = self.validate_draw(false);
+
+        self.record("draw_indexed", || result);
+    }
+
+    fn validate_draw_mesh_tasks(&self, features: PhysicalDeviceFeatures) -> Result<(), String> {
+        self.device
+            .validate_features(features, "draw_mesh_tasks")
+            .map_err(|e| e.to_string())?;
+
+        self.validate_draw(true)
+    }
+
+    pub fn draw_mesh_tasks(&mut self, _x: u32, _y: u32, _z: u32) {
+        let result = self.validate_draw_mesh_tasks(PhysicalDeviceFeatures::MESH_SHADER);
+
+        self.record("draw_mesh_tasks", || result);
+    }
+
+    pub fn draw_mesh_tasks_indirect(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        let result = self.validate_draw_mesh_tasks(PhysicalDeviceFeatures::MESH_SHADER);
+
+        self.record("draw_mesh_tasks_indirect", || {
+            result?;
+
+            validate_indirect_draws(buffer, offset, draw_count, stride)
+        });
+    }
+
+    pub fn draw_mesh_tasks_indirect_count(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        count_buffer: &Buffer,
+        count_buffer_offset: u64,
+        max_draw_count: u32,
+        stride: u32,
+    ) {
+        let result = self.validate_draw_mesh_tasks(
+            PhysicalDeviceFeatures::MESH_SHADER | PhysicalDeviceFeatures::DRAW_INDIRECT_COUNT,
+        );
+
+        self.record("draw_mesh_tasks_indirect_count", || {
+            result?;
+
+            if !count_buffer_offset.is_multiple_of(4) {
+                return Err("count_buffer_offset must be a multiple of 4".to_owned());
+            }
+
+            validate_buffer_range(
+                count_buffer,
+                count_buffer_offset,
+                4,
+                BufferUsageFlags::INDIRECT,
+            )?;
+            validate_indirect_draws(buffer, offset, max_draw_count, stride)
+        });
+    }
+
+    pub fn bind_graphics_pipeline(&mut self, pipeline: &GraphicsPipeline) {
+        let pipeline = pipeline.as_null();
+        let result = self.validate_inside_render_pass().and_then(|()| {
+            let Some(render_pass) = &self.render_pass else {
+                return Ok(());
+            };
+
+            if pipeline.color_formats() != render_pass.color_formats
+                || pipeline.depth_stencil_format() != render_pass.depth_stencil_format
+                || pipeline.sample_count() != render_pass.sample_count
+            {
+                return Err("Pipeline attachments do not match the render pass".to_owned());
+            }
+
+            Ok(())
+        });
+
+        self.record("bind_graphics_pipeline", || result);
+        self.graphics_pipeline = Some(pipeline.clone());
+    }
+
+    pub fn bind_compute_pipeline(&mut self, pipeline: &ComputePipeline) {
+        let result = self.validate_queue_type(&[QueueType::Graphics, QueueType::Compute]);
+
+        self.record("bind_compute_pipeline", || result);
+        self.compute_pipeline = Some(pipeline.as_null().clone());
+    }
+
+    pub fn bind_bind_group(&mut self, set: u32, bind_group: &BindGroup) {
+        let bind_group = bind_group.as_null();
+
+        let layout = match &self.render_pass {
+            Some(_) => self.graphics_pipeline.as_ref().map(|p| p.layout()),
+            None => self.compute_pipeline.as_ref().map(|p| p.layout()),
+        };
+        let result = match layout {
+            None => Err("requires a bound pipeline".to_owned()),
+            Some(layout) if layout.bind_group_layout(set) != Some(bind_group.layout()) => {
+                Err(format!(
+                    "bind group layout does not match set {set} of the pipeline layout"
+                ))
+            }
+            Some(_) if !bind_group.is_allocated() => {
+                Err("bind group used after its pool was reset".to_owned())
+            }
+            Some(_) => Ok(()),
+        };
+
+        self.record("bind_bind_group", || result);
+    }
+
+    pub fn push_constants(&mut self, stages: ShaderStageFlags, offset: u32, data: &[u8]) {
+        let layout = match &self.render_pass {
+            Some(_) => self.graphics_pipeline.as_ref().map(|p| p.layout()),
+            None => self.compute_pipeline.as_ref().map(|p| p.layout()),
+        };
+        let result = match layout {
+            None => Err("requires a bound pipeline".to_owned()),
+            Some(layout) => {
+                validate_push_constants(
+                    layout.push_constant_ranges(),
+                    stages,
+                    offset,
+                    data.len() as u32,
+                )
+            }
+        };
+
+        self.record("push_constants", || result);
+    }
+
+    fn validate_dispatch(&self) -> Result<(), String> {
+        self.validate_queue_type(&[QueueType::Graphics, QueueType::Compute])?;
+        self.validate_outside_render_pass()?;
+
+        if self.compute_pipeline.is_none() {
+            return Err("requires a bound compute pipeline".to_owned());
+        }
+
+        Ok(())
+    }
+
+    pub fn dispatch(&mut self, _x: u32, _y: u32, _z: u32) {
+        let result = self.validate_dispatch();
+
+        self.record("dispatch", || result);
+    }
+
+    pub fn dispatch_indirect(&mut self, buffer: &Buffer, offset: u64) {
+        let result = self.validate_dispatch();
+
+        self.record("dispatch_indirect", || {
+            result?;
+
+            if !offset.is_multiple_of(4) {
+                return Err("offset must be a multiple of 4".to_owned());
+            }
+
+            validate_buffer_range(buffer, offset, 12, BufferUsageFlags::INDIRECT)
+        });
+    }
+
+    pub fn reset_queries(&mut self, query_pool: &QueryPool, first: u32, count: u32) {
+        let result = self.validate_outside_render_pass();
+
+        self.record("reset_queries", || {
+            result?;
+
+            query_pool.as_null().validate_range(first, count)
+        });
+    }
+
+    pub fn write_timestamp(&mut self, query_pool: &QueryPool, index: u32) {
+        self.record("write_timestamp", || {
+            let query_pool = query_pool.as_null();
+
+            if query_pool.query_type() != QueryType::Timestamp {
+                return Err(format!(
+                    "query pool has query type {:?}",
+                    query_pool.query_type()
+                ));
+            }
+
+            query_pool.validate_range(index, 1)
+        });
+    }
+
+    pub fn begin_query(&mut self, query_pool: &QueryPool, index: u32) {
+        let query_pool = query_pool.as_null();
+
+        let result = query_pool.validate_range(index, 1).and_then(|()| {
+            if self.active_query.is_some() {
+                return Err("another query is still active".to_owned());
+            }
+
+            match query_pool.query_type() {
+                QueryType::Timestamp => {
+                    Err("timestamps are written with write_timestamp".to_owned())
+                }
+                QueryType::Occlusion => {
+                    self.validate_inside_render_pass()?;
+
+                    let render_pass = self.render_pass.as_ref().unwrap();
+                    if render_pass.occlusion_query_pool.as_ref() != Some(query_pool) {
+                        return Err(
+                            "query pool is not the occlusion query pool of the render pass"
+                                .to_owned(),
+                        );
+                    }
+
+                    Ok(())
+                }
+                QueryType::PipelineStatistics(_) => {
+                    self.validate_queue_type(&[QueueType::Graphics, QueueType::Compute])
+                }
+            }
+        });
+
+        let mut begun = false;
+        self.record("begin_query", || {
+            result?;
+            begun = true;
+            Ok(())
+        });
+
+        if begun {
+            self.active_query = Some(ActiveQuery {
+                query_pool: query_pool.clone(),
+                index,
+                in_render_pass: self.render_pass.is_some(),
+            });
+        }
+    }
+
+    pub fn end_query(&mut self, query_pool: &QueryPool, index: u32) {
+        let query_pool = query_pool.as_null();
+
+        let result = match self.active_query.take() {
+            Some(active_query)
+                if active_query.query_pool == *query_pool && active_query.index == index =>
+            {
+                Ok(())
+            }
+            active_query => {
+                self.active_query = active_query;
+                Err("query was not begun".to_owned())
+            }
+        };
+
+        self.record("end_query", || result);
+    }
+
+    pub fn resolve_queries(
+        &mut self,
+        query_pool: &QueryPool,
+        first: u32,
+        count: u32,
+        dst: &Buffer,
+        dst_offset: u64,
+    ) {
+        let result = self.validate_outside_render_pass();
+
+        self.record("resolve_queries", || {
+            result?;
+
+            let query_pool = query_pool.as_null();
+            query_pool.validate_range(first, count)?;
+
+            if !dst_offset.is_multiple_of(8) {
+                return Err("dst_offset must be a multiple of 8".to_owned());
+            }
+
+            let size = u64::from(count) * query_pool.query_type().result_size();
+            validate_buffer_range(dst, dst_offset, size, BufferUsageFlags::TRANSFER_DST)
+        });
+    }
+
+    pub fn push_debug_group(&mut self, _label: &str) {
+        self.record("push_debug_group", || Ok(()));
+        self.debug_groups.push(self.render_pass.is_some());
+    }
+
+    pub fn pop_debug_group(&mut self) {
+        let result = match self.debug_groups.last() {
+            None => Err("no debug group is open".to_owned()),
+            Some(false) if self.render_pass.is_some() => {
+                Err("the debug group was pushed outside the render pass".to_owned())
+            }
+            Some(_) => Ok(()),
+        };
+
+        let mut popped = false;
+        self.record("pop_debug_group", || {
+            result?;
+            popped = true;
+            Ok(())
+        });
+
+        if popped {
+            self.debug_groups.pop();
+        }
+    }
+
+    pub fn insert_debug_marker(&mut self, _label: &str) {
+        self.record("insert_debug_marker", || Ok(()));
+    }
+}
+
+impl Drop for NullCommandList {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use crate::{
+    null::{NullDevice, NullError, NullObjectId, NullPipelineLayout},
+    ComputePipelineDesc,
+};
+
+struct Inner {
+    device: NullDevice,
+    id: NullObjectId,
+
+    layout: NullPipelineLayout,
+    workgroup_size: [u32; 3],
+}
+
+#[derive(Clone)]
+pub struct NullComputePipeline(Arc<Inner>);
+
+impl NullComputePipeline {
+    pub fn new(
+        device: &NullDevice,
+        desc: &ComputePipelineDesc,
+        workgroup_size: [u32; 3],
+    ) -> Result<Self, NullError> {
+        for (i, constant) in desc.specialization_constants.iter().enumerate() {
+            if desc.specialization_constants[..i]
+                .iter()
+                .any(|c| c.id == constant.id)
+            {
+                return Err(NullError::Validation(format!(
+                    "Specialization constant {} is set twice",
+                    constant.id
+                )));
+            }
+        }
+
+        let layout = desc.layout.as_null().clone();
+
+        layout.validate_shader_bindings(desc.shader_module.reflection())?;
+
+        let id = device.register_object("ComputePipeline");
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+            id,
+
+            layout,
+            workgroup_size,
+        })))
+    }
+
+    #[inline]
+    pub fn layout(&self) -> &NullPipelineLayout {
+        &self.0.layout
+    }
+
+    #[inline]
+    pub fn workgroup_size(&self) -> [u32; 3] {
+        self.0.workgroup_size
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::{
+    null::{
+        NullBindlessHeap, NullError, NullFence, NullInstance, NullPhysicalDevice, NullQueue,
+        NullSemaphore, SamplerCache,
+    },
+    CalibratedTimestamp, DeviceDesc, PhysicalDevice, PhysicalDeviceFeatures, QueueType,
+};
+
+/// Identifies an object created by a [`NullDevice`] for lifetime tracking.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NullObjectId(u64);
+
+/// A submission whose semaphore waits are not satisfied yet.
+pub(crate) struct NullSubmission {
+    pub(crate) waits: Vec<(NullSemaphore, u64)>,
+    pub(crate) signals: Vec<(NullSemaphore, u64)>,
+    pub(crate) fence: Option<NullFence>,
+}
+
+impl NullSubmission {
+    fn is_ready(&self) -> bool {
+        self.waits
+            .iter()
+            .all(|(semaphore, value)| semaphore.is_reached(*value))
+    }
+
+    fn complete(self) {
+        for (semaphore, value) in self.signals {
+            semaphore.signal_from_queue(value);
+        }
+
+        if let Some(fence) = self.fence {
+            fence.signal_from_queue();
+        }
+    }
+}
+
+fn queue_slot(queue_type: QueueType) -> usize {
+    match queue_type {
+        QueueType::Graphics => 0,
+        QueueType::Compute => 1,
+        QueueType::Transfer => 2,
+    }
+}
+
+struct Inner {
+    physical_device: NullPhysicalDevice,
+    enabled_features: PhysicalDeviceFeatures,
+
+    next_object_id: AtomicU64,
+    next_address: AtomicU64,
+    live_objects: Mutex<HashMap<NullObjectId, &'static str>>,
+
+    /// Pending submissions with their serial, serials increase in submission order.
+    pending_submissions: Mutex<[VecDeque<(u64, NullSubmission)>; 3]>,
+    submission_serial: AtomicU64,
+    /// Value of the last upload.
+    upload_value: AtomicU64,
+
+    bindless_heap: Option<NullBindlessHeap>,
+    sampler_cache: SamplerCache,
+}
+
+#[derive(Clone)]
+pub struct NullDevice(Arc<Inner>);
+
+impl NullDevice {
+    pub fn new(_instance: &NullInstance, desc: &DeviceDesc) -> Result<Self, NullError> {
+        let physical_device = match &desc.physical_device {
+            PhysicalDevice::Null(physical_device) => physical_device,
+            #[allow(unreachable_patterns)]
+            _ => return Err(NullError::Custom("Invalid physical device".to_owned())),
+        };
+
+        Ok(Self(Arc::new(Inner {
+            physical_device: physical_device.clone(),
+            enabled_features: desc.enabled_features(),
+
+            next_object_id: AtomicU64::new(1),
+            next_address: AtomicU64::new(0x1_0000),
+            live_objects: Mutex::new(HashMap::new()),
+
+            pending_submissions: Mutex::new(Default::default()),
+            submission_serial: AtomicU64::new(0),
+            upload_value: AtomicU64::new(0),
+
+            bindless_heap: desc
+                .enabled_features()
+                .contains(PhysicalDeviceFeatures::DESCRIPTOR_INDEXING)
+                .then(NullBindlessHeap::new),
+            sampler_cache: Default::default(),
+        })))
+    }
+
+    #[inline]
+    pub fn physical_device(&self) -> &NullPhysicalDevice {
+        &self.0.physical_device
+    }
+
+    #[inline]
+    pub fn get_enabled_features(&self) -> PhysicalDeviceFeatures {
+        self.0.enabled_features
+    }
+
+    #[inline]
+    pub fn get_timestamp_period(&self) -> f32 {
+        self.0.physical_device.get_timestamp_period()
+    }
+
+    /// The null backend writes no timestamps, so there is nothing to calibrate.
+    #[inline]
+    pub fn get_calibrated_timestamp(&self) -> Result<Option<CalibratedTimestamp>, NullError> {
+        Ok(None)
+    }
+
+    #[inline]
+    pub fn get_queue(&self, queue_type: QueueType) -> NullQueue {
+        NullQueue::new(self, queue_type)
+    }
+
+    /// Returns an error unless every feature in `features` was enabled at device creation.
+    pub(crate) fn validate_features(
+        &self,
+        features: PhysicalDeviceFeatures,
+        what: &str,
+    ) -> Result<(), NullError> {
+        let missing = features.difference(self.0.enabled_features);
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(NullError::Validation(format!(
+                "{what} requires features that are not enabled: {missing:?}"
+            )))
+        }
+    }
+
+    /// Registers a new object of the given kind and returns its id.
+    pub(crate) fn register_object(&self, kind: &'static str) -> NullObjectId {
+        let id = NullObjectId(self.0.next_object_id.fetch_add(1, Ordering::Relaxed));
+
+        self.0.live_objects.lock().unwrap().insert(id, kind);
+
+        id
+    }
+
+    /// Unregisters an object, panicking if it was already destroyed.
+    pub(crate) fn unregister_object(&self, id: NullObjectId) {
+        let kind = self.0.live_objects.lock().unwrap().remove(&id);
+
+        assert!(kind.is_some(), "Object {id:?} destroyed twice");
+    }
+
+    /// Hands out a fake, 256 byte aligned GPU address range of `size` bytes.
+    pub(crate) fn allocate_address_range(&self, size: u64) -> u64 {
+        let size = (size + 255) & !255;
+
+        self.0.next_address.fetch_add(size, Ordering::Relaxed)
+    }
+
+    /// Queues `submission` behind earlier submissions to the same queue family and completes
+    /// everything that is no longer blocked.
+    pub(crate) fn enqueue_submission(&self, family: QueueType, submission: NullSubmission) {
+        let mut pending_submissions = self.0.pending_submissions.lock().unwrap();
+        let serial = self.0.submission_serial.fetch_add(1, Ordering::Relaxed) + 1;
+
+        pending_submissions[queue_slot(family)].push_back((serial, submission));
+        drop(pending_submissions);
+
+        self.process_submissions();
+    }
+
+    /// Completes pending submissions in order until every queue is empty or blocked.
+    pub(crate) fn process_submissions(&self) {
+        let mut pending_submissions = self.0.pending_submissions.lock().unwrap();
+
+        loop {
+            let mut progressed = false;
+
+            for queue in pending_submissions.iter_mut() {
+                while queue
+                    .front()
+                    .is_some_and(|(_, submission)| submission.is_ready())
+                {
+                    queue.pop_front().unwrap().1.complete();
+                    progressed = true;
+                }
+            }
+
+            if !progressed {
+                break;
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn has_pending_submissions(&self, family: QueueType) -> bool {
+        !self.0.pending_submissions.lock().unwrap()[queue_slot(family)].is_empty()
+    }
+
+    /// Returns the serial of the last submission to any queue.
+    #[inline]
+    pub(crate) fn submission_serial(&self) -> u64 {
+        self.0.submission_serial.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` once every submission up to `serial` has completed.
+    pub(crate) fn is_submission_complete(&self, serial: u64) -> bool {
+        self.0
+            .pending_submissions
+            .lock()
+            .unwrap()
+            .iter()
+            .flatten()
+            .all(|(pending_serial, _)| *pending_serial > serial)
+    }
+
+    /// Returns the value of the last upload.
+    #[inline]
+    pub(crate) fn upload_value(&self) -> u64 {
+        self.0.upload_value.load(Ordering::Relaxed)
+    }
+
+    /// Advances the upload timeline and returns the value of the new upload.
+    #[inline]
+    pub(crate) fn next_upload_value(&self) -> u64 {
+        self.0.upload_value.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    #[inline]
+    pub(crate) fn bindless_heap(&self) -> Option<&NullBindlessHeap> {
+        self.0.bindless_heap.as_ref()
+    }
+
+    /// Returns the samplers shared by every identical sampler descriptor.
+    #[inline]
+    pub(crate) fn sampler_cache(&self) -> &SamplerCache {
+        &self.0.sampler_cache
+    }
+
+    /// Returns the number of objects created from this device that are still alive.
+    #[inline]
+    pub fn live_object_count(&self) -> usize {
+        self.0.live_objects.lock().unwrap().len()
+    }
+
+    /// Returns the number of live objects of the given kind, e.g. `"Buffer"`.
+    pub fn live_object_count_of(&self, kind: &str) -> usize {
+        self.0
+            .live_objects
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|k| **k == kind)
+            .count()
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
+};
+
+use crate::{
+    null::{NullDevice, NullError, NullObjectId},
+    FenceDesc,
+};
+
+struct Inner {
+    device: NullDevice,
+    id: NullObjectId,
+
+    signaled: Mutex<bool>,
+    condvar: Condvar,
+}
+
+#[derive(Clone)]
+pub struct NullFence(Arc<Inner>);
+
+impl NullFence {
+    pub fn new(device: &NullDevice, desc: &FenceDesc) -> Result<Self, NullError> {
+        let id = device.register_object("Fence");
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+            id,
+
+            signaled: Mutex::new(desc.signaled),
+            condvar: Condvar::new(),
+        })))
+    }
+
+    /// Signals the fence on behalf of a completed submission.
+    pub(crate) fn signal_from_queue(&self) {
+        *self.0.signaled.lock().unwrap() = true;
+
+        self.0.condvar.notify_all();
+    }
+
+    pub fn is_signaled(&self) -> Result<bool, NullError> {
+        Ok(*self.0.signaled.lock().unwrap())
+    }
+
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<bool, NullError> {
+        let signaled = self.0.signaled.lock().unwrap();
+
+        let signaled = match timeout {
+            Some(timeout) => {
+                let (signaled, _) = self
+                    .0
+                    .condvar
+                    .wait_timeout_while(signaled, timeout, |signaled| !*signaled)
+                    .unwrap();
+                *signaled
+            }
+            None => {
+                let _signaled = self
+                    .0
+                    .condvar
+                    .wait_while(signaled, |signaled| !*signaled)
+                    .unwrap();
+                true
+            }
+        };
+
+        Ok(signaled)
+    }
+
+    pub fn reset(&self) -> Result<(), NullError> {
+        *self.0.signaled.lock().unwrap() = false;
+
+        Ok(())
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
@@ -0,0 +1,237 @@
+use std::sync::Arc;
+
+use crate::{
+    null::{NullDevice, NullError, NullObjectId, NullPipelineLayout},
+    Format, GraphicsPipelineDesc, MeshPipelineDesc, PhysicalDeviceFeatures, RasterStateDesc,
+    ShaderStage,
+};
+
+/// Matches the minimum `maxColorAttachments` guaranteed by Vulkan and the limit of Metal.
+const MAX_COLOR_ATTACHMENTS: usize = 8;
+
+struct Inner {
+    device: NullDevice,
+    id: NullObjectId,
+
+    layout: NullPipelineLayout,
+    is_mesh: bool,
+    color_formats: Vec<Format>,
+    depth_stencil_format: Option<Format>,
+    sample_count: u32,
+}
+
+#[derive(Clone)]
+pub struct NullGraphicsPipeline(Arc<Inner>);
+
+fn validate_shader_stage(
+    layout: &NullPipelineLayout,
+    shader_stage: &ShaderStage,
+) -> Result<(), NullError> {
+    let constants = shader_stage.specialization_constants;
+
+    for (i, constant) in constants.iter().enumerate() {
+        if constants[..i].iter().any(|c| c.id == constant.id) {
+            return Err(NullError::Validation(format!(
+                "Specialization constant {} of {} is set twice",
+                constant.id, shader_stage.entry_point
+            )));
+        }
+    }
+
+    layout.validate_shader_bindings(shader_stage.shader_module.reflection())
+}
+
+fn validate_raster_state(desc: &RasterStateDesc) -> Result<(), NullError> {
+    if desc.color_attachments.len() > MAX_COLOR_ATTACHMENTS {
+        return Err(NullError::Validation(format!(
+            "{} color attachments exceed the limit of {MAX_COLOR_ATTACHMENTS}",
+            desc.color_attachments.len()
+        )));
+    }
+
+    if let Some(color_attachment) = desc
+        .color_attachments
+        .iter()
+        .find(|color_attachment| color_attachment.format.is_depth_stencil())
+    {
+        return Err(NullError::Validation(format!(
+            "{:?} is not a color format",
+            color_attachment.format
+        )));
+    }
+
+    let depth_stencil_format = desc.depth_stencil_format;
+    if let Some(format) = depth_stencil_format.filter(|format| !format.is_depth_stencil()) {
+        return Err(NullError::Validation(format!(
+            "{format:?} is not a depth stencil format"
+        )));
+    }
+
+    let depth_stencil = &desc.depth_stencil;
+    if (depth_stencil.depth_test || depth_stencil.depth_write)
+        && !depth_stencil_format.is_some_and(|format| format.is_depth())
+    {
+        return Err(NullError::Validation(
+            "Depth testing requires a depth format".to_owned(),
+        ));
+    }
+    if depth_stencil.stencil_test && !depth_stencil_format.is_some_and(|format| format.is_stencil())
+    {
+        return Err(NullError::Validation(
+            "Stencil testing requires a stencil format".to_owned(),
+        ));
+    }
+
+    if let Some(depth_bias) = desc.rasterizer.depth_bias {
+        if !depth_bias.slope_scale.is_finite() || !depth_bias.clamp.is_finite() {
+            return Err(NullError::Validation(format!(
+                "Depth bias {depth_bias:?} is not finite"
+            )));
+        }
+    }
+
+    let sample_count = desc.multisample.sample_count;
+    if !sample_count.is_power_of_two() || sample_count > 64 {
+        return Err(NullError::Validation(format!(
+            "Invalid sample count {sample_count}"
+        )));
+    }
+
+    Ok(())
+}
+
+fn validate_vertex_input(desc: &GraphicsPipelineDesc) -> Result<(), NullError> {
+    let mut locations = Vec::new();
+
+    for (slot, vertex_buffer) in desc.vertex_buffers.iter().enumerate() {
+        for attribute in vertex_buffer.attributes {
+            if locations.contains(&attribute.location) {
+                return Err(NullError::Validation(format!(
+                    "Vertex attribute location {} is declared twice",
+                    attribute.location
+                )));
+            }
+            locations.push(attribute.location);
+
+            if vertex_buffer.stride != 0
+                && attribute.offset + attribute.format.size() > vertex_buffer.stride
+            {
+                return Err(NullError::Validation(format!(
+                    "Vertex attribute at location {} exceeds the stride of slot {slot}",
+                    attribute.location
+                )));
+            }
+        }
+    }
+
+    let vertex_shader = &desc.vertex_shader;
+    let Some(entry_point) = vertex_shader
+        .shader_module
+        .reflection()
+        .entry_point(vertex_shader.entry_point)
+    else {
+        return Ok(());
+    };
+
+    if let Some(vertex_input) = entry_point
+        .vertex_inputs
+        .iter()
+        .find(|vertex_input| !locations.contains(&vertex_input.location))
+    {
+        return Err(NullError::Validation(format!(
+            "Vertex input location {} is not provided by any vertex buffer",
+            vertex_input.location
+        )));
+    }
+
+    Ok(())
+}
+
+impl NullGraphicsPipeline {
+    pub fn new(device: &NullDevice, desc: &GraphicsPipelineDesc) -> Result<Self, NullError> {
+        validate_raster_state(&desc.raster_state())?;
+        validate_vertex_input(desc)?;
+
+        let layout = desc.layout.as_null().clone();
+
+        validate_shader_stage(&layout, &desc.vertex_shader)?;
+        if let Some(fragment_shader) = &desc.fragment_shader {
+            validate_shader_stage(&layout, fragment_shader)?;
+        }
+
+        Ok(Self::create(device, layout, false, &desc.raster_state()))
+    }
+
+    pub fn new_mesh(device: &NullDevice, desc: &MeshPipelineDesc) -> Result<Self, NullError> {
+        device.validate_features(PhysicalDeviceFeatures::MESH_SHADER, "Mesh pipelines")?;
+        validate_raster_state(&desc.raster_state())?;
+
+        let layout = desc.layout.as_null().clone();
+
+        if let Some(task_shader) = &desc.task_shader {
+            validate_shader_stage(&layout, task_shader)?;
+        }
+        validate_shader_stage(&layout, &desc.mesh_shader)?;
+        if let Some(fragment_shader) = &desc.fragment_shader {
+            validate_shader_stage(&layout, fragment_shader)?;
+        }
+
+        Ok(Self::create(device, layout, true, &desc.raster_state()))
+    }
+
+    fn create(
+        device: &NullDevice,
+        layout: NullPipelineLayout,
+        is_mesh: bool,
+        raster_state: &RasterStateDesc,
+    ) -> Self {
+        let id = device.register_object("GraphicsPipeline");
+
+        Self(Arc::new(Inner {
+            device: device.clone(),
+            id,
+
+            layout,
+            is_mesh,
+            color_formats: raster_state
+                .color_attachments
+                .iter()
+                .map(|color_attachment| color_attachment.format)
+                .collect(),
+            depth_stencil_format: raster_state.depth_stencil_format,
+            sample_count: raster_state.multisample.sample_count,
+        }))
+    }
+
+    #[inline]
+    pub fn layout(&self) -> &NullPipelineLayout {
+        &self.0.layout
+    }
+
+    /// Returns `true` for pipelines created from a [`MeshPipelineDesc`].
+    #[inline]
+    pub fn is_mesh(&self) -> bool {
+        self.0.is_mesh
+    }
+
+    #[inline]
+    pub fn color_formats(&self) -> &[Format] {
+        &self.0.color_formats
+    }
+
+    #[inline]
+    pub fn depth_stencil_format(&self) -> Option<Format> {
+        self.0.depth_stencil_format
+    }
+
+    #[inline]
+    pub fn sample_count(&self) -> u32 {
+        self.0.sample_count
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
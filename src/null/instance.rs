@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use crate::{
+    null::{NullError, NullPhysicalDevice},
+    InstanceDesc, PhysicalDevice,
+};
+
+struct Inner {
+    physical_devices: Vec<PhysicalDevice>,
+}
+
+#[derive(Clone)]
+pub struct NullInstance(Arc<Inner>);
+
+impl NullInstance {
+    pub fn new(desc: &InstanceDesc) -> Result<Self, NullError> {
+        let physical_devices = vec![PhysicalDevice::Null(NullPhysicalDevice::new(
+            &desc.null_physical_device,
+        )?)];
+
+        Ok(Self(Arc::new(Inner { physical_devices })))
+    }
+
+    #[inline]
+    pub fn get_physical_devices(&self) -> &[PhysicalDevice] {
+        &self.0.physical_devices
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use crate::{null::NullError, PhysicalDeviceFeatures};
+
+/// Describes the fake adapter reported by the null backend.
+#[derive(Clone, Debug)]
+pub struct NullPhysicalDeviceDesc {
+    pub name: String,
+    pub supported_features: PhysicalDeviceFeatures,
+    /// Whether the compute queue is reported as a dedicated queue or an alias of the graphics queue.
+    pub dedicated_compute_queue: bool,
+    /// Whether the transfer queue is reported as a dedicated queue or an alias of the graphics queue.
+    pub dedicated_transfer_queue: bool,
+    /// Largest end offset of the push constant ranges of a pipeline layout.
+    pub max_push_constants_size: u32,
+    /// Number of distinct samplers a device may create.
+    pub max_sampler_count: u32,
+    /// Nanoseconds per timestamp tick.
+    pub timestamp_period: f32,
+}
+
+impl Default for NullPhysicalDeviceDesc {
+    fn default() -> Self {
+        Self {
+            name: "Null Device".to_owned(),
+            supported_features: PhysicalDeviceFeatures::all(),
+            dedicated_compute_queue: true,
+            dedicated_transfer_queue: true,
+            max_push_constants_size: 128,
+            max_sampler_count: 4000,
+            timestamp_period: 1.0,
+        }
+    }
+}
+
+struct Inner {
+    desc: NullPhysicalDeviceDesc,
+}
+
+#[derive(Clone)]
+pub struct NullPhysicalDevice(Arc<Inner>);
+
+impl NullPhysicalDevice {
+    pub fn new(desc: &NullPhysicalDeviceDesc) -> Result<Self, NullError> {
+        if desc.name.is_empty() {
+            return Err(NullError::Validation(
+                "Physical device name must not be empty".to_owned(),
+            ));
+        }
+        if !desc.timestamp_period.is_finite() || desc.timestamp_period <= 0.0 {
+            return Err(NullError::Validation(
+                "Timestamp period must be positive".to_owned(),
+            ));
+        }
+
+        Ok(Self(Arc::new(Inner { desc: desc.clone() })))
+    }
+
+    #[inline]
+    pub fn desc(&self) -> &NullPhysicalDeviceDesc {
+        &self.0.desc
+    }
+
+    #[inline]
+    pub fn get_name(&self) -> &str {
+        &self.0.desc.name
+    }
+
+    #[inline]
+    pub fn get_supported_features(&self) -> PhysicalDeviceFeatures {
+        self.0.desc.supported_features
+    }
+
+    #[inline]
+    pub fn get_timestamp_period(&self) -> f32 {
+        self.0.desc.timestamp_period
+    }
+}
@@ -0,0 +1,151 @@
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use crate::{
+    bindless_descriptor_type,
+    null::{NullBindGroupLayout, NullDevice, NullError, NullObjectId},
+    validate_push_constant_ranges, BindGroupLayoutDesc, DescriptorBinding, PipelineLayoutDesc,
+    PushConstantRange, ShaderReflection,
+};
+
+struct Inner {
+    device: NullDevice,
+    id: NullObjectId,
+
+    descriptor_bindings: Vec<DescriptorBinding>,
+    /// Layout of each set, `None` for the bindless set.
+    bind_group_layouts: Vec<Option<NullBindGroupLayout>>,
+    push_constant_ranges: Vec<PushConstantRange>,
+}
+
+#[derive(Clone)]
+pub struct NullPipelineLayout(Arc<Inner>);
+
+impl PartialEq for NullPipelineLayout {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for NullPipelineLayout {}
+
+impl Hash for NullPipelineLayout {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+impl NullPipelineLayout {
+    pub fn new(device: &NullDevice, desc: &PipelineLayoutDesc) -> Result<Self, NullError> {
+        for (i, binding) in desc.descriptor_bindings.iter().enumerate() {
+            if Some(binding.set) == desc.bindless_set {
+                if bindless_descriptor_type(binding.binding) != Some(binding.descriptor_type) {
+                    return Err(NullError::Validation(format!(
+                        "Binding {} of the bindless set {} does not match the bindless heap",
+                        binding.binding, binding.set
+                    )));
+                }
+            } else if binding.count == 0 {
+                return Err(NullError::Validation(format!(
+                    "Runtime sized descriptor array at set {} binding {} is not supported",
+                    binding.set, binding.binding
+                )));
+            }
+
+            if desc.descriptor_bindings[..i]
+                .iter()
+                .any(|b| b.set == binding.set && b.binding == binding.binding)
+            {
+                return Err(NullError::Validation(format!(
+                    "Set {} binding {} is declared twice",
+                    binding.set, binding.binding
+                )));
+            }
+        }
+
+        validate_push_constant_ranges(
+            desc.push_constant_ranges,
+            device.physical_device().desc().max_push_constants_size,
+        )
+        .map_err(NullError::Validation)?;
+
+        let set_count = desc
+            .descriptor_bindings
+            .iter()
+            .map(|binding| binding.set)
+            .max()
+            .max(desc.bindless_set)
+            .map_or(0, |set| set + 1);
+
+        let bind_group_layouts = (0..set_count)
+            .map(|set| {
+                if Some(set) == desc.bindless_set {
+                    return Ok(None);
+                }
+
+                let entries = BindGroupLayoutDesc::entries_of_set(desc.descriptor_bindings, set);
+                NullBindGroupLayout::new(device, &entries).map(Some)
+            })
+            .collect::<Result<_, _>>()?;
+
+        let id = device.register_object("PipelineLayout");
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+            id,
+
+            descriptor_bindings: desc.descriptor_bindings.to_vec(),
+            bind_group_layouts,
+            push_constant_ranges: desc.push_constant_ranges.to_vec(),
+        })))
+    }
+
+    #[inline]
+    pub fn bind_group_layout(&self, set: u32) -> Option<&NullBindGroupLayout> {
+        self.0
+            .bind_group_layouts
+            .get(set as usize)
+            .and_then(Option::as_ref)
+    }
+
+    #[inline]
+    pub fn descriptor_bindings(&self) -> &[DescriptorBinding] {
+        &self.0.descriptor_bindings
+    }
+
+    #[inline]
+    pub fn push_constant_ranges(&self) -> &[PushConstantRange] {
+        &self.0.push_constant_ranges
+    }
+
+    /// Checks that every binding the shader uses is declared by the layout.
+    pub(crate) fn validate_shader_bindings(
+        &self,
+        reflection: &ShaderReflection,
+    ) -> Result<(), NullError> {
+        for binding in &reflection.descriptor_bindings {
+            if !self
+                .descriptor_bindings()
+                .iter()
+                .any(|b| b.set == binding.set && b.binding == binding.binding)
+            {
+                return Err(NullError::Validation(format!(
+                    "Layout is missing set {} binding {}",
+                    binding.set, binding.binding
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use crate::{
+    null::{NullDevice, NullError, NullObjectId},
+    QueryPoolDesc, QueryType,
+};
+
+struct Inner {
+    device: NullDevice,
+    id: NullObjectId,
+
+    query_type: QueryType,
+    count: u32,
+}
+
+#[derive(Clone)]
+pub struct NullQueryPool(Arc<Inner>);
+
+impl PartialEq for NullQueryPool {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.id == other.0.id
+    }
+}
+
+impl Eq for NullQueryPool {}
+
+impl NullQueryPool {
+    pub fn new(device: &NullDevice, desc: &QueryPoolDesc) -> Result<Self, NullError> {
+        let id = device.register_object("QueryPool");
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+            id,
+
+            query_type: desc.query_type,
+            count: desc.count,
+        })))
+    }
+
+    #[inline]
+    pub fn query_type(&self) -> QueryType {
+        self.0.query_type
+    }
+
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.0.count
+    }
+
+    /// Returns an error unless `first..first + count` are queries of the pool.
+    pub(crate) fn validate_range(&self, first: u32, count: u32) -> Result<(), String> {
+        if first
+            .checked_add(count)
+            .is_none_or(|end| end > self.0.count)
+        {
+            return Err(format!(
+                "Queries {first}..{} exceed the query count of {}",
+                first.saturating_add(count),
+                self.0.count
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
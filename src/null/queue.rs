@@ -0,0 +1,113 @@
+use crate::{
+    null::{NullDevice, NullError, NullSubmission},
+    QueueType, SemaphoreValue, SubmitDesc,
+};
+
+#[derive(Clone)]
+pub struct NullQueue {
+    device: NullDevice,
+
+    queue_type: QueueType,
+}
+
+impl NullQueue {
+    pub(crate) fn new(device: &NullDevice, queue_type: QueueType) -> Self {
+        Self {
+            device: device.clone(),
+
+            queue_type,
+        }
+    }
+
+    #[inline]
+    pub fn queue_type(&self) -> QueueType {
+        self.queue_type
+    }
+
+    #[inline]
+    pub fn is_dedicated(&self) -> bool {
+        let desc = self.device.physical_device().desc();
+
+        match self.queue_type {
+            QueueType::Graphics => true,
+            QueueType::Compute => desc.dedicated_compute_queue,
+            QueueType::Transfer => desc.dedicated_transfer_queue,
+        }
+    }
+
+    /// Returns the queue type whose family backs this queue.
+    fn family(&self) -> QueueType {
+        if self.is_dedicated() {
+            self.queue_type
+        } else {
+            QueueType::Graphics
+        }
+    }
+
+    pub fn submit(&self, desc: &SubmitDesc) -> Result<(), NullError> {
+        for command_list in desc.command_lists {
+            let command_list = command_list.as_null();
+
+            if !command_list.is_executable() {
+                return Err(NullError::Validation(
+                    "Command list submitted without being ended".to_owned(),
+                ));
+            }
+
+            let family = self.device.get_queue(command_list.queue_type()).family();
+            if family != self.family() {
+                return Err(NullError::Validation(format!(
+                    "Command list recorded for {:?} submitted to the {:?} queue",
+                    command_list.queue_type(),
+                    self.queue_type
+                )));
+            }
+        }
+
+        if let Some(fence) = desc.fence {
+            if fence.as_null().is_signaled()? {
+                return Err(NullError::Validation(
+                    "Fence submitted while still signaled".to_owned(),
+                ));
+            }
+        }
+
+        if desc.wait_upload > self.device.upload_value() {
+            return Err(NullError::Validation(format!(
+                "Submission waits for upload value {} that was never returned",
+                desc.wait_upload
+            )));
+        }
+
+        let to_null_values = |values: &[SemaphoreValue]| {
+            values
+                .iter()
+                .map(|value| (value.semaphore.as_null().clone(), value.value))
+                .collect()
+        };
+
+        self.device.enqueue_submission(
+            self.family(),
+            NullSubmission {
+                waits: to_null_values(desc.wait_semaphores),
+                signals: to_null_values(desc.signal_semaphores),
+                fence: desc.fence.map(|fence| fence.as_null().clone()),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Work on the null backend completes immediately, only submissions blocked on semaphores
+    /// remain pending.
+    pub fn wait_idle(&self) -> Result<(), NullError> {
+        if self.device.has_pending_submissions(self.family()) {
+            return Err(NullError::Validation(
+                "wait_idle would block forever on submissions waiting for unsignaled semaphores"
+                    .to_owned(),
+            ));
+        }
+
+        Ok(())
+    }
+}
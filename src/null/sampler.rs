@@ -0,0 +1,73 @@
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    null::{NullDevice, NullError, NullObjectId},
+    validate_sampler_desc, SamplerDesc,
+};
+
+/// Samplers of the device by descriptor, each one is registered once and lives as long as the
+/// device.
+pub(crate) type SamplerCache = Mutex<HashMap<SamplerDesc, NullSampler>>;
+
+struct Inner {
+    id: NullObjectId,
+
+    desc: SamplerDesc,
+}
+
+#[derive(Clone)]
+pub struct NullSampler(Arc<Inner>);
+
+impl PartialEq for NullSampler {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.id == other.0.id
+    }
+}
+
+impl Eq for NullSampler {}
+
+impl Hash for NullSampler {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.id.hash(state);
+    }
+}
+
+impl NullSampler {
+    pub fn new(device: &NullDevice, desc: &SamplerDesc) -> Result<Self, NullError> {
+        validate_sampler_desc(desc).map_err(NullError::Validation)?;
+
+        let mut cache = device.sampler_cache().lock().unwrap();
+
+        if let Some(sampler) = cache.get(desc) {
+            return Ok(sampler.clone());
+        }
+
+        let max_sampler_count = device.physical_device().desc().max_sampler_count;
+        if cache.len() >= max_sampler_count as usize {
+            return Err(NullError::Validation(format!(
+                "Device supports at most {max_sampler_count} distinct samplers"
+            )));
+        }
+
+        let sampler = Self(Arc::new(Inner {
+            id: device.register_object("Sampler"),
+
+            desc: *desc,
+        }));
+
+        cache.insert(*desc, sampler.clone());
+
+        Ok(sampler)
+    }
+
+    #[inline]
+    pub fn desc(&self) -> &SamplerDesc {
+        &self.0.desc
+    }
+}
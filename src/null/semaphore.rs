@@ -0,0 +1,125 @@
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    null::{NullDevice, NullError, NullObjectId},
+    PhysicalDeviceFeatures, SemaphoreDesc, SemaphoreValue,
+};
+
+struct Inner {
+    device: NullDevice,
+    id: NullObjectId,
+
+    value: Mutex<u64>,
+    condvar: Condvar,
+}
+
+#[derive(Clone)]
+pub struct NullSemaphore(Arc<Inner>);
+
+impl NullSemaphore {
+    pub fn new(device: &NullDevice, desc: &SemaphoreDesc) -> Result<Self, NullError> {
+        device.validate_features(PhysicalDeviceFeatures::TIMELINE_SEMAPHORE, "Semaphore")?;
+
+        let id = device.register_object("Semaphore");
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+            id,
+
+            value: Mutex::new(desc.initial_value),
+            condvar: Condvar::new(),
+        })))
+    }
+
+    pub fn get_value(&self) -> Result<u64, NullError> {
+        Ok(*self.0.value.lock().unwrap())
+    }
+
+    pub fn signal(&self, value: u64) -> Result<(), NullError> {
+        {
+            let mut current = self.0.value.lock().unwrap();
+
+            if value <= *current {
+                return Err(NullError::Validation(format!(
+                    "Semaphore signaled with {value}, which is not greater than the current value {}",
+                    *current
+                )));
+            }
+
+            *current = value;
+        }
+
+        self.0.condvar.notify_all();
+        self.0.device.process_submissions();
+
+        Ok(())
+    }
+
+    /// Advances the counter on behalf of a completed submission.
+    pub(crate) fn signal_from_queue(&self, value: u64) {
+        {
+            let mut current = self.0.value.lock().unwrap();
+            *current = (*current).max(value);
+        }
+
+        self.0.condvar.notify_all();
+    }
+
+    #[inline]
+    pub(crate) fn is_reached(&self, value: u64) -> bool {
+        *self.0.value.lock().unwrap() >= value
+    }
+
+    pub fn wait(&self, value: u64, timeout: Option<Duration>) -> Result<bool, NullError> {
+        let current = self.0.value.lock().unwrap();
+
+        let reached = match timeout {
+            Some(timeout) => {
+                let (current, _) = self
+                    .0
+                    .condvar
+                    .wait_timeout_while(current, timeout, |current| *current < value)
+                    .unwrap();
+                *current >= value
+            }
+            None => {
+                let _current = self
+                    .0
+                    .condvar
+                    .wait_while(current, |current| *current < value)
+                    .unwrap();
+                true
+            }
+        };
+
+        Ok(reached)
+    }
+
+    pub fn wait_for_values(
+        _device: &NullDevice,
+        values: &[SemaphoreValue],
+        timeout: Option<Duration>,
+    ) -> Result<bool, NullError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+        for value in values {
+            let remaining =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+
+            if !value.semaphore.as_null().wait(value.value, remaining)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
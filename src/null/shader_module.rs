@@ -0,0 +1,64 @@
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use crate::{
+    null::{NullDevice, NullError, NullObjectId},
+    ShaderModuleDesc, ShaderReflection,
+};
+
+struct Inner {
+    device: NullDevice,
+    id: NullObjectId,
+
+    reflection: ShaderReflection,
+}
+
+#[derive(Clone)]
+pub struct NullShaderModule(Arc<Inner>);
+
+impl PartialEq for NullShaderModule {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for NullShaderModule {}
+
+impl Hash for NullShaderModule {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+impl NullShaderModule {
+    pub fn new(
+        device: &NullDevice,
+        _desc: &ShaderModuleDesc,
+        _code: &[u32],
+        reflection: ShaderReflection,
+    ) -> Result<Self, NullError> {
+        let id = device.register_object("ShaderModule");
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+            id,
+
+            reflection,
+        })))
+    }
+
+    #[inline]
+    pub fn reflection(&self) -> &ShaderReflection {
+        &self.0.reflection
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
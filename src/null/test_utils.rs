@@ -0,0 +1,38 @@
+//! Helpers for tests running against the null backend.
+
+use crate::{
+    null::NullDevice, BackendType, Device, DeviceDesc, Instance, InstanceDesc,
+    PhysicalDeviceFeatures,
+};
+
+/// Creates a null instance whose physical device supports `supported_features`.
+pub(crate) fn create_instance(supported_features: PhysicalDeviceFeatures) -> Instance {
+    let mut desc = InstanceDesc {
+        backend_type: BackendType::Null,
+        ..Default::default()
+    };
+    desc.null_physical_device.supported_features = supported_features;
+
+    unsafe { Instance::new(&desc) }.unwrap()
+}
+
+/// Creates a null device with every supported feature of `optional_features` enabled.
+pub(crate) fn create_device(optional_features: PhysicalDeviceFeatures) -> Device {
+    let instance = create_instance(PhysicalDeviceFeatures::all());
+
+    instance
+        .create_device(&DeviceDesc {
+            physical_device: instance.get_physical_devices()[0].clone(),
+            required_features: PhysicalDeviceFeatures::empty(),
+            optional_features,
+        })
+        .unwrap()
+}
+
+pub(crate) fn null_device(device: &Device) -> &NullDevice {
+    match device {
+        Device::Null(device) => device,
+        #[allow(unreachable_patterns)]
+        _ => unreachable!(),
+    }
+}
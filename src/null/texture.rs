@@ -0,0 +1,337 @@
+use std::sync::Arc;
+
+use crate::{
+    null::{NullDevice, NullError, NullObjectId},
+    Extent3d, Format, Texture, TextureAspectFlags, TextureDesc, TextureDimension,
+    TextureSubresourceRange, TextureUsageFlags, TextureViewDesc, TextureViewDimension,
+};
+
+struct Inner {
+    device: NullDevice,
+    id: NullObjectId,
+
+    dimension: TextureDimension,
+    format: Format,
+    extent: Extent3d,
+    mip_levels: u32,
+    array_layers: u32,
+    sample_count: u32,
+    usage: TextureUsageFlags,
+    tracked: bool,
+}
+
+#[derive(Clone)]
+pub struct NullTexture(Arc<Inner>);
+
+fn validate_texture_desc(desc: &TextureDesc) -> Result<(), String> {
+    let extent = desc.extent;
+
+    if extent.width == 0 || extent.height == 0 || extent.depth == 0 {
+        return Err("extent must not be zero".to_owned());
+    }
+
+    match desc.dimension {
+        TextureDimension::D1 if extent.height != 1 || extent.depth != 1 => {
+            return Err("1D textures must have a height and depth of one".to_owned());
+        }
+        TextureDimension::D2 | TextureDimension::Cube if extent.depth != 1 => {
+            return Err("2D and cube textures must have a depth of one".to_owned());
+        }
+        TextureDimension::Cube if extent.width != extent.height => {
+            return Err("Cube textures must be square".to_owned());
+        }
+        TextureDimension::Cube if !desc.array_layers.is_multiple_of(6) => {
+            return Err("Cube textures must have a multiple of six array layers".to_owned());
+        }
+        TextureDimension::D3 if desc.array_layers != 1 => {
+            return Err("3D textures must not have array layers".to_owned());
+        }
+        _ => {}
+    }
+
+    if desc.array_layers == 0 {
+        return Err("array_layers must be greater than zero".to_owned());
+    }
+
+    let max_mip_levels = 32
+        - extent
+            .width
+            .max(extent.height)
+            .max(extent.depth)
+            .leading_zeros();
+    if desc.mip_levels == 0 || desc.mip_levels > max_mip_levels {
+        return Err(format!(
+            "mip_levels must be in 1..={max_mip_levels}, got {}",
+            desc.mip_levels
+        ));
+    }
+
+    if !desc.sample_count.is_power_of_two() || desc.sample_count > 64 {
+        return Err(format!("Invalid sample count {}", desc.sample_count));
+    }
+
+    if desc.sample_count > 1 && (desc.dimension != TextureDimension::D2 || desc.mip_levels != 1) {
+        return Err("Multisampled textures must be 2D with a single mip level".to_owned());
+    }
+
+    if desc.usage.is_empty() {
+        return Err("usage must not be empty".to_owned());
+    }
+
+    if desc
+        .usage
+        .contains(TextureUsageFlags::DEPTH_STENCIL_ATTACHMENT)
+        && !desc.format.is_depth_stencil()
+    {
+        return Err(format!(
+            "{:?} cannot be used as a depth stencil attachment",
+            desc.format
+        ));
+    }
+
+    if desc.usage.contains(TextureUsageFlags::COLOR_ATTACHMENT)
+        && (desc.format.is_depth_stencil() || desc.format.is_compressed())
+    {
+        return Err(format!(
+            "{:?} cannot be used as a color attachment",
+            desc.format
+        ));
+    }
+
+    let (block_width, block_height) = desc.format.block_extent();
+    if !extent.width.is_multiple_of(block_width) || !extent.height.is_multiple_of(block_height) {
+        return Err(format!(
+            "Extent must be a multiple of the {block_width}x{block_height} block size of {:?}",
+            desc.format
+        ));
+    }
+
+    Ok(())
+}
+
+impl NullTexture {
+    pub fn new(device: &NullDevice, desc: &TextureDesc) -> Result<Self, NullError> {
+        validate_texture_desc(desc)
+            .map_err(|e| NullError::Validation(format!("Texture {:?}: {e}", desc.label)))?;
+
+        let id = device.register_object("Texture");
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+            id,
+
+            dimension: desc.dimension,
+            format: desc.format,
+            extent: desc.extent,
+            mip_levels: desc.mip_levels,
+            array_layers: desc.array_layers,
+            sample_count: desc.sample_count,
+            usage: desc.usage,
+            tracked: desc.tracked,
+        })))
+    }
+
+    #[inline]
+    pub fn device(&self) -> &NullDevice {
+        &self.0.device
+    }
+
+    #[inline]
+    pub fn dimension(&self) -> TextureDimension {
+        self.0.dimension
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        self.0.format
+    }
+
+    #[inline]
+    pub fn extent(&self) -> Extent3d {
+        self.0.extent
+    }
+
+    #[inline]
+    pub fn mip_levels(&self) -> u32 {
+        self.0.mip_levels
+    }
+
+    #[inline]
+    pub fn array_layers(&self) -> u32 {
+        self.0.array_layers
+    }
+
+    #[inline]
+    pub fn sample_count(&self) -> u32 {
+        self.0.sample_count
+    }
+
+    #[inline]
+    pub fn usage(&self) -> TextureUsageFlags {
+        self.0.usage
+    }
+
+    #[inline]
+    pub fn tracked(&self) -> bool {
+        self.0.tracked
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
+
+struct ViewInner {
+    texture: Texture,
+    device: NullDevice,
+    id: NullObjectId,
+
+    dimension: TextureViewDimension,
+    format: Format,
+    range: TextureSubresourceRange,
+}
+
+#[derive(Clone)]
+pub struct NullTextureView(Arc<ViewInner>);
+
+fn validate_texture_view(
+    texture: &NullTexture,
+    dimension: TextureViewDimension,
+    format: Format,
+    range: &TextureSubresourceRange,
+) -> Result<(), String> {
+    let mip_level_count = range.mip_level_count.unwrap_or_default();
+    let array_layer_count = range.array_layer_count.unwrap_or_default();
+
+    if mip_level_count == 0 || range.base_mip_level + mip_level_count > texture.mip_levels() {
+        return Err(format!(
+            "Mip levels {}..{} out of range for a texture with {} levels",
+            range.base_mip_level,
+            range.base_mip_level + mip_level_count,
+            texture.mip_levels()
+        ));
+    }
+
+    if array_layer_count == 0 || range.base_array_layer + array_layer_count > texture.array_layers()
+    {
+        return Err(format!(
+            "Array layers {}..{} out of range for a texture with {} layers",
+            range.base_array_layer,
+            range.base_array_layer + array_layer_count,
+            texture.array_layers()
+        ));
+    }
+
+    let compatible = match dimension {
+        TextureViewDimension::D1 => {
+            texture.dimension() == TextureDimension::D1 && array_layer_count == 1
+        }
+        TextureViewDimension::D1Array => texture.dimension() == TextureDimension::D1,
+        TextureViewDimension::D2 => {
+            matches!(
+                texture.dimension(),
+                TextureDimension::D2 | TextureDimension::Cube
+            ) && array_layer_count == 1
+        }
+        TextureViewDimension::D2Array => {
+            matches!(
+                texture.dimension(),
+                TextureDimension::D2 | TextureDimension::Cube
+            )
+        }
+        TextureViewDimension::D3 => texture.dimension() == TextureDimension::D3,
+        TextureViewDimension::Cube => {
+            texture.dimension() == TextureDimension::Cube && array_layer_count == 6
+        }
+        TextureViewDimension::CubeArray => {
+            texture.dimension() == TextureDimension::Cube && array_layer_count.is_multiple_of(6)
+        }
+    };
+
+    if !compatible {
+        return Err(format!(
+            "{dimension:?} view with {array_layer_count} layers is incompatible with a {:?} texture",
+            texture.dimension()
+        ));
+    }
+
+    if format != texture.format()
+        && (format.block_size() != texture.format().block_size()
+            || format.block_extent() != texture.format().block_extent()
+            || format.is_depth_stencil()
+            || texture.format().is_depth_stencil())
+    {
+        return Err(format!(
+            "View format {format:?} is incompatible with texture format {:?}",
+            texture.format()
+        ));
+    }
+
+    if !TextureAspectFlags::from_format(texture.format()).contains(range.aspect) {
+        return Err(format!(
+            "Aspects {:?} are not present in {:?}",
+            range.aspect,
+            texture.format()
+        ));
+    }
+
+    Ok(())
+}
+
+impl NullTextureView {
+    pub fn new(
+        texture: &Texture,
+        null_texture: &NullTexture,
+        desc: &TextureViewDesc,
+    ) -> Result<Self, NullError> {
+        let dimension = desc
+            .dimension
+            .unwrap_or_else(|| texture.default_view_dimension());
+        let format = desc.format.unwrap_or(null_texture.format());
+        let range = desc.range.resolve(texture, format);
+
+        validate_texture_view(null_texture, dimension, format, &range)
+            .map_err(|e| NullError::Validation(format!("TextureView {:?}: {e}", desc.label)))?;
+
+        let device = null_texture.device().clone();
+        let id = device.register_object("TextureView");
+
+        Ok(Self(Arc::new(ViewInner {
+            texture: texture.clone(),
+            device,
+            id,
+
+            dimension,
+            format,
+            range,
+        })))
+    }
+
+    #[inline]
+    pub fn texture(&self) -> &Texture {
+        &self.0.texture
+    }
+
+    #[inline]
+    pub fn dimension(&self) -> TextureViewDimension {
+        self.0.dimension
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        self.0.format
+    }
+
+    #[inline]
+    pub fn range(&self) -> &TextureSubresourceRange {
+        &self.0.range
+    }
+}
+
+impl Drop for ViewInner {
+    fn drop(&mut self) {
+        self.device.unregister_object(self.id);
+    }
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use crate::{
+    null::{NullDevice, NullError},
+    BufferUpload, TextureUpload,
+};
+
+/// Uploads complete immediately on the null backend, the upload timeline counts them.
+pub(crate) struct NullUploader;
+
+impl NullUploader {
+    pub fn completed_value(device: &NullDevice) -> u64 {
+        device.upload_value()
+    }
+
+    /// Only values that were returned by an upload can be waited for without a timeout.
+    pub fn wait(
+        device: &NullDevice,
+        value: u64,
+        timeout: Option<Duration>,
+    ) -> Result<bool, NullError> {
+        if value <= device.upload_value() {
+            return Ok(true);
+        }
+
+        match timeout {
+            Some(_) => Ok(false),
+            None => {
+                Err(NullError::Validation(format!(
+                    "wait_for_upload would block forever on upload value {value}, which was never \
+                 returned"
+                )))
+            }
+        }
+    }
+
+    /// Host visible buffers receive the data so it can be read back.
+    pub fn upload_buffer(device: &NullDevice, upload: &BufferUpload) -> Result<u64, NullError> {
+        if let Some(mapped_ptr) = upload.buffer.as_null().mapped_ptr() {
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    upload.data.as_ptr(),
+                    mapped_ptr.as_ptr().add(upload.offset as usize),
+                    upload.data.len(),
+                );
+            }
+        }
+
+        Ok(device.next_upload_value())
+    }
+
+    pub fn upload_texture(device: &NullDevice, _upload: &TextureUpload) -> Result<u64, NullError> {
+        Ok(device.next_upload_value())
+    }
+}
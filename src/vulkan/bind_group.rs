@@ -0,0 +1,484 @@
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+use ash::vk;
+
+use crate::{
+    sort_bind_group_layout_entries,
+    vulkan::{
+        to_vk_descriptor_type, to_vk_shader_stage_flags, ResourceAccess, VulkanDevice, VulkanError,
+    },
+    BindGroupDesc, BindGroupLayoutEntry, BindGroupPoolDesc, BindingResource, Buffer,
+    DescriptorType, ShaderStageFlags, TextureView,
+};
+
+/// Descriptor types bind groups can hold, each block of a pool reserves this many kinds.
+const BIND_GROUP_DESCRIPTOR_TYPES: [DescriptorType; 5] = [
+    DescriptorType::UniformBuffer,
+    DescriptorType::StorageBuffer,
+    DescriptorType::SampledTexture,
+    DescriptorType::StorageTexture,
+    DescriptorType::Sampler,
+];
+
+fn to_vk_shader_pipeline_stages(stages: ShaderStageFlags) -> vk::PipelineStageFlags2 {
+    let mut flags = vk::PipelineStageFlags2::empty();
+
+    if stages.contains(ShaderStageFlags::VERTEX) {
+        flags |= vk::PipelineStageFlags2::VERTEX_SHADER;
+    }
+    if stages.contains(ShaderStageFlags::FRAGMENT) {
+        flags |= vk::PipelineStageFlags2::FRAGMENT_SHADER;
+    }
+    if stages.contains(ShaderStageFlags::COMPUTE) {
+        flags |= vk::PipelineStageFlags2::COMPUTE_SHADER;
+    }
+    if stages.contains(ShaderStageFlags::TASK) {
+        flags |= vk::PipelineStageFlags2::TASK_SHADER_EXT;
+    }
+    if stages.contains(ShaderStageFlags::MESH) {
+        flags |= vk::PipelineStageFlags2::MESH_SHADER_EXT;
+    }
+
+    flags
+}
+
+/// Returns how the shaders of `entry` access a tracked resource bound to it, storage bindings
+/// count as writes.
+fn to_resource_access(entry: &BindGroupLayoutEntry) -> ResourceAccess {
+    let (access, layout) = match entry.descriptor_type {
+        DescriptorType::UniformBuffer => {
+            (vk::AccessFlags2::UNIFORM_READ, vk::ImageLayout::UNDEFINED)
+        }
+        DescriptorType::SampledTexture => {
+            (
+                vk::AccessFlags2::SHADER_SAMPLED_READ,
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            )
+        }
+        DescriptorType::StorageTexture => {
+            (
+                vk::AccessFlags2::SHADER_STORAGE_READ | vk::AccessFlags2::SHADER_STORAGE_WRITE,
+                vk::ImageLayout::GENERAL,
+            )
+        }
+        _ => {
+            (
+                vk::AccessFlags2::SHADER_STORAGE_READ | vk::AccessFlags2::SHADER_STORAGE_WRITE,
+                vk::ImageLayout::UNDEFINED,
+            )
+        }
+    };
+
+    ResourceAccess {
+        stages: to_vk_shader_pipeline_stages(entry.stages),
+        access,
+        layout,
+    }
+}
+
+/// Descriptor set layouts of the device, keyed by entries sorted by binding.
+pub(crate) type DescriptorSetLayoutCache =
+    Mutex<HashMap<Vec<BindGroupLayoutEntry>, vk::DescriptorSetLayout>>;
+
+/// The descriptor set layout is owned by the device cache, objects using it keep the device alive.
+struct LayoutInner {
+    descriptor_set_layout: vk::DescriptorSetLayout,
+
+    entries: Vec<BindGroupLayoutEntry>,
+}
+
+#[derive(Clone)]
+pub struct VulkanBindGroupLayout(Arc<LayoutInner>);
+
+impl PartialEq for VulkanBindGroupLayout {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.descriptor_set_layout == other.0.descriptor_set_layout
+    }
+}
+
+impl Eq for VulkanBindGroupLayout {}
+
+impl Hash for VulkanBindGroupLayout {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.descriptor_set_layout.hash(state);
+    }
+}
+
+impl VulkanBindGroupLayout {
+    pub fn new(
+        device: &VulkanDevice,
+        entries: &[BindGroupLayoutEntry],
+    ) -> Result<Self, VulkanError> {
+        let entries = sort_bind_group_layout_entries(entries).map_err(VulkanError::Custom)?;
+
+        let mut cache = device.descriptor_set_layout_cache().lock().unwrap();
+
+        let descriptor_set_layout = match cache.get(&entries) {
+            Some(descriptor_set_layout) => *descriptor_set_layout,
+            None => {
+                let bindings = entries
+                    .iter()
+                    .map(|entry| {
+                        vk::DescriptorSetLayoutBinding {
+                            binding: entry.binding,
+                            descriptor_type: to_vk_descriptor_type(entry.descriptor_type),
+                            descriptor_count: entry.count,
+                            stage_flags: to_vk_shader_stage_flags(entry.stages),
+                            ..Default::default()
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                let descriptor_set_layout_create_info =
+                    vk::DescriptorSetLayoutCreateInfo::default().bindings(&bindings);
+
+                let descriptor_set_layout = unsafe {
+                    device
+                        .device()
+                        .create_descriptor_set_layout(&descriptor_set_layout_create_info, None)
+                }?;
+
+                cache.insert(entries.clone(), descriptor_set_layout);
+                descriptor_set_layout
+            }
+        };
+
+        Ok(Self(Arc::new(LayoutInner {
+            descriptor_set_layout,
+
+            entries,
+        })))
+    }
+
+    #[inline]
+    pub fn descriptor_set_layout(&self) -> vk::DescriptorSetLayout {
+        self.0.descriptor_set_layout
+    }
+
+    #[inline]
+    pub fn entries(&self) -> &[BindGroupLayoutEntry] {
+        &self.0.entries
+    }
+}
+
+struct PoolState {
+    descriptor_pools: Vec<vk::DescriptorPool>,
+    /// Index of the block sets are allocated from, earlier ones are full.
+    current: usize,
+    /// Incremented by every reset, bind groups of earlier generations are freed.
+    generation: u64,
+}
+
+struct PoolInner {
+    device: VulkanDevice,
+
+    max_bind_groups: u32,
+    max_descriptors: u32,
+    /// Applied to every block, which are created lazily.
+    label: Option<String>,
+
+    state: Mutex<PoolState>,
+}
+
+impl PoolInner {
+    fn create_descriptor_pool(&self) -> Result<vk::DescriptorPool, VulkanError> {
+        let pool_sizes = BIND_GROUP_DESCRIPTOR_TYPES.map(|descriptor_type| {
+            vk::DescriptorPoolSize {
+                ty: to_vk_descriptor_type(descriptor_type),
+                descriptor_count: self.max_descriptors,
+            }
+        });
+        let descriptor_pool_create_info = vk::DescriptorPoolCreateInfo::default()
+            .max_sets(self.max_bind_groups)
+            .pool_sizes(&pool_sizes);
+
+        let descriptor_pool = unsafe {
+            self.device
+                .device()
+                .create_descriptor_pool(&descriptor_pool_create_info, None)
+        }?;
+        self.device
+            .set_object_name(descriptor_pool, self.label.as_deref());
+
+        Ok(descriptor_pool)
+    }
+
+    /// Allocates a set from the current block, moving on to the next one, created if needed,
+    /// when it is full.
+    fn allocate_descriptor_set(
+        &self,
+        descriptor_set_layout: vk::DescriptorSetLayout,
+    ) -> Result<(vk::DescriptorSet, u64), VulkanError> {
+        let mut state = self.state.lock().unwrap();
+        let set_layouts = [descriptor_set_layout];
+
+        loop {
+            let is_new_block = state.current == state.descriptor_pools.len();
+            if is_new_block {
+                let descriptor_pool = self.create_descriptor_pool()?;
+                state.descriptor_pools.push(descriptor_pool);
+            }
+
+            let descriptor_set_allocate_info = vk::DescriptorSetAllocateInfo::default()
+                .descriptor_pool(state.descriptor_pools[state.current])
+                .set_layouts(&set_layouts);
+
+            match unsafe {
+                self.device
+                    .device()
+                    .allocate_descriptor_sets(&descriptor_set_allocate_info)
+            } {
+                Ok(descriptor_sets) => return Ok((descriptor_sets[0], state.generation)),
+                Err(vk::Result::ERROR_OUT_OF_POOL_MEMORY | vk::Result::ERROR_FRAGMENTED_POOL)
+                    if !is_new_block =>
+                {
+                    state.current += 1;
+                }
+                Err(result) => return Err(result.into()),
+            }
+        }
+    }
+
+    #[inline]
+    fn generation(&self) -> u64 {
+        self.state.lock().unwrap().generation
+    }
+}
+
+impl Drop for PoolInner {
+    fn drop(&mut self) {
+        unsafe {
+            for descriptor_pool in &self.state.get_mut().unwrap().descriptor_pools {
+                self.device
+                    .device()
+                    .destroy_descriptor_pool(*descriptor_pool, None);
+            }
+        }
+    }
+}
+
+/// Descriptor pools used as blocks, reset together instead of freeing sets one by one.
+pub struct VulkanBindGroupPool(Arc<PoolInner>);
+
+impl VulkanBindGroupPool {
+    pub fn new(device: &VulkanDevice, desc: &BindGroupPoolDesc) -> Result<Self, VulkanError> {
+        if desc.max_bind_groups == 0 || desc.max_descriptors == 0 {
+            return Err(VulkanError::Custom(
+                "Bind group pool blocks cannot be empty".to_owned(),
+            ));
+        }
+
+        Ok(Self(Arc::new(PoolInner {
+            device: device.clone(),
+
+            max_bind_groups: desc.max_bind_groups,
+            max_descriptors: desc.max_descriptors,
+            label: desc.label.map(str::to_owned),
+
+            state: Mutex::new(PoolState {
+                descriptor_pools: Vec::new(),
+                current: 0,
+                generation: 0,
+            }),
+        })))
+    }
+
+    pub fn create_bind_group(&self, desc: &BindGroupDesc) -> Result<VulkanBindGroup, VulkanError> {
+        let layout = desc.layout.as_vulkan();
+
+        for descriptor_type in BIND_GROUP_DESCRIPTOR_TYPES {
+            let count = layout
+                .entries()
+                .iter()
+                .filter(|entry| entry.descriptor_type == descriptor_type)
+                .map(|entry| entry.count)
+                .sum::<u32>();
+
+            if count > self.0.max_descriptors {
+                return Err(VulkanError::Custom(format!(
+                    "Bind group holds {count} {descriptor_type:?} descriptors, the pool blocks \
+                     hold {}",
+                    self.0.max_descriptors
+                )));
+            }
+        }
+
+        let (descriptor_set, generation) = self
+            .0
+            .allocate_descriptor_set(layout.descriptor_set_layout())?;
+        self.0.device.set_object_name(descriptor_set, desc.label);
+
+        let mut buffers = Vec::new();
+        let mut texture_views = Vec::new();
+        let mut buffer_infos = Vec::new();
+        let mut image_infos = Vec::new();
+
+        for entry in desc.entries {
+            let layout_entry = layout
+                .entries()
+                .iter()
+                .find(|layout_entry| layout_entry.binding == entry.binding)
+                .unwrap();
+            let access = to_resource_access(layout_entry);
+
+            match entry.resource {
+                BindingResource::Buffer {
+                    buffer,
+                    offset,
+                    size,
+                } => {
+                    if buffer.as_vulkan().state().is_some() {
+                        buffers.push((buffer.clone(), access));
+                    }
+
+                    buffer_infos.push((
+                        entry,
+                        vk::DescriptorBufferInfo {
+                            buffer: *buffer.as_vulkan().buffer(),
+                            offset,
+                            range: size.unwrap_or(vk::WHOLE_SIZE),
+                        },
+                    ));
+                }
+                BindingResource::TextureView(view) => {
+                    // Untracked textures stay in the general layout.
+                    let image_layout = if view.texture().as_vulkan().states().is_some() {
+                        texture_views.push((view.clone(), access));
+                        access.layout
+                    } else {
+                        vk::ImageLayout::GENERAL
+                    };
+
+                    image_infos.push((
+                        entry,
+                        vk::DescriptorImageInfo {
+                            sampler: vk::Sampler::null(),
+                            image_view: *view.as_vulkan().image_view(),
+                            image_layout,
+                        },
+                    ));
+                }
+                BindingResource::Sampler(sampler) => {
+                    image_infos.push((
+                        entry,
+                        vk::DescriptorImageInfo {
+                            sampler: sampler.as_vulkan().sampler(),
+                            ..Default::default()
+                        },
+                    ));
+                }
+            }
+        }
+
+        let descriptor_type = |binding| {
+            let layout_entry = layout
+                .entries()
+                .iter()
+                .find(|layout_entry| layout_entry.binding == binding)
+                .unwrap();
+
+            to_vk_descriptor_type(layout_entry.descriptor_type)
+        };
+
+        let writes = buffer_infos
+            .iter()
+            .map(|(entry, buffer_info)| {
+                vk::WriteDescriptorSet::default()
+                    .dst_set(descriptor_set)
+                    .dst_binding(entry.binding)
+                    .dst_array_element(entry.array_element)
+                    .descriptor_type(descriptor_type(entry.binding))
+                    .buffer_info(std::slice::from_ref(buffer_info))
+            })
+            .chain(image_infos.iter().map(|(entry, image_info)| {
+                vk::WriteDescriptorSet::default()
+                    .dst_set(descriptor_set)
+                    .dst_binding(entry.binding)
+                    .dst_array_element(entry.array_element)
+                    .descriptor_type(descriptor_type(entry.binding))
+                    .image_info(std::slice::from_ref(image_info))
+            }))
+            .collect::<Vec<_>>();
+
+        unsafe { self.0.device.device().update_descriptor_sets(&writes, &[]) };
+
+        Ok(VulkanBindGroup(Arc::new(BindGroupInner {
+            pool: self.0.clone(),
+            generation,
+
+            layout: layout.clone(),
+            descriptor_set,
+
+            buffers,
+            texture_views,
+        })))
+    }
+
+    pub fn reset(&mut self) -> Result<(), VulkanError> {
+        let mut state = self.0.state.lock().unwrap();
+
+        for descriptor_pool in &state.descriptor_pools {
+            unsafe {
+                self.0
+                    .device
+                    .device()
+                    .reset_descriptor_pool(*descriptor_pool, vk::DescriptorPoolResetFlags::empty())
+            }?;
+        }
+
+        state.current = 0;
+        state.generation += 1;
+
+        Ok(())
+    }
+}
+
+struct BindGroupInner {
+    /// Keeps the descriptor pool of the set alive.
+    pool: Arc<PoolInner>,
+    generation: u64,
+
+    layout: VulkanBindGroupLayout,
+    descriptor_set: vk::DescriptorSet,
+
+    /// Tracked resources and how the shaders of their bindings access them.
+    buffers: Vec<(Buffer, ResourceAccess)>,
+    texture_views: Vec<(TextureView, ResourceAccess)>,
+}
+
+#[derive(Clone)]
+pub struct VulkanBindGroup(Arc<BindGroupInner>);
+
+impl VulkanBindGroup {
+    #[inline]
+    pub fn layout(&self) -> &VulkanBindGroupLayout {
+        &self.0.layout
+    }
+
+    #[inline]
+    pub fn descriptor_set(&self) -> vk::DescriptorSet {
+        self.0.descriptor_set
+    }
+
+    /// Returns `false` once the pool was reset and the descriptor set freed.
+    #[inline]
+    pub(crate) fn is_allocated(&self) -> bool {
+        self.0.pool.generation() == self.0.generation
+    }
+
+    #[inline]
+    pub(crate) fn tracked_buffers(&self) -> &[(Buffer, ResourceAccess)] {
+        &self.0.buffers
+    }
+
+    #[inline]
+    pub(crate) fn tracked_texture_views(&self) -> &[(TextureView, ResourceAccess)] {
+        &self.0.texture_views
+    }
+}
@@ -0,0 +1,269 @@
+use std::sync::Mutex;
+
+use ash::{prelude::VkResult, vk};
+
+use crate::{
+    vulkan::{VulkanDevice, VulkanError},
+    BindlessSlots, Buffer, BufferUsageFlags, TextureUsageFlags, TextureView,
+    BINDLESS_HEAP_CAPACITY, BINDLESS_SAMPLED_TEXTURE_BINDING, BINDLESS_STORAGE_BUFFER_BINDING,
+    BINDLESS_STORAGE_TEXTURE_BINDING,
+};
+
+const BINDLESS_DESCRIPTOR_TYPES: [(u32, vk::DescriptorType); 3] = [
+    (
+        BINDLESS_STORAGE_BUFFER_BINDING,
+        vk::DescriptorType::STORAGE_BUFFER,
+    ),
+    (
+        BINDLESS_SAMPLED_TEXTURE_BINDING,
+        vk::DescriptorType::SAMPLED_IMAGE,
+    ),
+    (
+        BINDLESS_STORAGE_TEXTURE_BINDING,
+        vk::DescriptorType::STORAGE_IMAGE,
+    ),
+];
+
+/// A single update-after-bind descriptor set holding every registered resource.
+///
+/// Indices are shared between the arrays, a texture view usable as both sampled and storage
+/// texture is written to both at the same index. Released indices carry the submission values
+/// of every queue at release time.
+///
+/// The heap does not keep resources alive, destroying one before releasing its index leaves a
+/// stale descriptor shaders must not access.
+pub(crate) struct VulkanBindlessHeap {
+    descriptor_set_layout: vk::DescriptorSetLayout,
+    descriptor_pool: vk::DescriptorPool,
+    descriptor_set: vk::DescriptorSet,
+
+    slots: Mutex<BindlessSlots<(), Vec<u64>>>,
+}
+
+impl VulkanBindlessHeap {
+    pub(crate) unsafe fn new(
+        device: &ash::Device,
+        properties: &vk::PhysicalDeviceVulkan12Properties,
+    ) -> VkResult<Self> {
+        let capacity = [
+            BINDLESS_HEAP_CAPACITY,
+            properties.max_per_stage_descriptor_update_after_bind_storage_buffers,
+            properties.max_per_stage_descriptor_update_after_bind_sampled_images,
+            properties.max_per_stage_descriptor_update_after_bind_storage_images,
+            properties.max_per_stage_update_after_bind_resources / 3,
+            properties.max_descriptor_set_update_after_bind_storage_buffers,
+            properties.max_descriptor_set_update_after_bind_sampled_images,
+            properties.max_descriptor_set_update_after_bind_storage_images,
+        ]
+        .into_iter()
+        .min()
+        .unwrap();
+
+        let bindings = BINDLESS_DESCRIPTOR_TYPES.map(|(binding, descriptor_type)| {
+            vk::DescriptorSetLayoutBinding {
+                binding,
+                descriptor_type,
+                descriptor_count: capacity,
+                stage_flags: vk::ShaderStageFlags::ALL,
+                ..Default::default()
+            }
+        });
+        let binding_flags = [vk::DescriptorBindingFlags::PARTIALLY_BOUND
+            | vk::DescriptorBindingFlags::UPDATE_AFTER_BIND
+            | vk::DescriptorBindingFlags::UPDATE_UNUSED_WHILE_PENDING;
+            BINDLESS_DESCRIPTOR_TYPES.len()];
+
+        let mut binding_flags_create_info =
+            vk::DescriptorSetLayoutBindingFlagsCreateInfo::default().binding_flags(&binding_flags);
+        let descriptor_set_layout_create_info = vk::DescriptorSetLayoutCreateInfo::default()
+            .flags(vk::DescriptorSetLayoutCreateFlags::UPDATE_AFTER_BIND_POOL)
+            .bindings(&bindings)
+            .push_next(&mut binding_flags_create_info);
+
+        let descriptor_set_layout =
+            device.create_descriptor_set_layout(&descriptor_set_layout_create_info, None)?;
+
+        let pool_sizes = BINDLESS_DESCRIPTOR_TYPES.map(|(_, ty)| {
+            vk::DescriptorPoolSize {
+                ty,
+                descriptor_count: capacity,
+            }
+        });
+        let descriptor_pool_create_info = vk::DescriptorPoolCreateInfo::default()
+            .flags(vk::DescriptorPoolCreateFlags::UPDATE_AFTER_BIND)
+            .max_sets(1)
+            .pool_sizes(&pool_sizes);
+
+        let descriptor_pool = device
+            .create_descriptor_pool(&descriptor_pool_create_info, None)
+            .inspect_err(|_| device.destroy_descriptor_set_layout(descriptor_set_layout, None))?;
+
+        let set_layouts = [descriptor_set_layout];
+        let descriptor_set_allocate_info = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(descriptor_pool)
+            .set_layouts(&set_layouts);
+
+        let descriptor_set = device
+            .allocate_descriptor_sets(&descriptor_set_allocate_info)
+            .inspect_err(|_| {
+                device.destroy_descriptor_pool(descriptor_pool, None);
+                device.destroy_descriptor_set_layout(descriptor_set_layout, None);
+            })?[0];
+
+        Ok(Self {
+            descriptor_set_layout,
+            descriptor_pool,
+            descriptor_set,
+
+            slots: Mutex::new(BindlessSlots::new(capacity)),
+        })
+    }
+
+    #[inline]
+    pub(crate) fn descriptor_set_layout(&self) -> vk::DescriptorSetLayout {
+        self.descriptor_set_layout
+    }
+
+    #[inline]
+    pub(crate) fn descriptor_set(&self) -> vk::DescriptorSet {
+        self.descriptor_set
+    }
+
+    /// Destroys the descriptor objects, called by the device before it is destroyed itself.
+    pub(crate) unsafe fn destroy(&self, device: &ash::Device) {
+        device.destroy_descriptor_pool(self.descriptor_pool, None);
+        device.destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+    }
+
+    fn heap(device: &VulkanDevice) -> Result<&Self, VulkanError> {
+        device.bindless_heap().ok_or_else(|| {
+            VulkanError::Custom("The bindless heap requires timeline semaphores".to_owned())
+        })
+    }
+
+    /// Takes a free index and writes its descriptors with `write`, which runs
+    /// under the heap lock since the descriptor set is externally synchronized.
+    fn insert(device: &VulkanDevice, write: impl FnOnce(&Self, u32)) -> Result<u32, VulkanError> {
+        let heap = Self::heap(device)?;
+        let mut slots = heap.slots.lock().unwrap();
+
+        // Failing to query the timelines only delays recycling.
+        let index = slots.insert((), |submitted_values| {
+            device
+                .are_submissions_complete(submitted_values)
+                .unwrap_or(false)
+        });
+
+        let Some(index) = index else {
+            return Err(VulkanError::Custom(format!(
+                "Bindless heap is full, it holds {} resources",
+                slots.capacity()
+            )));
+        };
+
+        write(heap, index);
+
+        Ok(index)
+    }
+
+    pub fn register_buffer(device: &VulkanDevice, buffer: &Buffer) -> Result<u32, VulkanError> {
+        let vulkan_buffer = buffer.as_vulkan();
+
+        if !buffer.usage().contains(BufferUsageFlags::STORAGE) {
+            return Err(VulkanError::Custom(
+                "Bindless buffers require storage usage".to_owned(),
+            ));
+        }
+        if vulkan_buffer.state().is_some() {
+            return Err(VulkanError::Custom(
+                "Tracked buffers cannot be used bindless".to_owned(),
+            ));
+        }
+
+        let buffer_infos = [vk::DescriptorBufferInfo {
+            buffer: *vulkan_buffer.buffer(),
+            offset: 0,
+            range: vk::WHOLE_SIZE,
+        }];
+
+        Self::insert(device, |heap, index| {
+            let write = vk::WriteDescriptorSet::default()
+                .dst_set(heap.descriptor_set)
+                .dst_binding(BINDLESS_STORAGE_BUFFER_BINDING)
+                .dst_array_element(index)
+                .descriptor_type(vk::DescriptorType::STORAGE_BUFFER)
+                .buffer_info(&buffer_infos);
+
+            unsafe { device.device().update_descriptor_sets(&[write], &[]) };
+        })
+    }
+
+    /// Untracked textures stay in the general layout, which the descriptors are written with.
+    pub fn register_texture_view(
+        device: &VulkanDevice,
+        view: &TextureView,
+    ) -> Result<u32, VulkanError> {
+        let texture = view.texture();
+        let usage = texture.usage();
+
+        if !usage.intersects(TextureUsageFlags::SAMPLED | TextureUsageFlags::STORAGE) {
+            return Err(VulkanError::Custom(
+                "Bindless texture views require sampled or storage usage".to_owned(),
+            ));
+        }
+        if texture.as_vulkan().states().is_some() {
+            return Err(VulkanError::Custom(
+                "Tracked textures cannot be used bindless".to_owned(),
+            ));
+        }
+
+        let image_infos = [vk::DescriptorImageInfo {
+            sampler: vk::Sampler::null(),
+            image_view: *view.as_vulkan().image_view(),
+            image_layout: vk::ImageLayout::GENERAL,
+        }];
+
+        Self::insert(device, |heap, index| {
+            let writes = [
+                (
+                    TextureUsageFlags::SAMPLED,
+                    BINDLESS_SAMPLED_TEXTURE_BINDING,
+                    vk::DescriptorType::SAMPLED_IMAGE,
+                ),
+                (
+                    TextureUsageFlags::STORAGE,
+                    BINDLESS_STORAGE_TEXTURE_BINDING,
+                    vk::DescriptorType::STORAGE_IMAGE,
+                ),
+            ]
+            .into_iter()
+            .filter(|(required_usage, _, _)| usage.contains(*required_usage))
+            .map(|(_, binding, descriptor_type)| {
+                vk::WriteDescriptorSet::default()
+                    .dst_set(heap.descriptor_set)
+                    .dst_binding(binding)
+                    .dst_array_element(index)
+                    .descriptor_type(descriptor_type)
+                    .image_info(&image_infos)
+            })
+            .collect::<Vec<_>>();
+
+            unsafe { device.device().update_descriptor_sets(&writes, &[]) };
+        })
+    }
+
+    /// Stale descriptors are left in place, partially bound arrays only require the ones shaders
+    /// actually access to be valid.
+    pub fn release(device: &VulkanDevice, index: u32) -> Result<(), VulkanError> {
+        let heap = Self::heap(device)?;
+        let submitted_values = device.submitted_values();
+
+        if !heap.slots.lock().unwrap().release(index, submitted_values) {
+            return Err(VulkanError::Custom(format!(
+                "Bindless index {index} is not registered"
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    ptr::NonNull,
+    sync::{Arc, Mutex},
+};
+
+use ash::vk;
+use vk_mem_alloc::{Allocation, AllocationCreateFlags, AllocationCreateInfo, MemoryUsage};
+
+use crate::{
+    vulkan::{ResourceState, VulkanDevice, VulkanError},
+    BufferDesc, BufferUsageFlags, MemoryLocation,
+};
+
+struct Inner {
+    device: VulkanDevice,
+
+    buffer: vk::Buffer,
+    allocation: Allocation,
+
+    size: u64,
+    usage: BufferUsageFlags,
+    memory_location: MemoryLocation,
+    mapped_ptr: Option<NonNull<u8>>,
+    device_address: Option<u64>,
+    state: Option<Mutex<ResourceState>>,
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+#[derive(Clone)]
+pub struct VulkanBuffer(Arc<Inner>);
+
+fn to_vk_buffer_usage_flags(usage: BufferUsageFlags) -> vk::BufferUsageFlags {
+    let mut result = vk::BufferUsageFlags::empty();
+
+    if usage.contains(BufferUsageFlags::TRANSFER_SRC) {
+        result |= vk::BufferUsageFlags::TRANSFER_SRC;
+    }
+    if usage.contains(BufferUsageFlags::TRANSFER_DST) {
+        result |= vk::BufferUsageFlags::TRANSFER_DST;
+    }
+    if usage.contains(BufferUsageFlags::UNIFORM) {
+        result |= vk::BufferUsageFlags::UNIFORM_BUFFER;
+    }
+    if usage.contains(BufferUsageFlags::STORAGE) {
+        result |= vk::BufferUsageFlags::STORAGE_BUFFER;
+    }
+    if usage.contains(BufferUsageFlags::INDEX) {
+        result |= vk::BufferUsageFlags::INDEX_BUFFER;
+    }
+    if usage.contains(BufferUsageFlags::VERTEX) {
+        result |= vk::BufferUsageFlags::VERTEX_BUFFER;
+    }
+    if usage.contains(BufferUsageFlags::INDIRECT) {
+        result |= vk::BufferUsageFlags::INDIRECT_BUFFER;
+    }
+    if usage.contains(BufferUsageFlags::DEVICE_ADDRESS) {
+        result |= vk::BufferUsageFlags::SHADER_DEVICE_ADDRESS;
+    }
+
+    result
+}
+
+pub(crate) fn to_allocation_create_info(memory_location: MemoryLocation) -> AllocationCreateInfo {
+    match memory_location {
+        MemoryLocation::GpuOnly => {
+            AllocationCreateInfo {
+                usage: MemoryUsage::AUTO_PREFER_DEVICE,
+                ..Default::default()
+            }
+        }
+        MemoryLocation::Upload => {
+            AllocationCreateInfo {
+                flags: AllocationCreateFlags::MAPPED
+                    | AllocationCreateFlags::HOST_ACCESS_SEQUENTIAL_WRITE,
+                usage: MemoryUsage::AUTO,
+                ..Default::default()
+            }
+        }
+        MemoryLocation::Readback => {
+            AllocationCreateInfo {
+                flags: AllocationCreateFlags::MAPPED | AllocationCreateFlags::HOST_ACCESS_RANDOM,
+                usage: MemoryUsage::AUTO_PREFER_HOST,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+impl VulkanBuffer {
+    pub fn new(device: &VulkanDevice, desc: &BufferDesc) -> Result<Self, VulkanError> {
+        let queue_family_indices = device.queue_family_indices();
+
+        let mut buffer_create_info = vk::BufferCreateInfo::default()
+            .size(desc.size)
+            .usage(to_vk_buffer_usage_flags(desc.usage))
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        // Tracked buffers are shared by all queue families, so no ownership transfers are needed.
+        if desc.tracked && queue_family_indices.len() > 1 {
+            buffer_create_info = buffer_create_info
+                .sharing_mode(vk::SharingMode::CONCURRENT)
+                .queue_family_indices(&queue_family_indices);
+        }
+
+        let (buffer, allocation, allocation_info) = unsafe {
+            vk_mem_alloc::create_buffer(
+                *device.allocator(),
+                &buffer_create_info,
+                &to_allocation_create_info(desc.memory_location),
+            )
+        }?;
+        device.set_object_name(buffer, desc.label);
+
+        let mapped_ptr = if desc.memory_location.is_host_visible() {
+            NonNull::new(allocation_info.mapped_data.cast())
+        } else {
+            None
+        };
+
+        let device_address = if desc.usage.contains(BufferUsageFlags::DEVICE_ADDRESS) {
+            Some(unsafe {
+                device.device().get_buffer_device_address(
+                    &vk::BufferDeviceAddressInfo::default().buffer(buffer),
+                )
+            })
+        } else {
+            None
+        };
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+
+            buffer,
+            allocation,
+
+            size: desc.size,
+            usage: desc.usage,
+            memory_location: desc.memory_location,
+            mapped_ptr,
+            device_address,
+            state: desc.tracked.then(Mutex::default),
+        })))
+    }
+
+    #[inline]
+    pub fn buffer(&self) -> &vk::Buffer {
+        &self.0.buffer
+    }
+
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.0.size
+    }
+
+    #[inline]
+    pub fn usage(&self) -> BufferUsageFlags {
+        self.0.usage
+    }
+
+    #[inline]
+    pub fn memory_location(&self) -> MemoryLocation {
+        self.0.memory_location
+    }
+
+    #[inline]
+    pub fn mapped_ptr(&self) -> Option<NonNull<u8>> {
+        self.0.mapped_ptr
+    }
+
+    #[inline]
+    pub fn device_address(&self) -> Option<u64> {
+        self.0.device_address
+    }
+
+    /// Returns the last access of the buffer if it was created with `tracked` set.
+    #[inline]
+    pub(crate) fn state(&self) -> Option<&Mutex<ResourceState>> {
+        self.0.state.as_ref()
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            vk_mem_alloc::destroy_buffer(*self.device.allocator(), self.buffer, self.allocation);
+        }
+    }
+}
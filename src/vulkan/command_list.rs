@@ -0,0 +1,1669 @@
+use std::{ffi::CString, ops::Range};
+
+use ash::vk;
+
+use crate::{
+    validate_push_constants,
+    vulkan::{
+        to_vk_image_aspect_flags, to_vk_image_subresource_range, to_vk_shader_stage_flags,
+        ResourceAccess, ResourceBarrier, VulkanDevice, VulkanError, VulkanPipelineLayout,
+    },
+    Access, BindGroup, Buffer, BufferBarrier, BufferTextureCopy, ClearValue, CommandListDesc,
+    ComputePipeline, Format, GlobalBarrier, GraphicsPipeline, IndexFormat, LoadOp, Origin3d,
+    PhysicalDeviceFeatures, QueryPool, QueueTransfer, QueueType, RenderPassDesc, ScissorRect,
+    ShaderStageFlags, StoreOp, Texture, TextureAspectFlags, TextureBarrier, TextureCopy,
+    TextureSubresourceRange, TextureView, Viewport,
+};
+
+/// Barriers cannot be recorded inside a render pass, the ones needed by commands in it go into
+/// a command buffer that is submitted right before the one holding the render pass.
+struct RenderPassBarriers {
+    /// Position of the barrier command buffer in the recorded command buffers.
+    index: usize,
+    command_buffer: Option<vk::CommandBuffer>,
+    /// Debug groups open when the render pass began, the ones above are closed inside of it.
+    debug_group_depth: u32,
+    /// Whether the render pass is wrapped in a debug label.
+    labeled: bool,
+}
+
+pub struct VulkanCommandList {
+    device: VulkanDevice,
+
+    queue_type: QueueType,
+    family_index: u32,
+
+    command_pool: vk::CommandPool,
+    /// Every command buffer allocated from the pool, reused after it is reset.
+    pooled_command_buffers: Vec<vk::CommandBuffer>,
+    /// Command buffers begun since the pool was reset, in submission order.
+    recorded_command_buffers: Vec<vk::CommandBuffer>,
+    /// The command buffer commands are currently recorded into.
+    command_buffer: vk::CommandBuffer,
+    /// Applied to every command buffer, which are allocated lazily.
+    label: Option<String>,
+
+    render_pass: Option<RenderPassBarriers>,
+    /// Layouts of the last bound graphics and compute pipelines, bind groups are bound with them.
+    graphics_layout: Option<VulkanPipelineLayout>,
+    compute_layout: Option<VulkanPipelineLayout>,
+    buffer_barriers: Vec<vk::BufferMemoryBarrier2<'static>>,
+    image_barriers: Vec<vk::ImageMemoryBarrier2<'static>>,
+    debug_group_depth: u32,
+
+    error: Option<VulkanError>,
+}
+
+/// Untracked textures stay in the general layout, which every command accepts.
+pub(crate) const UNTRACKED_IMAGE_LAYOUT: vk::ImageLayout = vk::ImageLayout::GENERAL;
+
+fn to_vk_attachment_load_op(load_op: LoadOp) -> vk::AttachmentLoadOp {
+    match load_op {
+        LoadOp::Load => vk::AttachmentLoadOp::LOAD,
+        LoadOp::Clear => vk::AttachmentLoadOp::CLEAR,
+        LoadOp::DontCare => vk::AttachmentLoadOp::DONT_CARE,
+    }
+}
+
+fn to_vk_attachment_store_op(store_op: StoreOp) -> vk::AttachmentStoreOp {
+    match store_op {
+        StoreOp::Store => vk::AttachmentStoreOp::STORE,
+        StoreOp::DontCare => vk::AttachmentStoreOp::DONT_CARE,
+    }
+}
+
+/// Returns an attachment info for `view` in `layout`, resolving into `resolve_target` with
+/// `resolve_mode`.
+fn to_vk_rendering_attachment_info(
+    view: &TextureView,
+    layout: vk::ImageLayout,
+    resolve_target: Option<(&TextureView, vk::ImageLayout)>,
+    resolve_mode: vk::ResolveModeFlags,
+) -> vk::RenderingAttachmentInfo<'static> {
+    let mut attachment_info = vk::RenderingAttachmentInfo::default()
+        .image_view(*view.as_vulkan().image_view())
+        .image_layout(layout);
+
+    if let Some((resolve_target, resolve_layout)) = resolve_target {
+        attachment_info = attachment_info
+            .resolve_mode(resolve_mode)
+            .resolve_image_view(*resolve_target.as_vulkan().image_view())
+            .resolve_image_layout(resolve_layout);
+    }
+
+    attachment_info
+}
+
+/// Returns the stages and access of `access`, limited to the stages `queue_type` supports.
+fn to_vk_stage_access(
+    access: Access,
+    queue_type: QueueType,
+) -> (vk::PipelineStageFlags2, vk::AccessFlags2) {
+    let (stages, access) = match access {
+        Access::IndirectCommand => {
+            (
+                vk::PipelineStageFlags2::DRAW_INDIRECT,
+                vk::AccessFlags2::INDIRECT_COMMAND_READ,
+            )
+        }
+        Access::IndexBuffer => {
+            (
+                vk::PipelineStageFlags2::INDEX_INPUT,
+                vk::AccessFlags2::INDEX_READ,
+            )
+        }
+        Access::VertexBuffer => {
+            (
+                vk::PipelineStageFlags2::VERTEX_ATTRIBUTE_INPUT,
+                vk::AccessFlags2::VERTEX_ATTRIBUTE_READ,
+            )
+        }
+        Access::ShaderRead => {
+            (
+                vk::PipelineStageFlags2::PRE_RASTERIZATION_SHADERS
+                    | vk::PipelineStageFlags2::FRAGMENT_SHADER
+                    | vk::PipelineStageFlags2::COMPUTE_SHADER,
+                vk::AccessFlags2::SHADER_READ | vk::AccessFlags2::UNIFORM_READ,
+            )
+        }
+        Access::ShaderWrite => {
+            (
+                vk::PipelineStageFlags2::PRE_RASTERIZATION_SHADERS
+                    | vk::PipelineStageFlags2::FRAGMENT_SHADER
+                    | vk::PipelineStageFlags2::COMPUTE_SHADER,
+                vk::AccessFlags2::SHADER_WRITE,
+            )
+        }
+        Access::ColorAttachmentRead => {
+            (
+                vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+                vk::AccessFlags2::COLOR_ATTACHMENT_READ,
+            )
+        }
+        Access::ColorAttachmentWrite => {
+            (
+                vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+                vk::AccessFlags2::COLOR_ATTACHMENT_WRITE,
+            )
+        }
+        Access::DepthStencilAttachmentRead => {
+            (
+                vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+                    | vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+                vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ,
+            )
+        }
+        Access::DepthStencilAttachmentWrite => {
+            (
+                vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS
+                    | vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS,
+                vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE,
+            )
+        }
+        Access::TransferSrc => {
+            (
+                vk::PipelineStageFlags2::ALL_TRANSFER,
+                vk::AccessFlags2::TRANSFER_READ,
+            )
+        }
+        Access::TransferDst => {
+            (
+                vk::PipelineStageFlags2::ALL_TRANSFER,
+                vk::AccessFlags2::TRANSFER_WRITE,
+            )
+        }
+        Access::HostRead => (vk::PipelineStageFlags2::HOST, vk::AccessFlags2::HOST_READ),
+        Access::HostWrite => (vk::PipelineStageFlags2::HOST, vk::AccessFlags2::HOST_WRITE),
+    };
+
+    let stages = match queue_type {
+        QueueType::Graphics => stages,
+        QueueType::Compute => {
+            stages
+                & (vk::PipelineStageFlags2::DRAW_INDIRECT
+                    | vk::PipelineStageFlags2::COMPUTE_SHADER
+                    | vk::PipelineStageFlags2::ALL_TRANSFER
+                    | vk::PipelineStageFlags2::HOST)
+        }
+        QueueType::Transfer => {
+            stages & (vk::PipelineStageFlags2::ALL_TRANSFER | vk::PipelineStageFlags2::HOST)
+        }
+    };
+
+    if stages.is_empty() {
+        (vk::PipelineStageFlags2::NONE, vk::AccessFlags2::NONE)
+    } else {
+        (stages, access)
+    }
+}
+
+/// Returns the barrier between the `before` and `after` accesses on `queue_type`.
+fn to_resource_barrier(
+    before: &[Access],
+    after: &[Access],
+    queue_type: QueueType,
+    layouts: (vk::ImageLayout, vk::ImageLayout),
+) -> ResourceBarrier {
+    let fold = |accesses: &[Access]| {
+        accesses.iter().fold(
+            (vk::PipelineStageFlags2::NONE, vk::AccessFlags2::NONE),
+            |(stages, access), next| {
+                let (next_stages, next_access) = to_vk_stage_access(*next, queue_type);
+                (stages | next_stages, access | next_access)
+            },
+        )
+    };
+    let (src_stages, src_access) = fold(before);
+    let (dst_stages, dst_access) = fold(after);
+
+    ResourceBarrier {
+        src_stages,
+        src_access,
+        dst_stages,
+        dst_access,
+        old_layout: layouts.0,
+        new_layout: layouts.1,
+    }
+}
+
+pub(crate) fn to_vk_buffer_memory_barrier(
+    buffer: vk::Buffer,
+    barrier: &ResourceBarrier,
+) -> vk::BufferMemoryBarrier2<'static> {
+    vk::BufferMemoryBarrier2::default()
+        .src_stage_mask(barrier.src_stages)
+        .src_access_mask(barrier.src_access)
+        .dst_stage_mask(barrier.dst_stages)
+        .dst_access_mask(barrier.dst_access)
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .buffer(buffer)
+        .offset(0)
+        .size(vk::WHOLE_SIZE)
+}
+
+pub(crate) fn to_vk_image_memory_barrier(
+    image: vk::Image,
+    barrier: &ResourceBarrier,
+    subresource_range: vk::ImageSubresourceRange,
+) -> vk::ImageMemoryBarrier2<'static> {
+    vk::ImageMemoryBarrier2::default()
+        .src_stage_mask(barrier.src_stages)
+        .src_access_mask(barrier.src_access)
+        .dst_stage_mask(barrier.dst_stages)
+        .dst_access_mask(barrier.dst_access)
+        .old_layout(barrier.old_layout)
+        .new_layout(barrier.new_layout)
+        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+        .image(image)
+        .subresource_range(subresource_range)
+}
+
+fn to_vk_index_type(format: IndexFormat) -> vk::IndexType {
+    match format {
+        IndexFormat::Uint16 => vk::IndexType::UINT16,
+        IndexFormat::Uint32 => vk::IndexType::UINT32,
+    }
+}
+
+fn to_vk_offset_3d(origin: Origin3d) -> vk::Offset3D {
+    vk::Offset3D {
+        x: origin.x as i32,
+        y: origin.y as i32,
+        z: origin.z as i32,
+    }
+}
+
+/// Returns the aspect copied for `format`, depth takes precedence over stencil.
+fn to_vk_copy_aspect(format: Format) -> vk::ImageAspectFlags {
+    if format.is_depth() {
+        vk::ImageAspectFlags::DEPTH
+    } else if format.is_stencil() {
+        vk::ImageAspectFlags::STENCIL
+    } else {
+        vk::ImageAspectFlags::COLOR
+    }
+}
+
+pub(crate) fn to_vk_buffer_image_copy(
+    texture: &Texture,
+    region: &BufferTextureCopy,
+) -> vk::BufferImageCopy {
+    let format = texture.format();
+    let (block_width, block_height) = format.block_extent();
+
+    vk::BufferImageCopy {
+        buffer_offset: region.buffer_offset,
+        buffer_row_length: region.bytes_per_row / format.block_size() * block_width,
+        buffer_image_height: region.rows_per_image * block_height,
+        image_subresource: vk::ImageSubresourceLayers {
+            aspect_mask: to_vk_copy_aspect(format),
+            mip_level: region.mip_level,
+            base_array_layer: region.array_layer,
+            layer_count: 1,
+        },
+        image_offset: to_vk_offset_3d(region.origin),
+        image_extent: vk::Extent3D {
+            width: region.extent.width,
+            height: region.extent.height,
+            depth: region.extent.depth,
+        },
+    }
+}
+
+/// Cuts the label short at an interior nul byte, a debug group still has to be opened for it.
+fn to_debug_label_name(label: &str) -> CString {
+    CString::new(label.split('\0').next().unwrap()).unwrap()
+}
+
+impl VulkanCommandList {
+    pub fn new(device: &VulkanDevice, desc: &CommandListDesc) -> Result<Self, VulkanError> {
+        let family_index = device.get_queue(desc.queue_type).family_index();
+
+        let command_pool_create_info =
+            vk::CommandPoolCreateInfo::default().queue_family_index(family_index);
+
+        let command_pool = unsafe {
+            device
+                .device()
+                .create_command_pool(&command_pool_create_info, None)
+        }?;
+        device.set_object_name(command_pool, desc.label);
+
+        let command_buffer_allocate_info = vk::CommandBufferAllocateInfo::default()
+            .command_pool(command_pool)
+            .level(vk::CommandBufferLevel::PRIMARY)
+            .command_buffer_count(1);
+
+        let command_buffer = match unsafe {
+            device
+                .device()
+                .allocate_command_buffers(&command_buffer_allocate_info)
+        } {
+            Ok(command_buffers) => command_buffers[0],
+            Err(e) => {
+                unsafe { device.device().destroy_command_pool(command_pool, None) };
+                return Err(e.into());
+            }
+        };
+        device.set_object_name(command_buffer, desc.label);
+
+        Ok(Self {
+            device: device.clone(),
+
+            queue_type: desc.queue_type,
+            family_index,
+
+            command_pool,
+            pooled_command_buffers: vec![command_buffer],
+            recorded_command_buffers: Vec::new(),
+            command_buffer,
+            label: desc.label.map(str::to_owned),
+
+            render_pass: None,
+            graphics_layout: None,
+            compute_layout: None,
+            buffer_barriers: Vec::new(),
+            image_barriers: Vec::new(),
+            debug_group_depth: 0,
+
+            error: None,
+        })
+    }
+
+    /// Returns the command buffers recorded since `begin`, in submission order.
+    #[inline]
+    pub fn command_buffers(&self) -> &[vk::CommandBuffer] {
+        &self.recorded_command_buffers
+    }
+
+    #[inline]
+    pub fn family_index(&self) -> u32 {
+        self.family_index
+    }
+
+    #[inline]
+    pub fn queue_type(&self) -> QueueType {
+        self.queue_type
+    }
+
+    pub fn begin(&mut self) -> Result<(), VulkanError> {
+        self.error = None;
+        self.render_pass = None;
+        self.graphics_layout = None;
+        self.compute_layout = None;
+        self.buffer_barriers.clear();
+        self.image_barriers.clear();
+        self.recorded_command_buffers.clear();
+        self.debug_group_depth = 0;
+
+        unsafe {
+            self.device
+                .device()
+                .reset_command_pool(self.command_pool, vk::CommandPoolResetFlags::empty())
+        }?;
+
+        self.command_buffer = self.begin_command_buffer()?;
+        self.recorded_command_buffers.push(self.command_buffer);
+
+        Ok(())
+    }
+
+    pub fn end(&mut self) -> Result<(), VulkanError> {
+        if self.render_pass.is_some() {
+            self.record_error(VulkanError::Custom(
+                "Command list ended inside a render pass".to_owned(),
+            ));
+            self.end_render_pass();
+        }
+        if self.debug_group_depth > 0 {
+            self.record_error(VulkanError::Custom(
+                "Command list ended with an open debug group".to_owned(),
+            ));
+            for _ in 0..self.debug_group_depth {
+                self.end_debug_label();
+            }
+            self.debug_group_depth = 0;
+        }
+
+        unsafe { self.device.device().end_command_buffer(self.command_buffer) }?;
+
+        match self.error.take() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Keeps the first recording error so it can be reported by [`Self::end`].
+    fn record_error(&mut self, error: VulkanError) {
+        self.error.get_or_insert(error);
+    }
+
+    /// Returns `true` if the device enabled `vkCmdPipelineBarrier2` and friends.
+    fn has_synchronization_2(&self) -> bool {
+        self.device
+            .get_enabled_features()
+            .contains(PhysicalDeviceFeatures::SYNCHRONIZATION_2)
+    }
+
+    /// Begins the next unused command buffer of the pool, allocating one if all are in use.
+    fn begin_command_buffer(&mut self) -> Result<vk::CommandBuffer, VulkanError> {
+        let device = self.device.device();
+
+        let command_buffer = match self
+            .pooled_command_buffers
+            .get(self.recorded_command_buffers.len())
+        {
+            Some(command_buffer) => *command_buffer,
+            None => {
+                let command_buffer_allocate_info = vk::CommandBufferAllocateInfo::default()
+                    .command_pool(self.command_pool)
+                    .level(vk::CommandBufferLevel::PRIMARY)
+                    .command_buffer_count(1);
+
+                let command_buffer =
+                    unsafe { device.allocate_command_buffers(&command_buffer_allocate_info) }?[0];
+                self.device
+                    .set_object_name(command_buffer, self.label.as_deref());
+                self.pooled_command_buffers.push(command_buffer);
+                command_buffer
+            }
+        };
+
+        unsafe {
+            device.begin_command_buffer(command_buffer, &vk::CommandBufferBeginInfo::default())
+        }?;
+
+        Ok(command_buffer)
+    }
+
+    /// Transitions a tracked buffer to `access`, the barrier is recorded by the next
+    /// [`Self::flush_barriers`].
+    fn use_buffer(&mut self, buffer: &Buffer, access: ResourceAccess) {
+        let buffer = buffer.as_vulkan();
+
+        let Some(state) = buffer.state() else {
+            return;
+        };
+
+        if let Some(barrier) = state
+            .lock()
+            .unwrap()
+            .transition(access.for_buffer(), self.queue_type)
+        {
+            self.buffer_barriers
+                .push(to_vk_buffer_memory_barrier(*buffer.buffer(), &barrier));
+        }
+    }
+
+    /// Transitions the subresources of a tracked texture to `access` and returns the layout the
+    /// command has to use, the barriers are recorded by the next [`Self::flush_barriers`].
+    fn use_texture(
+        &mut self,
+        texture: &Texture,
+        mip_levels: Range<u32>,
+        array_layers: Range<u32>,
+        access: ResourceAccess,
+    ) -> vk::ImageLayout {
+        let vulkan_texture = texture.as_vulkan();
+
+        let Some(states) = vulkan_texture.states() else {
+            return UNTRACKED_IMAGE_LAYOUT;
+        };
+        let mut states = states.lock().unwrap();
+
+        let subresource_range = |mip_levels: Range<u32>, array_layers: Range<u32>| {
+            vk::ImageSubresourceRange::default()
+                .aspect_mask(to_vk_image_aspect_flags(TextureAspectFlags::from_format(
+                    texture.format(),
+                )))
+                .base_mip_level(mip_levels.start)
+                .level_count(mip_levels.len() as u32)
+                .base_array_layer(array_layers.start)
+                .layer_count(array_layers.len() as u32)
+        };
+
+        let mut barriers = Vec::new();
+        for mip_level in mip_levels.clone() {
+            for array_layer in array_layers.clone() {
+                let index = mip_level * texture.array_layers() + array_layer;
+
+                if let Some(barrier) = states[index as usize].transition(access, self.queue_type) {
+                    barriers.push((barrier, mip_level, array_layer));
+                }
+            }
+        }
+
+        let subresource_count = mip_levels.len() * array_layers.len();
+        let image = *vulkan_texture.image();
+
+        // Subresources usually share their state, so one barrier covers the whole range.
+        if barriers.len() == subresource_count
+            && barriers
+                .iter()
+                .all(|(barrier, ..)| *barrier == barriers[0].0)
+        {
+            self.image_barriers.push(to_vk_image_memory_barrier(
+                image,
+                &barriers[0].0,
+                subresource_range(mip_levels, array_layers),
+            ));
+        } else {
+            for (barrier, mip_level, array_layer) in barriers {
+                self.image_barriers.push(to_vk_image_memory_barrier(
+                    image,
+                    &barrier,
+                    subresource_range(mip_level..mip_level + 1, array_layer..array_layer + 1),
+                ));
+            }
+        }
+
+        access.layout
+    }
+
+    /// Transitions the subresources of `view` to `access`, see [`Self::use_texture`].
+    fn use_texture_view(&mut self, view: &TextureView, access: ResourceAccess) -> vk::ImageLayout {
+        let range = view.range();
+        let base_mip_level = range.base_mip_level;
+        let base_array_layer = range.base_array_layer;
+
+        self.use_texture(
+            view.texture(),
+            base_mip_level..base_mip_level + range.mip_level_count.unwrap_or(1),
+            base_array_layer..base_array_layer + range.array_layer_count.unwrap_or(1),
+            access,
+        )
+    }
+
+    /// Records the barriers collected since the last flush as a single batch.
+    fn flush_barriers(&mut self) {
+        if self.buffer_barriers.is_empty() && self.image_barriers.is_empty() {
+            return;
+        }
+
+        if !self.has_synchronization_2() {
+            self.record_error(VulkanError::Custom(
+                "Tracked resources require the SYNCHRONIZATION_2 feature".to_owned(),
+            ));
+            self.buffer_barriers.clear();
+            self.image_barriers.clear();
+            return;
+        }
+
+        let command_buffer = match &self.render_pass {
+            None => self.command_buffer,
+            Some(RenderPassBarriers {
+                command_buffer: Some(command_buffer),
+                ..
+            }) => *command_buffer,
+            Some(RenderPassBarriers {
+                index,
+                command_buffer: None,
+                ..
+            }) => {
+                let index = *index;
+
+                match self.begin_command_buffer() {
+                    Ok(command_buffer) => {
+                        self.recorded_command_buffers.insert(index, command_buffer);
+                        self.render_pass.as_mut().unwrap().command_buffer = Some(command_buffer);
+                        command_buffer
+                    }
+                    Err(e) => {
+                        self.record_error(e);
+                        self.buffer_barriers.clear();
+                        self.image_barriers.clear();
+                        return;
+                    }
+                }
+            }
+        };
+
+        let dependency_info = vk::DependencyInfo::default()
+            .buffer_memory_barriers(&self.buffer_barriers)
+            .image_memory_barriers(&self.image_barriers);
+
+        unsafe {
+            self.device
+                .device()
+                .cmd_pipeline_barrier2(command_buffer, &dependency_info);
+        }
+
+        self.buffer_barriers.clear();
+        self.image_barriers.clear();
+    }
+
+    pub fn copy_buffer(
+        &mut self,
+        src: &Buffer,
+        src_offset: u64,
+        dst: &Buffer,
+        dst_offset: u64,
+        size: u64,
+    ) {
+        let region = vk::BufferCopy {
+            src_offset,
+            dst_offset,
+            size,
+        };
+
+        self.use_buffer(src, ResourceAccess::TRANSFER_READ);
+        self.use_buffer(dst, ResourceAccess::TRANSFER_WRITE);
+        self.flush_barriers();
+
+        unsafe {
+            self.device.device().cmd_copy_buffer(
+                self.command_buffer,
+                *src.as_vulkan().buffer(),
+                *dst.as_vulkan().buffer(),
+                &[region],
+            );
+        }
+    }
+
+    pub fn copy_buffer_to_texture(
+        &mut self,
+        src: &Buffer,
+        dst: &Texture,
+        region: &BufferTextureCopy,
+    ) {
+        self.use_buffer(src, ResourceAccess::TRANSFER_READ);
+        let dst_layout = self.use_texture(
+            dst,
+            region.mip_level..region.mip_level + 1,
+            region.array_layer..region.array_layer + 1,
+            ResourceAccess::TRANSFER_WRITE,
+        );
+        self.flush_barriers();
+
+        unsafe {
+            self.device.device().cmd_copy_buffer_to_image(
+                self.command_buffer,
+                *src.as_vulkan().buffer(),
+                *dst.as_vulkan().image(),
+                dst_layout,
+                &[to_vk_buffer_image_copy(dst, region)],
+            );
+        }
+    }
+
+    pub fn copy_texture_to_buffer(
+        &mut self,
+        src: &Texture,
+        dst: &Buffer,
+        region: &BufferTextureCopy,
+    ) {
+        let src_layout = self.use_texture(
+            src,
+            region.mip_level..region.mip_level + 1,
+            region.array_layer..region.array_layer + 1,
+            ResourceAccess::TRANSFER_READ,
+        );
+        self.use_buffer(dst, ResourceAccess::TRANSFER_WRITE);
+        self.flush_barriers();
+
+        unsafe {
+            self.device.device().cmd_copy_image_to_buffer(
+                self.command_buffer,
+                *src.as_vulkan().image(),
+                src_layout,
+                *dst.as_vulkan().buffer(),
+                &[to_vk_buffer_image_copy(src, region)],
+            );
+        }
+    }
+
+    pub fn copy_texture(&mut self, src: &Texture, dst: &Texture, region: &TextureCopy) {
+        let src_layout = self.use_texture(
+            src,
+            region.src_mip_level..region.src_mip_level + 1,
+            region.src_array_layer..region.src_array_layer + 1,
+            ResourceAccess::TRANSFER_READ,
+        );
+        let dst_layout = self.use_texture(
+            dst,
+            region.dst_mip_level..region.dst_mip_level + 1,
+            region.dst_array_layer..region.dst_array_layer + 1,
+            ResourceAccess::TRANSFER_WRITE,
+        );
+        self.flush_barriers();
+
+        let region = vk::ImageCopy {
+            src_subresource: vk::ImageSubresourceLayers {
+                aspect_mask: to_vk_copy_aspect(src.format()),
+                mip_level: region.src_mip_level,
+                base_array_layer: region.src_array_layer,
+                layer_count: 1,
+            },
+            src_offset: to_vk_offset_3d(region.src_origin),
+            dst_subresource: vk::ImageSubresourceLayers {
+                aspect_mask: to_vk_copy_aspect(dst.format()),
+                mip_level: region.dst_mip_level,
+                base_array_layer: region.dst_array_layer,
+                layer_count: 1,
+            },
+            dst_offset: to_vk_offset_3d(region.dst_origin),
+            extent: vk::Extent3D {
+                width: region.extent.width,
+                height: region.extent.height,
+                depth: region.extent.depth,
+            },
+        };
+
+        unsafe {
+            self.device.device().cmd_copy_image(
+                self.command_buffer,
+                *src.as_vulkan().image(),
+                src_layout,
+                *dst.as_vulkan().image(),
+                dst_layout,
+                &[region],
+            );
+        }
+    }
+
+    pub fn clear_buffer(&mut self, buffer: &Buffer, offset: u64, size: u64, value: u8) {
+        self.use_buffer(buffer, ResourceAccess::TRANSFER_WRITE);
+        self.flush_barriers();
+
+        unsafe {
+            self.device.device().cmd_fill_buffer(
+                self.command_buffer,
+                *buffer.as_vulkan().buffer(),
+                offset,
+                size,
+                u32::from_ne_bytes([value; 4]),
+            );
+        }
+    }
+
+    pub fn clear_texture(
+        &mut self,
+        texture: &Texture,
+        range: &TextureSubresourceRange,
+        value: ClearValue,
+    ) {
+        let range = range.resolve(texture, texture.format());
+        let base_mip_level = range.base_mip_level;
+        let base_array_layer = range.base_array_layer;
+
+        let layout = self.use_texture(
+            texture,
+            base_mip_level..base_mip_level + range.mip_level_count.unwrap_or(0),
+            base_array_layer..base_array_layer + range.array_layer_count.unwrap_or(0),
+            ResourceAccess::TRANSFER_WRITE,
+        );
+        self.flush_barriers();
+
+        let range = to_vk_image_subresource_range(&range);
+        let image = *texture.as_vulkan().image();
+
+        match value {
+            ClearValue::Color(color) => unsafe {
+                self.device.device().cmd_clear_color_image(
+                    self.command_buffer,
+                    image,
+                    layout,
+                    &vk::ClearColorValue { float32: color },
+                    &[range],
+                );
+            },
+            ClearValue::DepthStencil { depth, stencil } => unsafe {
+                self.device.device().cmd_clear_depth_stencil_image(
+                    self.command_buffer,
+                    image,
+                    layout,
+                    &vk::ClearDepthStencilValue { depth, stencil },
+                    &[range],
+                );
+            },
+        }
+    }
+
+    /// Returns the source and destination family indices of `queue_transfer`.
+    fn queue_family_indices(&self, queue_transfer: Option<QueueTransfer>) -> (u32, u32) {
+        let Some(queue_transfer) = queue_transfer else {
+            return (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED);
+        };
+
+        let src_family_index = self.device.get_queue(queue_transfer.src).family_index();
+        let dst_family_index = self.device.get_queue(queue_transfer.dst).family_index();
+
+        // Queues of the same family share resources without a transfer.
+        if src_family_index == dst_family_index {
+            (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED)
+        } else {
+            (src_family_index, dst_family_index)
+        }
+    }
+
+    pub fn barrier(
+        &mut self,
+        buffer_barriers: &[BufferBarrier],
+        texture_barriers: &[TextureBarrier],
+        global_barriers: &[GlobalBarrier],
+    ) {
+        if self.render_pass.is_some() {
+            self.record_error(VulkanError::Custom(
+                "barrier is not allowed inside a render pass".to_owned(),
+            ));
+            return;
+        }
+        if !self.has_synchronization_2() {
+            self.record_error(VulkanError::Custom(
+                "barrier requires the SYNCHRONIZATION_2 feature".to_owned(),
+            ));
+            return;
+        }
+
+        if buffer_barriers
+            .iter()
+            .any(|barrier| barrier.buffer.as_vulkan().state().is_some())
+            || texture_barriers
+                .iter()
+                .any(|barrier| barrier.texture.as_vulkan().states().is_some())
+        {
+            self.record_error(VulkanError::Custom(
+                "barrier used on a tracked resource".to_owned(),
+            ));
+            return;
+        }
+
+        let queue_type = self.queue_type;
+
+        let memory_barriers = global_barriers
+            .iter()
+            .map(|barrier| {
+                let barrier = to_resource_barrier(
+                    barrier.before,
+                    barrier.after,
+                    queue_type,
+                    (vk::ImageLayout::UNDEFINED, vk::ImageLayout::UNDEFINED),
+                );
+
+                vk::MemoryBarrier2::default()
+                    .src_stage_mask(barrier.src_stages)
+                    .src_access_mask(barrier.src_access)
+                    .dst_stage_mask(barrier.dst_stages)
+                    .dst_access_mask(barrier.dst_access)
+            })
+            .collect::<Vec<_>>();
+
+        let buffer_memory_barriers = buffer_barriers
+            .iter()
+            .map(|barrier| {
+                let (src_family_index, dst_family_index) =
+                    self.queue_family_indices(barrier.queue_transfer);
+
+                to_vk_buffer_memory_barrier(
+                    *barrier.buffer.as_vulkan().buffer(),
+                    &to_resource_barrier(
+                        barrier.before,
+                        barrier.after,
+                        queue_type,
+                        (vk::ImageLayout::UNDEFINED, vk::ImageLayout::UNDEFINED),
+                    ),
+                )
+                .src_queue_family_index(src_family_index)
+                .dst_queue_family_index(dst_family_index)
+            })
+            .collect::<Vec<_>>();
+
+        let image_memory_barriers = texture_barriers
+            .iter()
+            .map(|barrier| {
+                let texture = barrier.texture;
+                let (src_family_index, dst_family_index) =
+                    self.queue_family_indices(barrier.queue_transfer);
+                let old_layout = if barrier.discard {
+                    vk::ImageLayout::UNDEFINED
+                } else {
+                    UNTRACKED_IMAGE_LAYOUT
+                };
+
+                to_vk_image_memory_barrier(
+                    *texture.as_vulkan().image(),
+                    &to_resource_barrier(
+                        barrier.before,
+                        barrier.after,
+                        queue_type,
+                        (old_layout, UNTRACKED_IMAGE_LAYOUT),
+                    ),
+                    to_vk_image_subresource_range(
+                        &barrier.range.resolve(texture, texture.format()),
+                    ),
+                )
+                .src_queue_family_index(src_family_index)
+                .dst_queue_family_index(dst_family_index)
+            })
+            .collect::<Vec<_>>();
+
+        let dependency_info = vk::DependencyInfo::default()
+            .memory_barriers(&memory_barriers)
+            .buffer_memory_barriers(&buffer_memory_barriers)
+            .image_memory_barriers(&image_memory_barriers);
+
+        unsafe {
+            self.device
+                .device()
+                .cmd_pipeline_barrier2(self.command_buffer, &dependency_info);
+        }
+    }
+
+    pub fn begin_render_pass(&mut self, desc: &RenderPassDesc) {
+        if !self
+            .device
+            .get_enabled_features()
+            .contains(PhysicalDeviceFeatures::DYNAMIC_RENDERING)
+        {
+            self.record_error(VulkanError::Custom(
+                "begin_render_pass requires the DYNAMIC_RENDERING feature".to_owned(),
+            ));
+            return;
+        }
+
+        if self.render_pass.is_some() {
+            self.record_error(VulkanError::Custom(
+                "begin_render_pass called inside a render pass".to_owned(),
+            ));
+            return;
+        }
+
+        let Some(first_view) = desc.first_view() else {
+            self.record_error(VulkanError::Custom(
+                "begin_render_pass requires at least one attachment".to_owned(),
+            ));
+            return;
+        };
+
+        let extent = first_view.extent();
+        let render_area = vk::Rect2D {
+            offset: vk::Offset2D { x: 0, y: 0 },
+            extent: vk::Extent2D {
+                width: extent.width,
+                height: extent.height,
+            },
+        };
+
+        let color_attachments = desc
+            .color_attachments
+            .iter()
+            .map(|color_attachment| {
+                let layout =
+                    self.use_texture_view(color_attachment.view, ResourceAccess::COLOR_ATTACHMENT);
+                let resolve_target = color_attachment.resolve_target.map(|resolve_target| {
+                    (
+                        resolve_target,
+                        self.use_texture_view(resolve_target, ResourceAccess::COLOR_ATTACHMENT),
+                    )
+                });
+
+                to_vk_rendering_attachment_info(
+                    color_attachment.view,
+                    layout,
+                    resolve_target,
+                    vk::ResolveModeFlags::AVERAGE,
+                )
+                .load_op(to_vk_attachment_load_op(color_attachment.load_op))
+                .store_op(to_vk_attachment_store_op(color_attachment.store_op))
+                .clear_value(vk::ClearValue {
+                    color: vk::ClearColorValue {
+                        float32: color_attachment.clear_color,
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut rendering_info = vk::RenderingInfo::default()
+            .render_area(render_area)
+            .layer_count(first_view.range().array_layer_count.unwrap_or(1))
+            .color_attachments(&color_attachments);
+
+        let depth_stencil_attachments = desc.depth_stencil_attachment.as_ref().map(|attachment| {
+            let clear_value = vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue {
+                    depth: attachment.clear_depth,
+                    stencil: attachment.clear_stencil,
+                },
+            };
+
+            let layout =
+                self.use_texture_view(attachment.view, ResourceAccess::DEPTH_STENCIL_ATTACHMENT);
+            let resolve_target = attachment.resolve_target.map(|resolve_target| {
+                (
+                    resolve_target,
+                    self.use_texture_view(resolve_target, ResourceAccess::DEPTH_STENCIL_RESOLVE),
+                )
+            });
+
+            // Depth and stencil resolve sample zero, the only mode every device supports.
+            let attachment_info = to_vk_rendering_attachment_info(
+                attachment.view,
+                layout,
+                resolve_target,
+                vk::ResolveModeFlags::SAMPLE_ZERO,
+            )
+            .clear_value(clear_value);
+
+            (
+                attachment_info
+                    .load_op(to_vk_attachment_load_op(attachment.depth_load_op))
+                    .store_op(to_vk_attachment_store_op(attachment.depth_store_op)),
+                attachment_info
+                    .load_op(to_vk_attachment_load_op(attachment.stencil_load_op))
+                    .store_op(to_vk_attachment_store_op(attachment.stencil_store_op)),
+                attachment.view.format(),
+            )
+        });
+        if let Some((depth_attachment, stencil_attachment, format)) = &depth_stencil_attachments {
+            if format.is_depth() {
+                rendering_info = rendering_info.depth_attachment(depth_attachment);
+            }
+            if format.is_stencil() {
+                rendering_info = rendering_info.stencil_attachment(stencil_attachment);
+            }
+        }
+
+        self.flush_barriers();
+
+        // The render pass starts a new command buffer, leaving room for the barriers of the
+        // commands recorded inside of it.
+        let command_buffer = match self.begin_command_buffer() {
+            Ok(command_buffer) => command_buffer,
+            Err(e) => {
+                self.record_error(e);
+                return;
+            }
+        };
+        if let Err(e) = unsafe { self.device.device().end_command_buffer(self.command_buffer) } {
+            self.record_error(e.into());
+        }
+
+        self.render_pass = Some(RenderPassBarriers {
+            index: self.recorded_command_buffers.len(),
+            command_buffer: None,
+            debug_group_depth: self.debug_group_depth,
+            labeled: desc.label.is_some(),
+        });
+        self.recorded_command_buffers.push(command_buffer);
+        self.command_buffer = command_buffer;
+        // Bindings made before the render pass are not inherited by the new command buffer.
+        self.graphics_layout = None;
+        self.compute_layout = None;
+
+        if let Some(label) = desc.label {
+            self.begin_debug_label(label);
+        }
+
+        let device = self.device.device();
+
+        unsafe {
+            device.cmd_begin_rendering(self.command_buffer, &rendering_info);
+            device.cmd_set_viewport(
+                self.command_buffer,
+                0,
+                &[vk::Viewport {
+                    x: 0.0,
+                    y: 0.0,
+                    width: extent.width as f32,
+                    height: extent.height as f32,
+                    min_depth: 0.0,
+                    max_depth: 1.0,
+                }],
+            );
+            device.cmd_set_scissor(self.command_buffer, 0, &[render_area]);
+        }
+    }
+
+    pub fn end_render_pass(&mut self) {
+        let Some(render_pass) = self.render_pass.take() else {
+            self.record_error(VulkanError::Custom(
+                "end_render_pass called outside of a render pass".to_owned(),
+            ));
+            return;
+        };
+
+        if self.debug_group_depth > render_pass.debug_group_depth {
+            self.record_error(VulkanError::Custom(
+                "end_render_pass called with an open debug group of the render pass".to_owned(),
+            ));
+            for _ in render_pass.debug_group_depth..self.debug_group_depth {
+                self.end_debug_label();
+            }
+            self.debug_group_depth = render_pass.debug_group_depth;
+        }
+
+        let device = self.device.device();
+
+        unsafe { device.cmd_end_rendering(self.command_buffer) };
+
+        if render_pass.labeled {
+            self.end_debug_label();
+        }
+
+        if let Some(command_buffer) = render_pass.command_buffer {
+            if let Err(e) = unsafe { device.end_command_buffer(command_buffer) } {
+                self.record_error(e.into());
+            }
+        }
+    }
+
+    pub fn set_viewport(&mut self, viewport: &Viewport) {
+        unsafe {
+            self.device.device().cmd_set_viewport(
+                self.command_buffer,
+                0,
+                &[vk::Viewport {
+                    x: viewport.x,
+                    y: viewport.y,
+                    width: viewport.width,
+                    height: viewport.height,
+                    min_depth: viewport.min_depth,
+                    max_depth: viewport.max_depth,
+                }],
+            );
+        }
+    }
+
+    pub fn set_scissor(&mut self, scissor: &ScissorRect) {
+        unsafe {
+            self.device.device().cmd_set_scissor(
+                self.command_buffer,
+                0,
+                &[vk::Rect2D {
+                    offset: vk::Offset2D {
+                        x: scissor.x as i32,
+                        y: scissor.y as i32,
+                    },
+                    extent: vk::Extent2D {
+                        width: scissor.width,
+                        height: scissor.height,
+                    },
+                }],
+            );
+        }
+    }
+
+    pub fn set_stencil_reference(&mut self, reference: u32) {
+        unsafe {
+            self.device.device().cmd_set_stencil_reference(
+                self.command_buffer,
+                vk::StencilFaceFlags::FRONT_AND_BACK,
+                reference,
+            );
+        }
+    }
+
+    pub fn set_blend_constants(&mut self, constants: [f32; 4]) {
+        unsafe {
+            self.device
+                .device()
+                .cmd_set_blend_constants(self.command_buffer, &constants);
+        }
+    }
+
+    pub fn bind_vertex_buffer(&mut self, slot: u32, buffer: &Buffer, offset: u64) {
+        self.use_buffer(buffer, ResourceAccess::VERTEX_BUFFER);
+        self.flush_barriers();
+
+        unsafe {
+            self.device.device().cmd_bind_vertex_buffers(
+                self.command_buffer,
+                slot,
+                &[*buffer.as_vulkan().buffer()],
+                &[offset],
+            );
+        }
+    }
+
+    pub fn bind_index_buffer(&mut self, buffer: &Buffer, offset: u64, format: IndexFormat) {
+        self.use_buffer(buffer, ResourceAccess::INDEX_BUFFER);
+        self.flush_barriers();
+
+        unsafe {
+            self.device.device().cmd_bind_index_buffer(
+                self.command_buffer,
+                *buffer.as_vulkan().buffer(),
+                offset,
+                to_vk_index_type(format),
+            );
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) {
+        unsafe {
+            self.device.device().cmd_draw(
+                self.command_buffer,
+                vertex_count,
+                instance_count,
+                first_vertex,
+                first_instance,
+            );
+        }
+    }
+
+    pub fn draw_indexed(
+        &mut self,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) {
+        unsafe {
+            self.device.device().cmd_draw_indexed(
+                self.command_buffer,
+                index_count,
+                instance_count,
+                first_index,
+                vertex_offset,
+                first_instance,
+            );
+        }
+    }
+
+    pub fn draw_mesh_tasks(&mut self, x: u32, y: u32, z: u32) {
+        let Some(mesh_shader_device) = self.device.ext_mesh_shader_device() else {
+            self.record_error(VulkanError::Custom(
+                "draw_mesh_tasks requires the MESH_SHADER feature".to_owned(),
+            ));
+            return;
+        };
+
+        unsafe { mesh_shader_device.cmd_draw_mesh_tasks(self.command_buffer, x, y, z) };
+    }
+
+    pub fn draw_mesh_tasks_indirect(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        draw_count: u32,
+        stride: u32,
+    ) {
+        self.use_buffer(buffer, ResourceAccess::INDIRECT_BUFFER);
+        self.flush_barriers();
+
+        let Some(mesh_shader_device) = self.device.ext_mesh_shader_device() else {
+            self.record_error(VulkanError::Custom(
+                "draw_mesh_tasks_indirect requires the MESH_SHADER feature".to_owned(),
+            ));
+            return;
+        };
+
+        unsafe {
+            mesh_shader_device.cmd_draw_mesh_tasks_indirect(
+                self.command_buffer,
+                *buffer.as_vulkan().buffer(),
+                offset,
+                draw_count,
+                stride,
+            )
+        };
+    }
+
+    pub fn draw_mesh_tasks_indirect_count(
+        &mut self,
+        buffer: &Buffer,
+        offset: u64,
+        count_buffer: &Buffer,
+        count_buffer_offset: u64,
+        max_draw_count: u32,
+        stride: u32,
+    ) {
+        self.use_buffer(buffer, ResourceAccess::INDIRECT_BUFFER);
+        self.use_buffer(count_buffer, ResourceAccess::INDIRECT_BUFFER);
+        self.flush_barriers();
+
+        let Some(mesh_shader_device) = self.device.ext_mesh_shader_device() else {
+            self.record_error(VulkanError::Custom(
+                "draw_mesh_tasks_indirect_count requires the MESH_SHADER feature".to_owned(),
+            ));
+            return;
+        };
+
+        if !self
+            .device
+            .get_enabled_features()
+            .contains(PhysicalDeviceFeatures::DRAW_INDIRECT_COUNT)
+        {
+            self.record_error(VulkanError::Custom(
+                "draw_mesh_tasks_indirect_count requires the DRAW_INDIRECT_COUNT feature"
+                    .to_owned(),
+            ));
+            return;
+        }
+
+        unsafe {
+            mesh_shader_device.cmd_draw_mesh_tasks_indirect_count(
+                self.command_buffer,
+                *buffer.as_vulkan().buffer(),
+                offset,
+                *count_buffer.as_vulkan().buffer(),
+                count_buffer_offset,
+                max_draw_count,
+                stride,
+            )
+        };
+    }
+
+    /// Binds the bindless heap to the set `layout` reserves for it, if any.
+    fn bind_bindless_heap(
+        &mut self,
+        pipeline_bind_point: vk::PipelineBindPoint,
+        layout: &VulkanPipelineLayout,
+    ) {
+        let (Some(set), Some(bindless_heap)) = (layout.bindless_set(), self.device.bindless_heap())
+        else {
+            return;
+        };
+
+        unsafe {
+            self.device.device().cmd_bind_descriptor_sets(
+                self.command_buffer,
+                pipeline_bind_point,
+                *layout.pipeline_layout(),
+                set,
+                &[bindless_heap.descriptor_set()],
+                &[],
+            );
+        }
+    }
+
+    pub fn bind_graphics_pipeline(&mut self, pipeline: &GraphicsPipeline) {
+        let pipeline = pipeline.as_vulkan();
+
+        unsafe {
+            self.device.device().cmd_bind_pipeline(
+                self.command_buffer,
+                vk::PipelineBindPoint::GRAPHICS,
+                *pipeline.pipeline(),
+            );
+        }
+
+        self.bind_bindless_heap(vk::PipelineBindPoint::GRAPHICS, pipeline.layout());
+        self.graphics_layout = Some(pipeline.layout().clone());
+    }
+
+    pub fn bind_compute_pipeline(&mut self, pipeline: &ComputePipeline) {
+        let pipeline = pipeline.as_vulkan();
+
+        unsafe {
+            self.device.device().cmd_bind_pipeline(
+                self.command_buffer,
+                vk::PipelineBindPoint::COMPUTE,
+                *pipeline.pipeline(),
+            );
+        }
+
+        self.bind_bindless_heap(vk::PipelineBindPoint::COMPUTE, pipeline.layout());
+        self.compute_layout = Some(pipeline.layout().clone());
+    }
+
+    pub fn bind_bind_group(&mut self, set: u32, bind_group: &BindGroup) {
+        let bind_group = bind_group.as_vulkan();
+
+        let (pipeline_bind_point, layout) = match &self.render_pass {
+            Some(_) => (vk::PipelineBindPoint::GRAPHICS, &self.graphics_layout),
+            None => (vk::PipelineBindPoint::COMPUTE, &self.compute_layout),
+        };
+        let Some(layout) = layout.clone() else {
+            self.record_error(VulkanError::Custom(
+                "bind_bind_group requires a bound pipeline".to_owned(),
+            ));
+            return;
+        };
+
+        if layout.bind_group_layout(set) != Some(bind_group.layout()) {
+            self.record_error(VulkanError::Custom(format!(
+                "Bind group layout does not match set {set} of the pipeline layout"
+            )));
+            return;
+        }
+        if !bind_group.is_allocated() {
+            self.record_error(VulkanError::Custom(
+                "Bind group used after its pool was reset".to_owned(),
+            ));
+            return;
+        }
+
+        for (buffer, access) in bind_group.tracked_buffers() {
+            self.use_buffer(buffer, *access);
+        }
+        for (view, access) in bind_group.tracked_texture_views() {
+            self.use_texture_view(view, *access);
+        }
+        self.flush_barriers();
+
+        unsafe {
+            self.device.device().cmd_bind_descriptor_sets(
+                self.command_buffer,
+                pipeline_bind_point,
+                *layout.pipeline_layout(),
+                set,
+                &[bind_group.descriptor_set()],
+                &[],
+            );
+        }
+    }
+
+    pub fn push_constants(&mut self, stages: ShaderStageFlags, offset: u32, data: &[u8]) {
+        let layout = match &self.render_pass {
+            Some(_) => &self.graphics_layout,
+            None => &self.compute_layout,
+        };
+        let Some(layout) = layout.clone() else {
+            self.record_error(VulkanError::Custom(
+                "push_constants requires a bound pipeline".to_owned(),
+            ));
+            return;
+        };
+
+        if let Err(e) = validate_push_constants(
+            layout.push_constant_ranges(),
+            stages,
+            offset,
+            data.len() as u32,
+        ) {
+            self.record_error(VulkanError::Custom(e));
+            return;
+        }
+
+        unsafe {
+            self.device.device().cmd_push_constants(
+                self.command_buffer,
+                *layout.pipeline_layout(),
+                to_vk_shader_stage_flags(stages),
+                offset,
+                data,
+            );
+        }
+    }
+
+    pub fn dispatch(&mut self, x: u32, y: u32, z: u32) {
+        unsafe {
+            self.device
+                .device()
+                .cmd_dispatch(self.command_buffer, x, y, z)
+        };
+    }
+
+    pub fn dispatch_indirect(&mut self, buffer: &Buffer, offset: u64) {
+        self.use_buffer(buffer, ResourceAccess::INDIRECT_BUFFER);
+        self.flush_barriers();
+
+        unsafe {
+            self.device.device().cmd_dispatch_indirect(
+                self.command_buffer,
+                *buffer.as_vulkan().buffer(),
+                offset,
+            );
+        }
+    }
+
+    pub fn reset_queries(&mut self, query_pool: &QueryPool, first: u32, count: u32) {
+        if self.render_pass.is_some() {
+            self.record_error(VulkanError::Custom(
+                "reset_queries is not allowed inside a render pass".to_owned(),
+            ));
+            return;
+        }
+
+        unsafe {
+            self.device.device().cmd_reset_query_pool(
+                self.command_buffer,
+                *query_pool.as_vulkan().query_pool(),
+                first,
+                count,
+            );
+        }
+    }
+
+    pub fn write_timestamp(&mut self, query_pool: &QueryPool, index: u32) {
+        let query_pool = *query_pool.as_vulkan().query_pool();
+        unsafe {
+            if self.has_synchronization_2() {
+                self.device.device().cmd_write_timestamp2(
+                    self.command_buffer,
+                    vk::PipelineStageFlags2::ALL_COMMANDS,
+                    query_pool,
+                    index,
+                );
+            } else {
+                self.device.device().cmd_write_timestamp(
+                    self.command_buffer,
+                    vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+                    query_pool,
+                    index,
+                );
+            }
+        }
+    }
+
+    pub fn begin_query(&mut self, query_pool: &QueryPool, index: u32) {
+        unsafe {
+            self.device.device().cmd_begin_query(
+                self.command_buffer,
+                *query_pool.as_vulkan().query_pool(),
+                index,
+                vk::QueryControlFlags::empty(),
+            );
+        }
+    }
+
+    pub fn end_query(&mut self, query_pool: &QueryPool, index: u32) {
+        unsafe {
+            self.device.device().cmd_end_query(
+                self.command_buffer,
+                *query_pool.as_vulkan().query_pool(),
+                index,
+            );
+        }
+    }
+
+    pub fn resolve_queries(
+        &mut self,
+        query_pool: &QueryPool,
+        first: u32,
+        count: u32,
+        dst: &Buffer,
+        dst_offset: u64,
+    ) {
+        if self.render_pass.is_some() {
+            self.record_error(VulkanError::Custom(
+                "resolve_queries is not allowed inside a render pass".to_owned(),
+            ));
+            return;
+        }
+
+        self.use_buffer(dst, ResourceAccess::TRANSFER_WRITE);
+        self.flush_barriers();
+
+        let query_pool = query_pool.as_vulkan();
+
+        unsafe {
+            self.device.device().cmd_copy_query_pool_results(
+                self.command_buffer,
+                *query_pool.query_pool(),
+                first,
+                count,
+                *dst.as_vulkan().buffer(),
+                dst_offset,
+                query_pool.query_type().result_size(),
+                vk::QueryResultFlags::TYPE_64 | vk::QueryResultFlags::WAIT,
+            );
+        }
+    }
+
+    fn begin_debug_label(&self, label: &str) {
+        let Some(debug_utils_device) = self.device.ext_debug_utils_device() else {
+            return;
+        };
+
+        let label = to_debug_label_name(label);
+        let debug_utils_label = vk::DebugUtilsLabelEXT::default().label_name(&label);
+
+        unsafe {
+            debug_utils_device.cmd_begin_debug_utils_label(self.command_buffer, &debug_utils_label)
+        };
+    }
+
+    fn end_debug_label(&self) {
+        if let Some(debug_utils_device) = self.device.ext_debug_utils_device() {
+            unsafe { debug_utils_device.cmd_end_debug_utils_label(self.command_buffer) };
+        }
+    }
+
+    pub fn push_debug_group(&mut self, label: &str) {
+        self.debug_group_depth += 1;
+        self.begin_debug_label(label);
+    }
+
+    pub fn pop_debug_group(&mut self) {
+        let render_pass_depth = self
+            .render_pass
+            .as_ref()
+            .map_or(0, |render_pass| render_pass.debug_group_depth);
+
+        if self.debug_group_depth == render_pass_depth {
+            self.record_error(VulkanError::Custom(if self.render_pass.is_some() {
+                "pop_debug_group called without a debug group of the render pass".to_owned()
+            } else {
+                "pop_debug_group called without an open debug group".to_owned()
+            }));
+            return;
+        }
+
+        self.debug_group_depth -= 1;
+        self.end_debug_label();
+    }
+
+    pub fn insert_debug_marker(&mut self, label: &str) {
+        let Some(debug_utils_device) = self.device.ext_debug_utils_device() else {
+            return;
+        };
+
+        let label = to_debug_label_name(label);
+        let debug_utils_label = vk::DebugUtilsLabelEXT::default().label_name(&label);
+
+        unsafe {
+            debug_utils_device.cmd_insert_debug_utils_label(self.command_buffer, &debug_utils_label)
+        };
+    }
+}
+
+impl Drop for VulkanCommandList {
+    fn drop(&mut self) {
+        unsafe {
+            self.device
+                .device()
+                .destroy_command_pool(self.command_pool, None);
+        }
+    }
+}
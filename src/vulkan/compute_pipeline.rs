@@ -0,0 +1,88 @@
+use std::{ffi::CString, sync::Arc};
+
+use ash::vk;
+
+use crate::{
+    vulkan::{to_vk_specialization_data, VulkanDevice, VulkanError, VulkanPipelineLayout},
+    ComputePipelineDesc,
+};
+
+struct Inner {
+    device: VulkanDevice,
+
+    pipeline: vk::Pipeline,
+    layout: VulkanPipelineLayout,
+    workgroup_size: [u32; 3],
+}
+
+#[derive(Clone)]
+pub struct VulkanComputePipeline(Arc<Inner>);
+
+impl VulkanComputePipeline {
+    pub fn new(
+        device: &VulkanDevice,
+        desc: &ComputePipelineDesc,
+        workgroup_size: [u32; 3],
+    ) -> Result<Self, VulkanError> {
+        let layout = desc.layout.as_vulkan().clone();
+
+        let entry_point = CString::new(desc.entry_point)
+            .map_err(|_| VulkanError::Custom("Entry point contains a nul byte".to_owned()))?;
+
+        let (map_entries, data) = to_vk_specialization_data(desc.specialization_constants);
+        let specialization_info = vk::SpecializationInfo::default()
+            .map_entries(&map_entries)
+            .data(&data);
+
+        let stage = vk::PipelineShaderStageCreateInfo::default()
+            .stage(vk::ShaderStageFlags::COMPUTE)
+            .module(*desc.shader_module.as_vulkan().shader_module())
+            .name(&entry_point)
+            .specialization_info(&specialization_info);
+
+        let compute_pipeline_create_info = vk::ComputePipelineCreateInfo::default()
+            .stage(stage)
+            .layout(*layout.pipeline_layout());
+
+        let pipeline = unsafe {
+            device.device().create_compute_pipelines(
+                vk::PipelineCache::null(),
+                &[compute_pipeline_create_info],
+                None,
+            )
+        }
+        .map_err(|(_, e)| e)?[0];
+        device.set_object_name(pipeline, desc.label);
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+
+            pipeline,
+            layout,
+            workgroup_size,
+        })))
+    }
+
+    #[inline]
+    pub fn pipeline(&self) -> &vk::Pipeline {
+        &self.0.pipeline
+    }
+
+    #[inline]
+    pub fn layout(&self) -> &VulkanPipelineLayout {
+        &self.0.layout
+    }
+
+    #[inline]
+    pub fn workgroup_size(&self) -> [u32; 3] {
+        self.0.workgroup_size
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.device().destroy_pipeline(self.pipeline, None);
+        }
+    }
+}
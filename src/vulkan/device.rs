@@ -1,19 +1,339 @@
-use std::sync::Arc;
+use std::{
+    ffi::{c_char, CStr, CString},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use ash::{ext::mesh_shader, vk};
+use ash::{
+    ext::{calibrated_timestamps, debug_utils, descriptor_buffer, memory_budget, mesh_shader},
+    khr::{
+        acceleration_structure, deferred_host_operations, fragment_shading_rate, ray_query,
+        ray_tracing_pipeline, swapchain,
+    },
+    prelude::VkResult,
+    vk,
+};
 use vk_mem_alloc::{Allocator, AllocatorCreateFlags, AllocatorCreateInfo};
 
 use crate::{
-    vulkan::{VulkanError, VulkanInstance},
-    DeviceDesc, PhysicalDevice,
+    vulkan::{
+        DescriptorSetLayoutCache, SamplerCache, VulkanBindlessHeap, VulkanError, VulkanInstance,
+        VulkanQueue, VulkanUploader,
+    },
+    CalibratedTimestamp, DeviceDesc, PhysicalDevice, PhysicalDeviceFeatures, QueueType,
 };
 
+/// Features the backend cannot work without, every queue tracks its submissions with a timeline
+/// semaphore.
+const REQUIRED_FEATURES: PhysicalDeviceFeatures = PhysicalDeviceFeatures::TIMELINE_SEMAPHORE;
+
+/// Features the backend itself relies on, enabled whenever the physical device supports them.
+const IMPLICIT_FEATURES: PhysicalDeviceFeatures = PhysicalDeviceFeatures::BUFFER_DEVICE_ADDRESS
+    .union(PhysicalDeviceFeatures::DYNAMIC_RENDERING)
+    .union(PhysicalDeviceFeatures::SYNCHRONIZATION_2);
+
+pub struct DeviceExtensions {
+    supported: Vec<vk::ExtensionProperties>,
+    enabled: Vec<*const c_char>,
+
+    ext_calibrated_timestamps: bool,
+    ext_descriptor_buffer: bool,
+    ext_memory_budget: bool,
+    ext_mesh_shader: bool,
+    khr_acceleration_structure: bool,
+    khr_deferred_host_operations: bool,
+    khr_fragment_shading_rate: bool,
+    khr_ray_query: bool,
+    khr_ray_tracing_pipeline: bool,
+    khr_swapchain: bool,
+}
+
+impl DeviceExtensions {
+    pub(crate) unsafe fn new(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+    ) -> VkResult<Self> {
+        let supported = instance.enumerate_device_extension_properties(physical_device)?;
+
+        Ok(Self {
+            supported,
+            enabled: Vec::new(),
+
+            ext_calibrated_timestamps: false,
+            ext_descriptor_buffer: false,
+            ext_memory_budget: false,
+            ext_mesh_shader: false,
+            khr_acceleration_structure: false,
+            khr_deferred_host_operations: false,
+            khr_fragment_shading_rate: false,
+            khr_ray_query: false,
+            khr_ray_tracing_pipeline: false,
+            khr_swapchain: false,
+        })
+    }
+
+    #[inline]
+    pub fn is_supported(&self, name: &CStr) -> bool {
+        self.supported
+            .iter()
+            .any(|e| e.extension_name_as_c_str() == Ok(name))
+    }
+
+    #[inline]
+    fn push(&mut self, name: &'static CStr) -> Result<(), VulkanError> {
+        if self.is_supported(name) {
+            if !self.enabled.contains(&name.as_ptr()) {
+                self.enabled.push(name.as_ptr());
+            }
+            Ok(())
+        } else {
+            Err(VulkanError::Custom(format!(
+                "Failed to push extension: {}",
+                name.to_str()?
+            )))
+        }
+    }
+
+    #[inline]
+    pub fn push_ext_calibrated_timestamps(&mut self) -> Result<(), VulkanError> {
+        let result = self.push(calibrated_timestamps::NAME);
+
+        if result.is_ok() {
+            self.ext_calibrated_timestamps = true;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn push_ext_descriptor_buffer(&mut self) -> Result<(), VulkanError> {
+        let result = self.push(descriptor_buffer::NAME);
+
+        if result.is_ok() {
+            self.ext_descriptor_buffer = true;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn push_ext_memory_budget(&mut self) -> Result<(), VulkanError> {
+        let result = self.push(memory_budget::NAME);
+
+        if result.is_ok() {
+            self.ext_memory_budget = true;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn push_ext_mesh_shader(&mut self) -> Result<(), VulkanError> {
+        let result = self.push(mesh_shader::NAME);
+
+        if result.is_ok() {
+            self.ext_mesh_shader = true;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn push_khr_acceleration_structure(&mut self) -> Result<(), VulkanError> {
+        let result = self
+            .push(deferred_host_operations::NAME)
+            .and_then(|_| self.push(acceleration_structure::NAME));
+
+        if result.is_ok() {
+            self.khr_deferred_host_operations = true;
+            self.khr_acceleration_structure = true;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn push_khr_fragment_shading_rate(&mut self) -> Result<(), VulkanError> {
+        let result = self.push(fragment_shading_rate::NAME);
+
+        if result.is_ok() {
+            self.khr_fragment_shading_rate = true;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn push_khr_ray_query(&mut self) -> Result<(), VulkanError> {
+        let result = self.push(ray_query::NAME);
+
+        if result.is_ok() {
+            self.khr_ray_query = true;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn push_khr_ray_tracing_pipeline(&mut self) -> Result<(), VulkanError> {
+        let result = self.push(ray_tracing_pipeline::NAME);
+
+        if result.is_ok() {
+            self.khr_ray_tracing_pipeline = true;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn push_khr_swapchain(&mut self) -> Result<(), VulkanError> {
+        let result = self.push(swapchain::NAME);
+
+        if result.is_ok() {
+            self.khr_swapchain = true;
+        }
+
+        result
+    }
+
+    #[inline]
+    pub fn ext_calibrated_timestamps(&self) -> bool {
+        self.ext_calibrated_timestamps
+    }
+
+    #[inline]
+    pub fn ext_descriptor_buffer(&self) -> bool {
+        self.ext_descriptor_buffer
+    }
+
+    #[inline]
+    pub fn ext_memory_budget(&self) -> bool {
+        self.ext_memory_budget
+    }
+
+    #[inline]
+    pub fn ext_mesh_shader(&self) -> bool {
+        self.ext_mesh_shader
+    }
+
+    #[inline]
+    pub fn khr_acceleration_structure(&self) -> bool {
+        self.khr_acceleration_structure
+    }
+
+    #[inline]
+    pub fn khr_deferred_host_operations(&self) -> bool {
+        self.khr_deferred_host_operations
+    }
+
+    #[inline]
+    pub fn khr_fragment_shading_rate(&self) -> bool {
+        self.khr_fragment_shading_rate
+    }
+
+    #[inline]
+    pub fn khr_ray_query(&self) -> bool {
+        self.khr_ray_query
+    }
+
+    #[inline]
+    pub fn khr_ray_tracing_pipeline(&self) -> bool {
+        self.khr_ray_tracing_pipeline
+    }
+
+    #[inline]
+    pub fn khr_swapchain(&self) -> bool {
+        self.khr_swapchain
+    }
+}
+
+unsafe impl Send for DeviceExtensions {}
+unsafe impl Sync for DeviceExtensions {}
+
+/// A `vk::Queue` together with the lock guarding its external synchronization.
+pub(crate) struct QueueEntry {
+    pub(crate) queue: vk::Queue,
+    pub(crate) family_index: u32,
+    /// Timeline semaphore every submission signals.
+    pub(crate) submission_semaphore: vk::Semaphore,
+    /// Holds the value of the last submission.
+    pub(crate) lock: Mutex<u64>,
+}
+
 struct Inner {
     device: ash::Device,
 
-    ext_mesh_shader_device: mesh_shader::Device,
+    queues: Vec<QueueEntry>,
+    graphics_queue_index: usize,
+    compute_queue_index: usize,
+    transfer_queue_index: usize,
+
+    enabled_features: PhysicalDeviceFeatures,
+    extensions: DeviceExtensions,
+    limits: vk::PhysicalDeviceLimits,
+
+    ext_calibrated_timestamps_device: Option<calibrated_timestamps::Device>,
+    ext_debug_utils_device: Option<debug_utils::Device>,
+    ext_descriptor_buffer_device: Option<descriptor_buffer::Device>,
+    ext_mesh_shader_device: Option<mesh_shader::Device>,
+    khr_acceleration_structure_device: Option<acceleration_structure::Device>,
+    khr_fragment_shading_rate_device: Option<fragment_shading_rate::Device>,
+    khr_ray_tracing_pipeline_device: Option<ray_tracing_pipeline::Device>,
+    khr_swapchain_device: Option<swapchain::Device>,
 
     allocator: Allocator,
+
+    bindless_heap: Option<VulkanBindlessHeap>,
+    descriptor_set_layout_cache: DescriptorSetLayoutCache,
+    sampler_cache: SamplerCache,
+    uploader: VulkanUploader,
+}
+
+/// The clock `std::time::Instant` is based on, calibrated timestamps are only sampled where it
+/// is a Vulkan time domain.
+const CPU_TIME_DOMAIN: vk::TimeDomainEXT = vk::TimeDomainEXT::CLOCK_MONOTONIC;
+
+/// Must only be called once `VK_EXT_calibrated_timestamps` is known to be supported.
+fn supports_cpu_time_domain(
+    instance: &VulkanInstance,
+    physical_device: vk::PhysicalDevice,
+) -> bool {
+    if !cfg!(any(target_os = "linux", target_os = "android")) {
+        return false;
+    }
+
+    let calibrated_timestamps_instance =
+        calibrated_timestamps::Instance::new(instance.entry(), instance.instance());
+
+    let time_domains = unsafe {
+        calibrated_timestamps_instance
+            .get_physical_device_calibrateable_time_domains(physical_device)
+    };
+
+    time_domains.is_ok_and(|time_domains| {
+        time_domains.contains(&vk::TimeDomainEXT::DEVICE) && time_domains.contains(&CPU_TIME_DOMAIN)
+    })
+}
+
+/// Converts a `CLOCK_MONOTONIC` time in nanoseconds to an `Instant`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn cpu_time_to_instant(nanoseconds: u64) -> Instant {
+    let now = Instant::now();
+
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut time) };
+    let now_nanoseconds = time.tv_sec as u64 * 1_000_000_000 + time.tv_nsec as u64;
+
+    let elapsed = Duration::from_nanos(now_nanoseconds.saturating_sub(nanoseconds));
+    now.checked_sub(elapsed).unwrap_or(now)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn cpu_time_to_instant(_nanoseconds: u64) -> Instant {
+    unreachable!("calibrated timestamps are only enabled with a CLOCK_MONOTONIC time domain")
 }
 
 fn find_direct_queue_family_index(properties: &[vk::QueueFamilyProperties]) -> Option<u32> {
@@ -115,6 +435,26 @@ fn find_queue_family_indices(properties: &[vk::QueueFamilyProperties]) -> Option
     Some((direct_index, compute_index, transfer_index))
 }
 
+/// Destroys what [`VulkanDevice::new`] created before one of its later steps failed.
+unsafe fn destroy_partial_device(
+    device: &ash::Device,
+    queues: &[QueueEntry],
+    allocator: Option<Allocator>,
+    bindless_heap: Option<&VulkanBindlessHeap>,
+) {
+    if let Some(bindless_heap) = bindless_heap {
+        bindless_heap.destroy(device);
+    }
+    if let Some(allocator) = allocator {
+        vk_mem_alloc::destroy_allocator(allocator);
+    }
+    for entry in queues {
+        device.destroy_semaphore(entry.submission_semaphore, None);
+    }
+
+    device.destroy_device(None);
+}
+
 #[derive(Clone)]
 pub struct VulkanDevice(Arc<Inner>);
 
@@ -137,28 +477,173 @@ impl VulkanDevice {
 
         let queue_priorities = [1.0];
 
-        let mut device_queue_create_infos = vec![vk::DeviceQueueCreateInfo::default()
-            .queue_family_index(direct_queue_family_index)
-            .queue_priorities(&queue_priorities)];
+        // The direct family always comes first, compute and transfer alias it or each other when
+        // the hardware offers no dedicated family.
+        let mut queue_family_indices = vec![direct_queue_family_index];
 
-        if compute_queue_family_index != direct_queue_family_index {
-            device_queue_create_infos.push(
-                vk::DeviceQueueCreateInfo::default()
-                    .queue_family_index(compute_queue_family_index)
-                    .queue_priorities(&queue_priorities),
-            );
+        for family_index in [compute_queue_family_index, transfer_queue_family_index] {
+            if !queue_family_indices.contains(&family_index) {
+                queue_family_indices.push(family_index);
+            }
         }
 
-        if transfer_queue_family_index != direct_queue_family_index {
-            device_queue_create_infos.push(
+        let device_queue_create_infos = queue_family_indices
+            .iter()
+            .map(|family_index| {
                 vk::DeviceQueueCreateInfo::default()
-                    .queue_family_index(transfer_queue_family_index)
-                    .queue_priorities(&queue_priorities),
+                    .queue_family_index(*family_index)
+                    .queue_priorities(&queue_priorities)
+            })
+            .collect::<Vec<_>>();
+
+        let missing_features =
+            REQUIRED_FEATURES.difference(physical_device.get_supported_features());
+        if !missing_features.is_empty() {
+            return Err(VulkanError::Custom(format!(
+                "Physical device lacks features the Vulkan backend requires: {missing_features:?}"
+            )));
+        }
+
+        let enabled_features = desc.enabled_features()
+            | REQUIRED_FEATURES
+            | (IMPLICIT_FEATURES & physical_device.get_supported_features());
+
+        let mut extensions = unsafe {
+            DeviceExtensions::new(instance.instance(), *physical_device.physical_device())
+        }?;
+
+        if enabled_features.contains(PhysicalDeviceFeatures::MESH_SHADER) {
+            extensions.push_ext_mesh_shader()?;
+        }
+        if enabled_features.intersects(
+            PhysicalDeviceFeatures::RAY_TRACING_PIPELINE | PhysicalDeviceFeatures::RAY_QUERY,
+        ) {
+            extensions.push_khr_acceleration_structure()?;
+        }
+        if enabled_features.contains(PhysicalDeviceFeatures::RAY_TRACING_PIPELINE) {
+            extensions.push_khr_ray_tracing_pipeline()?;
+        }
+        if enabled_features.contains(PhysicalDeviceFeatures::RAY_QUERY) {
+            extensions.push_khr_ray_query()?;
+        }
+        if enabled_features.contains(PhysicalDeviceFeatures::VARIABLE_RATE_SHADING) {
+            extensions.push_khr_fragment_shading_rate()?;
+        }
+        if enabled_features.contains(PhysicalDeviceFeatures::DESCRIPTOR_BUFFER) {
+            extensions.push_ext_descriptor_buffer()?;
+        }
+
+        // Not tied to a feature, enabled whenever available.
+        let _ = extensions.push_khr_swapchain();
+        let _ = extensions.push_ext_memory_budget();
+        if extensions.is_supported(calibrated_timestamps::NAME)
+            && supports_cpu_time_domain(instance, *physical_device.physical_device())
+        {
+            let _ = extensions.push_ext_calibrated_timestamps();
+        }
+
+        let enabled = |feature| {
+            if enabled_features.contains(feature) {
+                vk::TRUE
+            } else {
+                vk::FALSE
+            }
+        };
+
+        let descriptor_indexing = enabled(PhysicalDeviceFeatures::DESCRIPTOR_INDEXING);
+
+        let core_features = vk::PhysicalDeviceFeatures::default()
+            .shader_int16(enabled_features.contains(PhysicalDeviceFeatures::SHADER_INT16))
+            .shader_int64(enabled_features.contains(PhysicalDeviceFeatures::SHADER_INT64))
+            .shader_float64(enabled_features.contains(PhysicalDeviceFeatures::SHADER_FLOAT64))
+            .sampler_anisotropy(
+                enabled_features.contains(PhysicalDeviceFeatures::SAMPLER_ANISOTROPY),
+            )
+            .pipeline_statistics_query(
+                enabled_features.contains(PhysicalDeviceFeatures::PIPELINE_STATISTICS_QUERY),
             );
+
+        let mut vulkan_11_features = vk::PhysicalDeviceVulkan11Features {
+            storage_buffer16_bit_access: enabled(PhysicalDeviceFeatures::STORAGE_16BIT),
+            uniform_and_storage_buffer16_bit_access: enabled(PhysicalDeviceFeatures::STORAGE_16BIT),
+            ..Default::default()
+        };
+
+        let mut vulkan_12_features = vk::PhysicalDeviceVulkan12Features {
+            draw_indirect_count: enabled(PhysicalDeviceFeatures::DRAW_INDIRECT_COUNT),
+            sampler_filter_minmax: enabled(PhysicalDeviceFeatures::SAMPLER_FILTER_MINMAX),
+            shader_float16: enabled(PhysicalDeviceFeatures::SHADER_FLOAT16),
+            descriptor_indexing,
+            shader_sampled_image_array_non_uniform_indexing: descriptor_indexing,
+            shader_storage_buffer_array_non_uniform_indexing: descriptor_indexing,
+            shader_storage_image_array_non_uniform_indexing: descriptor_indexing,
+            descriptor_binding_sampled_image_update_after_bind: descriptor_indexing,
+            descriptor_binding_storage_image_update_after_bind: descriptor_indexing,
+            descriptor_binding_storage_buffer_update_after_bind: descriptor_indexing,
+            descriptor_binding_partially_bound: descriptor_indexing,
+            descriptor_binding_update_unused_while_pending: descriptor_indexing,
+            descriptor_binding_variable_descriptor_count: descriptor_indexing,
+            runtime_descriptor_array: descriptor_indexing,
+            timeline_semaphore: enabled(PhysicalDeviceFeatures::TIMELINE_SEMAPHORE),
+            buffer_device_address: enabled(PhysicalDeviceFeatures::BUFFER_DEVICE_ADDRESS),
+            ..Default::default()
+        };
+
+        let mut vulkan_13_features = vk::PhysicalDeviceVulkan13Features {
+            synchronization2: enabled(PhysicalDeviceFeatures::SYNCHRONIZATION_2),
+            dynamic_rendering: enabled(PhysicalDeviceFeatures::DYNAMIC_RENDERING),
+            ..Default::default()
+        };
+
+        let mut mesh_shader_features = vk::PhysicalDeviceMeshShaderFeaturesEXT::default()
+            .task_shader(true)
+            .mesh_shader(true);
+        let mut acceleration_structure_features =
+            vk::PhysicalDeviceAccelerationStructureFeaturesKHR::default()
+                .acceleration_structure(true);
+        let mut ray_tracing_pipeline_features =
+            vk::PhysicalDeviceRayTracingPipelineFeaturesKHR::default().ray_tracing_pipeline(true);
+        let mut ray_query_features =
+            vk::PhysicalDeviceRayQueryFeaturesKHR::default().ray_query(true);
+        let mut fragment_shading_rate_features =
+            vk::PhysicalDeviceFragmentShadingRateFeaturesKHR::default()
+                .pipeline_fragment_shading_rate(true);
+        let mut descriptor_buffer_features =
+            vk::PhysicalDeviceDescriptorBufferFeaturesEXT::default().descriptor_buffer(true);
+
+        let mut features = vk::PhysicalDeviceFeatures2::default()
+            .features(core_features)
+            .push_next(&mut vulkan_11_features)
+            .push_next(&mut vulkan_12_features);
+
+        if physical_device.properties().api_version >= vk::API_VERSION_1_3 {
+            features = features.push_next(&mut vulkan_13_features);
+        }
+        if enabled_features.contains(PhysicalDeviceFeatures::MESH_SHADER) {
+            features = features.push_next(&mut mesh_shader_features);
+        }
+        if enabled_features.intersects(
+            PhysicalDeviceFeatures::RAY_TRACING_PIPELINE | PhysicalDeviceFeatures::RAY_QUERY,
+        ) {
+            features = features.push_next(&mut acceleration_structure_features);
+        }
+        if enabled_features.contains(PhysicalDeviceFeatures::RAY_TRACING_PIPELINE) {
+            features = features.push_next(&mut ray_tracing_pipeline_features);
+        }
+        if enabled_features.contains(PhysicalDeviceFeatures::RAY_QUERY) {
+            features = features.push_next(&mut ray_query_features);
+        }
+        if enabled_features.contains(PhysicalDeviceFeatures::VARIABLE_RATE_SHADING) {
+            features = features.push_next(&mut fragment_shading_rate_features);
+        }
+        if enabled_features.contains(PhysicalDeviceFeatures::DESCRIPTOR_BUFFER) {
+            features = features.push_next(&mut descriptor_buffer_features);
         }
 
-        let device_create_info =
-            vk::DeviceCreateInfo::default().queue_create_infos(&device_queue_create_infos);
+        let device_create_info = vk::DeviceCreateInfo::default()
+            .queue_create_infos(&device_queue_create_infos)
+            .enabled_extension_names(&extensions.enabled)
+            .push_next(&mut features);
 
         let device = unsafe {
             instance.instance().create_device(
@@ -168,7 +653,73 @@ impl VulkanDevice {
             )
         }?;
 
-        let ext_mesh_shader_device = mesh_shader::Device::new(instance.instance(), &device);
+        let mut queues = Vec::with_capacity(queue_family_indices.len());
+
+        for family_index in &queue_family_indices {
+            let mut semaphore_type_create_info = vk::SemaphoreTypeCreateInfo::default()
+                .semaphore_type(vk::SemaphoreType::TIMELINE)
+                .initial_value(0);
+            let semaphore_create_info =
+                vk::SemaphoreCreateInfo::default().push_next(&mut semaphore_type_create_info);
+
+            let submission_semaphore = unsafe {
+                device
+                    .create_semaphore(&semaphore_create_info, None)
+                    .inspect_err(|_| destroy_partial_device(&device, &queues, None, None))
+            }?;
+
+            queues.push(QueueEntry {
+                queue: unsafe { device.get_device_queue(*family_index, 0) },
+                family_index: *family_index,
+                submission_semaphore,
+                lock: Mutex::new(0),
+            });
+        }
+
+        let queue_index = |family_index| {
+            queue_family_indices
+                .iter()
+                .position(|i| *i == family_index)
+                .unwrap()
+        };
+
+        let graphics_queue_index = queue_index(direct_queue_family_index);
+        let compute_queue_index = queue_index(compute_queue_family_index);
+        let transfer_queue_index = queue_index(transfer_queue_family_index);
+
+        let ext_calibrated_timestamps_device = extensions
+            .ext_calibrated_timestamps
+            .then(|| calibrated_timestamps::Device::new(instance.instance(), &device));
+        let ext_debug_utils_device = instance
+            .ext_debug_utils()
+            .then(|| debug_utils::Device::new(instance.instance(), &device));
+        let ext_descriptor_buffer_device = extensions
+            .ext_descriptor_buffer
+            .then(|| descriptor_buffer::Device::new(instance.instance(), &device));
+        let ext_mesh_shader_device = extensions
+            .ext_mesh_shader
+            .then(|| mesh_shader::Device::new(instance.instance(), &device));
+        let khr_acceleration_structure_device = extensions
+            .khr_acceleration_structure
+            .then(|| acceleration_structure::Device::new(instance.instance(), &device));
+        let khr_fragment_shading_rate_device = extensions
+            .khr_fragment_shading_rate
+            .then(|| fragment_shading_rate::Device::new(instance.instance(), &device));
+        let khr_ray_tracing_pipeline_device = extensions
+            .khr_ray_tracing_pipeline
+            .then(|| ray_tracing_pipeline::Device::new(instance.instance(), &device));
+        let khr_swapchain_device = extensions
+            .khr_swapchain
+            .then(|| swapchain::Device::new(instance.instance(), &device));
+
+        let mut allocator_create_flags = AllocatorCreateFlags::empty();
+
+        if enabled_features.contains(PhysicalDeviceFeatures::BUFFER_DEVICE_ADDRESS) {
+            allocator_create_flags |= AllocatorCreateFlags::BUFFER_DEVICE_ADDRESS;
+        }
+        if extensions.ext_memory_budget {
+            allocator_create_flags |= AllocatorCreateFlags::EXT_MEMORY_BUDGET;
+        }
 
         let allocator = unsafe {
             vk_mem_alloc::create_allocator(
@@ -176,17 +727,72 @@ impl VulkanDevice {
                 *physical_device.physical_device(),
                 &device,
                 Some(&AllocatorCreateInfo {
-                    flags: AllocatorCreateFlags::BUFFER_DEVICE_ADDRESS,
+                    flags: allocator_create_flags,
                     ..Default::default()
                 }),
             )
+            .inspect_err(|_| destroy_partial_device(&device, &queues, None, None))
+        }?;
+
+        // Recycling bindless indices waits for the submission timelines.
+        let bindless_heap = if enabled_features.contains(
+            PhysicalDeviceFeatures::DESCRIPTOR_INDEXING
+                | PhysicalDeviceFeatures::TIMELINE_SEMAPHORE,
+        ) {
+            let mut vulkan_12_properties = vk::PhysicalDeviceVulkan12Properties::default();
+            let mut properties =
+                vk::PhysicalDeviceProperties2::default().push_next(&mut vulkan_12_properties);
+
+            unsafe {
+                instance.instance().get_physical_device_properties2(
+                    *physical_device.physical_device(),
+                    &mut properties,
+                )
+            };
+
+            Some(unsafe {
+                VulkanBindlessHeap::new(&device, &vulkan_12_properties).inspect_err(|_| {
+                    destroy_partial_device(&device, &queues, Some(allocator), None)
+                })
+            }?)
+        } else {
+            None
+        };
+
+        // Uploads are tracked on the submission timeline of the transfer queue.
+        let uploader = unsafe {
+            VulkanUploader::new(&device, transfer_queue_family_index).inspect_err(|_| {
+                destroy_partial_device(&device, &queues, Some(allocator), bindless_heap.as_ref())
+            })
         }?;
 
         Ok(Self(Arc::new(Inner {
             device,
 
+            queues,
+            graphics_queue_index,
+            compute_queue_index,
+            transfer_queue_index,
+
+            enabled_features,
+            extensions,
+            limits: physical_device.properties().limits,
+
+            ext_calibrated_timestamps_device,
+            ext_debug_utils_device,
+            ext_descriptor_buffer_device,
             ext_mesh_shader_device,
+            khr_acceleration_structure_device,
+            khr_fragment_shading_rate_device,
+            khr_ray_tracing_pipeline_device,
+            khr_swapchain_device,
+
             allocator,
+
+            bindless_heap,
+            descriptor_set_layout_cache: Default::default(),
+            sampler_cache: Default::default(),
+            uploader,
         })))
     }
 
@@ -196,14 +802,218 @@ impl VulkanDevice {
     }
 
     #[inline]
-    pub fn ext_mesh_shader_device(&self) -> &mesh_shader::Device {
-        &self.0.ext_mesh_shader_device
+    pub fn get_enabled_features(&self) -> PhysicalDeviceFeatures {
+        self.0.enabled_features
+    }
+
+    #[inline]
+    pub fn limits(&self) -> &vk::PhysicalDeviceLimits {
+        &self.0.limits
+    }
+
+    #[inline]
+    pub fn get_timestamp_period(&self) -> f32 {
+        self.0.limits.timestamp_period
+    }
+
+    /// Samples the device timestamp together with the CPU clock, `None` without
+    /// `VK_EXT_calibrated_timestamps`.
+    pub fn get_calibrated_timestamp(&self) -> Result<Option<CalibratedTimestamp>, VulkanError> {
+        let Some(calibrated_timestamps_device) = &self.0.ext_calibrated_timestamps_device else {
+            return Ok(None);
+        };
+
+        let timestamp_infos = [
+            vk::CalibratedTimestampInfoEXT::default().time_domain(vk::TimeDomainEXT::DEVICE),
+            vk::CalibratedTimestampInfoEXT::default().time_domain(CPU_TIME_DOMAIN),
+        ];
+
+        let (timestamps, _max_deviation) =
+            unsafe { calibrated_timestamps_device.get_calibrated_timestamps(&timestamp_infos) }?;
+
+        Ok(Some(CalibratedTimestamp {
+            gpu_ticks: timestamps[0],
+            cpu_instant: cpu_time_to_instant(timestamps[1]),
+        }))
+    }
+
+    #[inline]
+    pub fn get_queue(&self, queue_type: QueueType) -> VulkanQueue {
+        VulkanQueue::new(self, queue_type)
+    }
+
+    #[inline]
+    pub(crate) fn queue_index(&self, queue_type: QueueType) -> usize {
+        match queue_type {
+            QueueType::Graphics => self.0.graphics_queue_index,
+            QueueType::Compute => self.0.compute_queue_index,
+            QueueType::Transfer => self.0.transfer_queue_index,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn queue_entry(&self, index: usize) -> &QueueEntry {
+        &self.0.queues[index]
+    }
+
+    /// Returns the value of the last submission to every device queue.
+    pub(crate) fn submitted_values(&self) -> Vec<u64> {
+        self.0
+            .queues
+            .iter()
+            .map(|entry| *entry.lock.lock().unwrap())
+            .collect()
+    }
+
+    /// Returns `true` once every device queue completed the submission with the given value, as
+    /// returned by [`Self::submitted_values`].
+    pub(crate) fn are_submissions_complete(&self, values: &[u64]) -> Result<bool, VulkanError> {
+        for (entry, value) in self.0.queues.iter().zip(values) {
+            let completed = unsafe {
+                self.0
+                    .device
+                    .get_semaphore_counter_value(entry.submission_semaphore)
+            }?;
+
+            if completed < *value {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Returns the distinct queue family indices of the device queues.
+    #[inline]
+    pub(crate) fn queue_family_indices(&self) -> Vec<u32> {
+        self.0
+            .queues
+            .iter()
+            .map(|entry| entry.family_index)
+            .collect()
+    }
+
+    #[inline]
+    pub fn extensions(&self) -> &DeviceExtensions {
+        &self.0.extensions
+    }
+
+    #[inline]
+    pub fn ext_calibrated_timestamps_device(&self) -> Option<&calibrated_timestamps::Device> {
+        self.0.ext_calibrated_timestamps_device.as_ref()
+    }
+
+    /// Returns the debug utils functions, `None` unless the instance enabled validation.
+    #[inline]
+    pub fn ext_debug_utils_device(&self) -> Option<&debug_utils::Device> {
+        self.0.ext_debug_utils_device.as_ref()
+    }
+
+    /// Names `object` in validation messages and captures, a no-op without debug utils.
+    pub(crate) fn set_object_name<T: vk::Handle>(&self, object: T, name: Option<&str>) {
+        let (Some(debug_utils_device), Some(name)) = (&self.0.ext_debug_utils_device, name) else {
+            return;
+        };
+        let Ok(name) = CString::new(name) else {
+            return;
+        };
+
+        let object_name_info = vk::DebugUtilsObjectNameInfoEXT::default()
+            .object_handle(object)
+            .object_name(&name);
+
+        // Naming is best effort, a failure must not fail object creation.
+        let _ = unsafe { debug_utils_device.set_debug_utils_object_name(&object_name_info) };
+    }
+
+    #[inline]
+    pub fn ext_descriptor_buffer_device(&self) -> Option<&descriptor_buffer::Device> {
+        self.0.ext_descriptor_buffer_device.as_ref()
+    }
+
+    #[inline]
+    pub fn ext_mesh_shader_device(&self) -> Option<&mesh_shader::Device> {
+        self.0.ext_mesh_shader_device.as_ref()
+    }
+
+    #[inline]
+    pub fn khr_acceleration_structure_device(&self) -> Option<&acceleration_structure::Device> {
+        self.0.khr_acceleration_structure_device.as_ref()
+    }
+
+    #[inline]
+    pub fn khr_fragment_shading_rate_device(&self) -> Option<&fragment_shading_rate::Device> {
+        self.0.khr_fragment_shading_rate_device.as_ref()
+    }
+
+    #[inline]
+    pub fn khr_ray_tracing_pipeline_device(&self) -> Option<&ray_tracing_pipeline::Device> {
+        self.0.khr_ray_tracing_pipeline_device.as_ref()
+    }
+
+    #[inline]
+    pub fn khr_swapchain_device(&self) -> Option<&swapchain::Device> {
+        self.0.khr_swapchain_device.as_ref()
+    }
+
+    #[inline]
+    pub fn allocator(&self) -> &Allocator {
+        &self.0.allocator
+    }
+
+    /// Returns the bindless heap, `None` unless descriptor indexing is enabled.
+    #[inline]
+    pub(crate) fn bindless_heap(&self) -> Option<&VulkanBindlessHeap> {
+        self.0.bindless_heap.as_ref()
+    }
+
+    /// Returns the descriptor set layouts shared by bind group and pipeline layouts.
+    #[inline]
+    pub(crate) fn descriptor_set_layout_cache(&self) -> &DescriptorSetLayoutCache {
+        &self.0.descriptor_set_layout_cache
+    }
+
+    /// Returns the samplers shared by every identical sampler descriptor.
+    #[inline]
+    pub(crate) fn sampler_cache(&self) -> &SamplerCache {
+        &self.0.sampler_cache
+    }
+
+    /// Returns the staging uploader.
+    #[inline]
+    pub(crate) fn uploader(&self) -> &VulkanUploader {
+        &self.0.uploader
     }
 }
 
 impl Drop for Inner {
     fn drop(&mut self) {
         unsafe {
+            let _ = self.device.device_wait_idle();
+
+            self.uploader.destroy(&self.device, self.allocator);
+
+            vk_mem_alloc::destroy_allocator(self.allocator);
+
+            if let Some(bindless_heap) = &self.bindless_heap {
+                bindless_heap.destroy(&self.device);
+            }
+
+            let descriptor_set_layout_cache = self.descriptor_set_layout_cache.get_mut().unwrap();
+            for descriptor_set_layout in descriptor_set_layout_cache.values() {
+                self.device
+                    .destroy_descriptor_set_layout(*descriptor_set_layout, None);
+            }
+
+            for sampler in self.sampler_cache.get_mut().unwrap().values() {
+                self.device.destroy_sampler(sampler.sampler(), None);
+            }
+
+            for entry in &self.queues {
+                self.device
+                    .destroy_semaphore(entry.submission_semaphore, None);
+            }
+
             self.device.destroy_device(None);
         }
     }
@@ -0,0 +1,71 @@
+use std::{sync::Arc, time::Duration};
+
+use ash::vk;
+
+use crate::{
+    vulkan::{to_vk_timeout, VulkanDevice, VulkanError},
+    FenceDesc,
+};
+
+struct Inner {
+    device: VulkanDevice,
+
+    fence: vk::Fence,
+}
+
+#[derive(Clone)]
+pub struct VulkanFence(Arc<Inner>);
+
+impl VulkanFence {
+    pub fn new(device: &VulkanDevice, desc: &FenceDesc) -> Result<Self, VulkanError> {
+        let mut fence_create_info = vk::FenceCreateInfo::default();
+        if desc.signaled {
+            fence_create_info = fence_create_info.flags(vk::FenceCreateFlags::SIGNALED);
+        }
+
+        let fence = unsafe { device.device().create_fence(&fence_create_info, None) }?;
+        device.set_object_name(fence, desc.label);
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+
+            fence,
+        })))
+    }
+
+    #[inline]
+    pub fn fence(&self) -> &vk::Fence {
+        &self.0.fence
+    }
+
+    pub fn is_signaled(&self) -> Result<bool, VulkanError> {
+        Ok(unsafe { self.0.device.device().get_fence_status(self.0.fence) }?)
+    }
+
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<bool, VulkanError> {
+        match unsafe {
+            self.0
+                .device
+                .device()
+                .wait_for_fences(&[self.0.fence], true, to_vk_timeout(timeout))
+        } {
+            Ok(()) => Ok(true),
+            Err(vk::Result::TIMEOUT) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn reset(&self) -> Result<(), VulkanError> {
+        unsafe { self.0.device.device().reset_fences(&[self.0.fence]) }?;
+
+        Ok(())
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.device().destroy_fence(self.fence, None);
+        }
+    }
+}
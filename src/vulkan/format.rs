@@ -0,0 +1,70 @@
+use ash::vk;
+
+use crate::Format;
+
+pub(crate) fn to_vk_format(format: Format) -> vk::Format {
+    match format {
+        Format::R8Unorm => vk::Format::R8_UNORM,
+        Format::R8Snorm => vk::Format::R8_SNORM,
+        Format::R8Uint => vk::Format::R8_UINT,
+        Format::R8Sint => vk::Format::R8_SINT,
+        Format::Rg8Unorm => vk::Format::R8G8_UNORM,
+        Format::Rg8Snorm => vk::Format::R8G8_SNORM,
+        Format::Rg8Uint => vk::Format::R8G8_UINT,
+        Format::Rg8Sint => vk::Format::R8G8_SINT,
+        Format::Rgba8Unorm => vk::Format::R8G8B8A8_UNORM,
+        Format::Rgba8UnormSrgb => vk::Format::R8G8B8A8_SRGB,
+        Format::Rgba8Snorm => vk::Format::R8G8B8A8_SNORM,
+        Format::Rgba8Uint => vk::Format::R8G8B8A8_UINT,
+        Format::Rgba8Sint => vk::Format::R8G8B8A8_SINT,
+        Format::Bgra8Unorm => vk::Format::B8G8R8A8_UNORM,
+        Format::Bgra8UnormSrgb => vk::Format::B8G8R8A8_SRGB,
+        Format::R16Unorm => vk::Format::R16_UNORM,
+        Format::R16Snorm => vk::Format::R16_SNORM,
+        Format::R16Uint => vk::Format::R16_UINT,
+        Format::R16Sint => vk::Format::R16_SINT,
+        Format::R16Float => vk::Format::R16_SFLOAT,
+        Format::Rg16Unorm => vk::Format::R16G16_UNORM,
+        Format::Rg16Snorm => vk::Format::R16G16_SNORM,
+        Format::Rg16Uint => vk::Format::R16G16_UINT,
+        Format::Rg16Sint => vk::Format::R16G16_SINT,
+        Format::Rg16Float => vk::Format::R16G16_SFLOAT,
+        Format::Rgba16Unorm => vk::Format::R16G16B16A16_UNORM,
+        Format::Rgba16Snorm => vk::Format::R16G16B16A16_SNORM,
+        Format::Rgba16Uint => vk::Format::R16G16B16A16_UINT,
+        Format::Rgba16Sint => vk::Format::R16G16B16A16_SINT,
+        Format::Rgba16Float => vk::Format::R16G16B16A16_SFLOAT,
+        Format::R32Uint => vk::Format::R32_UINT,
+        Format::R32Sint => vk::Format::R32_SINT,
+        Format::R32Float => vk::Format::R32_SFLOAT,
+        Format::Rg32Uint => vk::Format::R32G32_UINT,
+        Format::Rg32Sint => vk::Format::R32G32_SINT,
+        Format::Rg32Float => vk::Format::R32G32_SFLOAT,
+        Format::Rgba32Uint => vk::Format::R32G32B32A32_UINT,
+        Format::Rgba32Sint => vk::Format::R32G32B32A32_SINT,
+        Format::Rgba32Float => vk::Format::R32G32B32A32_SFLOAT,
+        Format::Rgb10a2Unorm => vk::Format::A2B10G10R10_UNORM_PACK32,
+        Format::Rgb10a2Uint => vk::Format::A2B10G10R10_UINT_PACK32,
+        Format::Rg11b10Float => vk::Format::B10G11R11_UFLOAT_PACK32,
+        Format::Rgb9e5Float => vk::Format::E5B9G9R9_UFLOAT_PACK32,
+        Format::D16Unorm => vk::Format::D16_UNORM,
+        Format::D32Float => vk::Format::D32_SFLOAT,
+        Format::S8Uint => vk::Format::S8_UINT,
+        Format::D24UnormS8Uint => vk::Format::D24_UNORM_S8_UINT,
+        Format::D32FloatS8Uint => vk::Format::D32_SFLOAT_S8_UINT,
+        Format::Bc1RgbaUnorm => vk::Format::BC1_RGBA_UNORM_BLOCK,
+        Format::Bc1RgbaUnormSrgb => vk::Format::BC1_RGBA_SRGB_BLOCK,
+        Format::Bc2RgbaUnorm => vk::Format::BC2_UNORM_BLOCK,
+        Format::Bc2RgbaUnormSrgb => vk::Format::BC2_SRGB_BLOCK,
+        Format::Bc3RgbaUnorm => vk::Format::BC3_UNORM_BLOCK,
+        Format::Bc3RgbaUnormSrgb => vk::Format::BC3_SRGB_BLOCK,
+        Format::Bc4RUnorm => vk::Format::BC4_UNORM_BLOCK,
+        Format::Bc4RSnorm => vk::Format::BC4_SNORM_BLOCK,
+        Format::Bc5RgUnorm => vk::Format::BC5_UNORM_BLOCK,
+        Format::Bc5RgSnorm => vk::Format::BC5_SNORM_BLOCK,
+        Format::Bc6hRgbUfloat => vk::Format::BC6H_UFLOAT_BLOCK,
+        Format::Bc6hRgbFloat => vk::Format::BC6H_SFLOAT_BLOCK,
+        Format::Bc7RgbaUnorm => vk::Format::BC7_UNORM_BLOCK,
+        Format::Bc7RgbaUnormSrgb => vk::Format::BC7_SRGB_BLOCK,
+    }
+}
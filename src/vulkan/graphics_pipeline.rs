@@ -0,0 +1,484 @@
+use std::{ffi::CString, sync::Arc};
+
+use ash::vk;
+
+use crate::{
+    vulkan::{
+        to_vk_format, to_vk_specialization_data, VulkanDevice, VulkanError, VulkanPipelineLayout,
+    },
+    BlendFactor, BlendOp, ColorWriteFlags, CompareOp, CullMode, FrontFace, GraphicsPipelineDesc,
+    MeshPipelineDesc, PolygonMode, PrimitiveTopology, RasterStateDesc, ShaderStage,
+    StencilFaceState, StencilOp, VertexFormat, VertexStepMode,
+};
+
+struct Inner {
+    device: VulkanDevice,
+
+    pipeline: vk::Pipeline,
+    layout: VulkanPipelineLayout,
+}
+
+#[derive(Clone)]
+pub struct VulkanGraphicsPipeline(Arc<Inner>);
+
+/// Owns the entry point name and specialization data a shader stage create info points to.
+pub(crate) struct VulkanShaderStage {
+    stage: vk::ShaderStageFlags,
+    module: vk::ShaderModule,
+    entry_point: CString,
+    map_entries: Vec<vk::SpecializationMapEntry>,
+    data: Vec<u8>,
+}
+
+impl VulkanShaderStage {
+    pub(crate) fn new(
+        stage: vk::ShaderStageFlags,
+        shader_stage: &ShaderStage,
+    ) -> Result<Self, VulkanError> {
+        let entry_point = CString::new(shader_stage.entry_point)
+            .map_err(|_| VulkanError::Custom("Entry point contains a nul byte".to_owned()))?;
+
+        let (map_entries, data) = to_vk_specialization_data(shader_stage.specialization_constants);
+
+        Ok(Self {
+            stage,
+            module: *shader_stage.shader_module.as_vulkan().shader_module(),
+            entry_point,
+            map_entries,
+            data,
+        })
+    }
+
+    pub(crate) fn specialization_info(&self) -> vk::SpecializationInfo<'_> {
+        vk::SpecializationInfo::default()
+            .map_entries(&self.map_entries)
+            .data(&self.data)
+    }
+
+    pub(crate) fn create_info<'a>(
+        &'a self,
+        specialization_info: &'a vk::SpecializationInfo<'a>,
+    ) -> vk::PipelineShaderStageCreateInfo<'a> {
+        vk::PipelineShaderStageCreateInfo::default()
+            .stage(self.stage)
+            .module(self.module)
+            .name(&self.entry_point)
+            .specialization_info(specialization_info)
+    }
+}
+
+fn to_vk_vertex_format(format: VertexFormat) -> vk::Format {
+    match format {
+        VertexFormat::Uint8x4 => vk::Format::R8G8B8A8_UINT,
+        VertexFormat::Sint8x4 => vk::Format::R8G8B8A8_SINT,
+        VertexFormat::Unorm8x4 => vk::Format::R8G8B8A8_UNORM,
+        VertexFormat::Snorm8x4 => vk::Format::R8G8B8A8_SNORM,
+        VertexFormat::Uint16x2 => vk::Format::R16G16_UINT,
+        VertexFormat::Uint16x4 => vk::Format::R16G16B16A16_UINT,
+        VertexFormat::Sint16x2 => vk::Format::R16G16_SINT,
+        VertexFormat::Sint16x4 => vk::Format::R16G16B16A16_SINT,
+        VertexFormat::Unorm16x2 => vk::Format::R16G16_UNORM,
+        VertexFormat::Unorm16x4 => vk::Format::R16G16B16A16_UNORM,
+        VertexFormat::Snorm16x2 => vk::Format::R16G16_SNORM,
+        VertexFormat::Snorm16x4 => vk::Format::R16G16B16A16_SNORM,
+        VertexFormat::Float16x2 => vk::Format::R16G16_SFLOAT,
+        VertexFormat::Float16x4 => vk::Format::R16G16B16A16_SFLOAT,
+        VertexFormat::Uint32 => vk::Format::R32_UINT,
+        VertexFormat::Uint32x2 => vk::Format::R32G32_UINT,
+        VertexFormat::Uint32x3 => vk::Format::R32G32B32_UINT,
+        VertexFormat::Uint32x4 => vk::Format::R32G32B32A32_UINT,
+        VertexFormat::Sint32 => vk::Format::R32_SINT,
+        VertexFormat::Sint32x2 => vk::Format::R32G32_SINT,
+        VertexFormat::Sint32x3 => vk::Format::R32G32B32_SINT,
+        VertexFormat::Sint32x4 => vk::Format::R32G32B32A32_SINT,
+        VertexFormat::Float32 => vk::Format::R32_SFLOAT,
+        VertexFormat::Float32x2 => vk::Format::R32G32_SFLOAT,
+        VertexFormat::Float32x3 => vk::Format::R32G32B32_SFLOAT,
+        VertexFormat::Float32x4 => vk::Format::R32G32B32A32_SFLOAT,
+    }
+}
+
+fn to_vk_primitive_topology(topology: PrimitiveTopology) -> vk::PrimitiveTopology {
+    match topology {
+        PrimitiveTopology::PointList => vk::PrimitiveTopology::POINT_LIST,
+        PrimitiveTopology::LineList => vk::PrimitiveTopology::LINE_LIST,
+        PrimitiveTopology::LineStrip => vk::PrimitiveTopology::LINE_STRIP,
+        PrimitiveTopology::TriangleList => vk::PrimitiveTopology::TRIANGLE_LIST,
+        PrimitiveTopology::TriangleStrip => vk::PrimitiveTopology::TRIANGLE_STRIP,
+    }
+}
+
+fn to_vk_polygon_mode(polygon_mode: PolygonMode) -> vk::PolygonMode {
+    match polygon_mode {
+        PolygonMode::Fill => vk::PolygonMode::FILL,
+        PolygonMode::Line => vk::PolygonMode::LINE,
+    }
+}
+
+fn to_vk_cull_mode(cull_mode: CullMode) -> vk::CullModeFlags {
+    match cull_mode {
+        CullMode::None => vk::CullModeFlags::NONE,
+        CullMode::Front => vk::CullModeFlags::FRONT,
+        CullMode::Back => vk::CullModeFlags::BACK,
+    }
+}
+
+fn to_vk_front_face(front_face: FrontFace) -> vk::FrontFace {
+    match front_face {
+        FrontFace::CounterClockwise => vk::FrontFace::COUNTER_CLOCKWISE,
+        FrontFace::Clockwise => vk::FrontFace::CLOCKWISE,
+    }
+}
+
+pub(crate) fn to_vk_compare_op(compare_op: CompareOp) -> vk::CompareOp {
+    match compare_op {
+        CompareOp::Never => vk::CompareOp::NEVER,
+        CompareOp::Less => vk::CompareOp::LESS,
+        CompareOp::Equal => vk::CompareOp::EQUAL,
+        CompareOp::LessEqual => vk::CompareOp::LESS_OR_EQUAL,
+        CompareOp::Greater => vk::CompareOp::GREATER,
+        CompareOp::NotEqual => vk::CompareOp::NOT_EQUAL,
+        CompareOp::GreaterEqual => vk::CompareOp::GREATER_OR_EQUAL,
+        CompareOp::Always => vk::CompareOp::ALWAYS,
+    }
+}
+
+fn to_vk_stencil_op(stencil_op: StencilOp) -> vk::StencilOp {
+    match stencil_op {
+        StencilOp::Keep => vk::StencilOp::KEEP,
+        StencilOp::Zero => vk::StencilOp::ZERO,
+        StencilOp::Replace => vk::StencilOp::REPLACE,
+        StencilOp::IncrementClamp => vk::StencilOp::INCREMENT_AND_CLAMP,
+        StencilOp::DecrementClamp => vk::StencilOp::DECREMENT_AND_CLAMP,
+        StencilOp::Invert => vk::StencilOp::INVERT,
+        StencilOp::IncrementWrap => vk::StencilOp::INCREMENT_AND_WRAP,
+        StencilOp::DecrementWrap => vk::StencilOp::DECREMENT_AND_WRAP,
+    }
+}
+
+fn to_vk_stencil_op_state(
+    state: &StencilFaceState,
+    read_mask: u32,
+    write_mask: u32,
+) -> vk::StencilOpState {
+    vk::StencilOpState {
+        fail_op: to_vk_stencil_op(state.fail_op),
+        pass_op: to_vk_stencil_op(state.pass_op),
+        depth_fail_op: to_vk_stencil_op(state.depth_fail_op),
+        compare_op: to_vk_compare_op(state.compare),
+        compare_mask: read_mask,
+        write_mask,
+        // Set dynamically with the stencil reference.
+        reference: 0,
+    }
+}
+
+fn to_vk_blend_factor(blend_factor: BlendFactor) -> vk::BlendFactor {
+    match blend_factor {
+        BlendFactor::Zero => vk::BlendFactor::ZERO,
+        BlendFactor::One => vk::BlendFactor::ONE,
+        BlendFactor::Src => vk::BlendFactor::SRC_COLOR,
+        BlendFactor::OneMinusSrc => vk::BlendFactor::ONE_MINUS_SRC_COLOR,
+        BlendFactor::SrcAlpha => vk::BlendFactor::SRC_ALPHA,
+        BlendFactor::OneMinusSrcAlpha => vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+        BlendFactor::Dst => vk::BlendFactor::DST_COLOR,
+        BlendFactor::OneMinusDst => vk::BlendFactor::ONE_MINUS_DST_COLOR,
+        BlendFactor::DstAlpha => vk::BlendFactor::DST_ALPHA,
+        BlendFactor::OneMinusDstAlpha => vk::BlendFactor::ONE_MINUS_DST_ALPHA,
+        BlendFactor::SrcAlphaSaturated => vk::BlendFactor::SRC_ALPHA_SATURATE,
+        BlendFactor::Constant => vk::BlendFactor::CONSTANT_COLOR,
+        BlendFactor::OneMinusConstant => vk::BlendFactor::ONE_MINUS_CONSTANT_COLOR,
+    }
+}
+
+fn to_vk_blend_op(blend_op: BlendOp) -> vk::BlendOp {
+    match blend_op {
+        BlendOp::Add => vk::BlendOp::ADD,
+        BlendOp::Subtract => vk::BlendOp::SUBTRACT,
+        BlendOp::ReverseSubtract => vk::BlendOp::REVERSE_SUBTRACT,
+        BlendOp::Min => vk::BlendOp::MIN,
+        BlendOp::Max => vk::BlendOp::MAX,
+    }
+}
+
+fn to_vk_color_component_flags(write_mask: ColorWriteFlags) -> vk::ColorComponentFlags {
+    let mut flags = vk::ColorComponentFlags::empty();
+
+    if write_mask.contains(ColorWriteFlags::RED) {
+        flags |= vk::ColorComponentFlags::R;
+    }
+    if write_mask.contains(ColorWriteFlags::GREEN) {
+        flags |= vk::ColorComponentFlags::G;
+    }
+    if write_mask.contains(ColorWriteFlags::BLUE) {
+        flags |= vk::ColorComponentFlags::B;
+    }
+    if write_mask.contains(ColorWriteFlags::ALPHA) {
+        flags |= vk::ColorComponentFlags::A;
+    }
+
+    flags
+}
+
+impl VulkanGraphicsPipeline {
+    pub fn new(device: &VulkanDevice, desc: &GraphicsPipelineDesc) -> Result<Self, VulkanError> {
+        let mut shader_stages = vec![VulkanShaderStage::new(
+            vk::ShaderStageFlags::VERTEX,
+            &desc.vertex_shader,
+        )?];
+        if let Some(fragment_shader) = &desc.fragment_shader {
+            shader_stages.push(VulkanShaderStage::new(
+                vk::ShaderStageFlags::FRAGMENT,
+                fragment_shader,
+            )?);
+        }
+
+        let vertex_binding_descriptions = desc
+            .vertex_buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, vertex_buffer)| {
+                vk::VertexInputBindingDescription {
+                    binding: binding as u32,
+                    stride: vertex_buffer.stride,
+                    input_rate: match vertex_buffer.step_mode {
+                        VertexStepMode::Vertex => vk::VertexInputRate::VERTEX,
+                        VertexStepMode::Instance => vk::VertexInputRate::INSTANCE,
+                    },
+                }
+            })
+            .collect::<Vec<_>>();
+        let vertex_attribute_descriptions = desc
+            .vertex_buffers
+            .iter()
+            .enumerate()
+            .flat_map(|(binding, vertex_buffer)| {
+                vertex_buffer.attributes.iter().map(move |attribute| {
+                    vk::VertexInputAttributeDescription {
+                        location: attribute.location,
+                        binding: binding as u32,
+                        format: to_vk_vertex_format(attribute.format),
+                        offset: attribute.offset,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let vertex_input_state = vk::PipelineVertexInputStateCreateInfo::default()
+            .vertex_binding_descriptions(&vertex_binding_descriptions)
+            .vertex_attribute_descriptions(&vertex_attribute_descriptions);
+
+        let input_assembly_state = vk::PipelineInputAssemblyStateCreateInfo::default()
+            .topology(to_vk_primitive_topology(desc.primitive_topology));
+
+        Self::create(
+            device,
+            desc.layout.as_vulkan(),
+            &shader_stages,
+            Some((&vertex_input_state, &input_assembly_state)),
+            &desc.raster_state(),
+            desc.label,
+        )
+    }
+
+    pub fn new_mesh(device: &VulkanDevice, desc: &MeshPipelineDesc) -> Result<Self, VulkanError> {
+        let mut shader_stages = Vec::new();
+        if let Some(task_shader) = &desc.task_shader {
+            shader_stages.push(VulkanShaderStage::new(
+                vk::ShaderStageFlags::TASK_EXT,
+                task_shader,
+            )?);
+        }
+        shader_stages.push(VulkanShaderStage::new(
+            vk::ShaderStageFlags::MESH_EXT,
+            &desc.mesh_shader,
+        )?);
+        if let Some(fragment_shader) = &desc.fragment_shader {
+            shader_stages.push(VulkanShaderStage::new(
+                vk::ShaderStageFlags::FRAGMENT,
+                fragment_shader,
+            )?);
+        }
+
+        // Mesh pipelines ignore vertex input and input assembly state.
+        Self::create(
+            device,
+            desc.layout.as_vulkan(),
+            &shader_stages,
+            None,
+            &desc.raster_state(),
+            desc.label,
+        )
+    }
+
+    fn create(
+        device: &VulkanDevice,
+        layout: &VulkanPipelineLayout,
+        shader_stages: &[VulkanShaderStage],
+        vertex_input: Option<(
+            &vk::PipelineVertexInputStateCreateInfo,
+            &vk::PipelineInputAssemblyStateCreateInfo,
+        )>,
+        raster_state: &RasterStateDesc,
+        label: Option<&str>,
+    ) -> Result<Self, VulkanError> {
+        let specialization_infos = shader_stages
+            .iter()
+            .map(|shader_stage| shader_stage.specialization_info())
+            .collect::<Vec<_>>();
+        let stages = shader_stages
+            .iter()
+            .zip(&specialization_infos)
+            .map(|(shader_stage, specialization_info)| {
+                shader_stage.create_info(specialization_info)
+            })
+            .collect::<Vec<_>>();
+
+        let viewport_state = vk::PipelineViewportStateCreateInfo::default()
+            .viewport_count(1)
+            .scissor_count(1);
+
+        let rasterizer = &raster_state.rasterizer;
+        let mut rasterization_state = vk::PipelineRasterizationStateCreateInfo::default()
+            .depth_clamp_enable(rasterizer.depth_clamp)
+            .polygon_mode(to_vk_polygon_mode(rasterizer.polygon_mode))
+            .cull_mode(to_vk_cull_mode(rasterizer.cull_mode))
+            .front_face(to_vk_front_face(rasterizer.front_face))
+            .line_width(1.0);
+        if let Some(depth_bias) = rasterizer.depth_bias {
+            rasterization_state = rasterization_state
+                .depth_bias_enable(true)
+                .depth_bias_constant_factor(depth_bias.constant as f32)
+                .depth_bias_slope_factor(depth_bias.slope_scale)
+                .depth_bias_clamp(depth_bias.clamp);
+        }
+
+        let multisample_state = vk::PipelineMultisampleStateCreateInfo::default()
+            .rasterization_samples(vk::SampleCountFlags::from_raw(
+                raster_state.multisample.sample_count,
+            ))
+            .alpha_to_coverage_enable(raster_state.multisample.alpha_to_coverage);
+
+        let depth_stencil = &raster_state.depth_stencil;
+        let depth_stencil_state = vk::PipelineDepthStencilStateCreateInfo::default()
+            .depth_test_enable(depth_stencil.depth_test)
+            .depth_write_enable(depth_stencil.depth_write)
+            .depth_compare_op(to_vk_compare_op(depth_stencil.depth_compare))
+            .stencil_test_enable(depth_stencil.stencil_test)
+            .front(to_vk_stencil_op_state(
+                &depth_stencil.stencil_front,
+                depth_stencil.stencil_read_mask,
+                depth_stencil.stencil_write_mask,
+            ))
+            .back(to_vk_stencil_op_state(
+                &depth_stencil.stencil_back,
+                depth_stencil.stencil_read_mask,
+                depth_stencil.stencil_write_mask,
+            ));
+
+        let color_blend_attachments = raster_state
+            .color_attachments
+            .iter()
+            .map(|color_attachment| {
+                let state = vk::PipelineColorBlendAttachmentState::default()
+                    .color_write_mask(to_vk_color_component_flags(color_attachment.write_mask));
+
+                match &color_attachment.blend {
+                    Some(blend) => {
+                        state
+                            .blend_enable(true)
+                            .src_color_blend_factor(to_vk_blend_factor(blend.color.src_factor))
+                            .dst_color_blend_factor(to_vk_blend_factor(blend.color.dst_factor))
+                            .color_blend_op(to_vk_blend_op(blend.color.operation))
+                            .src_alpha_blend_factor(to_vk_blend_factor(blend.alpha.src_factor))
+                            .dst_alpha_blend_factor(to_vk_blend_factor(blend.alpha.dst_factor))
+                            .alpha_blend_op(to_vk_blend_op(blend.alpha.operation))
+                    }
+                    None => state,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let color_blend_state =
+            vk::PipelineColorBlendStateCreateInfo::default().attachments(&color_blend_attachments);
+
+        let dynamic_states = [
+            vk::DynamicState::VIEWPORT,
+            vk::DynamicState::SCISSOR,
+            vk::DynamicState::STENCIL_REFERENCE,
+            vk::DynamicState::BLEND_CONSTANTS,
+        ];
+        let dynamic_state =
+            vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dynamic_states);
+
+        let color_attachment_formats = raster_state
+            .color_attachments
+            .iter()
+            .map(|color_attachment| to_vk_format(color_attachment.format))
+            .collect::<Vec<_>>();
+
+        let mut rendering_create_info = vk::PipelineRenderingCreateInfo::default()
+            .color_attachment_formats(&color_attachment_formats);
+        if let Some(format) = raster_state.depth_stencil_format {
+            if format.is_depth() {
+                rendering_create_info =
+                    rendering_create_info.depth_attachment_format(to_vk_format(format));
+            }
+            if format.is_stencil() {
+                rendering_create_info =
+                    rendering_create_info.stencil_attachment_format(to_vk_format(format));
+            }
+        }
+
+        let mut graphics_pipeline_create_info = vk::GraphicsPipelineCreateInfo::default()
+            .stages(&stages)
+            .viewport_state(&viewport_state)
+            .rasterization_state(&rasterization_state)
+            .multisample_state(&multisample_state)
+            .depth_stencil_state(&depth_stencil_state)
+            .color_blend_state(&color_blend_state)
+            .dynamic_state(&dynamic_state)
+            .layout(*layout.pipeline_layout())
+            .push_next(&mut rendering_create_info);
+        if let Some((vertex_input_state, input_assembly_state)) = vertex_input {
+            graphics_pipeline_create_info = graphics_pipeline_create_info
+                .vertex_input_state(vertex_input_state)
+                .input_assembly_state(input_assembly_state);
+        }
+
+        let pipeline = unsafe {
+            device.device().create_graphics_pipelines(
+                vk::PipelineCache::null(),
+                &[graphics_pipeline_create_info],
+                None,
+            )
+        }
+        .map_err(|(_, e)| e)?[0];
+        device.set_object_name(pipeline, label);
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+
+            pipeline,
+            layout: layout.clone(),
+        })))
+    }
+
+    #[inline]
+    pub fn pipeline(&self) -> &vk::Pipeline {
+        &self.0.pipeline
+    }
+
+    #[inline]
+    pub fn layout(&self) -> &VulkanPipelineLayout {
+        &self.0.layout
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.device().destroy_pipeline(self.pipeline, None);
+        }
+    }
+}
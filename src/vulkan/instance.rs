@@ -154,6 +154,7 @@ struct Inner {
     surface_instance: surface::Instance,
 
     debug_utils_messenger: vk::DebugUtilsMessengerEXT,
+    ext_debug_utils: bool,
 
     physical_devices: Vec<PhysicalDevice>,
 }
@@ -228,9 +229,12 @@ impl VulkanInstance {
             .enumerate_physical_devices()?
             .into_iter()
             .map(|physical_device| {
-                PhysicalDevice::Vulkan(VulkanPhysicalDevice::new(&instance, physical_device))
+                Ok(PhysicalDevice::Vulkan(VulkanPhysicalDevice::new(
+                    &instance,
+                    physical_device,
+                )?))
             })
-            .collect::<Vec<_>>();
+            .collect::<Result<Vec<_>, VulkanError>>()?;
 
         Ok(Self(Arc::new(Inner {
             entry,
@@ -240,16 +244,28 @@ impl VulkanInstance {
             surface_instance,
 
             debug_utils_messenger,
+            ext_debug_utils: extensions.ext_debug_utils,
 
             physical_devices,
         })))
     }
 
+    #[inline]
+    pub fn entry(&self) -> &Entry {
+        &self.0.entry
+    }
+
     #[inline]
     pub fn instance(&self) -> &ash::Instance {
         &self.0.instance
     }
 
+    /// Returns `true` if `VK_EXT_debug_utils` is enabled, which is the case with validation.
+    #[inline]
+    pub fn ext_debug_utils(&self) -> bool {
+        self.0.ext_debug_utils
+    }
+
     #[inline]
     pub fn get_physical_devices(&self) -> &[PhysicalDevice] {
         &self.0.physical_devices
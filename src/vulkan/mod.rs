@@ -1,14 +1,48 @@
+mod bind_group;
+mod bindless_heap;
+mod buffer;
+mod command_list;
+mod compute_pipeline;
 mod device;
+mod fence;
+mod format;
+mod graphics_pipeline;
 mod instance;
 mod physical_device;
+mod pipeline_layout;
+mod query_pool;
+mod queue;
+mod resource_state;
+mod sampler;
+mod semaphore;
+mod shader_module;
+mod texture;
+mod upload;
 
 use std::str::Utf8Error;
 
 use ash::vk;
+pub use bind_group::*;
+pub(crate) use bindless_heap::*;
+pub use buffer::*;
+pub use command_list::*;
+pub use compute_pipeline::*;
 pub use device::*;
+pub use fence::*;
+pub(crate) use format::*;
+pub use graphics_pipeline::*;
 pub use instance::*;
 pub use physical_device::*;
+pub use pipeline_layout::*;
+pub use query_pool::*;
+pub use queue::*;
+pub(crate) use resource_state::*;
+pub use sampler::*;
+pub use semaphore::*;
+pub use shader_module::*;
+pub use texture::*;
 use thiserror::Error;
+pub(crate) use upload::*;
 
 #[derive(Error, Debug)]
 pub enum VulkanError {
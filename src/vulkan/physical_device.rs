@@ -1,25 +1,226 @@
 use std::{ffi::CStr, sync::Arc};
 
-use ash::vk;
+use ash::{
+    ext::{descriptor_buffer, mesh_shader},
+    khr::{
+        acceleration_structure, deferred_host_operations, fragment_shading_rate, ray_query,
+        ray_tracing_pipeline,
+    },
+    vk,
+};
+
+use crate::{
+    vulkan::{DeviceExtensions, VulkanError},
+    PhysicalDeviceFeatures,
+};
 
 struct Inner {
     physical_device: vk::PhysicalDevice,
     properties: vk::PhysicalDeviceProperties2<'static>,
+    supported_features: PhysicalDeviceFeatures,
 }
 
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
 #[derive(Clone)]
 pub struct VulkanPhysicalDevice(Arc<Inner>);
 
+unsafe fn query_supported_features(
+    instance: &ash::Instance,
+    physical_device: vk::PhysicalDevice,
+    api_version: u32,
+) -> Result<PhysicalDeviceFeatures, VulkanError> {
+    let extensions = DeviceExtensions::new(instance, physical_device)?;
+
+    let has_mesh_shader = extensions.is_supported(mesh_shader::NAME);
+    let has_acceleration_structure = extensions.is_supported(acceleration_structure::NAME)
+        && extensions.is_supported(deferred_host_operations::NAME);
+    let has_ray_tracing_pipeline =
+        has_acceleration_structure && extensions.is_supported(ray_tracing_pipeline::NAME);
+    let has_ray_query = has_acceleration_structure && extensions.is_supported(ray_query::NAME);
+    let has_fragment_shading_rate = extensions.is_supported(fragment_shading_rate::NAME);
+    let has_descriptor_buffer = extensions.is_supported(descriptor_buffer::NAME);
+
+    let mut vulkan_11_features = vk::PhysicalDeviceVulkan11Features::default();
+    let mut vulkan_12_features = vk::PhysicalDeviceVulkan12Features::default();
+    let mut vulkan_13_features = vk::PhysicalDeviceVulkan13Features::default();
+    let mut mesh_shader_features = vk::PhysicalDeviceMeshShaderFeaturesEXT::default();
+    let mut acceleration_structure_features =
+        vk::PhysicalDeviceAccelerationStructureFeaturesKHR::default();
+    let mut ray_tracing_pipeline_features =
+        vk::PhysicalDeviceRayTracingPipelineFeaturesKHR::default();
+    let mut ray_query_features = vk::PhysicalDeviceRayQueryFeaturesKHR::default();
+    let mut fragment_shading_rate_features =
+        vk::PhysicalDeviceFragmentShadingRateFeaturesKHR::default();
+    let mut descriptor_buffer_features = vk::PhysicalDeviceDescriptorBufferFeaturesEXT::default();
+
+    let mut features = vk::PhysicalDeviceFeatures2::default()
+        .push_next(&mut vulkan_11_features)
+        .push_next(&mut vulkan_12_features);
+
+    // Dynamic rendering and synchronization2 are only queried from Vulkan 1.3 devices.
+    if api_version >= vk::API_VERSION_1_3 {
+        features = features.push_next(&mut vulkan_13_features);
+    }
+    if has_mesh_shader {
+        features = features.push_next(&mut mesh_shader_features);
+    }
+    if has_acceleration_structure {
+        features = features.push_next(&mut acceleration_structure_features);
+    }
+    if has_ray_tracing_pipeline {
+        features = features.push_next(&mut ray_tracing_pipeline_features);
+    }
+    if has_ray_query {
+        features = features.push_next(&mut ray_query_features);
+    }
+    if has_fragment_shading_rate {
+        features = features.push_next(&mut fragment_shading_rate_features);
+    }
+    if has_descriptor_buffer {
+        features = features.push_next(&mut descriptor_buffer_features);
+    }
+
+    instance.get_physical_device_features2(physical_device, &mut features);
+
+    let core_features = features.features;
+
+    let mut vulkan_11_properties = vk::PhysicalDeviceVulkan11Properties::default();
+    let mut properties =
+        vk::PhysicalDeviceProperties2::default().push_next(&mut vulkan_11_properties);
+
+    instance.get_physical_device_properties2(physical_device, &mut properties);
+
+    let required_subgroup_operations = vk::SubgroupFeatureFlags::BASIC
+        | vk::SubgroupFeatureFlags::VOTE
+        | vk::SubgroupFeatureFlags::ARITHMETIC
+        | vk::SubgroupFeatureFlags::BALLOT
+        | vk::SubgroupFeatureFlags::SHUFFLE;
+
+    let mut result = PhysicalDeviceFeatures::empty();
+
+    result.set(
+        PhysicalDeviceFeatures::MESH_SHADER,
+        mesh_shader_features.task_shader == vk::TRUE
+            && mesh_shader_features.mesh_shader == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::RAY_TRACING_PIPELINE,
+        acceleration_structure_features.acceleration_structure == vk::TRUE
+            && ray_tracing_pipeline_features.ray_tracing_pipeline == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::RAY_QUERY,
+        acceleration_structure_features.acceleration_structure == vk::TRUE
+            && ray_query_features.ray_query == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::DESCRIPTOR_INDEXING,
+        vulkan_12_features.descriptor_indexing == vk::TRUE
+            && vulkan_12_features.runtime_descriptor_array == vk::TRUE
+            && vulkan_12_features.descriptor_binding_partially_bound == vk::TRUE
+            && vulkan_12_features.descriptor_binding_update_unused_while_pending == vk::TRUE
+            && vulkan_12_features.descriptor_binding_variable_descriptor_count == vk::TRUE
+            && vulkan_12_features.shader_sampled_image_array_non_uniform_indexing == vk::TRUE
+            && vulkan_12_features.shader_storage_buffer_array_non_uniform_indexing == vk::TRUE
+            && vulkan_12_features.shader_storage_image_array_non_uniform_indexing == vk::TRUE
+            && vulkan_12_features.descriptor_binding_sampled_image_update_after_bind == vk::TRUE
+            && vulkan_12_features.descriptor_binding_storage_image_update_after_bind == vk::TRUE
+            && vulkan_12_features.descriptor_binding_storage_buffer_update_after_bind == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::TIMELINE_SEMAPHORE,
+        vulkan_12_features.timeline_semaphore == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::BUFFER_DEVICE_ADDRESS,
+        vulkan_12_features.buffer_device_address == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::SHADER_FLOAT16,
+        vulkan_12_features.shader_float16 == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::SHADER_INT16,
+        core_features.shader_int16 == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::SHADER_INT64,
+        core_features.shader_int64 == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::SHADER_FLOAT64,
+        core_features.shader_float64 == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::STORAGE_16BIT,
+        vulkan_11_features.storage_buffer16_bit_access == vk::TRUE
+            && vulkan_11_features.uniform_and_storage_buffer16_bit_access == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::SUBGROUP_OPERATIONS,
+        vulkan_11_properties
+            .subgroup_supported_operations
+            .contains(required_subgroup_operations)
+            && vulkan_11_properties
+                .subgroup_supported_stages
+                .contains(vk::ShaderStageFlags::COMPUTE),
+    );
+    result.set(
+        PhysicalDeviceFeatures::VARIABLE_RATE_SHADING,
+        fragment_shading_rate_features.pipeline_fragment_shading_rate == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::DRAW_INDIRECT_COUNT,
+        vulkan_12_features.draw_indirect_count == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::DYNAMIC_RENDERING,
+        vulkan_13_features.dynamic_rendering == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::SYNCHRONIZATION_2,
+        vulkan_13_features.synchronization2 == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::SAMPLER_ANISOTROPY,
+        core_features.sampler_anisotropy == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::SAMPLER_FILTER_MINMAX,
+        vulkan_12_features.sampler_filter_minmax == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::PIPELINE_STATISTICS_QUERY,
+        core_features.pipeline_statistics_query == vk::TRUE,
+    );
+    result.set(
+        PhysicalDeviceFeatures::DESCRIPTOR_BUFFER,
+        descriptor_buffer_features.descriptor_buffer == vk::TRUE
+            && vulkan_12_features.buffer_device_address == vk::TRUE,
+    );
+
+    Ok(result)
+}
+
 impl VulkanPhysicalDevice {
-    pub unsafe fn new(instance: &ash::Instance, physical_device: vk::PhysicalDevice) -> Self {
+    pub unsafe fn new(
+        instance: &ash::Instance,
+        physical_device: vk::PhysicalDevice,
+    ) -> Result<Self, VulkanError> {
         let mut properties = vk::PhysicalDeviceProperties2::default();
 
         instance.get_physical_device_properties2(physical_device, &mut properties);
 
-        Self(Arc::new(Inner {
+        let supported_features =
+            query_supported_features(instance, physical_device, properties.properties.api_version)?;
+
+        Ok(Self(Arc::new(Inner {
             physical_device,
             properties,
-        }))
+            supported_features,
+        })))
     }
 
     #[inline]
@@ -35,4 +236,19 @@ impl VulkanPhysicalDevice {
                 .unwrap()
         }
     }
+
+    #[inline]
+    pub fn get_supported_features(&self) -> PhysicalDeviceFeatures {
+        self.0.supported_features
+    }
+
+    #[inline]
+    pub fn get_timestamp_period(&self) -> f32 {
+        self.0.properties.properties.limits.timestamp_period
+    }
+
+    #[inline]
+    pub fn properties(&self) -> &vk::PhysicalDeviceProperties {
+        &self.0.properties.properties
+    }
 }
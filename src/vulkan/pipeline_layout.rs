@@ -0,0 +1,192 @@
+use std::{
+    collections::BTreeMap,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use ash::vk;
+
+use crate::{
+    bindless_descriptor_type, validate_push_constant_ranges,
+    vulkan::{to_vk_shader_stage_flags, VulkanBindGroupLayout, VulkanDevice, VulkanError},
+    BindGroupLayoutEntry, DescriptorBinding, PipelineLayoutDesc, PushConstantRange,
+};
+
+struct Inner {
+    device: VulkanDevice,
+
+    /// Set layouts are owned by the device, either cached or the bindless heap's.
+    descriptor_set_layouts: Vec<vk::DescriptorSetLayout>,
+    /// Layout of each set, `None` for the bindless set.
+    bind_group_layouts: Vec<Option<VulkanBindGroupLayout>>,
+    pipeline_layout: vk::PipelineLayout,
+
+    descriptor_bindings: Vec<DescriptorBinding>,
+    push_constant_ranges: Vec<PushConstantRange>,
+    bindless_set: Option<u32>,
+}
+
+#[derive(Clone)]
+pub struct VulkanPipelineLayout(Arc<Inner>);
+
+impl PartialEq for VulkanPipelineLayout {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for VulkanPipelineLayout {}
+
+impl Hash for VulkanPipelineLayout {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+impl VulkanPipelineLayout {
+    pub fn new(device: &VulkanDevice, desc: &PipelineLayoutDesc) -> Result<Self, VulkanError> {
+        validate_push_constant_ranges(
+            desc.push_constant_ranges,
+            device.limits().max_push_constants_size,
+        )
+        .map_err(VulkanError::Custom)?;
+
+        let mut sets = BTreeMap::<u32, Vec<BindGroupLayoutEntry>>::new();
+
+        let bindless_heap = match desc.bindless_set {
+            Some(_) => {
+                Some(device.bindless_heap().ok_or_else(|| {
+                    VulkanError::Custom("The bindless heap requires timeline semaphores".to_owned())
+                })?)
+            }
+            None => None,
+        };
+
+        for binding in desc.descriptor_bindings {
+            if Some(binding.set) == desc.bindless_set {
+                if bindless_descriptor_type(binding.binding) != Some(binding.descriptor_type) {
+                    return Err(VulkanError::Custom(format!(
+                        "Binding {} of the bindless set {} does not match the bindless heap",
+                        binding.binding, binding.set
+                    )));
+                }
+
+                continue;
+            }
+
+            if binding.count == 0 {
+                return Err(VulkanError::Custom(format!(
+                    "Runtime sized descriptor array at set {} binding {} is not supported",
+                    binding.set, binding.binding
+                )));
+            }
+
+            sets.entry(binding.set).or_default().push(binding.into());
+        }
+
+        // Sets without bindings still need an empty layout to keep the set numbers intact.
+        let set_count = sets
+            .keys()
+            .next_back()
+            .copied()
+            .max(desc.bindless_set)
+            .map_or(0, |set| set + 1);
+
+        let mut descriptor_set_layouts = Vec::new();
+        let mut bind_group_layouts = Vec::new();
+
+        for set in 0..set_count {
+            if let Some(bindless_heap) = bindless_heap.filter(|_| Some(set) == desc.bindless_set) {
+                descriptor_set_layouts.push(bindless_heap.descriptor_set_layout());
+                bind_group_layouts.push(None);
+                continue;
+            }
+
+            let entries = sets.get(&set).map_or(&[][..], |entries| entries);
+            let bind_group_layout = VulkanBindGroupLayout::new(device, entries)?;
+
+            descriptor_set_layouts.push(bind_group_layout.descriptor_set_layout());
+            bind_group_layouts.push(Some(bind_group_layout));
+        }
+
+        let push_constant_ranges = desc
+            .push_constant_ranges
+            .iter()
+            .map(|range| {
+                vk::PushConstantRange {
+                    stage_flags: to_vk_shader_stage_flags(range.stages),
+                    offset: range.offset,
+                    size: range.size,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let pipeline_layout_create_info = vk::PipelineLayoutCreateInfo::default()
+            .set_layouts(&descriptor_set_layouts)
+            .push_constant_ranges(&push_constant_ranges);
+
+        let pipeline_layout = unsafe {
+            device
+                .device()
+                .create_pipeline_layout(&pipeline_layout_create_info, None)
+        }?;
+        device.set_object_name(pipeline_layout, desc.label);
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+
+            descriptor_set_layouts,
+            bind_group_layouts,
+            pipeline_layout,
+
+            descriptor_bindings: desc.descriptor_bindings.to_vec(),
+            push_constant_ranges: desc.push_constant_ranges.to_vec(),
+            bindless_set: desc.bindless_set,
+        })))
+    }
+
+    #[inline]
+    pub fn pipeline_layout(&self) -> &vk::PipelineLayout {
+        &self.0.pipeline_layout
+    }
+
+    #[inline]
+    pub fn descriptor_set_layouts(&self) -> &[vk::DescriptorSetLayout] {
+        &self.0.descriptor_set_layouts
+    }
+
+    #[inline]
+    pub fn bind_group_layout(&self, set: u32) -> Option<&VulkanBindGroupLayout> {
+        self.0
+            .bind_group_layouts
+            .get(set as usize)
+            .and_then(Option::as_ref)
+    }
+
+    #[inline]
+    pub fn descriptor_bindings(&self) -> &[DescriptorBinding] {
+        &self.0.descriptor_bindings
+    }
+
+    #[inline]
+    pub fn push_constant_ranges(&self) -> &[PushConstantRange] {
+        &self.0.push_constant_ranges
+    }
+
+    #[inline]
+    pub fn bindless_set(&self) -> Option<u32> {
+        self.0.bindless_set
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device
+                .device()
+                .destroy_pipeline_layout(self.pipeline_layout, None);
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use ash::vk;
+
+use crate::{
+    vulkan::{VulkanDevice, VulkanError},
+    PipelineStatisticsFlags, QueryPoolDesc, QueryType,
+};
+
+fn to_vk_query_pipeline_statistic_flags(
+    flags: PipelineStatisticsFlags,
+) -> vk::QueryPipelineStatisticFlags {
+    let mut result = vk::QueryPipelineStatisticFlags::empty();
+
+    if flags.contains(PipelineStatisticsFlags::INPUT_ASSEMBLY_VERTICES) {
+        result |= vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_VERTICES;
+    }
+    if flags.contains(PipelineStatisticsFlags::INPUT_ASSEMBLY_PRIMITIVES) {
+        result |= vk::QueryPipelineStatisticFlags::INPUT_ASSEMBLY_PRIMITIVES;
+    }
+    if flags.contains(PipelineStatisticsFlags::VERTEX_SHADER_INVOCATIONS) {
+        result |= vk::QueryPipelineStatisticFlags::VERTEX_SHADER_INVOCATIONS;
+    }
+    if flags.contains(PipelineStatisticsFlags::CLIPPING_INVOCATIONS) {
+        result |= vk::QueryPipelineStatisticFlags::CLIPPING_INVOCATIONS;
+    }
+    if flags.contains(PipelineStatisticsFlags::CLIPPING_PRIMITIVES) {
+        result |= vk::QueryPipelineStatisticFlags::CLIPPING_PRIMITIVES;
+    }
+    if flags.contains(PipelineStatisticsFlags::FRAGMENT_SHADER_INVOCATIONS) {
+        result |= vk::QueryPipelineStatisticFlags::FRAGMENT_SHADER_INVOCATIONS;
+    }
+    if flags.contains(PipelineStatisticsFlags::COMPUTE_SHADER_INVOCATIONS) {
+        result |= vk::QueryPipelineStatisticFlags::COMPUTE_SHADER_INVOCATIONS;
+    }
+
+    result
+}
+
+struct Inner {
+    device: VulkanDevice,
+
+    query_pool: vk::QueryPool,
+
+    query_type: QueryType,
+    count: u32,
+}
+
+#[derive(Clone)]
+pub struct VulkanQueryPool(Arc<Inner>);
+
+impl VulkanQueryPool {
+    pub fn new(device: &VulkanDevice, desc: &QueryPoolDesc) -> Result<Self, VulkanError> {
+        let mut query_pool_create_info = vk::QueryPoolCreateInfo::default().query_count(desc.count);
+        query_pool_create_info = match desc.query_type {
+            QueryType::Timestamp => query_pool_create_info.query_type(vk::QueryType::TIMESTAMP),
+            QueryType::Occlusion => query_pool_create_info.query_type(vk::QueryType::OCCLUSION),
+            QueryType::PipelineStatistics(flags) => {
+                query_pool_create_info
+                    .query_type(vk::QueryType::PIPELINE_STATISTICS)
+                    .pipeline_statistics(to_vk_query_pipeline_statistic_flags(flags))
+            }
+        };
+
+        let query_pool = unsafe {
+            device
+                .device()
+                .create_query_pool(&query_pool_create_info, None)
+        }?;
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+
+            query_pool,
+
+            query_type: desc.query_type,
+            count: desc.count,
+        })))
+    }
+
+    #[inline]
+    pub fn query_pool(&self) -> &vk::QueryPool {
+        &self.0.query_pool
+    }
+
+    #[inline]
+    pub fn query_type(&self) -> QueryType {
+        self.0.query_type
+    }
+
+    #[inline]
+    pub fn count(&self) -> u32 {
+        self.0.count
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device
+                .device()
+                .destroy_query_pool(self.query_pool, None);
+        }
+    }
+}
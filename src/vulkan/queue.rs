@@ -0,0 +1,158 @@
+use ash::vk;
+
+use crate::{
+    vulkan::{VulkanDevice, VulkanError},
+    QueueType, SubmitDesc,
+};
+
+#[derive(Clone)]
+pub struct VulkanQueue {
+    device: VulkanDevice,
+
+    queue_type: QueueType,
+    index: usize,
+}
+
+impl VulkanQueue {
+    pub(crate) fn new(device: &VulkanDevice, queue_type: QueueType) -> Self {
+        Self {
+            device: device.clone(),
+
+            queue_type,
+            index: device.queue_index(queue_type),
+        }
+    }
+
+    #[inline]
+    pub fn queue(&self) -> &vk::Queue {
+        &self.device.queue_entry(self.index).queue
+    }
+
+    #[inline]
+    pub fn family_index(&self) -> u32 {
+        self.device.queue_entry(self.index).family_index
+    }
+
+    #[inline]
+    pub fn queue_type(&self) -> QueueType {
+        self.queue_type
+    }
+
+    #[inline]
+    pub fn is_dedicated(&self) -> bool {
+        self.queue_type == QueueType::Graphics || self.index != 0
+    }
+
+    pub fn submit(&self, desc: &SubmitDesc) -> Result<(), VulkanError> {
+        let entry = self.device.queue_entry(self.index);
+
+        let command_buffers = desc
+            .command_lists
+            .iter()
+            .map(|command_list| {
+                let command_list = command_list.as_vulkan();
+
+                if command_list.family_index() != entry.family_index {
+                    return Err(VulkanError::Custom(format!(
+                        "Command list recorded for {:?} submitted to the {:?} queue",
+                        command_list.queue_type(),
+                        self.queue_type
+                    )));
+                }
+
+                Ok(command_list.command_buffers())
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .concat();
+
+        let mut wait_semaphores = desc
+            .wait_semaphores
+            .iter()
+            .map(|value| (*value.semaphore.as_vulkan().semaphore(), value.value))
+            .collect::<Vec<_>>();
+
+        if desc.wait_upload > 0 {
+            let transfer_entry = self
+                .device
+                .queue_entry(self.device.queue_index(QueueType::Transfer));
+
+            wait_semaphores.push((transfer_entry.submission_semaphore, desc.wait_upload));
+        }
+
+        let signal_semaphores = desc
+            .signal_semaphores
+            .iter()
+            .map(|value| (*value.semaphore.as_vulkan().semaphore(), value.value))
+            .collect::<Vec<_>>();
+
+        let fence = desc
+            .fence
+            .map_or(vk::Fence::null(), |fence| *fence.as_vulkan().fence());
+
+        submit_command_buffers(
+            &self.device,
+            self.index,
+            &wait_semaphores,
+            &command_buffers,
+            &signal_semaphores,
+            fence,
+        )?;
+
+        Ok(())
+    }
+
+    pub fn wait_idle(&self) -> Result<(), VulkanError> {
+        let entry = self.device.queue_entry(self.index);
+        let _lock = entry.lock.lock().unwrap();
+
+        unsafe { self.device.device().queue_wait_idle(entry.queue) }?;
+
+        Ok(())
+    }
+}
+
+/// Submits `command_buffers` to the device queue at `index` and returns the value its
+/// submission timeline reaches once they have completed.
+pub(crate) fn submit_command_buffers(
+    device: &VulkanDevice,
+    index: usize,
+    wait_semaphores: &[(vk::Semaphore, u64)],
+    command_buffers: &[vk::CommandBuffer],
+    signal_semaphores: &[(vk::Semaphore, u64)],
+    fence: vk::Fence,
+) -> Result<u64, VulkanError> {
+    let entry = device.queue_entry(index);
+
+    let (wait_semaphores, wait_values): (Vec<_>, Vec<_>) = wait_semaphores.iter().copied().unzip();
+    let wait_dst_stage_mask = vec![vk::PipelineStageFlags::ALL_COMMANDS; wait_semaphores.len()];
+
+    let mut lock = entry.lock.lock().unwrap();
+    let submission_value = *lock + 1;
+
+    let (mut signal_semaphores, mut signal_values): (Vec<_>, Vec<_>) =
+        signal_semaphores.iter().copied().unzip();
+
+    signal_semaphores.push(entry.submission_semaphore);
+    signal_values.push(submission_value);
+
+    let mut timeline_semaphore_submit_info = vk::TimelineSemaphoreSubmitInfo::default()
+        .wait_semaphore_values(&wait_values)
+        .signal_semaphore_values(&signal_values);
+
+    let submit_info = vk::SubmitInfo::default()
+        .wait_semaphores(&wait_semaphores)
+        .wait_dst_stage_mask(&wait_dst_stage_mask)
+        .command_buffers(command_buffers)
+        .signal_semaphores(&signal_semaphores)
+        .push_next(&mut timeline_semaphore_submit_info);
+
+    unsafe {
+        device
+            .device()
+            .queue_submit(entry.queue, &[submit_info], fence)
+    }?;
+
+    *lock = submission_value;
+
+    Ok(submission_value)
+}
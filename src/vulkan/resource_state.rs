@@ -0,0 +1,365 @@
+use ash::vk;
+
+use crate::QueueType;
+
+/// Access writing a resource, any of these flags turns an access into a write.
+const WRITE_ACCESS: vk::AccessFlags2 = vk::AccessFlags2::from_raw(
+    vk::AccessFlags2::SHADER_WRITE.as_raw()
+        | vk::AccessFlags2::SHADER_STORAGE_WRITE.as_raw()
+        | vk::AccessFlags2::COLOR_ATTACHMENT_WRITE.as_raw()
+        | vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE.as_raw()
+        | vk::AccessFlags2::TRANSFER_WRITE.as_raw()
+        | vk::AccessFlags2::HOST_WRITE.as_raw()
+        | vk::AccessFlags2::MEMORY_WRITE.as_raw(),
+);
+
+/// How a command accesses a tracked resource.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ResourceAccess {
+    pub stages: vk::PipelineStageFlags2,
+    pub access: vk::AccessFlags2,
+    /// `UNDEFINED` for buffers, see [`Self::for_buffer`].
+    pub layout: vk::ImageLayout,
+}
+
+impl ResourceAccess {
+    pub const TRANSFER_READ: Self = Self {
+        stages: vk::PipelineStageFlags2::ALL_TRANSFER,
+        access: vk::AccessFlags2::TRANSFER_READ,
+        layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+    };
+    pub const TRANSFER_WRITE: Self = Self {
+        stages: vk::PipelineStageFlags2::ALL_TRANSFER,
+        access: vk::AccessFlags2::TRANSFER_WRITE,
+        layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+    };
+    pub const COLOR_ATTACHMENT: Self = Self {
+        stages: vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT,
+        access: vk::AccessFlags2::from_raw(
+            vk::AccessFlags2::COLOR_ATTACHMENT_READ.as_raw()
+                | vk::AccessFlags2::COLOR_ATTACHMENT_WRITE.as_raw(),
+        ),
+        layout: vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+    };
+    pub const DEPTH_STENCIL_ATTACHMENT: Self = Self {
+        stages: vk::PipelineStageFlags2::from_raw(
+            vk::PipelineStageFlags2::EARLY_FRAGMENT_TESTS.as_raw()
+                | vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS.as_raw(),
+        ),
+        access: vk::AccessFlags2::from_raw(
+            vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_READ.as_raw()
+                | vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE.as_raw(),
+        ),
+        layout: vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+    };
+    /// Depth stencil resolves run in the color attachment output stage.
+    pub const DEPTH_STENCIL_RESOLVE: Self = Self {
+        stages: vk::PipelineStageFlags2::from_raw(
+            vk::PipelineStageFlags2::LATE_FRAGMENT_TESTS.as_raw()
+                | vk::PipelineStageFlags2::COLOR_ATTACHMENT_OUTPUT.as_raw(),
+        ),
+        access: vk::AccessFlags2::from_raw(
+            vk::AccessFlags2::DEPTH_STENCIL_ATTACHMENT_WRITE.as_raw()
+                | vk::AccessFlags2::COLOR_ATTACHMENT_WRITE.as_raw(),
+        ),
+        layout: vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL,
+    };
+    pub const VERTEX_BUFFER: Self = Self {
+        stages: vk::PipelineStageFlags2::VERTEX_ATTRIBUTE_INPUT,
+        access: vk::AccessFlags2::VERTEX_ATTRIBUTE_READ,
+        layout: vk::ImageLayout::UNDEFINED,
+    };
+    pub const INDEX_BUFFER: Self = Self {
+        stages: vk::PipelineStageFlags2::INDEX_INPUT,
+        access: vk::AccessFlags2::INDEX_READ,
+        layout: vk::ImageLayout::UNDEFINED,
+    };
+    pub const INDIRECT_BUFFER: Self = Self {
+        stages: vk::PipelineStageFlags2::DRAW_INDIRECT,
+        access: vk::AccessFlags2::INDIRECT_COMMAND_READ,
+        layout: vk::ImageLayout::UNDEFINED,
+    };
+
+    /// Returns the access without its layout, so buffer accesses only differ in stages and
+    /// access flags and reads never transition.
+    #[inline]
+    pub fn for_buffer(self) -> Self {
+        Self {
+            layout: vk::ImageLayout::UNDEFINED,
+            ..self
+        }
+    }
+
+    #[inline]
+    pub fn is_write(&self) -> bool {
+        self.access.intersects(WRITE_ACCESS)
+    }
+}
+
+/// The two synchronization scopes and layout transition of a barrier.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ResourceBarrier {
+    pub src_stages: vk::PipelineStageFlags2,
+    pub src_access: vk::AccessFlags2,
+    pub dst_stages: vk::PipelineStageFlags2,
+    pub dst_access: vk::AccessFlags2,
+    pub old_layout: vk::ImageLayout,
+    pub new_layout: vk::ImageLayout,
+}
+
+/// Last accesses of a tracked buffer or texture subresource.
+///
+/// Reads since the last write are accumulated, so a write waits for all of them while further
+/// reads in stages that already saw the write need no barrier at all.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ResourceState {
+    queue_type: Option<QueueType>,
+    layout: vk::ImageLayout,
+
+    write_stages: vk::PipelineStageFlags2,
+    write_access: vk::AccessFlags2,
+    read_stages: vk::PipelineStageFlags2,
+    read_access: vk::AccessFlags2,
+}
+
+impl Default for ResourceState {
+    fn default() -> Self {
+        Self {
+            queue_type: None,
+            layout: vk::ImageLayout::UNDEFINED,
+
+            write_stages: vk::PipelineStageFlags2::NONE,
+            write_access: vk::AccessFlags2::NONE,
+            read_stages: vk::PipelineStageFlags2::NONE,
+            read_access: vk::AccessFlags2::NONE,
+        }
+    }
+}
+
+impl ResourceState {
+    /// Moves the resource to `access` on `queue_type` and returns the barrier that has to be
+    /// recorded before it, if any.
+    ///
+    /// Buffers pass accesses through [`ResourceAccess::for_buffer`] to never change layout.
+    pub fn transition(
+        &mut self,
+        access: ResourceAccess,
+        queue_type: QueueType,
+    ) -> Option<ResourceBarrier> {
+        let old_layout = self.layout;
+        let layout_changes = old_layout != access.layout;
+
+        // Accesses on another queue are ordered by the semaphore the submission waits on, only a
+        // layout transition still has to chain onto the semaphore wait, which covers all stages.
+        if self.queue_type.is_some_and(|last| last != queue_type) {
+            *self = Self {
+                queue_type: Some(queue_type),
+                layout: old_layout,
+                ..Self::default()
+            };
+
+            if layout_changes {
+                self.write_stages = vk::PipelineStageFlags2::ALL_COMMANDS;
+            }
+        }
+        self.queue_type = Some(queue_type);
+
+        if access.is_write() || layout_changes {
+            let src_stages = self.write_stages | self.read_stages;
+            let src_access = self.write_access;
+
+            self.layout = access.layout;
+            self.write_stages = access.stages;
+            if access.is_write() {
+                self.write_access = access.access;
+                self.read_stages = vk::PipelineStageFlags2::NONE;
+                self.read_access = vk::AccessFlags2::NONE;
+            } else {
+                // The layout transition is the write the following reads depend on.
+                self.write_access = vk::AccessFlags2::NONE;
+                self.read_stages = access.stages;
+                self.read_access = access.access;
+            }
+
+            if !layout_changes && src_stages.is_empty() {
+                return None;
+            }
+
+            return Some(ResourceBarrier {
+                src_stages,
+                src_access,
+                dst_stages: access.stages,
+                dst_access: access.access,
+                old_layout,
+                new_layout: access.layout,
+            });
+        }
+
+        let visible =
+            self.read_stages.contains(access.stages) && self.read_access.contains(access.access);
+
+        self.read_stages |= access.stages;
+        self.read_access |= access.access;
+
+        if self.write_stages.is_empty() || visible {
+            return None;
+        }
+
+        Some(ResourceBarrier {
+            src_stages: self.write_stages,
+            src_access: self.write_access,
+            dst_stages: access.stages,
+            dst_access: access.access,
+            old_layout,
+            new_layout: access.layout,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHADER_READ: ResourceAccess = ResourceAccess {
+        stages: vk::PipelineStageFlags2::FRAGMENT_SHADER,
+        access: vk::AccessFlags2::SHADER_SAMPLED_READ,
+        layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+    };
+
+    #[test]
+    fn read_after_read_needs_no_barrier() {
+        let mut state = ResourceState::default();
+
+        for access in [
+            ResourceAccess::TRANSFER_READ.for_buffer(),
+            ResourceAccess::TRANSFER_READ.for_buffer(),
+            ResourceAccess::VERTEX_BUFFER,
+            ResourceAccess::INDEX_BUFFER,
+        ] {
+            assert_eq!(state.transition(access, QueueType::Graphics), None);
+        }
+    }
+
+    #[test]
+    fn read_after_write_waits_for_the_write() {
+        let mut state = ResourceState::default();
+
+        assert_eq!(
+            state.transition(
+                ResourceAccess::TRANSFER_WRITE.for_buffer(),
+                QueueType::Graphics
+            ),
+            None
+        );
+        assert_eq!(
+            state.transition(ResourceAccess::VERTEX_BUFFER, QueueType::Graphics),
+            Some(ResourceBarrier {
+                src_stages: vk::PipelineStageFlags2::ALL_TRANSFER,
+                src_access: vk::AccessFlags2::TRANSFER_WRITE,
+                dst_stages: vk::PipelineStageFlags2::VERTEX_ATTRIBUTE_INPUT,
+                dst_access: vk::AccessFlags2::VERTEX_ATTRIBUTE_READ,
+                old_layout: vk::ImageLayout::UNDEFINED,
+                new_layout: vk::ImageLayout::UNDEFINED,
+            })
+        );
+        assert_eq!(
+            state.transition(ResourceAccess::VERTEX_BUFFER, QueueType::Graphics),
+            None
+        );
+    }
+
+    #[test]
+    fn write_after_read_waits_for_the_reads() {
+        let mut state = ResourceState::default();
+
+        state.transition(ResourceAccess::VERTEX_BUFFER, QueueType::Graphics);
+        state.transition(ResourceAccess::INDEX_BUFFER, QueueType::Graphics);
+
+        assert_eq!(
+            state.transition(
+                ResourceAccess::TRANSFER_WRITE.for_buffer(),
+                QueueType::Graphics
+            ),
+            Some(ResourceBarrier {
+                src_stages: vk::PipelineStageFlags2::VERTEX_ATTRIBUTE_INPUT
+                    | vk::PipelineStageFlags2::INDEX_INPUT,
+                src_access: vk::AccessFlags2::NONE,
+                dst_stages: vk::PipelineStageFlags2::ALL_TRANSFER,
+                dst_access: vk::AccessFlags2::TRANSFER_WRITE,
+                old_layout: vk::ImageLayout::UNDEFINED,
+                new_layout: vk::ImageLayout::UNDEFINED,
+            })
+        );
+    }
+
+    #[test]
+    fn layout_changes_need_a_barrier() {
+        let mut state = ResourceState::default();
+
+        assert_eq!(
+            state.transition(ResourceAccess::TRANSFER_WRITE, QueueType::Graphics),
+            Some(ResourceBarrier {
+                src_stages: vk::PipelineStageFlags2::NONE,
+                src_access: vk::AccessFlags2::NONE,
+                dst_stages: vk::PipelineStageFlags2::ALL_TRANSFER,
+                dst_access: vk::AccessFlags2::TRANSFER_WRITE,
+                old_layout: vk::ImageLayout::UNDEFINED,
+                new_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            })
+        );
+        assert_eq!(
+            state.transition(SHADER_READ, QueueType::Graphics),
+            Some(ResourceBarrier {
+                src_stages: vk::PipelineStageFlags2::ALL_TRANSFER,
+                src_access: vk::AccessFlags2::TRANSFER_WRITE,
+                dst_stages: SHADER_READ.stages,
+                dst_access: SHADER_READ.access,
+                old_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                new_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            })
+        );
+        assert_eq!(state.transition(SHADER_READ, QueueType::Graphics), None);
+
+        // A read in another layout waits for the reads in the old one.
+        assert_eq!(
+            state.transition(ResourceAccess::TRANSFER_READ, QueueType::Graphics),
+            Some(ResourceBarrier {
+                src_stages: SHADER_READ.stages,
+                src_access: vk::AccessFlags2::NONE,
+                dst_stages: vk::PipelineStageFlags2::ALL_TRANSFER,
+                dst_access: vk::AccessFlags2::TRANSFER_READ,
+                old_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                new_layout: vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            })
+        );
+    }
+
+    #[test]
+    fn queue_changes_reset_the_accesses() {
+        let mut buffer_state = ResourceState::default();
+        buffer_state.transition(
+            ResourceAccess::TRANSFER_WRITE.for_buffer(),
+            QueueType::Transfer,
+        );
+
+        assert_eq!(
+            buffer_state.transition(ResourceAccess::VERTEX_BUFFER, QueueType::Graphics),
+            None
+        );
+
+        let mut texture_state = ResourceState::default();
+        texture_state.transition(ResourceAccess::TRANSFER_WRITE, QueueType::Transfer);
+
+        assert_eq!(
+            texture_state.transition(SHADER_READ, QueueType::Graphics),
+            Some(ResourceBarrier {
+                src_stages: vk::PipelineStageFlags2::ALL_COMMANDS,
+                src_access: vk::AccessFlags2::NONE,
+                dst_stages: SHADER_READ.stages,
+                dst_access: SHADER_READ.access,
+                old_layout: vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                new_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            })
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    sync::{Arc, Mutex},
+};
+
+use ash::vk;
+
+use crate::{
+    validate_sampler_desc,
+    vulkan::{to_vk_compare_op, VulkanDevice, VulkanError},
+    AddressMode, BorderColor, FilterMode, MipmapMode, SamplerDesc, SamplerReductionMode,
+};
+
+fn to_vk_filter(filter: FilterMode) -> vk::Filter {
+    match filter {
+        FilterMode::Nearest => vk::Filter::NEAREST,
+        FilterMode::Linear => vk::Filter::LINEAR,
+    }
+}
+
+fn to_vk_sampler_mipmap_mode(mipmap_mode: MipmapMode) -> vk::SamplerMipmapMode {
+    match mipmap_mode {
+        MipmapMode::Nearest => vk::SamplerMipmapMode::NEAREST,
+        MipmapMode::Linear => vk::SamplerMipmapMode::LINEAR,
+    }
+}
+
+fn to_vk_sampler_address_mode(address_mode: AddressMode) -> vk::SamplerAddressMode {
+    match address_mode {
+        AddressMode::Repeat => vk::SamplerAddressMode::REPEAT,
+        AddressMode::MirroredRepeat => vk::SamplerAddressMode::MIRRORED_REPEAT,
+        AddressMode::ClampToEdge => vk::SamplerAddressMode::CLAMP_TO_EDGE,
+        AddressMode::ClampToBorder => vk::SamplerAddressMode::CLAMP_TO_BORDER,
+    }
+}
+
+fn to_vk_border_color(border_color: BorderColor) -> vk::BorderColor {
+    match border_color {
+        BorderColor::TransparentBlack => vk::BorderColor::FLOAT_TRANSPARENT_BLACK,
+        BorderColor::OpaqueBlack => vk::BorderColor::FLOAT_OPAQUE_BLACK,
+        BorderColor::OpaqueWhite => vk::BorderColor::FLOAT_OPAQUE_WHITE,
+    }
+}
+
+fn to_vk_sampler_reduction_mode(reduction_mode: SamplerReductionMode) -> vk::SamplerReductionMode {
+    match reduction_mode {
+        SamplerReductionMode::WeightedAverage => vk::SamplerReductionMode::WEIGHTED_AVERAGE,
+        SamplerReductionMode::Min => vk::SamplerReductionMode::MIN,
+        SamplerReductionMode::Max => vk::SamplerReductionMode::MAX,
+    }
+}
+
+/// Samplers of the device by descriptor, devices may only hold `maxSamplerAllocationCount`.
+pub(crate) type SamplerCache = Mutex<HashMap<SamplerDesc, VulkanSampler>>;
+
+/// The sampler is owned by the device cache, objects using it keep the device alive.
+struct Inner {
+    sampler: vk::Sampler,
+
+    desc: SamplerDesc,
+}
+
+#[derive(Clone)]
+pub struct VulkanSampler(Arc<Inner>);
+
+impl PartialEq for VulkanSampler {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.sampler == other.0.sampler
+    }
+}
+
+impl Eq for VulkanSampler {}
+
+impl Hash for VulkanSampler {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.sampler.hash(state);
+    }
+}
+
+impl VulkanSampler {
+    pub fn new(device: &VulkanDevice, desc: &SamplerDesc) -> Result<Self, VulkanError> {
+        validate_sampler_desc(desc).map_err(VulkanError::Custom)?;
+
+        let mut cache = device.sampler_cache().lock().unwrap();
+
+        if let Some(sampler) = cache.get(desc) {
+            return Ok(sampler.clone());
+        }
+
+        let max_sampler_count = device.limits().max_sampler_allocation_count;
+        if cache.len() >= max_sampler_count as usize {
+            return Err(VulkanError::Custom(format!(
+                "Device supports at most {max_sampler_count} distinct samplers"
+            )));
+        }
+
+        let mut reduction_mode_create_info = vk::SamplerReductionModeCreateInfo::default()
+            .reduction_mode(to_vk_sampler_reduction_mode(desc.reduction_mode));
+
+        let mut sampler_create_info = vk::SamplerCreateInfo::default()
+            .mag_filter(to_vk_filter(desc.mag_filter))
+            .min_filter(to_vk_filter(desc.min_filter))
+            .mipmap_mode(to_vk_sampler_mipmap_mode(desc.mipmap_mode))
+            .address_mode_u(to_vk_sampler_address_mode(desc.address_mode_u))
+            .address_mode_v(to_vk_sampler_address_mode(desc.address_mode_v))
+            .address_mode_w(to_vk_sampler_address_mode(desc.address_mode_w))
+            .anisotropy_enable(desc.max_anisotropy > 1)
+            .max_anisotropy(
+                (desc.max_anisotropy as f32).min(device.limits().max_sampler_anisotropy),
+            )
+            .compare_enable(desc.compare.is_some())
+            .compare_op(desc.compare.map_or(vk::CompareOp::NEVER, to_vk_compare_op))
+            .min_lod(desc.lod_min_clamp)
+            .max_lod(desc.lod_max_clamp)
+            .border_color(to_vk_border_color(desc.border_color));
+
+        // Min and max reductions need samplerFilterMinmax, other samplers keep the chain empty.
+        if desc.reduction_mode != SamplerReductionMode::WeightedAverage {
+            sampler_create_info = sampler_create_info.push_next(&mut reduction_mode_create_info);
+        }
+
+        let sampler = unsafe { device.device().create_sampler(&sampler_create_info, None) }?;
+
+        let sampler = Self(Arc::new(Inner {
+            sampler,
+
+            desc: *desc,
+        }));
+
+        cache.insert(*desc, sampler.clone());
+
+        Ok(sampler)
+    }
+
+    #[inline]
+    pub fn sampler(&self) -> vk::Sampler {
+        self.0.sampler
+    }
+
+    #[inline]
+    pub fn desc(&self) -> &SamplerDesc {
+        &self.0.desc
+    }
+}
@@ -0,0 +1,133 @@
+use std::{sync::Arc, time::Duration};
+
+use ash::vk;
+
+use crate::{
+    vulkan::{VulkanDevice, VulkanError},
+    PhysicalDeviceFeatures, SemaphoreDesc, SemaphoreValue,
+};
+
+struct Inner {
+    device: VulkanDevice,
+
+    semaphore: vk::Semaphore,
+}
+
+#[derive(Clone)]
+pub struct VulkanSemaphore(Arc<Inner>);
+
+/// Converts a timeout to nanoseconds, `None` waits indefinitely.
+pub(crate) fn to_vk_timeout(timeout: Option<Duration>) -> u64 {
+    timeout.map_or(u64::MAX, |timeout| {
+        timeout.as_nanos().try_into().unwrap_or(u64::MAX)
+    })
+}
+
+impl VulkanSemaphore {
+    pub fn new(device: &VulkanDevice, desc: &SemaphoreDesc) -> Result<Self, VulkanError> {
+        if !device
+            .get_enabled_features()
+            .contains(PhysicalDeviceFeatures::TIMELINE_SEMAPHORE)
+        {
+            return Err(VulkanError::Custom(
+                "Timeline semaphores are not supported by the device".to_owned(),
+            ));
+        }
+
+        let mut semaphore_type_create_info = vk::SemaphoreTypeCreateInfo::default()
+            .semaphore_type(vk::SemaphoreType::TIMELINE)
+            .initial_value(desc.initial_value);
+
+        let semaphore_create_info =
+            vk::SemaphoreCreateInfo::default().push_next(&mut semaphore_type_create_info);
+
+        let semaphore = unsafe {
+            device
+                .device()
+                .create_semaphore(&semaphore_create_info, None)
+        }?;
+        device.set_object_name(semaphore, desc.label);
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+
+            semaphore,
+        })))
+    }
+
+    #[inline]
+    pub fn semaphore(&self) -> &vk::Semaphore {
+        &self.0.semaphore
+    }
+
+    pub fn get_value(&self) -> Result<u64, VulkanError> {
+        Ok(unsafe {
+            self.0
+                .device
+                .device()
+                .get_semaphore_counter_value(self.0.semaphore)
+        }?)
+    }
+
+    pub fn signal(&self, value: u64) -> Result<(), VulkanError> {
+        let semaphore_signal_info = vk::SemaphoreSignalInfo::default()
+            .semaphore(self.0.semaphore)
+            .value(value);
+
+        unsafe {
+            self.0
+                .device
+                .device()
+                .signal_semaphore(&semaphore_signal_info)
+        }?;
+
+        Ok(())
+    }
+
+    pub fn wait(&self, value: u64, timeout: Option<Duration>) -> Result<bool, VulkanError> {
+        Self::wait_for_raw_values(&self.0.device, &[self.0.semaphore], &[value], timeout)
+    }
+
+    pub fn wait_for_values(
+        device: &VulkanDevice,
+        values: &[SemaphoreValue],
+        timeout: Option<Duration>,
+    ) -> Result<bool, VulkanError> {
+        let semaphores = values
+            .iter()
+            .map(|value| *value.semaphore.as_vulkan().semaphore())
+            .collect::<Vec<_>>();
+        let values = values.iter().map(|value| value.value).collect::<Vec<_>>();
+
+        Self::wait_for_raw_values(device, &semaphores, &values, timeout)
+    }
+
+    pub(crate) fn wait_for_raw_values(
+        device: &VulkanDevice,
+        semaphores: &[vk::Semaphore],
+        values: &[u64],
+        timeout: Option<Duration>,
+    ) -> Result<bool, VulkanError> {
+        let semaphore_wait_info = vk::SemaphoreWaitInfo::default()
+            .semaphores(semaphores)
+            .values(values);
+
+        match unsafe {
+            device
+                .device()
+                .wait_semaphores(&semaphore_wait_info, to_vk_timeout(timeout))
+        } {
+            Ok(()) => Ok(true),
+            Err(vk::Result::TIMEOUT) => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device.device().destroy_semaphore(self.semaphore, None);
+        }
+    }
+}
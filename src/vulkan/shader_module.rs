@@ -0,0 +1,142 @@
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
+
+use ash::vk;
+
+use crate::{
+    vulkan::{VulkanDevice, VulkanError},
+    DescriptorType, ShaderModuleDesc, ShaderReflection, ShaderStageFlags, SpecializationConstant,
+};
+
+pub(crate) fn to_vk_shader_stage_flags(stages: ShaderStageFlags) -> vk::ShaderStageFlags {
+    let mut flags = vk::ShaderStageFlags::empty();
+
+    if stages.contains(ShaderStageFlags::VERTEX) {
+        flags |= vk::ShaderStageFlags::VERTEX;
+    }
+    if stages.contains(ShaderStageFlags::FRAGMENT) {
+        flags |= vk::ShaderStageFlags::FRAGMENT;
+    }
+    if stages.contains(ShaderStageFlags::COMPUTE) {
+        flags |= vk::ShaderStageFlags::COMPUTE;
+    }
+    if stages.contains(ShaderStageFlags::TASK) {
+        flags |= vk::ShaderStageFlags::TASK_EXT;
+    }
+    if stages.contains(ShaderStageFlags::MESH) {
+        flags |= vk::ShaderStageFlags::MESH_EXT;
+    }
+
+    flags
+}
+
+pub(crate) fn to_vk_descriptor_type(descriptor_type: DescriptorType) -> vk::DescriptorType {
+    match descriptor_type {
+        DescriptorType::Sampler => vk::DescriptorType::SAMPLER,
+        DescriptorType::SampledTexture => vk::DescriptorType::SAMPLED_IMAGE,
+        DescriptorType::StorageTexture => vk::DescriptorType::STORAGE_IMAGE,
+        DescriptorType::CombinedTextureSampler => vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+        DescriptorType::UniformTexelBuffer => vk::DescriptorType::UNIFORM_TEXEL_BUFFER,
+        DescriptorType::StorageTexelBuffer => vk::DescriptorType::STORAGE_TEXEL_BUFFER,
+        DescriptorType::UniformBuffer => vk::DescriptorType::UNIFORM_BUFFER,
+        DescriptorType::StorageBuffer => vk::DescriptorType::STORAGE_BUFFER,
+        DescriptorType::AccelerationStructure => vk::DescriptorType::ACCELERATION_STRUCTURE_KHR,
+    }
+}
+
+/// Returns the map entries and data of `constants`, every constant occupies four bytes.
+pub(crate) fn to_vk_specialization_data(
+    constants: &[SpecializationConstant],
+) -> (Vec<vk::SpecializationMapEntry>, Vec<u8>) {
+    let map_entries = constants
+        .iter()
+        .enumerate()
+        .map(|(i, constant)| {
+            vk::SpecializationMapEntry {
+                constant_id: constant.id,
+                offset: (i * 4) as u32,
+                size: 4,
+            }
+        })
+        .collect();
+
+    let data = constants
+        .iter()
+        .flat_map(|constant| constant.value.to_ne_bytes())
+        .collect();
+
+    (map_entries, data)
+}
+
+struct Inner {
+    device: VulkanDevice,
+
+    shader_module: vk::ShaderModule,
+    reflection: ShaderReflection,
+}
+
+#[derive(Clone)]
+pub struct VulkanShaderModule(Arc<Inner>);
+
+impl PartialEq for VulkanShaderModule {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for VulkanShaderModule {}
+
+impl Hash for VulkanShaderModule {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state);
+    }
+}
+
+impl VulkanShaderModule {
+    pub fn new(
+        device: &VulkanDevice,
+        desc: &ShaderModuleDesc,
+        code: &[u32],
+        reflection: ShaderReflection,
+    ) -> Result<Self, VulkanError> {
+        let shader_module_create_info = vk::ShaderModuleCreateInfo::default().code(code);
+
+        let shader_module = unsafe {
+            device
+                .device()
+                .create_shader_module(&shader_module_create_info, None)
+        }?;
+        device.set_object_name(shader_module, desc.label);
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+
+            shader_module,
+            reflection,
+        })))
+    }
+
+    #[inline]
+    pub fn shader_module(&self) -> &vk::ShaderModule {
+        &self.0.shader_module
+    }
+
+    #[inline]
+    pub fn reflection(&self) -> &ShaderReflection {
+        &self.0.reflection
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device
+                .device()
+                .destroy_shader_module(self.shader_module, None);
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use std::sync::{Arc, Mutex};
+
+use ash::vk;
+use vk_mem_alloc::{Allocation, AllocationCreateInfo, MemoryUsage};
+
+use crate::{
+    vulkan::{to_vk_format, ResourceState, VulkanDevice, VulkanError},
+    Extent3d, Format, Texture, TextureAspectFlags, TextureDesc, TextureDimension,
+    TextureSubresourceRange, TextureUsageFlags, TextureViewDesc, TextureViewDimension,
+};
+
+struct Inner {
+    device: VulkanDevice,
+
+    image: vk::Image,
+    allocation: Allocation,
+
+    dimension: TextureDimension,
+    format: Format,
+    extent: Extent3d,
+    mip_levels: u32,
+    array_layers: u32,
+    sample_count: u32,
+    usage: TextureUsageFlags,
+    /// One state per subresource, indexed by mip level and then by array layer.
+    states: Option<Mutex<Vec<ResourceState>>>,
+}
+
+unsafe impl Send for Inner {}
+unsafe impl Sync for Inner {}
+
+#[derive(Clone)]
+pub struct VulkanTexture(Arc<Inner>);
+
+fn to_vk_image_usage_flags(usage: TextureUsageFlags) -> vk::ImageUsageFlags {
+    let mut result = vk::ImageUsageFlags::empty();
+
+    if usage.contains(TextureUsageFlags::TRANSFER_SRC) {
+        result |= vk::ImageUsageFlags::TRANSFER_SRC;
+    }
+    if usage.contains(TextureUsageFlags::TRANSFER_DST) {
+        result |= vk::ImageUsageFlags::TRANSFER_DST;
+    }
+    if usage.contains(TextureUsageFlags::SAMPLED) {
+        result |= vk::ImageUsageFlags::SAMPLED;
+    }
+    if usage.contains(TextureUsageFlags::STORAGE) {
+        result |= vk::ImageUsageFlags::STORAGE;
+    }
+    if usage.contains(TextureUsageFlags::COLOR_ATTACHMENT) {
+        result |= vk::ImageUsageFlags::COLOR_ATTACHMENT;
+    }
+    if usage.contains(TextureUsageFlags::DEPTH_STENCIL_ATTACHMENT) {
+        result |= vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT;
+    }
+
+    result
+}
+
+fn to_vk_image_type(dimension: TextureDimension) -> vk::ImageType {
+    match dimension {
+        TextureDimension::D1 => vk::ImageType::TYPE_1D,
+        TextureDimension::D2 | TextureDimension::Cube => vk::ImageType::TYPE_2D,
+        TextureDimension::D3 => vk::ImageType::TYPE_3D,
+    }
+}
+
+fn to_vk_image_view_type(dimension: TextureViewDimension) -> vk::ImageViewType {
+    match dimension {
+        TextureViewDimension::D1 => vk::ImageViewType::TYPE_1D,
+        TextureViewDimension::D1Array => vk::ImageViewType::TYPE_1D_ARRAY,
+        TextureViewDimension::D2 => vk::ImageViewType::TYPE_2D,
+        TextureViewDimension::D2Array => vk::ImageViewType::TYPE_2D_ARRAY,
+        TextureViewDimension::D3 => vk::ImageViewType::TYPE_3D,
+        TextureViewDimension::Cube => vk::ImageViewType::CUBE,
+        TextureViewDimension::CubeArray => vk::ImageViewType::CUBE_ARRAY,
+    }
+}
+
+pub(crate) fn to_vk_image_aspect_flags(aspect: TextureAspectFlags) -> vk::ImageAspectFlags {
+    let mut result = vk::ImageAspectFlags::empty();
+
+    if aspect.contains(TextureAspectFlags::COLOR) {
+        result |= vk::ImageAspectFlags::COLOR;
+    }
+    if aspect.contains(TextureAspectFlags::DEPTH) {
+        result |= vk::ImageAspectFlags::DEPTH;
+    }
+    if aspect.contains(TextureAspectFlags::STENCIL) {
+        result |= vk::ImageAspectFlags::STENCIL;
+    }
+
+    result
+}
+
+pub(crate) fn to_vk_image_subresource_range(
+    range: &TextureSubresourceRange,
+) -> vk::ImageSubresourceRange {
+    vk::ImageSubresourceRange::default()
+        .aspect_mask(to_vk_image_aspect_flags(range.aspect))
+        .base_mip_level(range.base_mip_level)
+        .level_count(range.mip_level_count.unwrap_or(vk::REMAINING_MIP_LEVELS))
+        .base_array_layer(range.base_array_layer)
+        .layer_count(
+            range
+                .array_layer_count
+                .unwrap_or(vk::REMAINING_ARRAY_LAYERS),
+        )
+}
+
+impl VulkanTexture {
+    pub fn new(device: &VulkanDevice, desc: &TextureDesc) -> Result<Self, VulkanError> {
+        let mut flags = vk::ImageCreateFlags::empty();
+
+        if desc.dimension == TextureDimension::Cube {
+            flags |= vk::ImageCreateFlags::CUBE_COMPATIBLE;
+        }
+        // Views may reinterpret color formats with the same block size and extent.
+        if !desc.format.is_depth_stencil() {
+            flags |= vk::ImageCreateFlags::MUTABLE_FORMAT;
+        }
+
+        let queue_family_indices = device.queue_family_indices();
+
+        let mut image_create_info = vk::ImageCreateInfo::default()
+            .flags(flags)
+            .image_type(to_vk_image_type(desc.dimension))
+            .format(to_vk_format(desc.format))
+            .extent(vk::Extent3D {
+                width: desc.extent.width,
+                height: desc.extent.height,
+                depth: desc.extent.depth,
+            })
+            .mip_levels(desc.mip_levels)
+            .array_layers(desc.array_layers)
+            .samples(vk::SampleCountFlags::from_raw(desc.sample_count))
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(to_vk_image_usage_flags(desc.usage))
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+
+        // Tracked textures are shared by all queue families, so no ownership transfers are needed.
+        if desc.tracked && queue_family_indices.len() > 1 {
+            image_create_info = image_create_info
+                .sharing_mode(vk::SharingMode::CONCURRENT)
+                .queue_family_indices(&queue_family_indices);
+        }
+
+        let (image, allocation, _) = unsafe {
+            vk_mem_alloc::create_image(
+                *device.allocator(),
+                &image_create_info,
+                &AllocationCreateInfo {
+                    usage: MemoryUsage::AUTO_PREFER_DEVICE,
+                    ..Default::default()
+                },
+            )
+        }?;
+        device.set_object_name(image, desc.label);
+
+        Ok(Self(Arc::new(Inner {
+            device: device.clone(),
+
+            image,
+            allocation,
+
+            dimension: desc.dimension,
+            format: desc.format,
+            extent: desc.extent,
+            mip_levels: desc.mip_levels,
+            array_layers: desc.array_layers,
+            sample_count: desc.sample_count,
+            usage: desc.usage,
+            states: desc.tracked.then(|| {
+                Mutex::new(vec![
+                    ResourceState::default();
+                    (desc.mip_levels * desc.array_layers) as usize
+                ])
+            }),
+        })))
+    }
+
+    #[inline]
+    pub fn image(&self) -> &vk::Image {
+        &self.0.image
+    }
+
+    #[inline]
+    pub fn device(&self) -> &VulkanDevice {
+        &self.0.device
+    }
+
+    #[inline]
+    pub fn dimension(&self) -> TextureDimension {
+        self.0.dimension
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        self.0.format
+    }
+
+    #[inline]
+    pub fn extent(&self) -> Extent3d {
+        self.0.extent
+    }
+
+    #[inline]
+    pub fn mip_levels(&self) -> u32 {
+        self.0.mip_levels
+    }
+
+    #[inline]
+    pub fn array_layers(&self) -> u32 {
+        self.0.array_layers
+    }
+
+    #[inline]
+    pub fn sample_count(&self) -> u32 {
+        self.0.sample_count
+    }
+
+    #[inline]
+    pub fn usage(&self) -> TextureUsageFlags {
+        self.0.usage
+    }
+
+    /// Returns the last access of every subresource if the texture was created with `tracked`
+    /// set.
+    #[inline]
+    pub(crate) fn states(&self) -> Option<&Mutex<Vec<ResourceState>>> {
+        self.0.states.as_ref()
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        unsafe {
+            vk_mem_alloc::destroy_image(*self.device.allocator(), self.image, self.allocation);
+        }
+    }
+}
+
+struct ViewInner {
+    texture: Texture,
+    device: VulkanDevice,
+
+    image_view: vk::ImageView,
+
+    dimension: TextureViewDimension,
+    format: Format,
+    range: TextureSubresourceRange,
+}
+
+#[derive(Clone)]
+pub struct VulkanTextureView(Arc<ViewInner>);
+
+impl VulkanTextureView {
+    pub fn new(
+        texture: &Texture,
+        vulkan_texture: &VulkanTexture,
+        desc: &TextureViewDesc,
+    ) -> Result<Self, VulkanError> {
+        let dimension = desc
+            .dimension
+            .unwrap_or_else(|| texture.default_view_dimension());
+        let format = desc.format.unwrap_or(vulkan_texture.format());
+        let range = desc.range.resolve(texture, format);
+
+        let image_view_create_info = vk::ImageViewCreateInfo::default()
+            .image(*vulkan_texture.image())
+            .view_type(to_vk_image_view_type(dimension))
+            .format(to_vk_format(format))
+            .subresource_range(to_vk_image_subresource_range(&range));
+
+        let device = vulkan_texture.device().clone();
+
+        let image_view = unsafe {
+            device
+                .device()
+                .create_image_view(&image_view_create_info, None)
+        }?;
+        device.set_object_name(image_view, desc.label);
+
+        Ok(Self(Arc::new(ViewInner {
+            texture: texture.clone(),
+            device,
+
+            image_view,
+
+            dimension,
+            format,
+            range,
+        })))
+    }
+
+    #[inline]
+    pub fn image_view(&self) -> &vk::ImageView {
+        &self.0.image_view
+    }
+
+    #[inline]
+    pub fn texture(&self) -> &Texture {
+        &self.0.texture
+    }
+
+    #[inline]
+    pub fn dimension(&self) -> TextureViewDimension {
+        self.0.dimension
+    }
+
+    #[inline]
+    pub fn format(&self) -> Format {
+        self.0.format
+    }
+
+    #[inline]
+    pub fn range(&self) -> &TextureSubresourceRange {
+        &self.0.range
+    }
+}
+
+impl Drop for ViewInner {
+    fn drop(&mut self) {
+        unsafe {
+            self.device
+                .device()
+                .destroy_image_view(self.image_view, None);
+        }
+    }
+}
@@ -0,0 +1,446 @@
+use std::{collections::VecDeque, ptr::NonNull, sync::Mutex, time::Duration};
+
+use ash::{prelude::VkResult, vk};
+use vk_mem_alloc::{Allocation, Allocator};
+
+use crate::{
+    vulkan::{
+        submit_command_buffers, to_allocation_create_info, to_vk_buffer_image_copy,
+        to_vk_buffer_memory_barrier, to_vk_image_aspect_flags, to_vk_image_memory_barrier,
+        ResourceAccess, ResourceBarrier, VulkanDevice, VulkanError, VulkanSemaphore,
+        UNTRACKED_IMAGE_LAYOUT,
+    },
+    BufferTextureCopy, BufferUpload, MemoryLocation, PhysicalDeviceFeatures, QueueType,
+    StagingRing, TextureAspectFlags, TextureUpload,
+};
+
+/// A persistently mapped buffer uploads are staged in.
+pub(crate) struct StagingBuffer {
+    buffer: vk::Buffer,
+    allocation: Allocation,
+    mapped_ptr: NonNull<u8>,
+}
+
+struct UploaderState {
+    command_pool: vk::CommandPool,
+    /// Command buffers with the value of the upload that last used them, oldest first.
+    command_buffers: VecDeque<(vk::CommandBuffer, u64)>,
+    staging_ring: StagingRing<StagingBuffer>,
+}
+
+/// Records uploads on the transfer queue, whose submission timeline is the upload timeline.
+///
+/// Staging buffers and command buffers are recycled once the timeline passed the value of the
+/// upload that last used them.
+pub(crate) struct VulkanUploader {
+    state: Mutex<UploaderState>,
+}
+
+unsafe impl Send for VulkanUploader {}
+unsafe impl Sync for VulkanUploader {}
+
+/// Returns the barrier moving the subresource of a discarded untracked texture to the layout it
+/// is copied in.
+fn discard_barrier() -> ResourceBarrier {
+    ResourceBarrier {
+        src_stages: vk::PipelineStageFlags2::NONE,
+        src_access: vk::AccessFlags2::NONE,
+        dst_stages: vk::PipelineStageFlags2::ALL_TRANSFER,
+        dst_access: vk::AccessFlags2::TRANSFER_WRITE,
+        old_layout: vk::ImageLayout::UNDEFINED,
+        new_layout: UNTRACKED_IMAGE_LAYOUT,
+    }
+}
+
+/// Returns the release half of the queue transfer of an untracked resource after the upload.
+fn untracked_release_barrier() -> ResourceBarrier {
+    ResourceBarrier {
+        src_stages: vk::PipelineStageFlags2::ALL_TRANSFER,
+        src_access: vk::AccessFlags2::TRANSFER_WRITE,
+        dst_stages: vk::PipelineStageFlags2::NONE,
+        dst_access: vk::AccessFlags2::NONE,
+        old_layout: UNTRACKED_IMAGE_LAYOUT,
+        new_layout: UNTRACKED_IMAGE_LAYOUT,
+    }
+}
+
+impl VulkanUploader {
+    pub(crate) unsafe fn new(device: &ash::Device, family_index: u32) -> VkResult<Self> {
+        let command_pool_create_info = vk::CommandPoolCreateInfo::default()
+            .flags(
+                vk::CommandPoolCreateFlags::TRANSIENT
+                    | vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER,
+            )
+            .queue_family_index(family_index);
+
+        let command_pool = device.create_command_pool(&command_pool_create_info, None)?;
+
+        Ok(Self {
+            state: Mutex::new(UploaderState {
+                command_pool,
+                command_buffers: VecDeque::new(),
+                staging_ring: StagingRing::default(),
+            }),
+        })
+    }
+
+    /// Destroys the command pool and staging buffers, called by the device before it is
+    /// destroyed itself.
+    pub(crate) unsafe fn destroy(&self, device: &ash::Device, allocator: Allocator) {
+        let mut state = self.state.lock().unwrap();
+
+        for staging_buffer in state.staging_ring.drain() {
+            vk_mem_alloc::destroy_buffer(
+                allocator,
+                staging_buffer.buffer,
+                staging_buffer.allocation,
+            );
+        }
+
+        device.destroy_command_pool(state.command_pool, None);
+    }
+
+    fn transfer_queue_index(device: &VulkanDevice) -> usize {
+        device.queue_index(QueueType::Transfer)
+    }
+
+    pub fn completed_value(device: &VulkanDevice) -> Result<u64, VulkanError> {
+        let entry = device.queue_entry(Self::transfer_queue_index(device));
+
+        Ok(unsafe {
+            device
+                .device()
+                .get_semaphore_counter_value(entry.submission_semaphore)
+        }?)
+    }
+
+    pub fn wait(
+        device: &VulkanDevice,
+        value: u64,
+        timeout: Option<Duration>,
+    ) -> Result<bool, VulkanError> {
+        let entry = device.queue_entry(Self::transfer_queue_index(device));
+
+        VulkanSemaphore::wait_for_raw_values(
+            device,
+            &[entry.submission_semaphore],
+            &[value],
+            timeout,
+        )
+    }
+
+    /// Returns the family indices of the queue transfer releasing an untracked resource to
+    /// `queue_type` after an upload, ignored if it shares the transfer queue family.
+    fn release_family_indices(device: &VulkanDevice, queue_type: QueueType) -> (u32, u32) {
+        let src_family_index = device.get_queue(QueueType::Transfer).family_index();
+        let dst_family_index = device.get_queue(queue_type).family_index();
+
+        if src_family_index == dst_family_index {
+            (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED)
+        } else {
+            (src_family_index, dst_family_index)
+        }
+    }
+
+    /// Stages `data`, records the copy with `record` and submits it to the transfer queue.
+    ///
+    /// `record` receives the command buffer and the staging buffer and offset of the data.
+    fn upload(
+        device: &VulkanDevice,
+        data: &[u8],
+        alignment: u64,
+        record: impl FnOnce(vk::CommandBuffer, vk::Buffer, u64),
+    ) -> Result<u64, VulkanError> {
+        if !device
+            .get_enabled_features()
+            .contains(PhysicalDeviceFeatures::SYNCHRONIZATION_2)
+        {
+            return Err(VulkanError::Custom(
+                "Uploads require the SYNCHRONIZATION_2 feature".to_owned(),
+            ));
+        }
+
+        let uploader = device.uploader();
+        let completed_value = Self::completed_value(device)?;
+
+        let mut state = uploader.state.lock().unwrap();
+        let state = &mut *state;
+
+        let (staging_buffer, offset) = state.staging_ring.allocate(
+            data.len() as u64,
+            alignment,
+            completed_value,
+            |size| Self::create_staging_buffer(device, size),
+            |staging_buffer| unsafe {
+                vk_mem_alloc::destroy_buffer(
+                    *device.allocator(),
+                    staging_buffer.buffer,
+                    staging_buffer.allocation,
+                );
+            },
+        )?;
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                staging_buffer.mapped_ptr.as_ptr().add(offset as usize),
+                data.len(),
+            );
+        }
+
+        let staging_buffer = staging_buffer.buffer;
+
+        let command_buffer = match state.command_buffers.front() {
+            Some((_, value)) if *value <= completed_value => {
+                state.command_buffers.pop_front().unwrap().0
+            }
+            _ => {
+                let command_buffer_allocate_info = vk::CommandBufferAllocateInfo::default()
+                    .command_pool(state.command_pool)
+                    .level(vk::CommandBufferLevel::PRIMARY)
+                    .command_buffer_count(1);
+
+                unsafe {
+                    device
+                        .device()
+                        .allocate_command_buffers(&command_buffer_allocate_info)
+                }?[0]
+            }
+        };
+
+        let result = unsafe {
+            Self::record_and_submit(device, command_buffer, |command_buffer| {
+                record(command_buffer, staging_buffer, offset)
+            })
+        };
+
+        // A failed submission leaves the command buffer unused, so it can be reused right away.
+        state
+            .command_buffers
+            .push_back((command_buffer, *result.as_ref().unwrap_or(&0)));
+        if let Ok(value) = &result {
+            state.staging_ring.submit(*value);
+        }
+
+        result
+    }
+
+    unsafe fn record_and_submit(
+        device: &VulkanDevice,
+        command_buffer: vk::CommandBuffer,
+        record: impl FnOnce(vk::CommandBuffer),
+    ) -> Result<u64, VulkanError> {
+        device
+            .device()
+            .reset_command_buffer(command_buffer, vk::CommandBufferResetFlags::empty())?;
+        device.device().begin_command_buffer(
+            command_buffer,
+            &vk::CommandBufferBeginInfo::default()
+                .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
+        )?;
+
+        record(command_buffer);
+
+        device.device().end_command_buffer(command_buffer)?;
+
+        submit_command_buffers(
+            device,
+            Self::transfer_queue_index(device),
+            &[],
+            &[command_buffer],
+            &[],
+            vk::Fence::null(),
+        )
+    }
+
+    fn create_staging_buffer(
+        device: &VulkanDevice,
+        size: u64,
+    ) -> Result<StagingBuffer, VulkanError> {
+        let buffer_create_info = vk::BufferCreateInfo::default()
+            .size(size)
+            .usage(vk::BufferUsageFlags::TRANSFER_SRC)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE);
+
+        let (buffer, allocation, allocation_info) = unsafe {
+            vk_mem_alloc::create_buffer(
+                *device.allocator(),
+                &buffer_create_info,
+                &to_allocation_create_info(MemoryLocation::Upload),
+            )
+        }?;
+
+        let Some(mapped_ptr) = NonNull::new(allocation_info.mapped_data.cast()) else {
+            unsafe { vk_mem_alloc::destroy_buffer(*device.allocator(), buffer, allocation) };
+
+            return Err(VulkanError::Custom(
+                "Staging buffer is not host visible".to_owned(),
+            ));
+        };
+
+        Ok(StagingBuffer {
+            buffer,
+            allocation,
+            mapped_ptr,
+        })
+    }
+
+    pub fn upload_buffer(device: &VulkanDevice, upload: &BufferUpload) -> Result<u64, VulkanError> {
+        let buffer = upload.buffer.as_vulkan();
+        let (src_family_index, dst_family_index) =
+            Self::release_family_indices(device, upload.queue_type);
+
+        Self::upload(
+            device,
+            upload.data,
+            16,
+            |command_buffer, staging_buffer, offset| {
+                let dependency = |barrier: &vk::BufferMemoryBarrier2| {
+                    let barriers = [*barrier];
+                    let dependency_info =
+                        vk::DependencyInfo::default().buffer_memory_barriers(&barriers);
+
+                    unsafe {
+                        device
+                            .device()
+                            .cmd_pipeline_barrier2(command_buffer, &dependency_info)
+                    };
+                };
+
+                let barrier = buffer.state().and_then(|state| {
+                    state.lock().unwrap().transition(
+                        ResourceAccess::TRANSFER_WRITE.for_buffer(),
+                        QueueType::Transfer,
+                    )
+                });
+
+                if let Some(barrier) = barrier {
+                    dependency(&to_vk_buffer_memory_barrier(*buffer.buffer(), &barrier));
+                }
+
+                let region = vk::BufferCopy {
+                    src_offset: offset,
+                    dst_offset: upload.offset,
+                    size: upload.data.len() as u64,
+                };
+
+                unsafe {
+                    device.device().cmd_copy_buffer(
+                        command_buffer,
+                        staging_buffer,
+                        *buffer.buffer(),
+                        &[region],
+                    );
+                }
+
+                if buffer.state().is_none() && src_family_index != vk::QUEUE_FAMILY_IGNORED {
+                    dependency(
+                        &to_vk_buffer_memory_barrier(
+                            *buffer.buffer(),
+                            &untracked_release_barrier(),
+                        )
+                        .src_queue_family_index(src_family_index)
+                        .dst_queue_family_index(dst_family_index),
+                    );
+                }
+            },
+        )
+    }
+
+    /// Untracked textures are copied in the general layout, uploads with `discard` set
+    /// transition the subresource to it. Tracked textures know their layout and ignore
+    /// `discard`.
+    pub fn upload_texture(
+        device: &VulkanDevice,
+        upload: &TextureUpload,
+    ) -> Result<u64, VulkanError> {
+        let texture = upload.texture;
+        let vulkan_texture = texture.as_vulkan();
+        let format = texture.format();
+        let (src_family_index, dst_family_index) =
+            Self::release_family_indices(device, upload.queue_type);
+
+        let subresource_range = vk::ImageSubresourceRange::default()
+            .aspect_mask(to_vk_image_aspect_flags(TextureAspectFlags::from_format(
+                format,
+            )))
+            .base_mip_level(upload.mip_level)
+            .level_count(1)
+            .base_array_layer(upload.array_layer)
+            .layer_count(1);
+
+        // Offsets into the staging buffer must be multiples of the block size and of four.
+        let alignment = u64::from(format.block_size()) * 4;
+
+        Self::upload(
+            device,
+            upload.data,
+            alignment,
+            |command_buffer, staging_buffer, offset| {
+                let dependency = |barrier: &vk::ImageMemoryBarrier2| {
+                    let barriers = [*barrier];
+                    let dependency_info =
+                        vk::DependencyInfo::default().image_memory_barriers(&barriers);
+
+                    unsafe {
+                        device
+                            .device()
+                            .cmd_pipeline_barrier2(command_buffer, &dependency_info)
+                    };
+                };
+
+                let (barrier, layout) = match vulkan_texture.states() {
+                    Some(states) => {
+                        let index = upload.mip_level * texture.array_layers() + upload.array_layer;
+                        let barrier = states.lock().unwrap()[index as usize]
+                            .transition(ResourceAccess::TRANSFER_WRITE, QueueType::Transfer);
+
+                        (barrier, ResourceAccess::TRANSFER_WRITE.layout)
+                    }
+                    None => (upload.discard.then(discard_barrier), UNTRACKED_IMAGE_LAYOUT),
+                };
+
+                if let Some(barrier) = barrier {
+                    dependency(&to_vk_image_memory_barrier(
+                        *vulkan_texture.image(),
+                        &barrier,
+                        subresource_range,
+                    ));
+                }
+
+                let region = BufferTextureCopy {
+                    buffer_offset: offset,
+                    bytes_per_row: 0,
+                    rows_per_image: 0,
+                    mip_level: upload.mip_level,
+                    array_layer: upload.array_layer,
+                    origin: upload.origin,
+                    extent: upload.extent,
+                };
+
+                unsafe {
+                    device.device().cmd_copy_buffer_to_image(
+                        command_buffer,
+                        staging_buffer,
+                        *vulkan_texture.image(),
+                        layout,
+                        &[to_vk_buffer_image_copy(texture, &region)],
+                    );
+                }
+
+                if vulkan_texture.states().is_none() && src_family_index != vk::QUEUE_FAMILY_IGNORED
+                {
+                    dependency(
+                        &to_vk_image_memory_barrier(
+                            *vulkan_texture.image(),
+                            &untracked_release_barrier(),
+                            subresource_range,
+                        )
+                        .src_queue_family_index(src_family_index)
+                        .dst_queue_family_index(dst_family_index),
+                    );
+                }
+            },
+        )
+    }
+}